@@ -1,11 +1,42 @@
 use anyhow::Result;
 use arrow::array::RecordBatch;
+use arrow::datatypes::Schema;
 use arrow::ipc::writer::StreamWriter;
 use arrow::ipc::writer::IpcWriteOptions;
 use bytes::Bytes;
 
 pub struct ArrowConverter;
 
+/// Encodes record batches into a single Arrow IPC stream one batch at a time,
+/// handing back the encoded bytes for each step so they can be sent immediately
+pub struct ArrowStreamEncoder {
+    writer: StreamWriter<Vec<u8>>,
+}
+
+impl ArrowStreamEncoder {
+    /// Create an encoder, returning it along with the encoded schema header
+    pub fn new(schema: &Schema) -> Result<(Self, Bytes)> {
+        let mut writer = StreamWriter::try_new_with_options(
+            Vec::new(),
+            schema,
+            IpcWriteOptions::default()
+        )?;
+        let header = Bytes::from(std::mem::take(writer.get_mut()));
+        Ok((Self { writer }, header))
+    }
+    
+    pub fn encode(&mut self, batch: &RecordBatch) -> Result<Bytes> {
+        self.writer.write(batch)?;
+        Ok(Bytes::from(std::mem::take(self.writer.get_mut())))
+    }
+    
+    /// Write the end-of-stream marker
+    pub fn finish(mut self) -> Result<Bytes> {
+        self.writer.finish()?;
+        Ok(Bytes::from(std::mem::take(self.writer.get_mut())))
+    }
+}
+
 impl ArrowConverter {
     pub fn record_batch_to_bytes(batch: &RecordBatch) -> Result<Bytes> {
         let mut buffer = Vec::new();
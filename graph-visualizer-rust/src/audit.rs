@@ -0,0 +1,160 @@
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Sender};
+use std::thread::JoinHandle;
+use tracing::{error, info};
+
+/// What triggered a mutation
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSource {
+    Api,
+    Webhook,
+    /// The background change monitor's automatic reload
+    Monitor,
+}
+
+#[derive(Debug, Serialize)]
+struct AuditRecord<'a> {
+    timestamp: String,
+    source: AuditSource,
+    action: &'a str,
+    node_ids: &'a [String],
+    edge_ids: &'a [String],
+}
+
+/// Append-only JSON-lines record of every mutation the visualizer performs.
+///
+/// Opt in with `AUDIT_LOG=true`. Records are appended to `AUDIT_LOG_PATH`
+/// when set and written to stdout otherwise. Edges have no UUID of their own,
+/// so they are identified as `from->to`. Reload records list the nodes
+/// and edges the reload deleted.
+///
+/// Records are written in order by a background thread, so recording never
+/// blocks a handler on I/O. Dropping the log writes out any still queued.
+pub struct AuditLog {
+    writer: Option<(Sender<Vec<u8>>, JoinHandle<()>)>,
+}
+
+impl AuditLog {
+    pub fn disabled() -> Self {
+        Self { writer: None }
+    }
+
+    pub fn from_env() -> io::Result<Self> {
+        let enabled = std::env::var("AUDIT_LOG")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(Self::disabled());
+        }
+
+        let sink: Box<dyn Write + Send> = match std::env::var("AUDIT_LOG_PATH").ok().filter(|p| !p.is_empty()) {
+            Some(path) => {
+                info!("Audit log enabled, appending to {}", path);
+                Box::new(OpenOptions::new().create(true).append(true).open(path)?)
+            }
+            None => {
+                info!("Audit log enabled, writing to stdout");
+                Box::new(io::stdout())
+            }
+        };
+        Ok(Self::to_writer(sink))
+    }
+
+    pub fn to_writer(mut sink: Box<dyn Write + Send>) -> Self {
+        let (sender, lines) = mpsc::channel::<Vec<u8>>();
+        let handle = std::thread::spawn(move || {
+            for line in lines {
+                // One write per record keeps lines whole
+                if let Err(e) = sink.write_all(&line).and_then(|_| sink.flush()) {
+                    error!("Failed to write audit record: {}", e);
+                }
+            }
+        });
+        Self { writer: Some((sender, handle)) }
+    }
+
+    pub fn record(&self, source: AuditSource, action: &str, node_ids: &[String], edge_ids: &[String]) {
+        let Some((ref sender, _)) = self.writer else {
+            return;
+        };
+
+        let record = AuditRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            source,
+            action,
+            node_ids,
+            edge_ids,
+        };
+        let mut line = match serde_json::to_vec(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                error!("Failed to serialize audit record for {}: {}", action, e);
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        if sender.send(line).is_err() {
+            error!("Audit writer stopped, dropping record for {}", action);
+        }
+    }
+}
+
+impl Drop for AuditLog {
+    fn drop(&mut self) {
+        if let Some((sender, handle)) = self.writer.take() {
+            drop(sender);
+            let _ = handle.join();
+        }
+    }
+}
+
+pub fn edge_id(from: &str, to: &str) -> String {
+    format!("{}->{}", from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    // Writer appending to a buffer the test keeps a handle on
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_records_written_as_json_lines() {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let log = AuditLog::to_writer(Box::new(SharedBuffer(buffer.clone())));
+        log.record(AuditSource::Api, "add_nodes", &["a".to_string(), "b".to_string()], &[]);
+        log.record(AuditSource::Webhook, "ingest_upsert", &[], &[edge_id("a", "b")]);
+        // Dropping waits for the queued records
+        drop(log);
+
+        let written = String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+        let records: Vec<serde_json::Value> = written
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["source"], "api");
+        assert_eq!(records[0]["action"], "add_nodes");
+        assert_eq!(records[0]["node_ids"], serde_json::json!(["a", "b"]));
+        assert_eq!(records[0]["edge_ids"], serde_json::json!([]));
+        assert!(chrono::DateTime::parse_from_rfc3339(records[0]["timestamp"].as_str().unwrap()).is_ok());
+        assert_eq!(records[1]["source"], "webhook");
+        assert_eq!(records[1]["edge_ids"], serde_json::json!(["a->b"]));
+    }
+}
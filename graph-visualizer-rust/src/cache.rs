@@ -20,13 +20,20 @@ pub struct CacheEntry<T> {
     pub last_accessed: Instant,
 }
 
-/// Adaptive TTL calculator based on access patterns
+/// Adaptive TTL calculator based on access patterns.
+///
+/// Keys accessed more than `hot_threshold` times get `hot_ttl`, more than
+/// `warm_threshold` times `warm_ttl`, and anything colder `cold_ttl`. The
+/// server reads these (`from_env`) from `CACHE_HOT_TTL_SECONDS`, `CACHE_WARM_TTL_SECONDS`,
+/// `CACHE_COLD_TTL_SECONDS`, `CACHE_HOT_THRESHOLD` and `CACHE_WARM_THRESHOLD`,
+/// falling back to the defaults below.
+#[derive(Debug, Clone, Copy)]
 pub struct AdaptiveTTL {
-    hot_threshold: u32,
-    warm_threshold: u32,
-    hot_ttl: Duration,
-    warm_ttl: Duration,
-    cold_ttl: Duration,
+    pub hot_threshold: u32,
+    pub warm_threshold: u32,
+    pub hot_ttl: Duration,
+    pub warm_ttl: Duration,
+    pub cold_ttl: Duration,
 }
 
 impl Default for AdaptiveTTL {
@@ -42,6 +49,33 @@ impl Default for AdaptiveTTL {
 }
 
 impl AdaptiveTTL {
+    /// Tiers from the `CACHE_*` environment variables
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Tiers from the variables `lookup` returns, keeping the default for any
+    /// that are missing or unparseable
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        let secs = |name: &str, default: Duration| {
+            lookup(name)
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+        let count = |name: &str, default: u32| {
+            lookup(name).and_then(|v| v.parse::<u32>().ok()).unwrap_or(default)
+        };
+        Self {
+            hot_threshold: count("CACHE_HOT_THRESHOLD", defaults.hot_threshold),
+            warm_threshold: count("CACHE_WARM_THRESHOLD", defaults.warm_threshold),
+            hot_ttl: secs("CACHE_HOT_TTL_SECONDS", defaults.hot_ttl),
+            warm_ttl: secs("CACHE_WARM_TTL_SECONDS", defaults.warm_ttl),
+            cold_ttl: secs("CACHE_COLD_TTL_SECONDS", defaults.cold_ttl),
+        }
+    }
+
     pub fn calculate_ttl(&self, access_count: u32) -> u64 {
         if access_count > self.hot_threshold {
             self.hot_ttl.as_secs()
@@ -58,6 +92,14 @@ pub struct RequestCoalescer<K: Clone + Eq + std::hash::Hash, V: Clone> {
     inflight: Arc<DashMap<K, Arc<tokio::sync::Mutex<Option<V>>>>>,
 }
 
+impl<K: Clone + Eq + std::hash::Hash, V: Clone> Clone for RequestCoalescer<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inflight: self.inflight.clone(),
+        }
+    }
+}
+
 impl<K: Clone + Eq + std::hash::Hash + Send + Sync + 'static, V: Clone + Send + Sync + 'static>
     RequestCoalescer<K, V>
 {
@@ -67,6 +109,11 @@ impl<K: Clone + Eq + std::hash::Hash + Send + Sync + 'static, V: Clone + Send +
         }
     }
 
+    /// Whether a computation for this key is currently running
+    pub fn is_inflight(&self, key: &K) -> bool {
+        self.inflight.contains_key(key)
+    }
+
     /// Execute function with request coalescing
     pub async fn get_or_compute<F, Fut>(&self, key: K, compute: F) -> V
     where
@@ -111,42 +158,89 @@ impl<K: Clone + Eq + std::hash::Hash + Send + Sync + 'static, V: Clone + Send +
     }
 }
 
-/// Bloom filter for negative caching
+/// Settings for the bloom-filter negative cache.
+///
+/// Keys whose computation returned no value are remembered for `ttl` so repeat
+/// lookups skip the computation. The tradeoff: a key that starts producing
+/// results (e.g. after ingestion) keeps returning nothing until the filter
+/// resets, and bloom false positives can short-circuit keys that never missed
+/// at roughly `false_positive_rate`. Use a short TTL, or disable negative
+/// caching per call, for data that changes quickly.
+#[derive(Debug, Clone, Copy)]
+pub struct NegativeCacheConfig {
+    pub ttl: Duration,
+    pub false_positive_rate: f64,
+    pub expected_items: usize,
+}
+
+impl Default for NegativeCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            false_positive_rate: 0.01, // 1% false positive rate
+            expected_items: 1_000_000,
+        }
+    }
+}
+
+struct MissFilter {
+    bloom: BloomFilter,
+    created_at: Instant,
+}
+
+/// Bloom filter of keys known to have no value, reset every `ttl`
 pub struct NegativeCache {
-    filter: Arc<RwLock<BloomFilter>>,
-    #[allow(dead_code)]
-    false_positive_rate: f64,
+    filter: Arc<RwLock<MissFilter>>,
+    config: NegativeCacheConfig,
 }
 
 impl NegativeCache {
-    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
-        let filter = BloomFilter::with_rate(false_positive_rate as f32, expected_items as u32);
+    pub fn new(config: NegativeCacheConfig) -> Self {
         Self {
-            filter: Arc::new(RwLock::new(filter)),
-            false_positive_rate,
+            filter: Arc::new(RwLock::new(Self::empty_filter(&config))),
+            config,
         }
     }
 
-    /// Check if key might exist (true = maybe exists, false = definitely doesn't exist)
-    pub async fn might_exist(&self, key: &str) -> bool {
-        let filter = self.filter.read().await;
-        filter.contains(&key)
+    fn empty_filter(config: &NegativeCacheConfig) -> MissFilter {
+        MissFilter {
+            bloom: BloomFilter::with_rate(
+                config.false_positive_rate as f32,
+                config.expected_items as u32,
+            ),
+            created_at: Instant::now(),
+        }
     }
 
-    /// Mark key as existing
-    pub async fn mark_exists(&self, key: &str) {
+    /// Check if key recently produced no value (subject to false positives)
+    pub async fn is_known_missing(&self, key: &str) -> bool {
+        if self.config.ttl.is_zero() {
+            return false;
+        }
+        {
+            let filter = self.filter.read().await;
+            if filter.created_at.elapsed() < self.config.ttl {
+                return filter.bloom.contains(&key);
+            }
+        }
+        // Filter expired; start a fresh one
+        self.clear().await;
+        false
+    }
+
+    /// Remember that key produced no value
+    pub async fn mark_missing(&self, key: &str) {
+        if self.config.ttl.is_zero() {
+            return;
+        }
         let mut filter = self.filter.write().await;
-        filter.insert(&key);
+        filter.bloom.insert(&key);
     }
 
-    /// Clear the filter (use periodically to prevent saturation)
-    #[allow(dead_code)]
+    /// Forget all recorded misses
     pub async fn clear(&self) {
         let mut filter = self.filter.write().await;
-        *filter = BloomFilter::with_rate(
-            self.false_positive_rate as f32,
-            1_000_000, // Default size
-        );
+        *filter = Self::empty_filter(&self.config);
     }
 }
 
@@ -207,6 +301,50 @@ impl AccessCounter {
     }
 }
 
+/// Value stored in Redis along with the time it was written, so entries past
+/// their TTL can still be served during the stale window
+#[derive(Serialize, Deserialize)]
+struct StoredEntry<T> {
+    value: T,
+    stored_at_ms: u64,
+    ttl_secs: u64,
+}
+
+impl<T> StoredEntry<T> {
+    fn is_fresh(&self) -> bool {
+        now_ms().saturating_sub(self.stored_at_ms) < self.ttl_secs * 1000
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Write a computed value to Redis. The key expires after `ttl + stale_ttl`.
+async fn store_entry<T: Serialize>(
+    redis_pool: &RedisPool,
+    key: &str,
+    value: &T,
+    ttl: u64,
+    stale_ttl: u64,
+) {
+    let entry = StoredEntry {
+        value,
+        stored_at_ms: now_ms(),
+        ttl_secs: ttl,
+    };
+    if let Ok(mut conn) = redis_pool.get().await {
+        if let Ok(json) = serde_json::to_string(&entry) {
+            let _ = conn
+                .set_ex::<_, _, ()>(key, &json, ttl + stale_ttl)
+                .await;
+        }
+    }
+}
+
 /// Enhanced cache operations with all optimizations
 pub struct EnhancedCache {
     redis_pool: RedisPool,
@@ -214,53 +352,130 @@ pub struct EnhancedCache {
     coalescer: RequestCoalescer<String, Option<String>>,
     negative_cache: NegativeCache,
     access_counter: AccessCounter,
+    stale_ttl: Duration,
 }
 
 impl EnhancedCache {
-    pub fn new(redis_pool: RedisPool) -> Self {
+    pub fn new(redis_pool: RedisPool, negative_cache: NegativeCacheConfig) -> Self {
         Self {
             redis_pool,
             adaptive_ttl: AdaptiveTTL::default(),
             coalescer: RequestCoalescer::new(),
-            negative_cache: NegativeCache::new(1_000_000, 0.01), // 1% false positive rate
+            negative_cache: NegativeCache::new(negative_cache),
             access_counter: AccessCounter::new(10_000),
+            stale_ttl: Duration::ZERO,
         }
     }
 
+    /// Replace the default hot/warm/cold TTL tiers
+    pub fn with_adaptive_ttl(mut self, adaptive_ttl: AdaptiveTTL) -> Self {
+        self.adaptive_ttl = adaptive_ttl;
+        self
+    }
+
+    /// Keep serving entries for `stale_ttl` past their TTL while a background
+    /// refresh recomputes them (stale-while-revalidate). Zero disables it.
+    pub fn with_stale_ttl(mut self, stale_ttl: Duration) -> Self {
+        self.stale_ttl = stale_ttl;
+        self
+    }
+
+    async fn read_entry<T>(&self, key: &str) -> Option<StoredEntry<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let mut conn = self.redis_pool.get().await.ok()?;
+        let cached = conn.get::<_, String>(key).await.ok()?;
+        serde_json::from_str::<StoredEntry<T>>(&cached).ok()
+    }
+
+    /// Recompute a stale key in the background. Skipped if a computation for
+    /// the key is already in flight.
+    fn spawn_refresh<T, F, Fut>(&self, key: &str, compute: F, ttl: u64)
+    where
+        T: Serialize + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Option<T>, anyhow::Error>> + Send + 'static,
+    {
+        let key = key.to_string();
+        if self.coalescer.is_inflight(&key) {
+            debug!("Refresh already in flight for key: {}", key);
+            return;
+        }
+
+        let coalescer = self.coalescer.clone();
+        let redis_pool = self.redis_pool.clone();
+        let stale_ttl = self.stale_ttl.as_secs();
+        tokio::spawn(async move {
+            let refresh_key = key.clone();
+            coalescer
+                .get_or_compute(key, || async move {
+                    debug!("Background refresh for stale key: {}", refresh_key);
+                    match compute().await {
+                        Ok(Some(value)) => {
+                            store_entry(&redis_pool, &refresh_key, &value, ttl, stale_ttl).await;
+                            serde_json::to_string(&value).ok()
+                        }
+                        Ok(None) => None,
+                        Err(e) => {
+                            debug!("Background refresh failed for key {}: {}", refresh_key, e);
+                            None
+                        }
+                    }
+                })
+                .await;
+        });
+    }
+
+    /// Cached value for `key`, computed on a miss. With `use_negative_cache:
+    /// false` keys that previously produced no value are always recomputed
+    /// (see `NegativeCacheConfig`).
     #[instrument(skip(self, compute))]
-    pub async fn get_or_compute<T, F, Fut>(
+    pub async fn get_or_compute_with<T, F, Fut>(
         &self,
         key: &str,
+        use_negative_cache: bool,
         compute: F,
     ) -> Result<Option<T>, anyhow::Error>
     where
         T: Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync + 'static,
-        F: FnOnce() -> Fut + Send,
-        Fut: Future<Output = Result<Option<T>, anyhow::Error>> + Send,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<Option<T>, anyhow::Error>> + Send + 'static,
     {
         // Check negative cache first
-        if !self.negative_cache.might_exist(key).await {
+        if use_negative_cache && self.negative_cache.is_known_missing(key).await {
             debug!("Negative cache hit for key: {}", key);
             return Ok(None);
         }
 
         // Increment access counter
         let access_count = self.access_counter.increment(key).await;
+        let ttl = self.adaptive_ttl.calculate_ttl(access_count);
+
+        // Serve fresh or stale entries without waiting on a computation
+        if let Some(entry) = self.read_entry::<T>(key).await {
+            if entry.is_fresh() {
+                debug!(
+                    "Cache hit for key: {} (access count: {})",
+                    key, access_count
+                );
+                return Ok(Some(entry.value));
+            }
+            if !self.stale_ttl.is_zero() {
+                debug!("Serving stale value for key: {}", key);
+                self.spawn_refresh(key, compute, ttl);
+                return Ok(Some(entry.value));
+            }
+        }
 
         // Use request coalescing
         let result = self
             .coalescer
             .get_or_compute(key.to_string(), || async move {
-                // Try to get from Redis
-                if let Ok(mut conn) = self.redis_pool.get().await {
-                    if let Ok(cached) = conn.get::<_, String>(key).await {
-                        if let Ok(value) = serde_json::from_str::<T>(&cached) {
-                            debug!(
-                                "Cache hit for key: {} (access count: {})",
-                                key, access_count
-                            );
-                            return serde_json::to_string(&value).ok();
-                        }
+                // Another request may have filled the entry while we waited
+                if let Some(entry) = self.read_entry::<T>(key).await {
+                    if entry.is_fresh() {
+                        return serde_json::to_string(&entry.value).ok();
                     }
                 }
 
@@ -268,28 +483,21 @@ impl EnhancedCache {
                 debug!("Cache miss for key: {}", key);
                 match compute().await {
                     Ok(Some(value)) => {
-                        // Mark as existing in negative cache
-                        self.negative_cache.mark_exists(key).await;
-
-                        // Calculate adaptive TTL
-                        let ttl = self.adaptive_ttl.calculate_ttl(access_count);
                         debug!(
                             "Setting TTL {} seconds for key: {} (access count: {})",
                             ttl, key, access_count
                         );
 
                         // Store in Redis with adaptive TTL
-                        if let Ok(mut conn) = self.redis_pool.get().await {
-                            if let Ok(json) = serde_json::to_string(&value) {
-                                let _ = conn.set_ex::<_, _, ()>(key, &json, ttl).await;
-                            }
-                        }
+                        store_entry(&self.redis_pool, key, &value, ttl, self.stale_ttl.as_secs()).await;
 
                         serde_json::to_string(&value).ok()
                     }
                     Ok(None) => {
-                        // Value doesn't exist, don't add to negative cache
-                        // (it's already not there)
+                        // Value doesn't exist, remember the miss
+                        if use_negative_cache {
+                            self.negative_cache.mark_missing(key).await;
+                        }
                         None
                     }
                     Err(e) => {
@@ -307,13 +515,124 @@ impl EnhancedCache {
         }
     }
 
+    /// Check that a pooled Redis connection answers PING
+    pub async fn ping(&self) -> anyhow::Result<()> {
+        let mut conn = self.redis_pool.get().await?;
+        deadpool_redis::redis::cmd("PING")
+            .query_async::<_, String>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
     /// Periodic maintenance task
     #[allow(dead_code)]
     pub async fn maintenance(&self) {
         // Clean up old access counts
         self.access_counter.cleanup(Duration::from_secs(3600)).await;
 
-        // Optionally clear bloom filter if it's getting too full
-        // (in production, monitor false positive rate and clear when needed)
+        // The negative cache resets itself once its TTL elapses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Nothing listens on the Redis port, so every lookup misses
+    fn unreachable_redis_cache() -> EnhancedCache {
+        let redis_pool = deadpool_redis::Config::from_url("redis://127.0.0.1:1")
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+        EnhancedCache::new(redis_pool, NegativeCacheConfig::default())
+    }
+
+    // Look `key` up, counting the computations it causes
+    async fn lookup(cache: &EnhancedCache, key: &str, use_negative_cache: bool, computed: &Arc<AtomicUsize>) {
+        let computed = computed.clone();
+        let value = cache
+            .get_or_compute_with::<String, _, _>(key, use_negative_cache, move || async move {
+                computed.fetch_add(1, Ordering::SeqCst);
+                Ok(None)
+            })
+            .await
+            .unwrap();
+        assert!(value.is_none());
+        // Let the coalescer forget the finished computation
+        tokio::time::sleep(Duration::from_millis(150)).await;
+    }
+
+    #[tokio::test]
+    async fn test_miss_recomputed_without_negative_cache() {
+        let cache = unreachable_redis_cache();
+        let computed = Arc::new(AtomicUsize::new(0));
+
+        lookup(&cache, "empty", true, &computed).await;
+        assert_eq!(computed.load(Ordering::SeqCst), 1);
+        // The negative cache remembers the miss
+        lookup(&cache, "empty", true, &computed).await;
+        assert_eq!(computed.load(Ordering::SeqCst), 1);
+        // Bypassing it recomputes, every time
+        lookup(&cache, "empty", false, &computed).await;
+        lookup(&cache, "empty", false, &computed).await;
+        assert_eq!(computed.load(Ordering::SeqCst), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_adaptive_ttl_tiers_from_environment() {
+        let vars: HashMap<&str, &str> = [
+            ("CACHE_HOT_THRESHOLD", "50"),
+            ("CACHE_HOT_TTL_SECONDS", "3600"),
+            ("CACHE_COLD_TTL_SECONDS", "not a number"),
+        ].into();
+        let ttl = AdaptiveTTL::from_lookup(|name| vars.get(name).map(|v| v.to_string()));
+
+        assert_eq!(ttl.hot_threshold, 50);
+        assert_eq!(ttl.warm_threshold, 10);
+        assert_eq!(ttl.calculate_ttl(51), 3600);
+        assert_eq!(ttl.calculate_ttl(50), 300);
+        assert_eq!(ttl.calculate_ttl(11), 300);
+        // Unparseable values keep the default
+        assert_eq!(ttl.calculate_ttl(10), 60);
+    }
+
+    #[tokio::test]
+    async fn test_stale_entry_served_while_refreshed_once() {
+        let server = crate::mock_redis::MockRedis::start().await;
+        let redis_pool = deadpool_redis::Config::from_url(format!("redis://{}", server.addr))
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+        let cache = EnhancedCache::new(redis_pool, NegativeCacheConfig::default())
+            .with_stale_ttl(Duration::from_secs(60));
+
+        // Written 20s ago with a 10s TTL: expired, but inside the stale window
+        let entry = StoredEntry { value: "stale".to_string(), stored_at_ms: now_ms() - 20_000, ttl_secs: 10 };
+        server.set("key", serde_json::to_string(&entry).unwrap());
+
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        let get = || {
+            let refreshes = refreshes.clone();
+            cache.get_or_compute_with::<String, _, _>("key", true, move || async move {
+                refreshes.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                Ok(Some("fresh".to_string()))
+            })
+        };
+
+        // Served without waiting on the refresh, however often it's asked for
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            assert_eq!(get().await.unwrap().as_deref(), Some("stale"));
+        }
+        assert!(start.elapsed() < Duration::from_millis(200));
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+        let stored: StoredEntry<String> = serde_json::from_str(&server.get("key").unwrap()).unwrap();
+        assert_eq!(stored.value, "fresh");
+        assert!(stored.is_fresh());
+        assert_eq!(get().await.unwrap().as_deref(), Some("fresh"));
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Consecutive-failure circuit breaker for an upstream service.
+///
+/// After `failure_threshold` failures in a row the circuit opens and requests
+/// are rejected without being sent until `cooldown` has elapsed. Then the
+/// circuit is half-open: a single probe request is let through and everything
+/// else is still rejected until it finishes. Success closes the circuit,
+/// failure reopens it for another cooldown. A probe that never reports back
+/// is given up on after a further cooldown so another can be sent.
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    // When the half-open probe was let through, while it is in flight
+    probe_started: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Whether a request may be sent now
+    pub fn allow_request(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let Some(until) = state.open_until else {
+            return true;
+        };
+        let now = Instant::now();
+        if now < until {
+            return false;
+        }
+        if state
+            .probe_started
+            .is_some_and(|started| now < started + self.cooldown)
+        {
+            return false;
+        }
+        state.probe_started = Some(now);
+        true
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.open_until.is_some() {
+            info!("Circuit closed after successful request");
+        }
+        state.consecutive_failures = 0;
+        state.open_until = None;
+        state.probe_started = None;
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        state.probe_started = None;
+        if state.consecutive_failures >= self.failure_threshold {
+            warn!(
+                "Circuit opened after {} consecutive failures, rejecting requests for {:?}",
+                state.consecutive_failures, self.cooldown
+            );
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    /// Seconds until requests are allowed again, if the circuit is open
+    pub fn retry_after(&self) -> Option<u64> {
+        let state = self.state.lock().unwrap();
+        state
+            .open_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .map(|remaining| remaining.as_secs().max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tripped(cooldown: Duration) -> CircuitBreaker {
+        let breaker = CircuitBreaker::new(2, cooldown);
+        breaker.record_failure();
+        breaker.record_failure();
+        breaker
+    }
+
+    #[test]
+    fn test_opens_after_threshold_failures() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(breaker.allow_request());
+        breaker.record_failure();
+        assert!(!breaker.allow_request());
+        assert!(breaker.retry_after().is_some());
+    }
+
+    #[test]
+    fn test_half_open_lets_one_probe_through() {
+        let breaker = tripped(Duration::from_millis(20));
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert!(breaker.allow_request());
+        assert!(!breaker.allow_request());
+        assert!(!breaker.allow_request());
+
+        breaker.record_success();
+        assert!(breaker.allow_request());
+        assert!(breaker.allow_request());
+    }
+
+    #[test]
+    fn test_failed_probe_reopens_circuit() {
+        let breaker = tripped(Duration::from_millis(20));
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert!(breaker.allow_request());
+        breaker.record_failure();
+        assert!(!breaker.allow_request());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.allow_request());
+        assert!(!breaker.allow_request());
+    }
+}
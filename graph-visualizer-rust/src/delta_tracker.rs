@@ -1,5 +1,7 @@
 use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use serde::{Serialize, Deserialize};
 use crate::{Node, Edge};
@@ -25,13 +27,30 @@ pub enum DeltaOperation {
     Refresh,
 }
 
+/// Outcome of a reconnecting client's request to catch up from a sequence
+#[derive(Debug)]
+pub enum Backfill {
+    /// Every delta emitted after the client's sequence, oldest first
+    Deltas(Vec<GraphDelta>),
+    /// The missed deltas are no longer in the history; reload the full graph
+    ResyncRequired,
+}
+
 #[derive(Clone)]
 pub struct DeltaTracker {
     current_nodes: Arc<RwLock<HashMap<String, Node>>>,
     current_edges: Arc<RwLock<HashMap<(String, String), Edge>>>,
-    sequence_counter: Arc<RwLock<u64>>,
-    delta_history: Arc<RwLock<VecDeque<GraphDelta>>>,
+    sequence_counter: Arc<AtomicU64>,
+    delta_history: Arc<Mutex<VecDeque<GraphDelta>>>,
     max_history_size: usize,
+    flush_interval: Duration,
+    coalescing: Arc<Mutex<CoalesceState>>,
+}
+
+#[derive(Default)]
+struct CoalesceState {
+    pending: Option<GraphDelta>,
+    last_emit: Option<Instant>,
 }
 
 impl DeltaTracker {
@@ -39,76 +58,100 @@ impl DeltaTracker {
         Self {
             current_nodes: Arc::new(RwLock::new(HashMap::new())),
             current_edges: Arc::new(RwLock::new(HashMap::new())),
-            sequence_counter: Arc::new(RwLock::new(0)),
-            delta_history: Arc::new(RwLock::new(VecDeque::new())),
+            sequence_counter: Arc::new(AtomicU64::new(0)),
+            delta_history: Arc::new(Mutex::new(VecDeque::new())),
             max_history_size: 100, // Keep last 100 deltas
+            flush_interval: Duration::ZERO,
+            coalescing: Arc::new(Mutex::new(CoalesceState::default())),
         }
     }
     
-    pub async fn compute_delta(&self, new_nodes: Vec<Node>, new_edges: Vec<Edge>) -> GraphDelta {
-        let mut nodes_added = Vec::new();
-        let mut nodes_updated = Vec::new();
-        let mut nodes_removed = Vec::new();
-        let mut edges_added = Vec::new();
-        let mut edges_updated = Vec::new();
-        let mut edges_removed = Vec::new();
-        
-        // Get current state
-        let current_nodes = self.current_nodes.read().await;
-        let current_edges = self.current_edges.read().await;
-        
-        // Create maps for new data
-        let new_nodes_map: HashMap<String, Node> = new_nodes
-            .into_iter()
-            .map(|n| (n.id.clone(), n))
-            .collect();
-        
-        let new_edges_map: HashMap<(String, String), Edge> = new_edges
-            .into_iter()
-            .map(|e| ((e.from.clone(), e.to.clone()), e))
-            .collect();
+    /// Merge deltas arriving within `flush_interval` of the last broadcast into
+    /// one. Zero (the default) broadcasts every delta as-is.
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+    
+    pub fn flush_interval(&self) -> Duration {
+        self.flush_interval
+    }
+    
+    /// Assign the next sequence number to a delta that is about to be emitted
+    /// and keep it in the history.
+    ///
+    /// Sequences increase by exactly one per emitted delta and are never reset,
+    /// so a client that sees a gap (a sequence other than last + 1) has missed
+    /// deltas and should send a `backfill` command, fetch `/api/graph/changes`
+    /// or request a full reload.
+    pub fn record(&self, mut delta: GraphDelta) -> GraphDelta {
+        // Numbered under the history lock so the history stays in sequence order
+        let mut history = self.delta_history.lock().unwrap();
+        delta.sequence = self.sequence_counter.fetch_add(1, Ordering::SeqCst) + 1;
+        history.push_back(delta.clone());
         
-        // Find added and updated nodes
-        for (id, new_node) in &new_nodes_map {
-            match current_nodes.get(id) {
-                Some(old_node) => {
-                    if !nodes_equal(old_node, new_node) {
-                        nodes_updated.push(new_node.clone());
-                    }
-                }
-                None => {
-                    nodes_added.push(new_node.clone());
-                }
-            }
+        // Limit history size
+        while history.len() > self.max_history_size {
+            history.pop_front();
         }
         
-        // Find removed nodes
-        for id in current_nodes.keys() {
-            if !new_nodes_map.contains_key(id) {
-                nodes_removed.push(id.clone());
-            }
+        delta
+    }
+    
+    /// Sequence of the most recently emitted delta
+    pub fn last_sequence(&self) -> u64 {
+        self.sequence_counter.load(Ordering::SeqCst)
+    }
+    
+    /// Route a delta through the coalescer. Returns the sequenced delta to
+    /// broadcast now, or `None` if it was merged into the pending delta for
+    /// `flush_pending`.
+    pub fn coalesce(&self, delta: GraphDelta) -> Option<GraphDelta> {
+        if self.flush_interval.is_zero() {
+            return Some(self.record(delta));
         }
         
-        // Find added and updated edges
-        for (key, new_edge) in &new_edges_map {
-            match current_edges.get(key) {
-                Some(old_edge) => {
-                    if !edges_equal(old_edge, new_edge) {
-                        edges_updated.push(new_edge.clone());
-                    }
-                }
-                None => {
-                    edges_added.push(new_edge.clone());
-                }
-            }
+        let mut state = self.coalescing.lock().unwrap();
+        let idle = state.pending.is_none()
+            && state.last_emit.is_none_or(|t| t.elapsed() >= self.flush_interval);
+        // Recorded under the coalescing lock, so a delta merged after this one
+        // can't be numbered before it
+        if idle {
+            state.last_emit = Some(Instant::now());
+            return Some(self.record(delta));
         }
         
-        // Find removed edges
-        for key in current_edges.keys() {
-            if !new_edges_map.contains_key(key) {
-                edges_removed.push(key.clone());
-            }
-        }
+        state.pending = Some(match state.pending.take() {
+            Some(pending) => merge_deltas(pending, delta),
+            None => delta,
+        });
+        None
+    }
+    
+    /// Take the merged delta accumulated since the last broadcast, if any
+    pub fn flush_pending(&self) -> Option<GraphDelta> {
+        let mut state = self.coalescing.lock().unwrap();
+        let pending = state.pending.take()?;
+        state.last_emit = Some(Instant::now());
+        Some(self.record(pending))
+    }
+    
+    pub async fn compute_delta(&self, new_nodes: Vec<Node>, new_edges: Vec<Edge>) -> GraphDelta {
+        // Get current state
+        let current_nodes = self.current_nodes.read().await;
+        let current_edges = self.current_edges.read().await;
+        let initial = current_nodes.is_empty() && current_edges.is_empty();
+        
+        // Create maps for new data
+        let (new_nodes_map, new_edges_map) = index_graph(new_nodes, new_edges);
+        let GraphDiff {
+            nodes_added,
+            nodes_updated,
+            nodes_removed,
+            edges_added,
+            edges_updated,
+            edges_removed,
+        } = diff_graphs(&current_nodes, &current_edges, &new_nodes_map, &new_edges_map);
         
         // Update current state
         drop(current_nodes);
@@ -120,13 +163,9 @@ impl DeltaTracker {
         *nodes_write = new_nodes_map;
         *edges_write = new_edges_map;
         
-        // Increment sequence counter
-        let mut seq = self.sequence_counter.write().await;
-        *seq += 1;
-        let sequence = *seq;
-        
-        let delta = GraphDelta {
-            operation: if sequence == 1 { DeltaOperation::Initial } else { DeltaOperation::Update },
+        // The sequence is assigned when the delta is emitted (see `record`)
+        GraphDelta {
+            operation: if initial { DeltaOperation::Initial } else { DeltaOperation::Update },
             nodes_added,
             nodes_updated,
             nodes_removed,
@@ -137,48 +176,34 @@ impl DeltaTracker {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_millis() as u64,
-            sequence,
-        };
-        
-        // Store delta in history
-        let mut history = self.delta_history.write().await;
-        history.push_back(delta.clone());
-        
-        // Limit history size
-        while history.len() > self.max_history_size {
-            history.pop_front();
+            sequence: 0,
         }
-        
-        delta
     }
     
+    /// Forget the tracked graph state. The sequence keeps counting so clients
+    /// still detect the discontinuity.
     pub async fn reset(&self) {
         let mut nodes = self.current_nodes.write().await;
         let mut edges = self.current_edges.write().await;
-        let mut seq = self.sequence_counter.write().await;
-        let mut history = self.delta_history.write().await;
         
         nodes.clear();
         edges.clear();
-        *seq = 0;
-        history.clear();
+        self.delta_history.lock().unwrap().clear();
     }
     
     pub async fn get_stats(&self) -> (usize, usize, u64) {
         let nodes = self.current_nodes.read().await;
         let edges = self.current_edges.read().await;
-        let seq = self.sequence_counter.read().await;
         
-        (nodes.len(), edges.len(), *seq)
+        (nodes.len(), edges.len(), self.last_sequence())
     }
     
     pub async fn get_current_sequence(&self) -> u64 {
-        let seq = self.sequence_counter.read().await;
-        *seq
+        self.last_sequence()
     }
     
     pub async fn get_changes_since(&self, since_sequence: u64, limit: usize) -> Vec<GraphDelta> {
-        let history = self.delta_history.read().await;
+        let history = self.delta_history.lock().unwrap();
         
         history
             .iter()
@@ -187,6 +212,200 @@ impl DeltaTracker {
             .cloned()
             .collect()
     }
+    
+    /// Replay the deltas a client missed since `last_sequence`, provided the
+    /// bounded history still holds all of them.
+    pub fn backfill(&self, last_sequence: u64) -> Backfill {
+        let current = self.last_sequence();
+        if last_sequence == current {
+            return Backfill::Deltas(Vec::new());
+        }
+        if last_sequence > current {
+            // Sequence from before a server restart
+            return Backfill::ResyncRequired;
+        }
+        
+        let history = self.delta_history.lock().unwrap();
+        match history.front() {
+            Some(oldest) if oldest.sequence <= last_sequence + 1 => Backfill::Deltas(
+                history
+                    .iter()
+                    .filter(|delta| delta.sequence > last_sequence)
+                    .cloned()
+                    .collect(),
+            ),
+            _ => Backfill::ResyncRequired,
+        }
+    }
+}
+
+/// Nodes and edges keyed the way the tracker compares them: nodes by id,
+/// edges by `(from, to)`
+pub fn index_graph(nodes: Vec<Node>, edges: Vec<Edge>) -> (HashMap<String, Node>, HashMap<(String, String), Edge>) {
+    let nodes = nodes.into_iter().map(|n| (n.id.clone(), n)).collect();
+    let edges = edges.into_iter().map(|e| ((e.from.clone(), e.to.clone()), e)).collect();
+    (nodes, edges)
+}
+
+/// Differences between two static graph states, sorted by id
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GraphDiff {
+    pub nodes_added: Vec<Node>,
+    pub nodes_updated: Vec<Node>,
+    pub nodes_removed: Vec<String>,
+    pub edges_added: Vec<Edge>,
+    pub edges_updated: Vec<Edge>,
+    pub edges_removed: Vec<(String, String)>,
+}
+
+/// Compare `old` against `new`. Updated entries carry their new version.
+pub fn diff_graphs(
+    old_nodes: &HashMap<String, Node>,
+    old_edges: &HashMap<(String, String), Edge>,
+    new_nodes: &HashMap<String, Node>,
+    new_edges: &HashMap<(String, String), Edge>,
+) -> GraphDiff {
+    let mut diff = GraphDiff::default();
+    
+    // Find added and updated nodes
+    for (id, new_node) in new_nodes {
+        match old_nodes.get(id) {
+            Some(old_node) => {
+                if !nodes_equal(old_node, new_node) {
+                    diff.nodes_updated.push(new_node.clone());
+                }
+            }
+            None => {
+                diff.nodes_added.push(new_node.clone());
+            }
+        }
+    }
+    
+    // Find removed nodes
+    for id in old_nodes.keys() {
+        if !new_nodes.contains_key(id) {
+            diff.nodes_removed.push(id.clone());
+        }
+    }
+    
+    // Find added and updated edges
+    for (key, new_edge) in new_edges {
+        match old_edges.get(key) {
+            Some(old_edge) => {
+                if !edges_equal(old_edge, new_edge) {
+                    diff.edges_updated.push(new_edge.clone());
+                }
+            }
+            None => {
+                diff.edges_added.push(new_edge.clone());
+            }
+        }
+    }
+    
+    // Find removed edges
+    for key in old_edges.keys() {
+        if !new_edges.contains_key(key) {
+            diff.edges_removed.push(key.clone());
+        }
+    }
+    
+    diff.nodes_added.sort_by(|a, b| a.id.cmp(&b.id));
+    diff.nodes_updated.sort_by(|a, b| a.id.cmp(&b.id));
+    diff.nodes_removed.sort();
+    diff.edges_added.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+    diff.edges_updated.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+    diff.edges_removed.sort();
+    diff
+}
+
+// Combine two deltas, with `later` superseding `earlier` for the same node or edge
+fn merge_deltas(earlier: GraphDelta, later: GraphDelta) -> GraphDelta {
+    let mut nodes_added: Vec<Node> = earlier.nodes_added;
+    let mut nodes_updated: Vec<Node> = earlier.nodes_updated;
+    let mut nodes_removed: Vec<String> = earlier.nodes_removed;
+    
+    for node in later.nodes_added {
+        if nodes_removed.contains(&node.id) {
+            // Clients still hold the node removed earlier in the window
+            nodes_removed.retain(|id| id != &node.id);
+            upsert_by(&mut nodes_updated, node, |n| n.id.clone());
+        } else {
+            nodes_updated.retain(|n| n.id != node.id);
+            upsert_by(&mut nodes_added, node, |n| n.id.clone());
+        }
+    }
+    for node in later.nodes_updated {
+        // An update to a node added in this window is still an add
+        if let Some(existing) = nodes_added.iter_mut().find(|n| n.id == node.id) {
+            *existing = node;
+        } else {
+            upsert_by(&mut nodes_updated, node, |n| n.id.clone());
+        }
+    }
+    for id in later.nodes_removed {
+        // A node added and removed within the window never reached clients
+        let added_in_window = nodes_added.iter().any(|n| n.id == id);
+        nodes_added.retain(|n| n.id != id);
+        nodes_updated.retain(|n| n.id != id);
+        if !added_in_window && !nodes_removed.contains(&id) {
+            nodes_removed.push(id);
+        }
+    }
+    
+    let mut edges_added: Vec<Edge> = earlier.edges_added;
+    let mut edges_updated: Vec<Edge> = earlier.edges_updated;
+    let mut edges_removed: Vec<(String, String)> = earlier.edges_removed;
+    let edge_key = |e: &Edge| (e.from.clone(), e.to.clone());
+    
+    for edge in later.edges_added {
+        let key = edge_key(&edge);
+        if edges_removed.contains(&key) {
+            edges_removed.retain(|k| k != &key);
+            upsert_by(&mut edges_updated, edge, edge_key);
+        } else {
+            edges_updated.retain(|e| edge_key(e) != key);
+            upsert_by(&mut edges_added, edge, edge_key);
+        }
+    }
+    for edge in later.edges_updated {
+        let key = edge_key(&edge);
+        if let Some(existing) = edges_added.iter_mut().find(|e| edge_key(e) == key) {
+            *existing = edge;
+        } else {
+            upsert_by(&mut edges_updated, edge, edge_key);
+        }
+    }
+    for key in later.edges_removed {
+        let added_in_window = edges_added.iter().any(|e| edge_key(e) == key);
+        edges_added.retain(|e| edge_key(e) != key);
+        edges_updated.retain(|e| edge_key(e) != key);
+        if !added_in_window && !edges_removed.contains(&key) {
+            edges_removed.push(key);
+        }
+    }
+    
+    GraphDelta {
+        operation: match earlier.operation {
+            DeltaOperation::Initial => DeltaOperation::Initial,
+            _ => later.operation,
+        },
+        nodes_added,
+        nodes_updated,
+        nodes_removed,
+        edges_added,
+        edges_updated,
+        edges_removed,
+        timestamp: later.timestamp,
+        sequence: later.sequence,
+    }
+}
+
+fn upsert_by<T, K: PartialEq>(items: &mut Vec<T>, item: T, key: impl Fn(&T) -> K) {
+    let item_key = key(&item);
+    match items.iter_mut().find(|existing| key(existing) == item_key) {
+        Some(existing) => *existing = item,
+        None => items.push(item),
+    }
 }
 
 fn nodes_equal(a: &Node, b: &Node) -> bool {
@@ -238,4 +457,180 @@ fn properties_equal_meaningful(a: &HashMap<String, serde_json::Value>, b: &HashM
     }
     
     true
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_delta() -> GraphDelta {
+        GraphDelta {
+            operation: DeltaOperation::Update,
+            nodes_added: Vec::new(),
+            nodes_updated: Vec::new(),
+            nodes_removed: Vec::new(),
+            edges_added: Vec::new(),
+            edges_updated: Vec::new(),
+            edges_removed: Vec::new(),
+            timestamp: 0,
+            sequence: 0,
+        }
+    }
+
+    fn node(id: &str, label: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            label: label.to_string(),
+            node_type: "Entity".to_string(),
+            summary: None,
+            properties: HashMap::new(),
+        }
+    }
+
+    fn node_ids(nodes: &[Node]) -> Vec<&str> {
+        nodes.iter().map(|n| n.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_add_then_update_merges_into_one_add() {
+        let added = GraphDelta { nodes_added: vec![node("a", "first")], ..empty_delta() };
+        let updated = GraphDelta { nodes_updated: vec![node("a", "second")], ..empty_delta() };
+
+        let merged = merge_deltas(added, updated);
+        assert_eq!(node_ids(&merged.nodes_added), vec!["a"]);
+        assert_eq!(merged.nodes_added[0].label, "second");
+        assert!(merged.nodes_updated.is_empty());
+    }
+
+    #[test]
+    fn test_add_then_remove_cancels_out() {
+        let edge = Edge {
+            from: "a".to_string(),
+            to: "b".to_string(),
+            edge_type: "RELATES_TO".to_string(),
+            weight: 1.0,
+            properties: HashMap::new(),
+        };
+        let added = GraphDelta { nodes_added: vec![node("a", "a")], edges_added: vec![edge], ..empty_delta() };
+        let removed = GraphDelta {
+            nodes_removed: vec!["a".to_string()],
+            edges_removed: vec![("a".to_string(), "b".to_string())],
+            ..empty_delta()
+        };
+
+        let merged = merge_deltas(added, removed);
+        assert!(merged.nodes_added.is_empty());
+        assert!(merged.nodes_removed.is_empty());
+        assert!(merged.edges_added.is_empty());
+        assert!(merged.edges_removed.is_empty());
+    }
+
+    #[test]
+    fn test_remove_readd_remove_still_removes() {
+        let removed = || GraphDelta { nodes_removed: vec!["a".to_string()], ..empty_delta() };
+        let readded = GraphDelta { nodes_added: vec![node("a", "back")], ..empty_delta() };
+
+        let merged = merge_deltas(merge_deltas(removed(), readded), removed());
+        assert!(merged.nodes_added.is_empty());
+        assert!(merged.nodes_updated.is_empty());
+        assert_eq!(merged.nodes_removed, vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_flush_after_window_emits_one_merged_delta() {
+        let tracker = DeltaTracker::new().with_flush_interval(Duration::from_secs(60));
+        // Idle, so the first delta goes out at once
+        let first = tracker.coalesce(GraphDelta { nodes_added: vec![node("a", "a")], ..empty_delta() });
+        assert_eq!(first.map(|d| d.sequence), Some(1));
+
+        assert!(tracker.coalesce(GraphDelta { nodes_added: vec![node("b", "first")], ..empty_delta() }).is_none());
+        assert!(tracker.coalesce(GraphDelta { nodes_updated: vec![node("b", "second")], ..empty_delta() }).is_none());
+        assert!(tracker.coalesce(GraphDelta { nodes_added: vec![node("c", "c")], ..empty_delta() }).is_none());
+        assert_eq!(tracker.last_sequence(), 1);
+
+        let flushed = tracker.flush_pending().expect("nothing pending");
+        assert_eq!(flushed.sequence, 2);
+        assert_eq!(node_ids(&flushed.nodes_added), vec!["b", "c"]);
+        assert_eq!(flushed.nodes_added[0].label, "second");
+        assert!(tracker.flush_pending().is_none());
+    }
+
+    #[test]
+    fn test_concurrent_records_get_strictly_increasing_sequences() {
+        let tracker = DeltaTracker::new();
+        let emitters: Vec<_> = (0..8)
+            .map(|_| {
+                let tracker = tracker.clone();
+                std::thread::spawn(move || {
+                    (0..50).map(|_| tracker.record(empty_delta()).sequence).collect::<Vec<u64>>()
+                })
+            })
+            .collect();
+        let mut all = Vec::new();
+        for emitter in emitters {
+            let sequences = emitter.join().unwrap();
+            // Each emitter sees its own deltas numbered in order
+            assert!(sequences.windows(2).all(|pair| pair[0] < pair[1]));
+            all.extend(sequences);
+        }
+
+        all.sort_unstable();
+        assert_eq!(all, (1..=400).collect::<Vec<u64>>());
+        assert_eq!(tracker.last_sequence(), 400);
+
+        let latest = tracker.record(empty_delta());
+        assert_eq!(tracker.last_sequence(), latest.sequence);
+        assert_eq!(latest.sequence, 401);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_coalescing_keeps_history_in_sequence_order() {
+        let tracker = DeltaTracker::new().with_flush_interval(Duration::from_millis(1));
+        let emitters: Vec<_> = (0..8)
+            .map(|_| {
+                let tracker = tracker.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..50 {
+                        tracker.coalesce(empty_delta());
+                        tracker.flush_pending();
+                    }
+                })
+            })
+            .collect();
+        for emitter in emitters {
+            emitter.join().unwrap();
+        }
+
+        let history: Vec<u64> = tracker.get_changes_since(0, usize::MAX).await
+            .iter()
+            .map(|d| d.sequence)
+            .collect();
+        assert!(!history.is_empty());
+        assert!(history.windows(2).all(|pair| pair[1] == pair[0] + 1), "history out of order: {:?}", history);
+        assert_eq!(history.last().copied(), Some(tracker.last_sequence()));
+    }
+
+    fn sequences(backfill: Backfill) -> Option<Vec<u64>> {
+        match backfill {
+            Backfill::Deltas(deltas) => Some(deltas.iter().map(|d| d.sequence).collect()),
+            Backfill::ResyncRequired => None,
+        }
+    }
+
+    #[test]
+    fn test_backfill_replays_missed_deltas_while_history_holds_them() {
+        let tracker = DeltaTracker::new();
+        for _ in 0..105 {
+            tracker.record(empty_delta());
+        }
+        assert_eq!(tracker.last_sequence(), 105);
+
+        assert_eq!(sequences(tracker.backfill(105)), Some(vec![]));
+        assert_eq!(sequences(tracker.backfill(103)), Some(vec![104, 105]));
+        // The history of 100 starts at 6, so a client at 5 misses nothing
+        assert_eq!(sequences(tracker.backfill(5)), Some((6..=105).collect()));
+        assert_eq!(sequences(tracker.backfill(4)), None);
+        // A sequence from before a restart
+        assert_eq!(sequences(tracker.backfill(500)), None);
+    }
+}
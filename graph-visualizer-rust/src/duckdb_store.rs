@@ -1,31 +1,185 @@
 use anyhow::Result;
-use arrow::array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt32Array};
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray, UInt32Array};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow_schema::SchemaRef;
 use chrono::{DateTime, Utc};
 use duckdb::{params, Connection};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::{Edge, Node};
 
+/// Cluster strength for nodes whose membership can't be measured (no edges yet)
+const DEFAULT_CLUSTER_STRENGTH: f64 = 0.7;
+
 #[derive(Clone)]
 pub struct DuckDBStore {
     conn: Arc<Mutex<Connection>>,
     schema_nodes: SchemaRef,
     schema_edges: SchemaRef,
     update_queue: Arc<RwLock<UpdateQueue>>,
+    /// Held from queueing through processing so a caller's update only carries its own items
+    ingest_lock: Arc<tokio::sync::Mutex<()>>,
+    color_map: Arc<std::sync::RwLock<ColorMap>>,
+    edge_strengths: EdgeStrengths,
+    node_sizing: NodeSizing,
+    pending_edge_max_retries: u32,
+    pending_edge_stale: chrono::Duration,
+    /// Synthesized timestamps count back from here, or from now when unset
+    synthetic_timestamp_anchor: Option<DateTime<Utc>>,
+    dropped_edges: Arc<std::sync::atomic::AtomicU64>,
 }
 
-#[derive(Debug, Clone)]
-struct PendingEdge {
-    edge: Edge,
-    retry_count: u32,
-    first_seen: chrono::DateTime<Utc>,
-    last_retry: chrono::DateTime<Utc>,
+/// One bucket of the node growth timeseries
+#[derive(Debug, Clone, Serialize)]
+pub struct GrowthBucket {
+    /// Start of the bucket, `YYYY-MM-DD`
+    pub bucket: String,
+    pub node_count: u64,
+    /// Nodes created up to and including this bucket
+    pub cumulative: u64,
+}
+
+/// Aggregate centrality of the loaded nodes of one type
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeTypeStats {
+    pub node_type: String,
+    pub node_count: u64,
+    pub avg_degree: f64,
+    pub max_degree: f64,
+    pub avg_pagerank: f64,
+    pub max_pagerank: f64,
+    pub avg_betweenness: f64,
+    pub max_betweenness: f64,
+    pub avg_eigenvector: f64,
+    pub max_eigenvector: f64,
+}
+
+/// Custom node/edge colors by type, falling back to the built-in palette
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColorMap {
+    #[serde(default)]
+    pub nodes: HashMap<String, String>,
+    #[serde(default)]
+    pub edges: HashMap<String, String>,
+}
+
+impl ColorMap {
+    /// Load a color map from a JSON file of the form `{"nodes": {...}, "edges": {...}}`
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+/// Cosmograph link strength by edge type; unlisted types get `default`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeStrengths {
+    #[serde(default)]
+    pub strengths: HashMap<String, f64>,
+    #[serde(default = "default_edge_strength")]
+    pub default: f64,
+}
+
+fn default_edge_strength() -> f64 {
+    1.0
+}
+
+impl Default for EdgeStrengths {
+    fn default() -> Self {
+        let strengths = [
+            ("entity_entity", 1.5),  // Stronger Entity-Entity connections
+            ("relates_to", 1.5),
+            ("episodic", 0.5),  // Weaker Episodic connections
+            ("temporal", 0.5),
+            ("mentioned_in", 0.5),
+        ];
+        Self {
+            strengths: strengths.iter().map(|(t, s)| (t.to_string(), *s)).collect(),
+            default: default_edge_strength(),
+        }
+    }
+}
+
+impl EdgeStrengths {
+    /// Load `{"strengths": {...}, "default": 1.0}` from a JSON file; listed
+    /// types override the built-in table. Strengths must be finite and not negative.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let custom: Self = serde_json::from_str(&contents)?;
+        let listed = custom.strengths.iter().map(|(edge_type, strength)| (edge_type.as_str(), *strength));
+        for (edge_type, strength) in listed.chain([("default", custom.default)]) {
+            if !strength.is_finite() || strength < 0.0 {
+                anyhow::bail!("Invalid strength {} for edge type {}: must be a non-negative number", strength, edge_type);
+            }
+        }
+        let mut strengths = Self::default();
+        strengths.strengths.extend(custom.strengths);
+        strengths.default = custom.default;
+        Ok(strengths)
+    }
+    
+    pub fn strength(&self, edge_type: &str) -> f64 {
+        self.strengths.get(edge_type).copied().unwrap_or(self.default)
+    }
+}
+
+/// Node size as `base + degree * degree_multiplier`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct NodeSize {
+    #[serde(default = "default_node_base_size")]
+    pub base: f64,
+    #[serde(default = "default_node_degree_multiplier")]
+    pub degree_multiplier: f64,
+}
+
+fn default_node_base_size() -> f64 {
+    4.0
+}
+
+fn default_node_degree_multiplier() -> f64 {
+    20.0
+}
+
+impl Default for NodeSize {
+    fn default() -> Self {
+        Self { base: default_node_base_size(), degree_multiplier: default_node_degree_multiplier() }
+    }
+}
+
+/// Node sizing by node type; unlisted types use `default`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeSizing {
+    #[serde(default)]
+    pub types: HashMap<String, NodeSize>,
+    #[serde(default)]
+    pub default: NodeSize,
+}
+
+impl NodeSizing {
+    /// Load `{"types": {"Episodic": {"base": 2.0, "degree_multiplier": 5.0}}, "default": {...}}`
+    /// from a JSON file
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+    
+    pub fn size(&self, node_type: &str, degree: f64) -> f64 {
+        let sizing = self.types.get(node_type).unwrap_or(&self.default);
+        sizing.base + degree * sizing.degree_multiplier
+    }
+}
+
+/// Edge waiting for one of its endpoint nodes to arrive
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingEdge {
+    pub edge: Edge,
+    pub retry_count: u32,
+    pub first_seen: chrono::DateTime<Utc>,
+    pub last_retry: chrono::DateTime<Utc>,
 }
 
 #[derive(Default)]
@@ -44,6 +198,16 @@ pub struct GraphUpdate {
     pub timestamp: u64,
 }
 
+/// Fresh scores for one node; a metric left `None` keeps its stored value
+#[derive(Debug, Clone)]
+pub struct CentralityScores {
+    pub id: String,
+    pub degree: Option<f64>,
+    pub pagerank: Option<f64>,
+    pub betweenness: Option<f64>,
+    pub eigenvector: Option<f64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum UpdateOperation {
@@ -77,6 +241,7 @@ impl DuckDBStore {
             Field::new("created_at_timestamp", DataType::Float64, true), // For timeline
             Field::new("cluster", DataType::Utf8, true), // For clustering
             Field::new("clusterStrength", DataType::Float64, true), // Clustering strength
+            Field::new("created_at_synthetic", DataType::Boolean, false), // Timestamp was synthesized
         ]));
         
         // Create edge schema for Arrow
@@ -89,6 +254,7 @@ impl DuckDBStore {
             Field::new("weight", DataType::Float64, false),
             Field::new("color", DataType::Utf8, true),
             Field::new("strength", DataType::Float64, true), // Link strength for Cosmograph
+            Field::new("properties", DataType::Utf8, true), // Edge properties as a JSON object
         ]));
         
         // Create tables
@@ -110,7 +276,8 @@ impl DuckDBStore {
                 created_at VARCHAR,              -- ISO string
                 created_at_timestamp DOUBLE,     -- milliseconds since epoch
                 cluster VARCHAR,
-                clusterStrength DOUBLE
+                clusterStrength DOUBLE,
+                created_at_synthetic BOOLEAN NOT NULL DEFAULT FALSE
             )",
             params![],
         )?;
@@ -125,11 +292,44 @@ impl DuckDBStore {
                 weight DOUBLE NOT NULL DEFAULT 1.0,
                 color VARCHAR,
                 strength DOUBLE DEFAULT 1.0,
+                properties VARCHAR,              -- JSON object
                 PRIMARY KEY (source, target, edge_type)
             )",
             params![],
         )?;
         
+        // Named copies of the graph for /api/diff, replaced when a name is reused
+        conn.execute(
+            "CREATE TABLE snapshots (
+                name VARCHAR PRIMARY KEY,
+                created_at VARCHAR NOT NULL
+            )",
+            params![],
+        )?;
+        conn.execute(
+            "CREATE TABLE snapshot_nodes (
+                snapshot VARCHAR NOT NULL,
+                id VARCHAR NOT NULL,
+                label VARCHAR NOT NULL,
+                node_type VARCHAR NOT NULL,
+                summary VARCHAR,
+                PRIMARY KEY (snapshot, id)
+            )",
+            params![],
+        )?;
+        conn.execute(
+            "CREATE TABLE snapshot_edges (
+                snapshot VARCHAR NOT NULL,
+                source VARCHAR NOT NULL,
+                target VARCHAR NOT NULL,
+                edge_type VARCHAR NOT NULL,
+                weight DOUBLE NOT NULL,
+                properties VARCHAR,
+                PRIMARY KEY (snapshot, source, target, edge_type)
+            )",
+            params![],
+        )?;
+        
         // Migration: Try to add created_at column if it doesn't exist (for existing databases)
         // This is a best-effort migration - if it fails, we assume the column already exists
         let _ = conn.execute("ALTER TABLE nodes ADD COLUMN created_at VARCHAR", params![]);
@@ -147,27 +347,120 @@ impl DuckDBStore {
             schema_nodes,
             schema_edges,
             update_queue: Arc::new(RwLock::new(UpdateQueue::default())),
+            ingest_lock: Arc::new(tokio::sync::Mutex::new(())),
+            color_map: Arc::new(std::sync::RwLock::new(ColorMap::default())),
+            edge_strengths: EdgeStrengths::default(),
+            node_sizing: NodeSizing::default(),
+            pending_edge_max_retries: 10,
+            pending_edge_stale: chrono::Duration::minutes(5),
+            synthetic_timestamp_anchor: None,
+            dropped_edges: Arc::new(std::sync::atomic::AtomicU64::new(0)),
         })
     }
     
+    /// Override how long edges with unknown endpoints are retried before being dropped
+    pub fn with_pending_edge_limits(mut self, max_retries: u32, stale_secs: i64) -> Self {
+        self.pending_edge_max_retries = max_retries;
+        self.pending_edge_stale = chrono::Duration::seconds(stale_secs);
+        self
+    }
+    
+    /// Replace the per-type size function used for loaded and added nodes
+    pub fn with_node_sizing(mut self, node_sizing: NodeSizing) -> Self {
+        self.node_sizing = node_sizing;
+        self
+    }
+    
+    /// Replace the link strength table used for new edges
+    pub fn with_edge_strengths(mut self, edge_strengths: EdgeStrengths) -> Self {
+        self.edge_strengths = edge_strengths;
+        self
+    }
+    
+    /// Anchor synthesized timestamps: a node without `created_at` is dated `idx`
+    /// days before `anchor`, or before the time it's loaded when `None`
+    pub fn with_synthetic_timestamp_anchor(mut self, anchor: Option<DateTime<Utc>>) -> Self {
+        self.synthetic_timestamp_anchor = anchor;
+        self
+    }
+    
+    fn synthetic_timestamp(&self, idx: u32) -> f64 {
+        let anchor = self.synthetic_timestamp_anchor.unwrap_or_else(Utc::now);
+        (anchor - chrono::Duration::days(idx as i64)).timestamp_millis() as f64
+    }
+    
+    /// Number of pending edges dropped since startup
+    pub fn dropped_edge_count(&self) -> u64 {
+        self.dropped_edges.load(std::sync::atomic::Ordering::Relaxed)
+    }
+    
+    pub fn get_color_map(&self) -> ColorMap {
+        self.color_map.read().unwrap().clone()
+    }
+    
+    /// Merge custom colors into the color map and re-color the stored nodes/edges
+    pub async fn set_color_map(&self, colors: ColorMap) -> Result<ColorMap> {
+        {
+            let mut color_map = self.color_map.write().unwrap();
+            color_map.nodes.extend(colors.nodes.clone());
+            color_map.edges.extend(colors.edges.clone());
+        }
+        
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for (node_type, color) in &colors.nodes {
+            tx.execute("UPDATE nodes SET color = ? WHERE node_type = ?", params![color, node_type])?;
+        }
+        for (edge_type, color) in &colors.edges {
+            tx.execute("UPDATE edges SET color = ? WHERE edge_type = ?", params![color, edge_type])?;
+        }
+        tx.commit()?;
+        
+        info!("Color map updated: {} node types, {} edge types", colors.nodes.len(), colors.edges.len());
+        Ok(self.get_color_map())
+    }
+    
     pub async fn load_initial_data(&self, nodes: Vec<Node>, edges: Vec<Edge>) -> Result<()> {
         info!("Loading initial data: {} nodes, {} edges", nodes.len(), edges.len());
         
         let mut conn = self.conn.lock().unwrap();
         let tx = conn.transaction()?;
         
+        // Nodes we already hold keep their index across reloads so client-side
+        // idx references stay valid mid-session; only unseen nodes get new ones
+        let known_indices: HashMap<String, u32> = {
+            let mut stmt = tx.prepare("SELECT id, idx FROM nodes")?;
+            let rows = stmt.query_map(params![], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
+            })?;
+            rows.collect::<duckdb::Result<_>>()?
+        };
+        let mut next_idx = known_indices.values().max().map_or(0, |max| max + 1);
+        
+        // Replace the previous contents atomically so deleted entities disappear
+        tx.execute("DELETE FROM edges", params![])?;
+        tx.execute("DELETE FROM nodes", params![])?;
+        
         // Insert nodes with indices - use INSERT OR REPLACE to handle duplicates
-        let stmt_node = "INSERT OR REPLACE INTO nodes (id, idx, label, node_type, summary, degree_centrality, pagerank_centrality, betweenness_centrality, eigenvector_centrality, x, y, color, size, created_at, created_at_timestamp, cluster, clusterStrength) 
-                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+        let stmt_node = "INSERT OR REPLACE INTO nodes (id, idx, label, node_type, summary, degree_centrality, pagerank_centrality, betweenness_centrality, eigenvector_centrality, x, y, color, size, created_at, created_at_timestamp, cluster, clusterStrength, created_at_synthetic) 
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
         
         let mut node_to_idx = HashMap::new();
+        let cluster_strength_by_id = cluster_strengths(&nodes, &edges);
         
-        // Sort nodes by UUID for deterministic index assignment
-        // This ensures the same UUID always gets the same index across reloads
+        // Sort nodes by UUID so new nodes are numbered deterministically
         let mut sorted_nodes = nodes.clone();
         sorted_nodes.sort_by(|a, b| a.id.cmp(&b.id));
         
-        for (idx, node) in sorted_nodes.iter().enumerate() {
+        for node in sorted_nodes.iter() {
+            let idx = match known_indices.get(&node.id) {
+                Some(&idx) => idx,
+                None => {
+                    next_idx += 1;
+                    next_idx - 1
+                }
+            };
+            
             let degree = node.properties.get("degree_centrality")
                 .and_then(|v| v.as_f64())
                 .unwrap_or(0.0);
@@ -195,37 +488,38 @@ impl DuckDBStore {
             };
             
             let color = self.get_node_color(&node.node_type);
-            let size = 4.0 + (degree * 20.0); // Size based on centrality
+            let size = self.node_sizing.size(&node.node_type, degree); // Size based on centrality
             
-            // Default clustering by node_type with strength 0.7
+            // Cluster by node_type, pulled in proportion to how many edges stay in-cluster
             let cluster = node.node_type.clone();
-            let cluster_strength = 0.7;
+            let cluster_strength = cluster_strength_by_id.get(&node.id).copied()
+                .unwrap_or(DEFAULT_CLUSTER_STRENGTH);
             
             // Compute both created_at string and timestamp
-            let (created_str, timestamp) = if let Some(created_str) = node.properties.get("created_at")
+            let (created_str, timestamp, synthetic) = if let Some(created_str) = node.properties.get("created_at")
                 .and_then(|v| v.as_str()) {
                 // Have string, compute timestamp
-                let ts = DateTime::parse_from_rfc3339(created_str)
-                    .map(|dt| dt.timestamp_millis() as f64)
-                    .unwrap_or_else(|e| {
+                match DateTime::parse_from_rfc3339(created_str) {
+                    Ok(dt) => (created_str.to_string(), dt.timestamp_millis() as f64, false),
+                    Err(e) => {
                         warn!("Failed to parse created_at '{}': {}", created_str, e);
-                        (idx as f64) * 86400000.0 // Fallback to synthetic
-                    });
-                (created_str.to_string(), ts)
+                        (created_str.to_string(), self.synthetic_timestamp(idx), true) // Fallback to synthetic
+                    }
+                }
             } else {
                 // No string, synthesize both
                 debug!("Node {} has no created_at, synthesizing both fields", node.id);
-                let ts = (idx as f64) * 86400000.0; // Synthetic timestamp
+                let ts = self.synthetic_timestamp(idx); // Synthetic timestamp
                 let dt = DateTime::<Utc>::from_timestamp_millis(ts as i64)
                     .unwrap_or_else(|| Utc::now());
-                (dt.to_rfc3339(), ts)
+                (dt.to_rfc3339(), ts, true)
             };
             
             tx.execute(
                 stmt_node,
                 params![
                     &node.id,
-                    idx as u32,
+                    idx,
                     &node.label,
                     &node.node_type,
                     &node.summary,
@@ -240,29 +534,24 @@ impl DuckDBStore {
                     &created_str,      // created_at string
                     timestamp,         // created_at_timestamp
                     cluster,
-                    cluster_strength
+                    cluster_strength,
+                    synthetic          // created_at_synthetic
                 ],
             )?;
             
-            node_to_idx.insert(node.id.clone(), idx as u32);
+            node_to_idx.insert(node.id.clone(), idx);
         }
         
         // Insert edges with indices
-        let stmt_edge = "INSERT OR IGNORE INTO edges (source, sourceidx, target, targetidx, edge_type, weight, color, strength) 
-                         VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
+        let stmt_edge = "INSERT OR IGNORE INTO edges (source, sourceidx, target, targetidx, edge_type, weight, color, strength, properties) 
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)";
         
         for edge in edges.iter() {
             if let (Some(&source_idx), Some(&target_idx)) = 
                 (node_to_idx.get(&edge.from), node_to_idx.get(&edge.to)) {
                 
                 let color = self.get_edge_color(&edge.edge_type);
-                
-                // Calculate link strength based on edge type
-                let strength = match edge.edge_type.as_str() {
-                    "entity_entity" | "relates_to" => 1.5,  // Stronger Entity-Entity connections
-                    "episodic" | "temporal" | "mentioned_in" => 0.5,  // Weaker Episodic connections
-                    _ => 1.0,  // Default strength
-                };
+                let strength = self.edge_strengths.strength(&edge.edge_type);
                 
                 tx.execute(
                     stmt_edge,
@@ -274,7 +563,8 @@ impl DuckDBStore {
                         &edge.edge_type,
                         edge.weight,
                         color,
-                        strength
+                        strength,
+                        properties_to_json(&edge.properties)
                     ],
                 )?;
             }
@@ -286,14 +576,94 @@ impl DuckDBStore {
         Ok(())
     }
     
+    /// Nodes and edges currently stored that are absent from a fresh snapshot,
+    /// i.e. what a reload with this data would delete
+    pub async fn find_removed(&self, nodes: &[Node], edges: &[Edge]) -> Result<(Vec<Node>, Vec<Edge>)> {
+        let new_node_ids: std::collections::HashSet<&str> = nodes.iter().map(|n| n.id.as_str()).collect();
+        let new_edge_keys: std::collections::HashSet<(&str, &str, &str)> = edges.iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.edge_type.as_str()))
+            .collect();
+        
+        let (removed_ids, removed_edges) = {
+            let conn = self.conn.lock().unwrap();
+            
+            let mut stmt = conn.prepare("SELECT id FROM nodes")?;
+            let mut removed_ids = Vec::new();
+            for id in stmt.query_map(params![], |row| row.get::<_, String>(0))? {
+                let id = id?;
+                if !new_node_ids.contains(id.as_str()) {
+                    removed_ids.push(id);
+                }
+            }
+            
+            let mut stmt = conn.prepare("SELECT source, target, edge_type, weight, properties FROM edges")?;
+            let mut removed_edges = Vec::new();
+            let rows = stmt.query_map(params![], |row| {
+                Ok(Edge {
+                    from: row.get(0)?,
+                    to: row.get(1)?,
+                    edge_type: row.get(2)?,
+                    weight: row.get(3)?,
+                    properties: properties_from_json(row.get(4)?),
+                })
+            })?;
+            for edge in rows {
+                let edge = edge?;
+                if !new_edge_keys.contains(&(edge.from.as_str(), edge.to.as_str(), edge.edge_type.as_str())) {
+                    removed_edges.push(edge);
+                }
+            }
+            
+            (removed_ids, removed_edges)
+        };
+        
+        let removed_nodes = self.get_nodes_by_ids(&removed_ids).await?;
+        Ok((removed_nodes, removed_edges))
+    }
+    
+    pub fn nodes_schema(&self) -> SchemaRef {
+        self.schema_nodes.clone()
+    }
+    
     pub async fn get_nodes_as_arrow(&self) -> Result<RecordBatch> {
+        self.query_nodes_as_arrow(&[], None)
+    }
+    
+    /// Arrow batch holding only the given nodes, in idx order
+    pub async fn get_nodes_as_arrow_by_ids(&self, ids: &[String]) -> Result<RecordBatch> {
+        if ids.is_empty() {
+            return Ok(RecordBatch::new_empty(self.schema_nodes.clone()));
+        }
+        self.query_nodes_as_arrow(ids, None)
+    }
+    
+    /// One page of the nodes in idx order, for streaming large exports
+    pub async fn get_nodes_as_arrow_page(&self, offset: usize, limit: usize) -> Result<RecordBatch> {
+        self.query_nodes_as_arrow(&[], Some((offset, limit)))
+    }
+    
+    // An empty id list selects every node; page is (offset, limit)
+    fn query_nodes_as_arrow(&self, ids: &[String], page: Option<(usize, usize)>) -> Result<RecordBatch> {
         let conn = self.conn.lock().unwrap();
         
-        let mut stmt = conn.prepare(
-            "SELECT id, idx, label, node_type, summary, degree_centrality, pagerank_centrality, betweenness_centrality, eigenvector_centrality, x, y, color, size, created_at, created_at_timestamp, cluster, clusterStrength 
+        let filter = if ids.is_empty() {
+            String::new()
+        } else {
+            let placeholders: Vec<String> = (1..=ids.len()).map(|i| format!("${}", i)).collect();
+            format!("WHERE id IN ({})", placeholders.join(", "))
+        };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, idx, label, node_type, summary, degree_centrality, pagerank_centrality, betweenness_centrality, eigenvector_centrality, x, y, color, size, created_at, created_at_timestamp, cluster, clusterStrength, created_at_synthetic 
              FROM nodes 
-             ORDER BY idx"
-        )?;
+             {}
+             ORDER BY idx
+             {}",
+            filter,
+            page.map(|(offset, limit)| format!("LIMIT {} OFFSET {}", limit, offset)).unwrap_or_default()
+        ))?;
+        let params: Vec<&dyn duckdb::ToSql> = ids.iter()
+            .map(|id| id as &dyn duckdb::ToSql)
+            .collect();
         
         let mut ids = Vec::new();
         let mut indices = Vec::new();
@@ -312,8 +682,9 @@ impl DuckDBStore {
         let mut timestamps = Vec::new();      // Numeric timestamps
         let mut clusters = Vec::new();
         let mut cluster_strengths = Vec::new();
+        let mut synthetics = Vec::new();
         
-        let rows = stmt.query_map(params![], |row| {
+        let rows = stmt.query_map(&params[..], |row| {
             Ok((
                 row.get::<_, String>(0)?,     // id
                 row.get::<_, u32>(1)?,        // idx
@@ -332,11 +703,12 @@ impl DuckDBStore {
                 row.get::<_, Option<f64>>(14)?,   // created_at_timestamp
                 row.get::<_, Option<String>>(15)?, // cluster
                 row.get::<_, Option<f64>>(16)?,   // clusterStrength
+                row.get::<_, bool>(17)?,          // created_at_synthetic
             ))
         })?;
         
         for row in rows {
-            let (id, idx, label, node_type, summary, degree, pagerank, betweenness, eigenvector, x, y, color, size, created_at, timestamp, cluster, cluster_strength) = row?;
+            let (id, idx, label, node_type, summary, degree, pagerank, betweenness, eigenvector, x, y, color, size, created_at, timestamp, cluster, cluster_strength, synthetic) = row?;
             ids.push(id);
             indices.push(idx);
             labels.push(label);
@@ -354,6 +726,7 @@ impl DuckDBStore {
             timestamps.push(timestamp);
             clusters.push(cluster);
             cluster_strengths.push(cluster_strength);
+            synthetics.push(synthetic);
         }
         
         let batch = RecordBatch::try_new(
@@ -376,6 +749,7 @@ impl DuckDBStore {
                 Arc::new(Float64Array::from(timestamps)) as ArrayRef,     // created_at_timestamp
                 Arc::new(StringArray::from(clusters)) as ArrayRef,
                 Arc::new(Float64Array::from(cluster_strengths)) as ArrayRef,
+                Arc::new(BooleanArray::from(synthetics)) as ArrayRef,
             ],
         )?;
         
@@ -405,7 +779,7 @@ impl DuckDBStore {
         
         // Now get edges and recalculate indices based on current node positions
         let mut stmt = conn.prepare(
-            "SELECT e.source, e.target, e.edge_type, e.weight, e.color, e.strength 
+            "SELECT e.source, e.target, e.edge_type, e.weight, e.color, e.strength, e.properties 
              FROM edges e
              INNER JOIN nodes n1 ON e.source = n1.id
              INNER JOIN nodes n2 ON e.target = n2.id"
@@ -419,6 +793,7 @@ impl DuckDBStore {
         let mut weights = Vec::new();
         let mut colors = Vec::new();
         let mut strengths = Vec::new();
+        let mut properties = Vec::new();
         
         let rows = stmt.query_map(params![], |row| {
             Ok((
@@ -428,11 +803,12 @@ impl DuckDBStore {
                 row.get::<_, f64>(3)?,        // weight
                 row.get::<_, Option<String>>(4)?, // color
                 row.get::<_, Option<f64>>(5)?, // strength
+                row.get::<_, Option<String>>(6)?, // properties
             ))
         })?;
         
         for row in rows {
-            let (source, target, edge_type, weight, color, strength) = row?;
+            let (source, target, edge_type, weight, color, strength, props) = row?;
             
             // Look up the actual indices based on current node positions
             if let (Some(&source_idx), Some(&target_idx)) = 
@@ -445,6 +821,69 @@ impl DuckDBStore {
                 weights.push(weight);
                 colors.push(color);
                 strengths.push(strength.unwrap_or(1.0));
+                properties.push(props);
+            }
+        }
+        
+        let batch = RecordBatch::try_new(
+            self.schema_edges.clone(),
+            vec![
+                Arc::new(StringArray::from(sources)) as ArrayRef,
+                Arc::new(UInt32Array::from(source_indices)) as ArrayRef,
+                Arc::new(StringArray::from(targets)) as ArrayRef,
+                Arc::new(UInt32Array::from(target_indices)) as ArrayRef,
+                Arc::new(StringArray::from(edge_types)) as ArrayRef,
+                Arc::new(Float64Array::from(weights)) as ArrayRef,
+                Arc::new(StringArray::from(colors)) as ArrayRef,
+                Arc::new(Float64Array::from(strengths)) as ArrayRef,
+                Arc::new(StringArray::from(properties)) as ArrayRef,
+            ],
+        )?;
+        
+        Ok(batch)
+    }
+    
+    /// Arrow batch for specific edges, using the supplied node positions for
+    /// sourceidx/targetidx. Edges missing from DuckDB or the map are skipped.
+    pub async fn get_edges_as_arrow_for(&self, edges: &[&Edge], node_positions: &HashMap<String, u32>) -> Result<RecordBatch> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT weight, color, strength, properties FROM edges WHERE source = ? AND target = ? AND edge_type = ?"
+        )?;
+        
+        let mut sources = Vec::new();
+        let mut source_indices = Vec::new();
+        let mut targets = Vec::new();
+        let mut target_indices = Vec::new();
+        let mut edge_types = Vec::new();
+        let mut weights = Vec::new();
+        let mut colors = Vec::new();
+        let mut strengths = Vec::new();
+        let mut properties = Vec::new();
+        
+        for edge in edges {
+            let (Some(&source_idx), Some(&target_idx)) =
+                (node_positions.get(&edge.from), node_positions.get(&edge.to)) else {
+                continue;
+            };
+            let row = stmt.query_row(params![&edge.from, &edge.to, &edge.edge_type], |row| {
+                Ok((
+                    row.get::<_, f64>(0)?,            // weight
+                    row.get::<_, Option<String>>(1)?, // color
+                    row.get::<_, Option<f64>>(2)?,    // strength
+                    row.get::<_, Option<String>>(3)?, // properties
+                ))
+            });
+            if let Ok((weight, color, strength, props)) = row {
+                sources.push(edge.from.clone());
+                source_indices.push(source_idx);
+                targets.push(edge.to.clone());
+                target_indices.push(target_idx);
+                edge_types.push(edge.edge_type.clone());
+                weights.push(weight);
+                colors.push(color);
+                strengths.push(strength.unwrap_or(1.0));
+                properties.push(props);
             }
         }
         
@@ -459,6 +898,7 @@ impl DuckDBStore {
                 Arc::new(Float64Array::from(weights)) as ArrayRef,
                 Arc::new(StringArray::from(colors)) as ArrayRef,
                 Arc::new(Float64Array::from(strengths)) as ArrayRef,
+                Arc::new(StringArray::from(properties)) as ArrayRef,
             ],
         )?;
         
@@ -480,7 +920,34 @@ impl DuckDBStore {
         queue.edges_to_add.extend(edges);
     }
     
+    /// Edges currently buffered because their source or target is unknown
+    pub async fn get_pending_edges(&self) -> Vec<PendingEdge> {
+        self.update_queue.read().await.pending_edges.clone()
+    }
+    
+    /// Queue and process `nodes` and `edges` as one unit.
+    ///
+    /// No other caller can queue or drain in between, so the returned update
+    /// holds exactly these items plus any buffered edges that became resolvable.
+    pub async fn apply_updates(&self, nodes: Vec<Node>, edges: Vec<Edge>) -> Result<Option<GraphUpdate>> {
+        let _guard = self.ingest_lock.lock().await;
+        
+        if !nodes.is_empty() {
+            self.queue_nodes(nodes).await;
+        }
+        if !edges.is_empty() {
+            self.queue_edges(edges).await;
+        }
+        
+        self.drain_queue().await
+    }
+    
     pub async fn process_updates(&self) -> Result<Option<GraphUpdate>> {
+        let _guard = self.ingest_lock.lock().await;
+        self.drain_queue().await
+    }
+    
+    async fn drain_queue(&self) -> Result<Option<GraphUpdate>> {
         let mut queue = self.update_queue.write().await;
         
         if queue.nodes_to_add.is_empty() && 
@@ -538,25 +1005,23 @@ impl DuckDBStore {
                     .unwrap_or(0.0);
                 
                 let color = self.get_node_color(&node.node_type);
-                let size = 4.0 + (degree * 20.0);
+                let size = self.node_sizing.size(&node.node_type, degree);
                 
-                // Default clustering by node_type with strength 0.7
+                // Cluster by node_type; strength is recomputed from edges on the next full load
                 let cluster = node.node_type.clone();
-                let cluster_strength = 0.7;
+                let cluster_strength = DEFAULT_CLUSTER_STRENGTH;
                 
                 // Parse real timestamp from created_at if available
-                let timestamp = if let Some(created_str) = node.properties.get("created_at")
-                    .and_then(|v| v.as_str()) {
-                    DateTime::parse_from_rfc3339(created_str)
-                        .map(|dt| dt.timestamp_millis() as f64)
-                        .unwrap_or((start_idx as f64) * 86400000.0)
-                } else {
-                    (start_idx as f64) * 86400000.0
-                };
+                let parsed = node.properties.get("created_at")
+                    .and_then(|v| v.as_str())
+                    .and_then(|created_str| DateTime::parse_from_rfc3339(created_str).ok())
+                    .map(|dt| dt.timestamp_millis() as f64);
+                let synthetic = parsed.is_none();
+                let timestamp = parsed.unwrap_or_else(|| self.synthetic_timestamp(start_idx));
 
                 tx.execute(
-                    "INSERT OR REPLACE INTO nodes (id, idx, label, node_type, summary, degree_centrality, pagerank_centrality, betweenness_centrality, eigenvector_centrality, x, y, color, size, created_at_timestamp, cluster, clusterStrength) 
-                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    "INSERT OR REPLACE INTO nodes (id, idx, label, node_type, summary, degree_centrality, pagerank_centrality, betweenness_centrality, eigenvector_centrality, x, y, color, size, created_at_timestamp, cluster, clusterStrength, created_at_synthetic) 
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                     params![
                         &node.id,
                         start_idx,
@@ -573,7 +1038,8 @@ impl DuckDBStore {
                         size,
                         timestamp,
                         cluster,
-                        cluster_strength
+                        cluster_strength,
+                        synthetic
                     ],
                 )?;
                 
@@ -605,6 +1071,8 @@ impl DuckDBStore {
         
         // Process new edges with validation
         let mut validated_edges = Vec::new();
+        // Edges buffered by this call are first retried by the next one
+        let mut newly_pending = Vec::new();
         let now = Utc::now();
         
         if !queue.edges_to_add.is_empty() {
@@ -628,17 +1096,11 @@ impl DuckDBStore {
                 
                 if let (Some(src_idx), Some(tgt_idx)) = (source_idx, target_idx) {
                     let color = self.get_edge_color(&edge.edge_type);
-                    
-                    // Calculate link strength based on edge type
-                    let strength = match edge.edge_type.as_str() {
-                        "entity_entity" | "relates_to" => 1.5,  // Stronger Entity-Entity connections
-                        "episodic" | "temporal" | "mentioned_in" => 0.5,  // Weaker Episodic connections
-                        _ => 1.0,  // Default strength
-                    };
+                    let strength = self.edge_strengths.strength(&edge.edge_type);
                     
                     tx.execute(
-                        "INSERT OR IGNORE INTO edges (source, sourceidx, target, targetidx, edge_type, weight, color, strength) 
-                         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                        "INSERT OR IGNORE INTO edges (source, sourceidx, target, targetidx, edge_type, weight, color, strength, properties) 
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
                         params![
                             &edge.from,
                             src_idx,
@@ -647,7 +1109,8 @@ impl DuckDBStore {
                             &edge.edge_type,
                             edge.weight,
                             color,
-                            strength
+                            strength,
+                            properties_to_json(&edge.properties)
                         ],
                     )?;
                     
@@ -656,7 +1119,7 @@ impl DuckDBStore {
                     // Buffer edge for later retry
                     warn!("Edge references unknown nodes, buffering: {} -> {} (source_idx: {:?}, target_idx: {:?})",
                           edge.from, edge.to, source_idx, target_idx);
-                    queue.pending_edges.push(PendingEdge {
+                    newly_pending.push(PendingEdge {
                         edge: edge.clone(),
                         retry_count: 0,
                         first_seen: now,
@@ -671,21 +1134,25 @@ impl DuckDBStore {
             debug!("Processing {} pending edges", queue.pending_edges.len());
             
             let mut still_pending = Vec::new();
-            let max_retries = 10;
-            let stale_threshold = chrono::Duration::minutes(5);
+            let max_retries = self.pending_edge_max_retries;
+            let stale_threshold = self.pending_edge_stale;
             
             for mut pending in queue.pending_edges.drain(..) {
                 // Check if edge is too old
                 if now.signed_duration_since(pending.first_seen) > stale_threshold {
-                    warn!("Dropping stale pending edge after 5 minutes: {} -> {}", 
-                          pending.edge.from, pending.edge.to);
+                    warn!("Dropping stale pending edge after {}s: {} -[{}]-> {} (weight: {}, first_seen: {}, retries: {})", 
+                          stale_threshold.num_seconds(), pending.edge.from, pending.edge.edge_type, pending.edge.to,
+                          pending.edge.weight, pending.first_seen.to_rfc3339(), pending.retry_count);
+                    self.dropped_edges.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                     continue;
                 }
                 
                 // Check if max retries exceeded
                 if pending.retry_count >= max_retries {
-                    warn!("Dropping pending edge after {} retries: {} -> {}", 
-                          max_retries, pending.edge.from, pending.edge.to);
+                    warn!("Dropping pending edge after {} retries: {} -[{}]-> {} (weight: {}, first_seen: {})", 
+                          max_retries, pending.edge.from, pending.edge.edge_type, pending.edge.to,
+                          pending.edge.weight, pending.first_seen.to_rfc3339());
+                    self.dropped_edges.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                     continue;
                 }
                 
@@ -704,17 +1171,11 @@ impl DuckDBStore {
                 
                 if let (Some(src_idx), Some(tgt_idx)) = (source_idx, target_idx) {
                     let color = self.get_edge_color(&pending.edge.edge_type);
-                    
-                    // Calculate link strength based on edge type
-                    let strength = match pending.edge.edge_type.as_str() {
-                        "entity_entity" | "relates_to" => 1.5,  // Stronger Entity-Entity connections
-                        "episodic" | "temporal" | "mentioned_in" => 0.5,  // Weaker Episodic connections
-                        _ => 1.0,  // Default strength
-                    };
+                    let strength = self.edge_strengths.strength(&pending.edge.edge_type);
                     
                     tx.execute(
-                        "INSERT OR IGNORE INTO edges (source, sourceidx, target, targetidx, edge_type, weight, color, strength) 
-                         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                        "INSERT OR IGNORE INTO edges (source, sourceidx, target, targetidx, edge_type, weight, color, strength, properties) 
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
                         params![
                             &pending.edge.from,
                             src_idx,
@@ -723,7 +1184,8 @@ impl DuckDBStore {
                             &pending.edge.edge_type,
                             pending.edge.weight,
                             color,
-                            strength
+                            strength,
+                            properties_to_json(&pending.edge.properties)
                         ],
                     )?;
                     
@@ -745,6 +1207,7 @@ impl DuckDBStore {
                 debug!("{} edges still pending", queue.pending_edges.len());
             }
         }
+        queue.pending_edges.extend(newly_pending);
         
         // Only set edges in update if we have validated edges
         if !validated_edges.is_empty() {
@@ -764,6 +1227,44 @@ impl DuckDBStore {
         Ok(Some(update))
     }
     
+    /// Overwrite stored centrality scores, resizing nodes by their type and new degree.
+    /// Returns the number of nodes that were present and updated.
+    pub async fn update_centrality_scores(&self, scores: &[CentralityScores]) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut updated = 0;
+        
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE nodes SET degree_centrality = COALESCE(?, degree_centrality), pagerank_centrality = COALESCE(?, pagerank_centrality), \
+                 betweenness_centrality = COALESCE(?, betweenness_centrality), eigenvector_centrality = COALESCE(?, eigenvector_centrality), size = ? WHERE id = ?"
+            )?;
+            // Read every node's type and degree once rather than once per score
+            let nodes: HashMap<String, (String, f64)> = {
+                let mut node_stmt = tx.prepare("SELECT id, node_type, COALESCE(degree_centrality, 0.0) FROM nodes")?;
+                let rows = node_stmt.query_map(params![], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?;
+                rows.collect::<duckdb::Result<_>>()?
+            };
+            for score in scores {
+                let Some((node_type, stored_degree)) = nodes.get(&score.id) else {
+                    continue;
+                };
+                let size = self.node_sizing.size(node_type, score.degree.unwrap_or(*stored_degree));
+                updated += stmt.execute(params![
+                    score.degree,
+                    score.pagerank,
+                    score.betweenness,
+                    score.eigenvector,
+                    size,
+                    &score.id,
+                ])?;
+            }
+        }
+        
+        tx.commit()?;
+        Ok(updated)
+    }
+    
     pub async fn get_stats(&self) -> Result<(usize, usize)> {
         let conn = self.conn.lock().unwrap();
         
@@ -782,7 +1283,76 @@ impl DuckDBStore {
         Ok((node_count as usize, edge_count as usize))
     }
     
+    /// Node counts bucketed by creation time. `interval` is a DuckDB
+    /// `date_trunc` part (`day`, `week` or `month`); nodes without a real
+    /// creation timestamp (`created_at_synthetic`) are left out.
+    pub async fn get_node_growth(&self, interval: &str) -> Result<Vec<GrowthBucket>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT strftime(date_trunc(?, epoch_ms(CAST(created_at_timestamp AS BIGINT))), '%Y-%m-%d') AS bucket, COUNT(*)
+             FROM nodes
+             WHERE created_at_timestamp IS NOT NULL AND NOT created_at_synthetic
+             GROUP BY bucket
+             ORDER BY bucket"
+        )?;
+
+        let rows = stmt.query_map(params![interval], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut cumulative = 0u64;
+        let mut buckets = Vec::new();
+        for row in rows {
+            let (bucket, count) = row?;
+            cumulative += count as u64;
+            buckets.push(GrowthBucket {
+                bucket,
+                node_count: count as u64,
+                cumulative,
+            });
+        }
+
+        Ok(buckets)
+    }
+
+    /// Average and maximum of each centrality per node type, most common type first
+    pub async fn get_node_type_stats(&self) -> Result<Vec<NodeTypeStats>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT node_type, COUNT(*),
+                    AVG(COALESCE(degree_centrality, 0)), MAX(COALESCE(degree_centrality, 0)),
+                    AVG(COALESCE(pagerank_centrality, 0)), MAX(COALESCE(pagerank_centrality, 0)),
+                    AVG(COALESCE(betweenness_centrality, 0)), MAX(COALESCE(betweenness_centrality, 0)),
+                    AVG(COALESCE(eigenvector_centrality, 0)), MAX(COALESCE(eigenvector_centrality, 0))
+             FROM nodes
+             GROUP BY node_type
+             ORDER BY COUNT(*) DESC, node_type"
+        )?;
+
+        let rows = stmt.query_map(params![], |row| {
+            Ok(NodeTypeStats {
+                node_type: row.get(0)?,
+                node_count: row.get::<_, i64>(1)? as u64,
+                avg_degree: row.get(2)?,
+                max_degree: row.get(3)?,
+                avg_pagerank: row.get(4)?,
+                max_pagerank: row.get(5)?,
+                avg_betweenness: row.get(6)?,
+                max_betweenness: row.get(7)?,
+                avg_eigenvector: row.get(8)?,
+                max_eigenvector: row.get(9)?,
+            })
+        })?;
+
+        Ok(rows.collect::<duckdb::Result<Vec<_>>>()?)
+    }
+
     fn get_node_color(&self, node_type: &str) -> String {
+        if let Some(color) = self.color_map.read().unwrap().nodes.get(node_type) {
+            return color.clone();
+        }
         match node_type {
             "EntityNode" => "#4CAF50".to_string(),
             "EpisodicNode" => "#2196F3".to_string(),
@@ -792,6 +1362,9 @@ impl DuckDBStore {
     }
     
     fn get_edge_color(&self, edge_type: &str) -> String {
+        if let Some(color) = self.color_map.read().unwrap().edges.get(edge_type) {
+            return color.clone();
+        }
         match edge_type {
             "RELATES_TO" => "#666666".to_string(),
             "MENTIONS" => "#999999".to_string(),
@@ -800,6 +1373,28 @@ impl DuckDBStore {
         }
     }
     
+    /// Which of `ids` are loaded as nodes
+    pub async fn existing_node_ids(&self, ids: &[String]) -> Result<HashSet<String>> {
+        if ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        
+        let conn = self.conn.lock().unwrap();
+        
+        let placeholders: Vec<String> = ids.iter().enumerate()
+            .map(|(i, _)| format!("${}", i + 1))
+            .collect();
+        let query = format!("SELECT id FROM nodes WHERE id IN ({})", placeholders.join(", "));
+        
+        let mut stmt = conn.prepare(&query)?;
+        let params: Vec<&dyn duckdb::ToSql> = ids.iter()
+            .map(|id| id as &dyn duckdb::ToSql)
+            .collect();
+        let rows = stmt.query_map(&params[..], |row| row.get::<_, String>(0))?;
+        
+        Ok(rows.collect::<duckdb::Result<_>>()?)
+    }
+    
     pub async fn get_nodes_by_ids(&self, ids: &[String]) -> Result<Vec<Node>> {
         if ids.is_empty() {
             return Ok(vec![]);
@@ -857,6 +1452,7 @@ impl DuckDBStore {
             }
             if let Ok(timestamp) = row.get::<_, f64>(14) {  // created_at_timestamp column
                 properties.insert("created_at_timestamp".to_string(), serde_json::json!(timestamp));
+                properties.insert("created_at_synthetic".to_string(), serde_json::json!(row.get::<_, bool>(17)?));
                 
                 // If created_at string is missing, synthesize it from timestamp
                 if !properties.contains_key("created_at") {
@@ -915,6 +1511,97 @@ impl DuckDBStore {
                 to: row.get(2)?,
                 edge_type: row.get(4)?,
                 weight: row.get(5)?,
+                properties: properties_from_json(row.get(8)?),
+            })
+        })?;
+        
+        let mut edges = Vec::new();
+        for edge in edge_iter {
+            edges.push(edge?);
+        }
+        
+        Ok(edges)
+    }
+    
+    /// Copy the current nodes and edges into snapshot `name`, replacing any
+    /// earlier snapshot of that name. Returns the node and edge counts.
+    pub async fn create_snapshot(&self, name: &str) -> Result<(usize, usize)> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM snapshot_edges WHERE snapshot = ?", params![name])?;
+        tx.execute("DELETE FROM snapshot_nodes WHERE snapshot = ?", params![name])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO snapshots (name, created_at) VALUES (?, ?)",
+            params![name, Utc::now().to_rfc3339()],
+        )?;
+        let node_count = tx.execute(
+            "INSERT INTO snapshot_nodes SELECT ?, id, label, node_type, summary FROM nodes",
+            params![name],
+        )?;
+        let edge_count = tx.execute(
+            "INSERT INTO snapshot_edges SELECT ?, source, target, edge_type, weight, properties FROM edges",
+            params![name],
+        )?;
+        tx.commit()?;
+        Ok((node_count, edge_count))
+    }
+    
+    /// Nodes and edges of snapshot `name`, or `None` if it was never captured.
+    /// Nodes carry only their label, type and summary.
+    pub async fn get_snapshot(&self, name: &str) -> Result<Option<(Vec<Node>, Vec<Edge>)>> {
+        let conn = self.conn.lock().unwrap();
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM snapshots WHERE name = ?",
+            params![name],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Ok(None);
+        }
+        
+        let graph = read_graph(
+            &conn,
+            "SELECT id, label, node_type, summary FROM snapshot_nodes WHERE snapshot = ?",
+            "SELECT source, target, edge_type, weight, properties FROM snapshot_edges WHERE snapshot = ?",
+            &[&name],
+        )?;
+        Ok(Some(graph))
+    }
+    
+    /// The current nodes and edges, in the same shape as `get_snapshot`
+    pub async fn get_graph(&self) -> Result<(Vec<Node>, Vec<Edge>)> {
+        let conn = self.conn.lock().unwrap();
+        read_graph(
+            &conn,
+            "SELECT id, label, node_type, summary FROM nodes",
+            "SELECT source, target, edge_type, weight, properties FROM edges",
+            &[],
+        )
+    }
+    
+    /// Edges incident to a node. `outgoing`/`incoming` select which ends to match.
+    pub async fn get_node_edges(&self, id: &str, outgoing: bool, incoming: bool) -> Result<Vec<Edge>> {
+        let condition = match (outgoing, incoming) {
+            (true, true) => "source = $1 OR target = $1",
+            (true, false) => "source = $1",
+            (false, true) => "target = $1",
+            (false, false) => return Ok(vec![]),
+        };
+        
+        let conn = self.conn.lock().unwrap();
+        let query = format!(
+            "SELECT source, target, edge_type, weight, properties FROM edges WHERE {} ORDER BY sourceidx, targetidx",
+            condition
+        );
+        
+        let mut stmt = conn.prepare(&query)?;
+        let edge_iter = stmt.query_map([&id], |row| {
+            Ok(Edge {
+                from: row.get(0)?,
+                to: row.get(1)?,
+                edge_type: row.get(2)?,
+                weight: row.get(3)?,
+                properties: properties_from_json(row.get(4)?),
             })
         })?;
         
@@ -957,6 +1644,7 @@ impl DuckDBStore {
             }
             if let Ok(timestamp) = row.get::<_, f64>(14) {  // created_at_timestamp column
                 properties.insert("created_at_timestamp".to_string(), serde_json::Value::from(timestamp));
+                properties.insert("created_at_synthetic".to_string(), serde_json::Value::from(row.get::<_, bool>(17)?));
                 
                 // If created_at string is missing, synthesize it from timestamp
                 if !properties.contains_key("created_at") {
@@ -973,6 +1661,15 @@ impl DuckDBStore {
             if let Ok(color) = row.get::<_, String>(11) {  // color column
                 properties.insert("color".to_string(), serde_json::Value::from(color));
             }
+            if let Ok(idx) = row.get::<_, i32>(1) {  // idx column
+                properties.insert("idx".to_string(), serde_json::Value::from(idx));
+            }
+            if let Ok(cluster) = row.get::<_, String>(15) {  // cluster column
+                properties.insert("cluster".to_string(), serde_json::Value::from(cluster));
+            }
+            if let Ok(cluster_strength) = row.get::<_, f64>(16) {  // clusterStrength column
+                properties.insert("clusterStrength".to_string(), serde_json::Value::from(cluster_strength));
+            }
             
             Ok(Node {
                 id: row.get(0)?,
@@ -989,4 +1686,396 @@ impl DuckDBStore {
             Ok(None)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Run a query of `(id, label, node_type, summary)` node rows and one of
+/// `(source, target, edge_type, weight, properties)` edge rows, both bound to `params`
+fn read_graph(
+    conn: &Connection,
+    nodes_query: &str,
+    edges_query: &str,
+    params: &[&dyn duckdb::ToSql],
+) -> Result<(Vec<Node>, Vec<Edge>)> {
+    let mut stmt = conn.prepare(nodes_query)?;
+    let nodes = stmt.query_map(params, |row| {
+        Ok(Node {
+            id: row.get(0)?,
+            label: row.get(1)?,
+            node_type: row.get(2)?,
+            summary: row.get(3)?,
+            properties: HashMap::new(),
+        })
+    })?.collect::<duckdb::Result<Vec<_>>>()?;
+    
+    let mut stmt = conn.prepare(edges_query)?;
+    let edges = stmt.query_map(params, |row| {
+        Ok(Edge {
+            from: row.get(0)?,
+            to: row.get(1)?,
+            edge_type: row.get(2)?,
+            weight: row.get(3)?,
+            properties: properties_from_json(row.get(4)?),
+        })
+    })?.collect::<duckdb::Result<Vec<_>>>()?;
+    
+    Ok((nodes, edges))
+}
+
+// Edge properties are stored as a JSON object; an empty map is stored as NULL
+fn properties_to_json(properties: &HashMap<String, serde_json::Value>) -> Option<String> {
+    if properties.is_empty() {
+        return None;
+    }
+    serde_json::to_string(properties).ok()
+}
+
+fn properties_from_json(json: Option<String>) -> HashMap<String, serde_json::Value> {
+    json.and_then(|j| serde_json::from_str(&j).ok()).unwrap_or_default()
+}
+
+/// Fraction of each node's edges whose other endpoint shares its cluster (node_type),
+/// in [0, 1]. Nodes without edges to known nodes are left out.
+fn cluster_strengths(nodes: &[Node], edges: &[Edge]) -> HashMap<String, f64> {
+    let cluster_of: HashMap<&str, &str> = nodes.iter()
+        .map(|n| (n.id.as_str(), n.node_type.as_str()))
+        .collect();
+    
+    // (intra-cluster edges, total edges) per node
+    let mut counts: HashMap<&str, (u32, u32)> = HashMap::new();
+    for edge in edges {
+        if let (Some(from_cluster), Some(to_cluster)) =
+            (cluster_of.get(edge.from.as_str()), cluster_of.get(edge.to.as_str())) {
+            let intra = u32::from(from_cluster == to_cluster);
+            for id in [edge.from.as_str(), edge.to.as_str()] {
+                let entry = counts.entry(id).or_default();
+                entry.0 += intra;
+                entry.1 += 1;
+            }
+        }
+    }
+    
+    counts.into_iter()
+        .map(|(id, (intra, total))| (id.to_string(), intra as f64 / total as f64))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, node_type: &str, created_at: Option<&str>) -> Node {
+        let mut properties = HashMap::new();
+        if let Some(created_at) = created_at {
+            properties.insert("created_at".to_string(), serde_json::json!(created_at));
+        }
+        Node {
+            id: id.to_string(),
+            label: id.to_string(),
+            node_type: node_type.to_string(),
+            summary: None,
+            properties,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_growth_buckets_skip_synthesized_timestamps() {
+        let store = DuckDBStore::new().unwrap();
+        store.load_initial_data(vec![
+            node("a", "Entity", Some("2024-01-15T10:30:00Z")),
+            node("b", "Entity", Some("2024-01-15T18:00:00Z")),
+            node("c", "Entity", Some("2024-01-20T09:00:00Z")),
+            node("d", "Entity", None),
+        ], vec![]).await.unwrap();
+
+        let buckets: Vec<_> = store.get_node_growth("day").await.unwrap()
+            .into_iter()
+            .map(|b| (b.bucket, b.node_count, b.cumulative))
+            .collect();
+        assert_eq!(buckets, vec![
+            ("2024-01-15".to_string(), 2, 2),
+            ("2024-01-20".to_string(), 1, 3),
+        ]);
+    }
+
+    fn edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            edge_type: "RELATES_TO".to_string(),
+            weight: 1.0,
+            properties: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_diff_reports_changes_since_capture() {
+        let store = DuckDBStore::new().unwrap();
+        store.load_initial_data(
+            vec![node("a", "Entity", None), node("b", "Entity", None), node("c", "Entity", None)],
+            vec![edge("a", "b"), edge("b", "c")],
+        ).await.unwrap();
+        assert_eq!(store.create_snapshot("before").await.unwrap(), (3, 2));
+
+        // Reload without c and with b renamed, then add d incrementally
+        let mut renamed = node("b", "Entity", None);
+        renamed.label = "B".to_string();
+        store.load_initial_data(
+            vec![node("a", "Entity", None), renamed],
+            vec![edge("a", "b")],
+        ).await.unwrap();
+        store.apply_updates(vec![node("d", "Entity", None)], vec![edge("a", "d")]).await.unwrap();
+
+        let (old_nodes, old_edges) = store.get_snapshot("before").await.unwrap().unwrap();
+        let (new_nodes, new_edges) = store.get_graph().await.unwrap();
+        let (old_nodes, old_edges) = crate::delta_tracker::index_graph(old_nodes, old_edges);
+        let (new_nodes, new_edges) = crate::delta_tracker::index_graph(new_nodes, new_edges);
+        let diff = crate::delta_tracker::diff_graphs(&old_nodes, &old_edges, &new_nodes, &new_edges);
+
+        let ids = |nodes: &[Node]| nodes.iter().map(|n| n.id.clone()).collect::<Vec<_>>();
+        let pairs = |edges: &[Edge]| edges.iter().map(|e| (e.from.clone(), e.to.clone())).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.nodes_added), vec!["d"]);
+        assert_eq!(ids(&diff.nodes_updated), vec!["b"]);
+        assert_eq!(diff.nodes_updated[0].label, "B");
+        assert_eq!(diff.nodes_removed, vec!["c"]);
+        assert_eq!(pairs(&diff.edges_added), vec![("a".to_string(), "d".to_string())]);
+        assert!(diff.edges_updated.is_empty());
+        assert_eq!(diff.edges_removed, vec![("b".to_string(), "c".to_string())]);
+        assert!(store.get_snapshot("missing").await.unwrap().is_none());
+    }
+
+    // Timestamp and synthetic flag a node was stored with
+    async fn stored_timestamp(store: &DuckDBStore, id: &str) -> (f64, bool) {
+        let node = store.get_node_by_id(id).await.unwrap().unwrap();
+        (
+            node.properties["created_at_timestamp"].as_f64().unwrap(),
+            node.properties["created_at_synthetic"].as_bool().unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_synthesized_timestamps_are_recent_and_flagged() {
+        let store = DuckDBStore::new().unwrap();
+        store.load_initial_data(vec![
+            node("real", "Entity", Some("2024-01-15T10:30:00Z")),
+            node("loaded", "Entity", None),
+        ], vec![]).await.unwrap();
+        store.apply_updates(vec![node("added", "Entity", None)], vec![]).await.unwrap();
+
+        let real = DateTime::parse_from_rfc3339("2024-01-15T10:30:00Z").unwrap().timestamp_millis() as f64;
+        assert_eq!(stored_timestamp(&store, "real").await, (real, false));
+        // Dated `idx` days before now: within a few days, not decades ago
+        let now = Utc::now().timestamp_millis() as f64;
+        let week = chrono::Duration::days(7).num_milliseconds() as f64;
+        for id in ["loaded", "added"] {
+            let (timestamp, synthetic) = stored_timestamp(&store, id).await;
+            assert!(synthetic, "{} not flagged", id);
+            assert!(timestamp <= now && now - timestamp < week, "{} dated {}", id, timestamp);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_synthesized_timestamps_count_back_from_anchor() {
+        let anchor = DateTime::parse_from_rfc3339("2025-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let store = DuckDBStore::new().unwrap().with_synthetic_timestamp_anchor(Some(anchor));
+        store.load_initial_data(vec![node("a", "Entity", None), node("b", "Entity", None)], vec![]).await.unwrap();
+
+        let day = chrono::Duration::days(1).num_milliseconds() as f64;
+        let anchor = anchor.timestamp_millis() as f64;
+        assert_eq!(stored_timestamp(&store, "a").await, (anchor, true));
+        assert_eq!(stored_timestamp(&store, "b").await, (anchor - day, true));
+    }
+
+    // Link strength stored for each edge, keyed by `from->to`
+    fn stored_strengths(store: &DuckDBStore) -> HashMap<String, f64> {
+        let conn = store.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT source, target, strength FROM edges").unwrap();
+        stmt.query_map([], |row| {
+            Ok((format!("{}->{}", row.get::<_, String>(0)?, row.get::<_, String>(1)?), row.get(2)?))
+        }).unwrap().collect::<duckdb::Result<_>>().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_custom_edge_strength_applied_on_load_and_update() {
+        let mut strengths = EdgeStrengths::default();
+        strengths.strengths.insert("MENTORS".to_string(), 3.0);
+        let store = DuckDBStore::new().unwrap().with_edge_strengths(strengths);
+        let mentors = |from: &str, to: &str| Edge { edge_type: "MENTORS".to_string(), ..edge(from, to) };
+        store.load_initial_data(
+            vec![node("a", "Entity", None), node("b", "Entity", None)],
+            vec![mentors("a", "b"), edge("b", "a")],
+        ).await.unwrap();
+        store.apply_updates(vec![node("c", "Entity", None)], vec![mentors("a", "c")]).await.unwrap();
+
+        let stored = stored_strengths(&store);
+        assert_eq!(stored["a->b"], 3.0);
+        assert_eq!(stored["a->c"], 3.0);
+        assert_eq!(stored["b->a"], 1.0);
+    }
+
+    #[test]
+    fn test_edge_strengths_file_rejects_negative_strengths() {
+        let path = std::env::temp_dir().join(format!("edge-strengths-{}.json", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        for (contents, valid) in [
+            (r#"{"strengths": {"MENTORS": 3.0}, "default": 0.0}"#, true),
+            (r#"{"strengths": {"MENTORS": -1.0}}"#, false),
+            (r#"{"default": -0.5}"#, false),
+        ] {
+            std::fs::write(path, contents).unwrap();
+            assert_eq!(EdgeStrengths::from_file(path).is_ok(), valid, "{}", contents);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_centrality_update_sizes_nodes_by_type() {
+        let mut sizing = NodeSizing::default();
+        sizing.types.insert("Episodic".to_string(), NodeSize { base: 2.0, degree_multiplier: 5.0 });
+        let store = DuckDBStore::new().unwrap().with_node_sizing(sizing);
+        store.load_initial_data(vec![node("entity", "Entity", None), node("episode", "Episodic", None)], vec![]).await.unwrap();
+
+        let score = |id: &str| CentralityScores {
+            id: id.to_string(),
+            degree: Some(0.5),
+            pagerank: Some(0.0),
+            betweenness: Some(0.0),
+            eigenvector: Some(0.0),
+        };
+        let updated = store.update_centrality_scores(&[score("entity"), score("episode"), score("missing")]).await.unwrap();
+        assert_eq!(updated, 2);
+
+        let size = |id: &str| store.conn.lock().unwrap()
+            .query_row("SELECT size FROM nodes WHERE id = ?", params![id], |row| row.get::<_, f64>(0))
+            .unwrap();
+        // Same degree, different sizing: 4 + 0.5 * 20 and 2 + 0.5 * 5
+        assert_eq!(size("entity"), 14.0);
+        assert_eq!(size("episode"), 4.5);
+    }
+
+    #[tokio::test]
+    async fn test_node_indices_survive_full_reloads() {
+        let store = DuckDBStore::new().unwrap();
+        let idx = |node: Option<Node>| node.unwrap().properties["idx"].as_u64().unwrap();
+        store.load_initial_data(
+            vec![node("a", "Entity", None), node("b", "Entity", None), node("c", "Entity", None)],
+            vec![],
+        ).await.unwrap();
+        let a = idx(store.get_node_by_id("a").await.unwrap());
+        let c = idx(store.get_node_by_id("c").await.unwrap());
+
+        // Reordered, with b gone and d new
+        store.load_initial_data(
+            vec![node("d", "Entity", None), node("c", "Entity", None), node("a", "Entity", None)],
+            vec![],
+        ).await.unwrap();
+        assert_eq!(idx(store.get_node_by_id("a").await.unwrap()), a);
+        assert_eq!(idx(store.get_node_by_id("c").await.unwrap()), c);
+        // New nodes never reuse an index, not even a removed node's
+        assert_eq!(idx(store.get_node_by_id("d").await.unwrap()), 3);
+        assert!(store.get_node_by_id("b").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cluster_strength_is_the_intra_cluster_edge_fraction() {
+        let nodes = vec![
+            node("a", "Entity", None),
+            node("b", "Entity", None),
+            node("e", "Episodic", None),
+            node("lonely", "Entity", None),
+        ];
+        // a: 1 of 2 edges stays in Entity, b: 1 of 1, e: 0 of 1; the dangling edge is ignored
+        let edges = vec![edge("a", "b"), edge("e", "a"), edge("a", "ghost")];
+        let strengths = cluster_strengths(&nodes, &edges);
+        assert_eq!(strengths.len(), 3);
+        assert_eq!(strengths["a"], 0.5);
+        assert_eq!(strengths["b"], 1.0);
+        assert_eq!(strengths["e"], 0.0);
+
+        // Nodes without edges get the default when stored
+        let store = DuckDBStore::new().unwrap();
+        store.load_initial_data(nodes, edges).await.unwrap();
+        let strength = |id: &str| store.conn.lock().unwrap()
+            .query_row("SELECT clusterStrength FROM nodes WHERE id = ?", params![id], |row| row.get::<_, f64>(0))
+            .unwrap();
+        assert_eq!(strength("a"), 0.5);
+        assert_eq!(strength("lonely"), DEFAULT_CLUSTER_STRENGTH);
+    }
+
+    #[tokio::test]
+    async fn test_node_type_stats_aggregate_centrality_per_type() {
+        let store = DuckDBStore::new().unwrap();
+        store.load_initial_data(vec![
+            node("a", "Entity", None),
+            node("b", "Entity", None),
+            node("e", "Episodic", None),
+        ], vec![]).await.unwrap();
+        let score = |id: &str, degree: f64, pagerank: f64| CentralityScores {
+            id: id.to_string(),
+            degree: Some(degree),
+            pagerank: Some(pagerank),
+            betweenness: Some(0.0),
+            eigenvector: Some(0.0),
+        };
+        store.update_centrality_scores(&[score("a", 0.2, 0.1), score("b", 0.6, 0.3), score("e", 0.1, 0.05)]).await.unwrap();
+
+        let stats = store.get_node_type_stats().await.unwrap();
+        assert_eq!(stats.iter().map(|s| (s.node_type.as_str(), s.node_count)).collect::<Vec<_>>(), vec![("Entity", 2), ("Episodic", 1)]);
+        let entity = &stats[0];
+        assert!((entity.avg_degree - 0.4).abs() < 1e-9);
+        assert_eq!(entity.max_degree, 0.6);
+        assert!((entity.avg_pagerank - 0.2).abs() < 1e-9);
+        assert_eq!(entity.max_pagerank, 0.3);
+        assert_eq!(stats[1].max_degree, 0.1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_updates_each_report_their_own_items() {
+        let store = DuckDBStore::new().unwrap();
+        let tasks: Vec<_> = (0..8).map(|i| {
+            let store = store.clone();
+            tokio::spawn(async move {
+                let id = format!("n{}", i);
+                let update = store.apply_updates(vec![node(&id, "Entity", None)], vec![]).await.unwrap().unwrap();
+                (id, update)
+            })
+        }).collect();
+
+        for task in tasks {
+            let (id, update) = task.await.unwrap();
+            let ids: Vec<String> = update.nodes.unwrap().into_iter().map(|n| n.id).collect();
+            assert_eq!(ids, vec![id]);
+        }
+        assert_eq!(store.get_stats().await.unwrap().0, 8);
+    }
+
+    #[tokio::test]
+    async fn test_pending_edges_dropped_after_limits() {
+        let store = DuckDBStore::new().unwrap().with_pending_edge_limits(1, 1);
+        store.load_initial_data(vec![node("a", "Entity", None)], vec![]).await.unwrap();
+        async fn pending(store: &DuckDBStore) -> Vec<String> {
+            store.get_pending_edges().await.into_iter().map(|p| p.edge.to).collect()
+        }
+
+        store.apply_updates(vec![], vec![edge("a", "ghost"), edge("a", "late")]).await.unwrap();
+        assert_eq!(pending(&store).await, vec!["ghost", "late"]);
+
+        // The late node arrives within the retry budget
+        let update = store.apply_updates(vec![node("late", "Entity", None)], vec![]).await.unwrap().unwrap();
+        assert_eq!(update.edges.unwrap().iter().map(|e| e.to.as_str()).collect::<Vec<_>>(), vec!["late"]);
+        assert_eq!(pending(&store).await, vec!["ghost"]);
+        assert_eq!(store.dropped_edge_count(), 0);
+
+        // Its one retry used up, the ghost edge goes
+        store.process_updates().await.unwrap();
+        assert!(pending(&store).await.is_empty());
+        assert_eq!(store.dropped_edge_count(), 1);
+
+        // Past the stale window an edge goes regardless of retries left
+        store.apply_updates(vec![], vec![edge("a", "slow")]).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        store.process_updates().await.unwrap();
+        assert!(pending(&store).await.is_empty());
+        assert_eq!(store.dropped_edge_count(), 2);
+        assert_eq!(store.get_stats().await.unwrap(), (2, 1));
+    }
+}
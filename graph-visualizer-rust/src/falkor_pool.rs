@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use deadpool::managed::{Manager, Metrics, Object, Pool, RecycleError, RecycleResult};
+use deadpool::Runtime;
+use falkordb::{FalkorAsyncClient, FalkorClientBuilder, FalkorConnectionInfo, FalkorDBError};
+use std::num::NonZeroU8;
+use std::time::Duration;
+
+pub type FalkorPool = Pool<FalkorManager>;
+pub type FalkorConnection = Object<FalkorManager>;
+
+// A client returned this recently is handed out again without a ping
+const RECYCLE_PING_AFTER: Duration = Duration::from_secs(10);
+// Longest a recycle ping may hold up a checkout before the client is replaced
+const RECYCLE_PING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Opens the pooled FalkorDB clients. Each holds a single connection, so a
+/// checked-out client never waits on another request's query.
+pub struct FalkorManager {
+    connection_string: String,
+    graph_name: String,
+}
+
+#[async_trait]
+impl Manager for FalkorManager {
+    type Type = FalkorAsyncClient;
+    type Error = FalkorDBError;
+
+    async fn create(&self) -> Result<FalkorAsyncClient, FalkorDBError> {
+        let connection_info: FalkorConnectionInfo = self.connection_string.as_str().try_into()?;
+        FalkorClientBuilder::new_async()
+            .with_connection_info(connection_info)
+            .with_num_connections(NonZeroU8::MIN)
+            .build()
+            .await
+    }
+
+    async fn recycle(&self, client: &mut FalkorAsyncClient, metrics: &Metrics) -> RecycleResult<FalkorDBError> {
+        if metrics.last_used() < RECYCLE_PING_AFTER {
+            return Ok(());
+        }
+        let mut graph = client.select_graph(&self.graph_name);
+        match tokio::time::timeout(RECYCLE_PING_TIMEOUT, graph.query("RETURN 1").execute()).await {
+            Ok(result) => {
+                result?;
+                Ok(())
+            }
+            Err(_) => Err(RecycleError::StaticMessage("FalkorDB ping timed out")),
+        }
+    }
+}
+
+/// Pool of up to `FALKOR_POOL_SIZE` clients (default 16), shared by the
+/// request handlers and the change monitor. Waiting for a free client and
+/// opening a new one are each bounded by `FALKOR_POOL_TIMEOUT_MS` (default 5000).
+pub fn create_pool(connection_string: String, graph_name: String) -> anyhow::Result<FalkorPool> {
+    let size = std::env::var("FALKOR_POOL_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&size| size > 0)
+        .unwrap_or(16);
+    let timeout_ms = std::env::var("FALKOR_POOL_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|&ms| ms > 0)
+        .unwrap_or(5000);
+    create_pool_with(connection_string, graph_name, size, Duration::from_millis(timeout_ms))
+}
+
+fn create_pool_with(
+    connection_string: String,
+    graph_name: String,
+    size: usize,
+    timeout: Duration,
+) -> anyhow::Result<FalkorPool> {
+    let manager = FalkorManager { connection_string, graph_name };
+    Ok(Pool::builder(manager)
+        .max_size(size)
+        .wait_timeout(Some(timeout))
+        .create_timeout(Some(timeout))
+        .runtime(Runtime::Tokio1)
+        .build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    // Run `requests` concurrent queries through `pool`, returning queries per second
+    async fn throughput(pool: &FalkorPool, requests: usize) -> f64 {
+        let start = Instant::now();
+        let queries = (0..requests).map(|_| async {
+            let client = pool.get().await.unwrap();
+            let mut graph = client.select_graph("pool_bench");
+            graph.query("UNWIND range(1, 20000) AS i RETURN sum(i)").execute().await.unwrap();
+        });
+        futures::future::join_all(queries).await;
+        requests as f64 / start.elapsed().as_secs_f64()
+    }
+
+    #[tokio::test]
+    #[ignore = "Load test; requires FalkorDB on FALKORDB_HOST:FALKORDB_PORT"]
+    async fn bench_pool_beats_single_shared_client() {
+        let host = std::env::var("FALKORDB_HOST").unwrap_or_else(|_| "localhost".to_string());
+        let port = std::env::var("FALKORDB_PORT").unwrap_or_else(|_| "6379".to_string());
+        let connection_string = format!("redis://{}:{}", host, port);
+        let timeout = Duration::from_secs(30);
+
+        // One connection is what every request used to share
+        let single = create_pool_with(connection_string.clone(), "pool_bench".to_string(), 1, timeout).unwrap();
+        let pooled = create_pool_with(connection_string, "pool_bench".to_string(), 16, timeout).unwrap();
+        // Warm up so connection setup isn't measured
+        throughput(&single, 1).await;
+        throughput(&pooled, 16).await;
+
+        let single_qps = throughput(&single, 200).await;
+        let pooled_qps = throughput(&pooled, 200).await;
+        println!("single client: {:.0} queries/s, pool of 16: {:.0} queries/s", single_qps, pooled_qps);
+        assert!(pooled_qps > single_qps, "pool ({:.0}/s) no faster than one client ({:.0}/s)", pooled_qps, single_qps);
+    }
+}
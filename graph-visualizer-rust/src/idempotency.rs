@@ -0,0 +1,142 @@
+use axum::http::{HeaderValue, StatusCode};
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Response recorded for a completed request
+#[derive(Clone)]
+pub struct StoredResponse {
+    pub status: StatusCode,
+    pub content_type: Option<HeaderValue>,
+    pub body: Bytes,
+}
+
+enum Entry {
+    InFlight { started: Instant },
+    Done { at: Instant, response: StoredResponse },
+}
+
+/// Result of claiming an idempotency key
+pub enum Claim {
+    /// First sighting; the caller runs the request and completes the guard
+    New(ClaimGuard),
+    /// A request with this key already succeeded
+    Replay(StoredResponse),
+    /// A request with this key is still running
+    InFlight,
+}
+
+/// Remembers successful update responses by `Idempotency-Key` so retried
+/// requests are answered from the record instead of being applied twice.
+///
+/// Keys live for `IDEMPOTENCY_KEY_TTL_SECS` (default 600). Only 2xx responses
+/// are recorded; a failed request releases its key so the client can retry.
+pub struct IdempotencyStore {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl IdempotencyStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn from_env() -> Self {
+        let ttl_secs = std::env::var("IDEMPOTENCY_KEY_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(600);
+        Self::new(Duration::from_secs(ttl_secs))
+    }
+
+    pub fn claim(self: &Arc<Self>, key: String) -> Claim {
+        let mut entries = self.entries.lock().unwrap();
+        let ttl = self.ttl;
+        entries.retain(|_, entry| match entry {
+            Entry::InFlight { started } => started.elapsed() < ttl,
+            Entry::Done { at, .. } => at.elapsed() < ttl,
+        });
+
+        match entries.get(&key) {
+            Some(Entry::Done { response, .. }) => Claim::Replay(response.clone()),
+            Some(Entry::InFlight { .. }) => Claim::InFlight,
+            None => {
+                entries.insert(key.clone(), Entry::InFlight { started: Instant::now() });
+                Claim::New(ClaimGuard { store: self.clone(), key: Some(key) })
+            }
+        }
+    }
+}
+
+/// Held while a claimed request runs. Dropping it without `complete`
+/// (an error response or a cancelled request) frees the key again.
+pub struct ClaimGuard {
+    store: Arc<IdempotencyStore>,
+    key: Option<String>,
+}
+
+impl ClaimGuard {
+    pub fn complete(mut self, response: StoredResponse) {
+        if let Some(key) = self.key.take() {
+            let mut entries = self.store.entries.lock().unwrap();
+            entries.insert(key, Entry::Done { at: Instant::now(), response });
+        }
+    }
+}
+
+impl Drop for ClaimGuard {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.store.entries.lock().unwrap().remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &'static str) -> StoredResponse {
+        StoredResponse {
+            status: StatusCode::OK,
+            content_type: Some(HeaderValue::from_static("application/json")),
+            body: Bytes::from_static(body.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_completed_request_is_replayed() {
+        let store = Arc::new(IdempotencyStore::new(Duration::from_secs(60)));
+        let Claim::New(guard) = store.claim("key".to_string()) else {
+            panic!("first claim not new");
+        };
+        assert!(matches!(store.claim("key".to_string()), Claim::InFlight));
+        guard.complete(response("{\"ok\":true}"));
+
+        match store.claim("key".to_string()) {
+            Claim::Replay(stored) => {
+                assert_eq!(stored.status, StatusCode::OK);
+                assert_eq!(stored.body, "{\"ok\":true}");
+            }
+            _ => panic!("completed request not replayed"),
+        }
+        assert!(matches!(store.claim("other".to_string()), Claim::New(_)));
+    }
+
+    #[test]
+    fn test_dropped_claim_frees_the_key() {
+        let store = Arc::new(IdempotencyStore::new(Duration::from_secs(60)));
+        drop(store.claim("key".to_string()));
+        assert!(matches!(store.claim("key".to_string()), Claim::New(_)));
+    }
+
+    #[test]
+    fn test_records_expire_after_ttl() {
+        let store = Arc::new(IdempotencyStore::new(Duration::ZERO));
+        if let Claim::New(guard) = store.claim("key".to_string()) {
+            guard.complete(response("{}"));
+        }
+        assert!(matches!(store.claim("key".to_string()), Claim::New(_)));
+    }
+}
@@ -1,20 +1,21 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::{StatusCode, header, HeaderMap},
+    extract::{DefaultBodyLimit, Path, Query, Request, State},
+    http::{Method, StatusCode, header, HeaderMap},
+    middleware::{self, Next},
     response::{IntoResponse, Json, Response},
     routing::{get, patch, post},
     Router,
     body::Body,
 };
 use dashmap::DashMap;
-use falkordb::{FalkorClientBuilder, FalkorConnectionInfo, FalkorValue, FalkorAsyncClient};
+use falkordb::{AsyncGraph, FalkorValue, FalkorAsyncClient, LazyResultSet, QueryResult};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use tower_http::{
     cors::CorsLayer,
     compression::CompressionLayer,
 };
-use tracing::{error, info, debug};
+use tracing::{error, info, debug, warn};
 use tokio::sync::{broadcast, RwLock};
 use arrow::record_batch::RecordBatch;
 use bytes::Bytes;
@@ -27,30 +28,265 @@ mod arrow_converter;
 mod delta_tracker;
 mod cache;
 mod websocket;
+mod circuit_breaker;
+mod audit;
+mod idempotency;
+mod falkor_pool;
+mod request_id;
+mod reload_debouncer;
+#[cfg(test)]
+mod mock_redis;
 
-use duckdb_store::{DuckDBStore, GraphUpdate, UpdateOperation};
-use arrow_converter::ArrowConverter;
-use delta_tracker::{DeltaTracker, GraphDelta};
-use cache::EnhancedCache;
-use websocket::{websocket_handler, BroadcastExt};
+use duckdb_store::{CentralityScores, ColorMap, DuckDBStore, EdgeStrengths, GraphUpdate, GrowthBucket, NodeSizing, NodeTypeStats, UpdateOperation};
+use arrow_converter::{ArrowConverter, ArrowStreamEncoder};
+use delta_tracker::{diff_graphs, index_graph, DeltaTracker, GraphDelta, GraphDiff};
+use cache::{AdaptiveTTL, EnhancedCache, NegativeCacheConfig};
+use circuit_breaker::CircuitBreaker;
+use audit::{AuditLog, AuditSource};
+use idempotency::{Claim, IdempotencyStore, StoredResponse};
+use falkor_pool::{FalkorConnection, FalkorPool};
+use request_id::{propagate_request_id, RequestIdExt};
+use reload_debouncer::{GraphCounts, ReloadDebouncer};
+use websocket::{websocket_handler, BroadcastExt, ReloadTrigger, ServerEvent};
 use deadpool_redis::{Config as RedisConfig, Runtime};
 
 #[derive(Clone)]
 pub struct AppState {
-    pub(crate) client: Arc<FalkorAsyncClient>,
+    pub(crate) falkor_pool: FalkorPool,
     pub(crate) graph_name: String,
     pub(crate) graph_cache: Arc<DashMap<String, GraphData>>,
     pub(crate) duckdb_store: Arc<DuckDBStore>,
     pub(crate) update_tx: broadcast::Sender<GraphUpdate>,
     pub(crate) delta_tx: broadcast::Sender<GraphDelta>,
+    pub(crate) event_tx: broadcast::Sender<ServerEvent>,
     pub(crate) arrow_cache: Arc<RwLock<Option<ArrowCache>>>,
     pub(crate) delta_tracker: Arc<DeltaTracker>,
     pub(crate) http_client: Arc<reqwest::Client>,
+    pub(crate) centrality_breaker: Arc<CircuitBreaker>,
     pub(crate) centrality_url: String,
+    pub(crate) centrality_all_timeout: std::time::Duration,
+    pub(crate) search_url: Option<String>,
+    pub(crate) auto_recompute_centrality: bool,
     pub(crate) cache_config: CacheConfig,
     pub(crate) enhanced_cache: Option<Arc<EnhancedCache>>,
+    pub(crate) arrow_stream_threshold: usize,
+    pub(crate) ws_max_message_bytes: usize,
+    pub(crate) load_limits: Arc<RwLock<LoadLimits>>,
+    pub(crate) api_token: Option<String>,
+    pub(crate) webhook_secret: Option<String>,
+    pub(crate) label_fields: LabelFields,
+    pub(crate) type_allowlist: TypeAllowlist,
+    pub(crate) audit_log: Arc<AuditLog>,
+    pub(crate) entire_graph_limits: EntireGraphLimits,
+    pub(crate) query_timeout_ms: u64,
+    pub(crate) reload_timeout_ms: u64,
+    pub(crate) idempotency: Arc<IdempotencyStore>,
 }
 
+// Limits for the DuckDB load, initially from NODE_LIMIT/EDGE_LIMIT/MIN_DEGREE_CENTRALITY
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct LoadLimits {
+    node_limit: usize,
+    edge_limit: usize,
+    min_degree: f64,
+}
+
+impl LoadLimits {
+    fn from_env() -> Self {
+        Self {
+            node_limit: std::env::var("NODE_LIMIT")
+                .unwrap_or_else(|_| "1500".to_string())
+                .parse::<usize>()
+                .unwrap_or(1500),
+            edge_limit: std::env::var("EDGE_LIMIT")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse::<usize>()
+                .unwrap_or(5000),
+            min_degree: std::env::var("MIN_DEGREE_CENTRALITY")
+                .unwrap_or_else(|_| "0.001".to_string())
+                .parse::<f64>()
+                .unwrap_or(0.001),
+        }
+    }
+}
+
+// Largest graph the unfiltered entire_graph query will materialize, from
+// ENTIRE_GRAPH_MAX_NODES/ENTIRE_GRAPH_MAX_EDGES
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EntireGraphLimits {
+    max_nodes: usize,
+    max_edges: usize,
+}
+
+impl EntireGraphLimits {
+    fn from_env() -> Self {
+        Self {
+            max_nodes: std::env::var("ENTIRE_GRAPH_MAX_NODES")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(500_000),
+            max_edges: std::env::var("ENTIRE_GRAPH_MAX_EDGES")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(2_000_000),
+        }
+    }
+    
+    fn check(&self, nodes: usize, edges: usize) -> Result<(), GraphTooLarge> {
+        if nodes > self.max_nodes || edges > self.max_edges {
+            return Err(GraphTooLarge { nodes, edges, limits: *self });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Graph has {nodes} nodes and {edges} edges, over the entire_graph limit of {} nodes and {} edges; use a filtered query instead", limits.max_nodes, limits.max_edges)]
+struct GraphTooLarge {
+    nodes: usize,
+    edges: usize,
+    limits: EntireGraphLimits,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Query exceeded the {timeout_ms} ms FalkorDB timeout; narrow the query or raise FALKORDB_QUERY_TIMEOUT_MS")]
+struct QueryTimedOut {
+    timeout_ms: u64,
+}
+
+// Run a query bounded by FALKORDB_QUERY_TIMEOUT_MS (0 leaves it unbounded).
+// FalkorDB aborts an overrunning query with "Query timed out".
+async fn run_query<'a>(
+    graph: &'a mut AsyncGraph,
+    query: &'a str,
+    timeout_ms: u64,
+) -> anyhow::Result<QueryResult<LazyResultSet<'a>>> {
+    let mut builder = graph.query(query);
+    if timeout_ms > 0 {
+        builder = builder.with_timeout(timeout_ms as i64);
+    }
+    builder.execute().await.map_err(|e| {
+        if e.to_string().to_lowercase().contains("timed out") {
+            QueryTimedOut { timeout_ms }.into()
+        } else {
+            e.into()
+        }
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct LoadLimitsUpdate {
+    node_limit: Option<usize>,
+    edge_limit: Option<usize>,
+    min_degree: Option<f64>,
+}
+
+// Node and edge types accepted from the ingestion webhook, from ALLOWED_NODE_TYPES and
+// ALLOWED_EDGE_TYPES ("Entity,Episodic"). An unset list accepts every type.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TypeAllowlist {
+    node_types: Option<std::collections::HashSet<String>>,
+    edge_types: Option<std::collections::HashSet<String>>,
+}
+
+impl TypeAllowlist {
+    fn from_env() -> Self {
+        let parse = |var: &str| {
+            let types: std::collections::HashSet<String> = std::env::var(var)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect();
+            (!types.is_empty()).then_some(types)
+        };
+        Self { node_types: parse("ALLOWED_NODE_TYPES"), edge_types: parse("ALLOWED_EDGE_TYPES") }
+    }
+    
+    // Drop nodes and edges of unlisted types, along with edges touching a dropped node.
+    // Returns the accepted entities and how many of each were rejected.
+    fn filter(&self, nodes: Vec<Node>, edges: Vec<Edge>) -> (Vec<Node>, Vec<Edge>, usize, usize) {
+        let (nodes, rejected_nodes): (Vec<Node>, Vec<Node>) = nodes.into_iter()
+            .partition(|n| self.node_types.as_ref().is_none_or(|types| types.contains(&n.node_type)));
+        let rejected_ids: std::collections::HashSet<&str> = rejected_nodes.iter().map(|n| n.id.as_str()).collect();
+        let (edges, rejected_edges): (Vec<Edge>, Vec<Edge>) = edges.into_iter().partition(|e| {
+            self.edge_types.as_ref().is_none_or(|types| types.contains(&e.edge_type))
+                && !rejected_ids.contains(e.from.as_str())
+                && !rejected_ids.contains(e.to.as_str())
+        });
+        
+        for node in &rejected_nodes {
+            warn!("Dropped webhook node {} with disallowed type {}", node.id, node.node_type);
+        }
+        for edge in &rejected_edges {
+            warn!("Dropped webhook edge {}->{} of type {}", edge.from, edge.to, edge.edge_type);
+        }
+        (nodes, edges, rejected_nodes.len(), rejected_edges.len())
+    }
+}
+
+// Node properties to use as the display label instead of `name`, per node type from
+// NODE_LABEL_FIELDS ("Document:title,Task:summary"). Nodes missing the property keep `name`.
+// Labels are cut to LABEL_MAX_LENGTH characters (default 50).
+#[derive(Debug, Clone)]
+pub(crate) struct LabelFields {
+    default_field: Option<String>, // applies to every node type, from `label_field`
+    per_type: HashMap<String, String>,
+    max_len: usize,
+}
+
+impl LabelFields {
+    fn from_env() -> Self {
+        let per_type = std::env::var("NODE_LABEL_FIELDS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|entry| entry.split_once(':'))
+            .map(|(node_type, field)| (node_type.trim().to_string(), field.trim().to_string()))
+            .filter(|(node_type, field)| !node_type.is_empty() && !field.is_empty())
+            .collect();
+        let max_len = std::env::var("LABEL_MAX_LENGTH")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&len| len > 0)
+            .unwrap_or(DEFAULT_LABEL_MAX_LENGTH);
+        Self { default_field: None, per_type, max_len }
+    }
+    
+    // The per-request `label_field` takes precedence over the per-type fields
+    fn with_override(&self, field: Option<&str>) -> Self {
+        let mut fields = self.clone();
+        if let Some(field) = field.map(str::trim).filter(|f| !f.is_empty()) {
+            fields.default_field = Some(field.to_string());
+        }
+        fields
+    }
+    
+    fn is_empty(&self) -> bool {
+        self.default_field.is_none() && self.per_type.is_empty()
+    }
+    
+    fn label_for(&self, node_type: &str, props: &HashMap<String, serde_json::Value>) -> Option<String> {
+        let field = self.default_field.as_ref().or_else(|| self.per_type.get(node_type))?;
+        match props.get(field)? {
+            serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+    
+    fn apply(&self, data: &mut GraphData) {
+        for node in &mut data.nodes {
+            if let Some(label) = self.label_for(&node.node_type, &node.properties) {
+                node.label = label;
+            }
+            node.label = truncate_string(&node.label, self.max_len);
+        }
+    }
+}
+
+const DEFAULT_LABEL_MAX_LENGTH: usize = 50;
+
 #[derive(Clone)]
 struct CacheConfig {
     enabled: bool,
@@ -81,13 +317,160 @@ impl CacheStrategy {
 struct ArrowCache {
     nodes_batch: RecordBatch,
     edges_batch: RecordBatch,
-    nodes_bytes: Bytes,
-    edges_bytes: Bytes,
+    nodes_bytes: PrecompressedBytes,
+    edges_bytes: PrecompressedBytes,
     nodes_etag: String,
     edges_etag: String,
     timestamp: std::time::Instant,
 }
 
+/// Serialized Arrow bytes with gzip and zstd variants computed once per refresh,
+/// so cache hits don't recompress identical bytes on every request
+#[derive(Clone)]
+struct PrecompressedBytes {
+    raw: Bytes,
+    gzip: Option<Bytes>,
+    zstd: Option<Bytes>,
+}
+
+impl PrecompressedBytes {
+    /// Compress on the blocking pool; call without holding the Arrow cache lock
+    async fn compress(raw: Bytes) -> Self {
+        let fallback = raw.clone();
+        tokio::task::spawn_blocking(move || Self::compress_blocking(raw))
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Arrow compression task failed, serving uncompressed: {}", e);
+                Self::uncompressed(fallback)
+            })
+    }
+    
+    /// Raw bytes only, until `compress` produces the variants
+    fn uncompressed(raw: Bytes) -> Self {
+        Self { raw, gzip: None, zstd: None }
+    }
+    
+    fn is_compressed(&self) -> bool {
+        self.gzip.is_some() || self.zstd.is_some()
+    }
+    
+    fn compress_blocking(raw: Bytes) -> Self {
+        let gzip = {
+            use std::io::Write;
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&raw).and_then(|_| encoder.finish())
+        };
+        let zstd = zstd::bulk::compress(&raw, 3);
+        if let Err(e) = gzip.as_ref().and(zstd.as_ref()) {
+            warn!("Failed to precompress Arrow bytes, serving uncompressed: {}", e);
+        }
+        Self {
+            raw,
+            gzip: gzip.ok().map(Bytes::from),
+            zstd: zstd.ok().map(Bytes::from),
+        }
+    }
+    
+    /// Content-Encoding of the best variant the client accepts
+    fn encoding_for(&self, headers: &HeaderMap) -> Option<&'static str> {
+        let accepted = headers
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        if self.zstd.is_some() && accepts_encoding(accepted, "zstd") {
+            Some("zstd")
+        } else if self.gzip.is_some() && accepts_encoding(accepted, "gzip") {
+            Some("gzip")
+        } else {
+            None
+        }
+    }
+    
+    /// Pick the best variant the client accepts, with its Content-Encoding
+    fn for_request(&self, headers: &HeaderMap) -> (Option<&'static str>, Bytes) {
+        let encoding = self.encoding_for(headers);
+        let bytes = match encoding {
+            Some("zstd") => self.zstd.clone(),
+            Some("gzip") => self.gzip.clone(),
+            _ => None,
+        };
+        (encoding, bytes.unwrap_or_else(|| self.raw.clone()))
+    }
+}
+
+// A variant's ETag: the raw bytes' ETag marked with the Content-Encoding, since
+// the encodings are different representations
+fn encoded_etag(etag: &str, encoding: Option<&str>) -> String {
+    match (encoding, etag.strip_suffix('"')) {
+        (Some(encoding), Some(open)) => format!("{}-{}\"", open, encoding),
+        _ => etag.to_string(),
+    }
+}
+
+// Whether an Accept-Encoding header allows `encoding` (ignoring entries with q=0)
+fn accepts_encoding(accept_encoding: &str, encoding: &str) -> bool {
+    accept_encoding.split(',').any(|entry| {
+        let mut parts = entry.split(';').map(str::trim);
+        let name = parts.next().unwrap_or("");
+        let rejected = parts.any(|p| {
+            p.strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q == 0.0)
+        });
+        name.eq_ignore_ascii_case(encoding) && !rejected
+    })
+}
+
+// Whether If-None-Match names `etag`
+fn client_has_etag(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|client_etag| client_etag == etag)
+}
+
+// Serve the variant of `bytes` the client accepts under that variant's ETag, or
+// 304 when the client already holds it
+fn arrow_bytes_response(
+    bytes: &PrecompressedBytes,
+    etag: &str,
+    schema: &'static str,
+    cache_hit: bool,
+    headers: &HeaderMap,
+    ttl_seconds: u64,
+) -> Response<Body> {
+    let (encoding, body) = bytes.for_request(headers);
+    let etag = encoded_etag(etag, encoding);
+    let cache_control = format!("public, max-age={}", ttl_seconds);
+    if client_has_etag(headers, &etag) {
+        debug!("Client has current version (ETag match)");
+        return Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, &etag)
+            .header("Cache-Control", cache_control)
+            .header("Vary", "Accept-Encoding")
+            .body(Body::empty())
+            .unwrap();
+    }
+    
+    // A set Content-Encoding makes CompressionLayer pass the body through untouched
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/vnd.apache.arrow.stream")
+        .header("X-Arrow-Schema", schema)
+        .header("X-Cache-Hit", if cache_hit { "true" } else { "false" })
+        .header(header::ETAG, &etag)
+        .header("Cache-Control", cache_control)
+        .header("Vary", "Accept-Encoding");
+    if let Some(encoding) = encoding {
+        response = response.header(header::CONTENT_ENCODING, encoding);
+    }
+    response.body(Body::from(body)).unwrap()
+}
+
+// Share of the cached rows an update may add before we fall back to a full rebuild
+const INCREMENTAL_ARROW_MAX_RATIO: f64 = 0.1;
+
 impl ArrowCache {
     fn generate_etag(data: &[u8]) -> String {
         let mut hasher = Sha256::new();
@@ -95,6 +478,130 @@ impl ArrowCache {
         let hash = hasher.finalize();
         format!("W/\"{}\"", general_purpose::URL_SAFE_NO_PAD.encode(&hash[..8]))
     }
+    
+    /// Append the rows of an update to the cached batches. Returns false when the
+    /// update can't be applied incrementally and the cache needs a full rebuild.
+    /// The new bytes are left uncompressed for `compress_arrow_cache`.
+    async fn apply_update(&mut self, store: &DuckDBStore, update: &GraphUpdate) -> anyhow::Result<bool> {
+        if !matches!(update.operation, UpdateOperation::AddNodes | UpdateOperation::AddEdges) {
+            return Ok(false);
+        }
+        
+        let new_nodes = update.nodes.as_deref().unwrap_or(&[]);
+        let new_edges = update.edges.as_deref().unwrap_or(&[]);
+        let cached_rows = (self.nodes_batch.num_rows() + self.edges_batch.num_rows()).max(1);
+        if (new_nodes.len() + new_edges.len()) as f64 / cached_rows as f64 > INCREMENTAL_ARROW_MAX_RATIO {
+            return Ok(false);
+        }
+        
+        // Positions of the cached nodes; edges reference nodes by row position
+        let mut node_positions: HashMap<String, u32> = string_column(&self.nodes_batch, "id")?
+            .iter()
+            .enumerate()
+            .filter_map(|(i, id)| id.map(|id| (id.to_string(), i as u32)))
+            .collect();
+        
+        // Replaced nodes get a new idx, which reorders the batch
+        if new_nodes.iter().any(|n| node_positions.contains_key(&n.id)) {
+            return Ok(false);
+        }
+        
+        if !new_nodes.is_empty() {
+            let ids: Vec<String> = new_nodes.iter().map(|n| n.id.clone()).collect();
+            let delta = store.get_nodes_as_arrow_by_ids(&ids).await?;
+            let base = self.nodes_batch.num_rows() as u32;
+            for (i, id) in string_column(&delta, "id")?.iter().enumerate() {
+                if let Some(id) = id {
+                    node_positions.insert(id.to_string(), base + i as u32);
+                }
+            }
+            self.nodes_batch = arrow::compute::concat_batches(&self.nodes_batch.schema(), [&self.nodes_batch, &delta])?;
+            let bytes = ArrowConverter::record_batch_to_bytes(&self.nodes_batch)?;
+            self.nodes_etag = Self::generate_etag(&bytes);
+            self.nodes_bytes = PrecompressedBytes::uncompressed(bytes);
+        }
+        
+        // Edges are INSERT OR IGNORE, so skip ones the cache already holds
+        let sources = string_column(&self.edges_batch, "source")?;
+        let targets = string_column(&self.edges_batch, "target")?;
+        let types = string_column(&self.edges_batch, "edge_type")?;
+        let cached_edges: std::collections::HashSet<(&str, &str, &str)> = (0..self.edges_batch.num_rows())
+            .map(|i| (sources.value(i), targets.value(i), types.value(i)))
+            .collect();
+        let fresh_edges: Vec<&Edge> = new_edges.iter()
+            .filter(|e| !cached_edges.contains(&(e.from.as_str(), e.to.as_str(), e.edge_type.as_str())))
+            .collect();
+        
+        if !fresh_edges.is_empty() {
+            let delta = store.get_edges_as_arrow_for(&fresh_edges, &node_positions).await?;
+            self.edges_batch = arrow::compute::concat_batches(&self.edges_batch.schema(), [&self.edges_batch, &delta])?;
+            let bytes = ArrowConverter::record_batch_to_bytes(&self.edges_batch)?;
+            self.edges_etag = Self::generate_etag(&bytes);
+            self.edges_bytes = PrecompressedBytes::uncompressed(bytes);
+        }
+        
+        self.timestamp = std::time::Instant::now();
+        Ok(true)
+    }
+}
+
+fn string_column<'a>(batch: &'a RecordBatch, name: &str) -> anyhow::Result<&'a arrow::array::StringArray> {
+    batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<arrow::array::StringArray>())
+        .ok_or_else(|| anyhow::anyhow!("Missing string column '{}'", name))
+}
+
+// Apply a processed update to the Arrow cache, invalidating it if that isn't possible
+async fn apply_update_to_arrow_cache(state: &AppState, update: &GraphUpdate) {
+    let mut arrow_cache = state.arrow_cache.write().await;
+    if let Some(cached) = arrow_cache.as_mut() {
+        let start = std::time::Instant::now();
+        match cached.apply_update(&state.duckdb_store, update).await {
+            Ok(true) => debug!("Arrow cache updated incrementally in {:?}", start.elapsed()),
+            Ok(false) => {
+                debug!("Update too large for incremental Arrow update, invalidating cache");
+                *arrow_cache = None;
+            }
+            Err(e) => {
+                error!("Incremental Arrow update failed, invalidating cache: {}", e);
+                *arrow_cache = None;
+            }
+        }
+    }
+    drop(arrow_cache);
+    compress_arrow_cache(state).await;
+}
+
+// Compress cached Arrow bytes left uncompressed, without holding the cache lock
+// while compressing. Bytes replaced in the meantime are left to their writer.
+async fn compress_arrow_cache(state: &AppState) {
+    let pending = {
+        let cache = state.arrow_cache.read().await;
+        let Some(cached) = cache.as_ref() else { return };
+        let pending = |bytes: &PrecompressedBytes, etag: &str| {
+            (!bytes.is_compressed()).then(|| (bytes.raw.clone(), etag.to_string()))
+        };
+        (pending(&cached.nodes_bytes, &cached.nodes_etag), pending(&cached.edges_bytes, &cached.edges_etag))
+    };
+    let nodes = match pending.0 {
+        Some((raw, etag)) => Some((PrecompressedBytes::compress(raw).await, etag)),
+        None => None,
+    };
+    let edges = match pending.1 {
+        Some((raw, etag)) => Some((PrecompressedBytes::compress(raw).await, etag)),
+        None => None,
+    };
+    
+    let mut cache = state.arrow_cache.write().await;
+    if let Some(cached) = cache.as_mut() {
+        if let Some((bytes, _)) = nodes.filter(|(_, etag)| *etag == cached.nodes_etag) {
+            cached.nodes_bytes = bytes;
+        }
+        if let Some((bytes, _)) = edges.filter(|(_, etag)| *etag == cached.edges_etag) {
+            cached.edges_bytes = bytes;
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -120,6 +627,9 @@ struct Edge {
     to: String,
     edge_type: String,
     weight: f64,
+    // Relationship properties such as `fact`, `created_at` and `episodes`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    properties: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -149,6 +659,99 @@ struct QueryParams {
     limit: Option<usize>,
     offset: Option<usize>,
     search: Option<String>,
+    node_type: Option<String>,
+    created_after: Option<String>,   // ISO-8601 / RFC3339
+    created_before: Option<String>,  // ISO-8601 / RFC3339
+    fields: Option<String>,          // comma-separated node properties to keep
+    label_field: Option<String>,     // node property to label by, falling back to name
+    edge_types: Option<String>,      // comma-separated relationship types to keep
+    min_degree: Option<f64>,         // overrides the default degree_centrality floors
+}
+
+// Optional filters applied on top of a visualize query type
+#[derive(Debug, Default, Clone)]
+struct QueryFilters {
+    node_type: Option<String>,
+    created_after: Option<String>,
+    created_before: Option<String>,
+    edge_types: Vec<String>, // empty means every relationship type
+    min_degree: Option<f64>,
+}
+
+impl QueryFilters {
+    fn from_params(params: &QueryParams) -> Self {
+        Self {
+            node_type: params.node_type.clone().filter(|t| !t.is_empty()),
+            created_after: params.created_after.clone().filter(|t| !t.is_empty()),
+            created_before: params.created_before.clone().filter(|t| !t.is_empty()),
+            edge_types: params.edge_types.as_deref()
+                .map(|types| types.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from).collect())
+                .unwrap_or_default(),
+            min_degree: params.min_degree,
+        }
+    }
+    
+    fn is_empty(&self) -> bool {
+        self.node_type.is_none() && self.created_after.is_none() && self.created_before.is_none()
+            && self.edge_types.is_empty() && self.min_degree.is_none()
+    }
+    
+    fn validate(&self) -> Result<(), String> {
+        for (name, value) in [("created_after", &self.created_after), ("created_before", &self.created_before)] {
+            if let Some(ts) = value {
+                if chrono::DateTime::parse_from_rfc3339(ts).is_err() {
+                    return Err(format!("Invalid {} timestamp '{}': expected RFC3339", name, ts));
+                }
+            }
+        }
+        if let Some(min_degree) = self.min_degree {
+            if !min_degree.is_finite() || min_degree < 0.0 {
+                return Err(format!("Invalid min_degree {}: must be a non-negative number", min_degree));
+            }
+        }
+        Ok(())
+    }
+    
+    // high_degree's built-in `degree_centrality > floor` for `var`, dropped when min_degree is given
+    fn default_degree_floor(&self, var: &str, floor: f64) -> String {
+        if self.min_degree.is_some() {
+            return String::new();
+        }
+        format!(" AND EXISTS({var}.degree_centrality) AND {var}.degree_centrality > {floor}")
+    }
+    
+    // Extra `AND ...` conditions restricting the node bound to `var`
+    fn node_conditions(&self, var: &str) -> String {
+        let mut conditions = String::new();
+        if let Some(ref node_type) = self.node_type {
+            conditions.push_str(&format!(
+                " AND COALESCE({var}.type, labels({var})[0]) = '{}'",
+                escape_cypher_string(node_type)
+            ));
+        }
+        if let Some(ref after) = self.created_after {
+            conditions.push_str(&format!(" AND {var}.created_at >= '{}'", escape_cypher_string(after)));
+        }
+        if let Some(ref before) = self.created_before {
+            conditions.push_str(&format!(" AND {var}.created_at <= '{}'", escape_cypher_string(before)));
+        }
+        if let Some(min_degree) = self.min_degree {
+            conditions.push_str(&format!(" AND COALESCE({var}.degree_centrality, 0.0) >= {}", min_degree));
+        }
+        conditions
+    }
+    
+    // Extra `AND ...` condition restricting the relationship bound to `var`
+    fn edge_conditions(&self, var: &str) -> String {
+        if self.edge_types.is_empty() {
+            return String::new();
+        }
+        let types = self.edge_types.iter()
+            .map(|t| format!("'{}'", escape_cypher_string(t)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(" AND type({var}) IN [{types}]")
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -180,6 +783,25 @@ struct UpdateSummaryRequest {
     summary: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct BulkSummaryUpdateRequest {
+    updates: Vec<SummaryUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SummaryUpdate {
+    uuid: String,
+    summary: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SummaryUpdateResult {
+    uuid: String,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct NodeUpdateResponse {
     uuid: String,
@@ -187,6 +809,27 @@ struct NodeUpdateResponse {
     summary: String,
 }
 
+// Build the FalkorDB URL, adding `user:pass@` when credentials are configured
+fn falkordb_connection_string(host: &str, port: &str, username: Option<&str>, password: Option<&str>) -> String {
+    let userinfo = match (username, password) {
+        (Some(user), Some(pass)) => format!("{}:{}@", encode_userinfo(user), encode_userinfo(pass)),
+        (Some(user), None) => format!("{}@", encode_userinfo(user)),
+        (None, Some(pass)) => format!(":{}@", encode_userinfo(pass)),
+        (None, None) => String::new(),
+    };
+    format!("redis://{}{}:{}", userinfo, host, port)
+}
+
+// Percent-encode everything except RFC 3986 unreserved characters
+fn encode_userinfo(value: &str) -> String {
+    value.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
@@ -199,34 +842,168 @@ async fn main() -> anyhow::Result<()> {
     let falkor_port = std::env::var("FALKORDB_PORT").unwrap_or_else(|_| "6379".to_string());
     let graph_name = std::env::var("GRAPH_NAME").unwrap_or_else(|_| "graphiti_migration".to_string());
     
-    let connection_string = format!("redis://{}:{}", falkor_host, falkor_port);
-    info!("Connecting to FalkorDB at {}", connection_string);
+    let falkor_username = std::env::var("FALKORDB_USERNAME").ok().filter(|u| !u.is_empty());
+    let falkor_password = std::env::var("FALKORDB_PASSWORD").ok().filter(|p| !p.is_empty());
     
-    let connection_info: FalkorConnectionInfo = connection_string
-        .as_str()
-        .try_into()
-        .expect("Invalid connection info");
+    let connection_string = falkordb_connection_string(
+        &falkor_host,
+        &falkor_port,
+        falkor_username.as_deref(),
+        falkor_password.as_deref(),
+    );
+    info!(
+        "Connecting to FalkorDB at redis://{}:{}{}",
+        falkor_host,
+        falkor_port,
+        if falkor_password.is_some() { " (authenticated)" } else { "" }
+    );
     
-    let client = FalkorClientBuilder::new_async()
-        .with_connection_info(connection_info)
-        .build()
-        .await
-        .expect("Failed to build FalkorDB client");
+    let falkor_pool = falkor_pool::create_pool(connection_string, graph_name.clone())
+        .expect("Failed to create FalkorDB connection pool");
+    // Open the first connection now so a bad address fails at startup
+    drop(falkor_pool.get().await.expect("Failed to connect to FalkorDB"));
+    info!("FalkorDB connection pool ready (max {} connections)", falkor_pool.status().max_size);
     
     // Initialize DuckDB store
-    let duckdb_store = Arc::new(DuckDBStore::new().expect("Failed to create DuckDB store"));
+    let pending_edge_max_retries = std::env::var("PENDING_EDGE_MAX_RETRIES")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse::<u32>()
+        .unwrap_or(10);
+    let pending_edge_stale_secs = std::env::var("PENDING_EDGE_STALE_SECS")
+        .unwrap_or_else(|_| "300".to_string())
+        .parse::<i64>()
+        .unwrap_or(300);
+    // Link strengths by edge type, overriding the built-in table
+    let edge_strengths = match std::env::var("EDGE_STRENGTHS_PATH") {
+        Ok(path) => match EdgeStrengths::from_file(&path) {
+            Ok(edge_strengths) => {
+                info!("Loaded edge strengths from {}", path);
+                edge_strengths
+            }
+            Err(e) => {
+                error!("Failed to load edge strengths from {}: {}", path, e);
+                EdgeStrengths::default()
+            }
+        },
+        Err(_) => EdgeStrengths::default(),
+    };
+    // Per-type node base size and degree multiplier
+    let node_sizing = match std::env::var("NODE_SIZING_PATH") {
+        Ok(path) => match NodeSizing::from_file(&path) {
+            Ok(node_sizing) => {
+                info!("Loaded node sizing from {}", path);
+                node_sizing
+            }
+            Err(e) => {
+                error!("Failed to load node sizing from {}: {}", path, e);
+                NodeSizing::default()
+            }
+        },
+        Err(_) => NodeSizing::default(),
+    };
+    // Nodes without created_at are dated back from this instant, one day per index
+    let synthetic_timestamp_anchor = std::env::var("SYNTHETIC_TIMESTAMP_ANCHOR").ok()
+        .and_then(|v| match chrono::DateTime::parse_from_rfc3339(&v) {
+            Ok(anchor) => Some(anchor.with_timezone(&chrono::Utc)),
+            Err(e) => {
+                warn!("Ignoring invalid SYNTHETIC_TIMESTAMP_ANCHOR '{}': {}", v, e);
+                None
+            }
+        });
+    let duckdb_store = Arc::new(
+        DuckDBStore::new()
+            .expect("Failed to create DuckDB store")
+            .with_pending_edge_limits(pending_edge_max_retries, pending_edge_stale_secs)
+            .with_synthetic_timestamp_anchor(synthetic_timestamp_anchor)
+            .with_edge_strengths(edge_strengths)
+            .with_node_sizing(node_sizing),
+    );
+    
+    // Load custom color map if configured
+    if let Ok(color_map_path) = std::env::var("COLOR_MAP_PATH") {
+        match ColorMap::from_file(&color_map_path) {
+            Ok(color_map) => {
+                info!("Loaded color map from {}", color_map_path);
+                duckdb_store.set_color_map(color_map).await?;
+            }
+            Err(e) => error!("Failed to load color map from {}: {}", color_map_path, e),
+        }
+    }
     
     // Create update channel for real-time updates
     let (update_tx, _) = broadcast::channel::<GraphUpdate>(100);
     let (delta_tx, _) = broadcast::channel::<GraphDelta>(100);
+    let (event_tx, _) = broadcast::channel::<ServerEvent>(100);
     
     // Initialize delta tracker
-    let delta_tracker = Arc::new(DeltaTracker::new());
+    let delta_flush_ms = std::env::var("DELTA_FLUSH_INTERVAL_MS")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse::<u64>()
+        .unwrap_or(0);
+    let delta_tracker = Arc::new(
+        DeltaTracker::new().with_flush_interval(std::time::Duration::from_millis(delta_flush_ms)),
+    );
+    
+    // Broadcast deltas merged during bursts once per flush interval
+    if delta_flush_ms > 0 {
+        info!("Coalescing deltas with a {}ms flush interval", delta_flush_ms);
+        let delta_tracker = delta_tracker.clone();
+        let delta_tx = delta_tx.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(delta_tracker.flush_interval());
+            loop {
+                interval.tick().await;
+                if let Some(delta) = delta_tracker.flush_pending() {
+                    debug!("Flushing coalesced delta: {} nodes added, {} nodes updated, {} edges added",
+                        delta.nodes_added.len(), delta.nodes_updated.len(), delta.edges_added.len());
+                    let _ = delta_tx.send(delta);
+                }
+            }
+        });
+    }
     
     // Set up HTTP client for centrality service proxy
-    let http_client = Arc::new(reqwest::Client::new());
+    let connect_timeout_ms = std::env::var("CENTRALITY_CONNECT_TIMEOUT_MS")
+        .unwrap_or_else(|_| "2000".to_string())
+        .parse::<u64>()
+        .unwrap_or(2000);
+    let request_timeout_secs = std::env::var("CENTRALITY_REQUEST_TIMEOUT_SECS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse::<u64>()
+        .unwrap_or(30);
+    let http_client = Arc::new(
+        reqwest::Client::builder()
+            .connect_timeout(std::time::Duration::from_millis(connect_timeout_ms))
+            .timeout(std::time::Duration::from_secs(request_timeout_secs))
+            .build()
+            .expect("Failed to build HTTP client"),
+    );
+    // Computing every metric over the whole graph takes far longer than the other calls
+    let centrality_all_timeout_secs = std::env::var("CENTRALITY_ALL_TIMEOUT_SECS")
+        .unwrap_or_else(|_| "300".to_string())
+        .parse::<u64>()
+        .unwrap_or(300);
+    
+    // Stop sending requests to the centrality service after repeated failures
+    let breaker_threshold = std::env::var("CENTRALITY_BREAKER_THRESHOLD")
+        .unwrap_or_else(|_| "5".to_string())
+        .parse::<u32>()
+        .unwrap_or(5);
+    let breaker_cooldown_secs = std::env::var("CENTRALITY_BREAKER_COOLDOWN_SECS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse::<u64>()
+        .unwrap_or(30);
+    let centrality_breaker = Arc::new(CircuitBreaker::new(
+        breaker_threshold,
+        std::time::Duration::from_secs(breaker_cooldown_secs),
+    ));
     let centrality_url = std::env::var("CENTRALITY_SERVICE_URL")
         .unwrap_or_else(|_| "http://graphiti-centrality-rs:3003".to_string());
+    let search_url = std::env::var("SEARCH_SERVICE_URL").ok().filter(|url| !url.is_empty());
+    let auto_recompute_centrality = std::env::var("AUTO_RECOMPUTE_CENTRALITY")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
     
     // Load cache configuration from environment
     let cache_config = CacheConfig {
@@ -257,7 +1034,31 @@ async fn main() -> anyhow::Result<()> {
             info!("Initializing enhanced cache with Redis at: {}", redis_url);
             let redis_config = RedisConfig::from_url(redis_url);
             if let Ok(redis_pool) = redis_config.create_pool(Some(Runtime::Tokio1)) {
-                Some(Arc::new(EnhancedCache::new(redis_pool)))
+                let stale_ttl = std::env::var("CACHE_STALE_TTL_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse::<u64>()
+                    .unwrap_or(60);
+                let defaults = NegativeCacheConfig::default();
+                let negative_cache = NegativeCacheConfig {
+                    ttl: std::env::var("NEGATIVE_CACHE_TTL_SECONDS")
+                        .ok()
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .map(std::time::Duration::from_secs)
+                        .unwrap_or(defaults.ttl),
+                    false_positive_rate: std::env::var("NEGATIVE_CACHE_FP_RATE")
+                        .ok()
+                        .and_then(|v| v.parse::<f64>().ok())
+                        .filter(|rate| *rate > 0.0 && *rate < 1.0)
+                        .unwrap_or(defaults.false_positive_rate),
+                    ..defaults
+                };
+                let adaptive_ttl = AdaptiveTTL::from_env();
+                info!("Adaptive cache TTLs: {:?}", adaptive_ttl);
+                Some(Arc::new(
+                    EnhancedCache::new(redis_pool, negative_cache)
+                        .with_adaptive_ttl(adaptive_ttl)
+                        .with_stale_ttl(std::time::Duration::from_secs(stale_ttl)),
+                ))
             } else {
                 error!("Failed to create Redis pool, falling back to in-memory cache");
                 None
@@ -271,177 +1072,102 @@ async fn main() -> anyhow::Result<()> {
         None
     };
     
+    // Node exports above this many rows are streamed in pages when not cached
+    let arrow_stream_threshold = std::env::var("ARROW_STREAM_THRESHOLD")
+        .unwrap_or_else(|_| "50000".to_string())
+        .parse::<usize>()
+        .unwrap_or(50000);
+    
+    // Full graph:update WebSocket messages above this size are sent in chunks
+    let ws_max_message_bytes = std::env::var("WS_MAX_MESSAGE_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&bytes| bytes > 0)
+        .unwrap_or(4 * 1024 * 1024);
+    
+    // Per-query FalkorDB timeout for graph queries; 0 disables it
+    let query_timeout_ms = std::env::var("FALKORDB_QUERY_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(10_000);
+    
+    // Full reloads read the entire graph, so they get their own timeout; 0 (the default) disables it
+    let reload_timeout_ms = std::env::var("FALKORDB_RELOAD_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    
+    // Mutating routes require a bearer token when API_TOKEN is set
+    let api_token = std::env::var("API_TOKEN").ok().filter(|t| !t.is_empty());
+    if api_token.is_none() {
+        info!("API_TOKEN not set, mutating endpoints are unauthenticated");
+    }
+    
+    // Webhook payloads must carry an HMAC-SHA256 signature when WEBHOOK_SECRET is set
+    let webhook_secret = std::env::var("WEBHOOK_SECRET").ok().filter(|s| !s.is_empty());
+    
     let state = AppState {
-        client: Arc::new(client),
+        falkor_pool,
         graph_name: graph_name.clone(),
         graph_cache: Arc::new(DashMap::new()),
         duckdb_store: duckdb_store.clone(),
         update_tx: update_tx.clone(),
         delta_tx: delta_tx.clone(),
+        event_tx: event_tx.clone(),
         arrow_cache: Arc::new(RwLock::new(None)),
         delta_tracker: delta_tracker.clone(),
         http_client,
+        centrality_breaker,
         centrality_url,
+        centrality_all_timeout: std::time::Duration::from_secs(centrality_all_timeout_secs),
+        search_url,
+        auto_recompute_centrality,
         cache_config,
         enhanced_cache,
+        arrow_stream_threshold,
+        ws_max_message_bytes,
+        load_limits: Arc::new(RwLock::new(LoadLimits::from_env())),
+        api_token,
+        webhook_secret,
+        label_fields: LabelFields::from_env(),
+        type_allowlist: TypeAllowlist::from_env(),
+        audit_log: Arc::new(AuditLog::from_env()?),
+        entire_graph_limits: EntireGraphLimits::from_env(),
+        query_timeout_ms,
+        reload_timeout_ms,
+        idempotency: Arc::new(IdempotencyStore::from_env()),
     };
     
     // Load initial data into DuckDB with optimized separate queries
     {
-        // Read limits from environment variables
-        let node_limit = std::env::var("NODE_LIMIT")
-            .unwrap_or_else(|_| "1500".to_string())
-            .parse::<usize>()
-            .unwrap_or(1500);
-        
-        let edge_limit = std::env::var("EDGE_LIMIT")
-            .unwrap_or_else(|_| "5000".to_string())
-            .parse::<usize>()
-            .unwrap_or(5000);
-        
-        let min_degree = std::env::var("MIN_DEGREE_CENTRALITY")
-            .unwrap_or_else(|_| "0.001".to_string())
-            .parse::<f64>()
-            .unwrap_or(0.001);
+        let limits = *state.load_limits.read().await;
         
         info!("Loading initial graph data into DuckDB with limits - Nodes: {}, Edges: {}, Min Degree: {}", 
-              node_limit, edge_limit, min_degree);
+              limits.node_limit, limits.edge_limit, limits.min_degree);
         let prerender_start = std::time::Instant::now();
         
-        // Step 1: Load nodes first (much more efficient)
-        // If min_degree is 0, load ALL nodes without filtering
-        let nodes_query = if min_degree <= 0.0 {
-            format!(
-                "MATCH (n) RETURN n.uuid as id, n.name as name, COALESCE(n.type, labels(n)[0]) as label, COALESCE(n.degree_centrality, 0.0) as degree, n.created_at as created_at, n.summary as summary, n.pagerank_centrality as pagerank, n.betweenness_centrality as betweenness, n.eigenvector_centrality as eigenvector ORDER BY COALESCE(n.degree_centrality, 0.0) DESC LIMIT {}",
-                node_limit
-            )
-        } else {
-            format!(
-                "MATCH (n) WHERE EXISTS(n.degree_centrality) AND n.degree_centrality > {} RETURN n.uuid as id, n.name as name, COALESCE(n.type, labels(n)[0]) as label, n.degree_centrality as degree, n.created_at as created_at, n.summary as summary, n.pagerank_centrality as pagerank, n.betweenness_centrality as betweenness, n.eigenvector_centrality as eigenvector ORDER BY n.degree_centrality DESC LIMIT {}",
-                min_degree, node_limit
-            )
-        };
-        
-        let mut graph = state.client.select_graph(&graph_name);
-        let mut nodes_result = graph.query(&nodes_query).execute().await?;
-        let mut node_ids = Vec::new();
-        let mut nodes = Vec::new();
+        let client = state.falkor_pool.get().await?;
+        let (nodes, edges) = fetch_limited_graph(&client, &graph_name, &limits, &state.label_fields).await?;
         
-        while let Some(row) = nodes_result.data.next() {
-            if let Some(id) = row.get(0).and_then(|v| v.as_string()) {
-                node_ids.push(format!("'{}'", id));
-                
-                // Build properties map with real data
-                let mut properties = HashMap::new();
-                
-                // Add degree centrality
-                if let Some(degree) = row.get(3).and_then(|v| v.to_f64()) {
-                    properties.insert("degree_centrality".to_string(), serde_json::Value::from(degree));
-                }
-                
-                // Add created_at timestamp
-                if let Some(created) = row.get(4).and_then(|v| v.as_string()) {
-                    properties.insert("created_at".to_string(), serde_json::Value::String(created.to_string()));
-                }
-                
-                // Get summary from row index 5
-                let summary = row.get(5).and_then(|v| v.as_string()).map(|s| s.to_string());
-                
-                // Add pagerank centrality from row index 6
-                if let Some(pagerank) = row.get(6).and_then(|v| v.to_f64()) {
-                    properties.insert("pagerank_centrality".to_string(), serde_json::Value::from(pagerank));
-                }
-                
-                // Add betweenness centrality from row index 7
-                if let Some(betweenness) = row.get(7).and_then(|v| v.to_f64()) {
-                    properties.insert("betweenness_centrality".to_string(), serde_json::Value::from(betweenness));
-                }
-                
-                // Add eigenvector centrality from row index 8
-                if let Some(raw_eigenvector) = row.get(8) {
-                    println!("DEBUG: raw eigenvector value: {:?}", raw_eigenvector);
-                    if let Some(eigenvector) = raw_eigenvector.to_f64() {
-                        println!("DEBUG: converted eigenvector to f64: {}", eigenvector);
-                        properties.insert("eigenvector_centrality".to_string(), serde_json::Value::from(eigenvector));
-                    } else {
-                        println!("DEBUG: failed to convert eigenvector to f64: {:?}", raw_eigenvector);
-                    }
-                } else {
-                    println!("DEBUG: no eigenvector value at index 8");
-                }
-                
-                // Add name to properties so frontend can access it
-                let name = row.get(1).and_then(|v| v.as_string()).map_or("", |v| v).to_string();
-                properties.insert("name".to_string(), serde_json::Value::String(name.clone()));
-                
-                nodes.push(Node {
-                    id: id.to_string(),
-                    label: name, // Use name as the display label
-                    node_type: row.get(2).and_then(|v| v.as_string()).map_or("Unknown", |v| v).to_string(),
-                    summary,
-                    properties,
-                });
+        let initial_data = GraphData { 
+            nodes: nodes.clone(),
+            edges: edges.clone(),
+            stats: GraphStats {
+                total_nodes: nodes.len(),
+                total_edges: edges.len(),
+                node_types: HashMap::new(),
+                avg_degree: 0.0,
+                max_degree: 0.0,
             }
-        }
+        };
         
-        // Step 2: Load edges only for loaded nodes - paginate to avoid FalkorDB's 10K result limit
-        let mut edges = Vec::new();
-        let batch_size: usize = 5000;
-        let mut offset: usize = 0;
-        
-        loop {
-            let edges_query = format!(
-                "MATCH (n)-[r]->(m) WHERE n.uuid IN [{}] AND m.uuid IN [{}] RETURN n.uuid, type(r), m.uuid, r.weight SKIP {} LIMIT {}",
-                node_ids.join(","),
-                node_ids.join(","),
-                offset,
-                batch_size.min((edge_limit as usize).saturating_sub(offset))
-            );
-            
-            info!("Fetching edges batch: offset={}, limit={}", offset, batch_size);
-            
-            let mut graph = state.client.select_graph(&graph_name);
-            let mut edges_result = graph.query(&edges_query).execute().await?;
-            let mut batch_count = 0;
-            
-            while let Some(row) = edges_result.data.next() {
-                batch_count += 1;
-                edges.push(Edge {
-                    from: row.get(0).and_then(|v| v.as_string()).map_or("", |v| v).to_string(),
-                    to: row.get(2).and_then(|v| v.as_string()).map_or("", |v| v).to_string(),
-                    edge_type: row.get(1).and_then(|v| v.as_string()).map_or("", |v| v).to_string(),
-                    weight: row.get(3).and_then(|v| v.to_f64()).unwrap_or(1.0),
-                });
-            }
-            
-            info!("Batch fetched {} edges", batch_count);
-            
-            if batch_count < batch_size || edges.len() >= edge_limit as usize {
-                break;
-            }
-            
-            offset += batch_size;
-        }
-        
-        info!("Total fetched {} edges from FalkorDB", edges.len());
-        
-        let initial_data = GraphData { 
-            nodes: nodes.clone(),
-            edges: edges.clone(),
-            stats: GraphStats {
-                total_nodes: nodes.len(),
-                total_edges: edges.len(),
-                node_types: HashMap::new(),
-                avg_degree: 0.0,
-                max_degree: 0.0,
-            }
-        };
-        
-        duckdb_store.load_initial_data(initial_data.nodes.clone(), initial_data.edges.clone()).await?;
-        info!("Initial data loaded: {} nodes, {} edges", initial_data.nodes.len(), initial_data.edges.len());
+        duckdb_store.load_initial_data(initial_data.nodes.clone(), initial_data.edges.clone()).await?;
+        info!("Initial data loaded: {} nodes, {} edges", initial_data.nodes.len(), initial_data.edges.len());
         
         // Initialize delta tracker with initial data
-        let initial_delta = delta_tracker.compute_delta(initial_data.nodes.clone(), initial_data.edges.clone()).await;
+        let initial_delta = delta_tracker.record(
+            delta_tracker.compute_delta(initial_data.nodes.clone(), initial_data.edges.clone()).await,
+        );
         info!("Delta tracker initialized with sequence: {}", initial_delta.sequence);
         
         // Prerender Arrow format for faster initial load
@@ -461,8 +1187,8 @@ async fn main() -> anyhow::Result<()> {
                     edges_batch: edges_batch.clone(),
                     nodes_etag: ArrowCache::generate_etag(&nodes_bytes),
                     edges_etag: ArrowCache::generate_etag(&edges_bytes),
-                    nodes_bytes,
-                    edges_bytes,
+                    nodes_bytes: PrecompressedBytes::compress(nodes_bytes).await,
+                    edges_bytes: PrecompressedBytes::compress(edges_bytes).await,
                     timestamp: std::time::Instant::now(),
                 };
                 
@@ -487,25 +1213,28 @@ async fn main() -> anyhow::Result<()> {
     });
     
     // Spawn background task for monitoring database changes
-    let client_clone = state.client.clone();
-    let cache_clone = state.graph_cache.clone();
-    let arrow_cache_clone = state.arrow_cache.clone();
-    let update_tx_clone = update_tx.clone();
-    let delta_tx_clone = delta_tx.clone();
-    let delta_tracker_clone = state.delta_tracker.clone();
-    let cache_config_clone = state.cache_config.clone();
+    let monitor_state = state.clone();
+    let falkor_pool_clone = state.falkor_pool.clone();
     let graph_name_clone = graph_name.clone();
-    let store_clone = state.duckdb_store.clone();
+    let label_fields_clone = state.label_fields.clone();
+    let entire_graph_limits = state.entire_graph_limits;
+    let reload_timeout_ms = state.reload_timeout_ms;
+    let mut debouncer = ReloadDebouncer::from_env();
     
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
-        let mut last_node_count = 0;
-        let mut last_edge_count = 0;
-        let mut last_centrality_sum = 0.0;
         
         loop {
             interval.tick().await;
             
+            let client_clone = match falkor_pool_clone.get().await {
+                Ok(client) => client,
+                Err(e) => {
+                    warn!("Change monitor could not get a FalkorDB connection: {}", e);
+                    continue;
+                }
+            };
+            
             // Check database for changes
             {
                 // Get node count first
@@ -548,89 +1277,83 @@ async fn main() -> anyhow::Result<()> {
                 };
                 
                 // Detect changes (including centrality updates)
-                let centrality_changed = (last_centrality_sum > 0.0 && (current_centrality_sum - last_centrality_sum).abs() > 0.001) ||
-                                        (last_centrality_sum == 0.0 && current_centrality_sum > 0.0);
-                
-                if last_node_count > 0 && (current_node_count != last_node_count || 
-                                           current_edge_count != last_edge_count || 
-                                           centrality_changed) {
-                    info!("Graph changed: nodes {} -> {}, edges {} -> {}", 
-                          last_node_count, current_node_count,
-                          last_edge_count, current_edge_count);
-                    
+                let counts = GraphCounts {
+                    nodes: current_node_count,
+                    edges: current_edge_count,
+                    centrality_sum: current_centrality_sum,
+                };
+                if debouncer.observe(counts, std::time::Instant::now()) {
                     // RELOAD DATA FROM FALKORDB INTO DUCKDB
                     info!("Auto-reloading DuckDB from FalkorDB due to detected changes");
                     
                     // Fetch fresh data from FalkorDB
-                    let query = build_query("entire_graph", 100000, 0, None);
-                    if let Ok(graph_data) = execute_graph_query(&client_clone, &graph_name_clone, &query).await {
+                    let reload_start = std::time::Instant::now();
+                    let query = build_query("entire_graph", 100000, 0, None, &QueryFilters::default());
+                    if let Ok(mut graph_data) = execute_graph_query(&client_clone, &graph_name_clone, &query, &entire_graph_limits, reload_timeout_ms).await {
+                        label_fields_clone.apply(&mut graph_data);
                         info!("Fetched {} nodes and {} edges from FalkorDB", 
                             graph_data.nodes.len(), graph_data.edges.len());
                         
-                        // Reload DuckDB with fresh data
-                        if let Ok(_) = store_clone.load_initial_data(graph_data.nodes.clone(), graph_data.edges.clone()).await {
-                            info!("DuckDB reloaded successfully with fresh data");
-                            
-                            // Clear caches after successful reload
-                            cache_clone.clear();
-                            let mut arrow_cache_guard = arrow_cache_clone.write().await;
-                            *arrow_cache_guard = None;
-                            drop(arrow_cache_guard);
-                            info!("Caches cleared after successful reload");
-                            
-                            // Compute delta with the new data
-                            let delta = delta_tracker_clone.compute_delta(
-                                graph_data.nodes,
-                                graph_data.edges
-                            ).await;
-                            
-                            // Log delta statistics
-                            info!("Broadcasting delta: {} nodes added, {} nodes updated, {} nodes removed, {} edges added, {} edges updated, {} edges removed",
-                                delta.nodes_added.len(),
-                                delta.nodes_updated.len(),
-                                delta.nodes_removed.len(),
-                                delta.edges_added.len(),
-                                delta.edges_updated.len(),
-                                delta.edges_removed.len()
-                            );
-                            
-                            // Broadcast delta instead of full update
-                            let _ = delta_tx_clone.send(delta);
-                        } else {
-                            error!("Failed to reload DuckDB with fresh data");
+                        match replace_graph(&monitor_state, &graph_data.nodes, &graph_data.edges, AuditSource::Monitor).await {
+                            Ok(()) => {
+                                info!("DuckDB reloaded successfully with fresh data");
+                                monitor_state.broadcast_event(ServerEvent::ReloadComplete {
+                                    nodes: graph_data.nodes.len(),
+                                    edges: graph_data.edges.len(),
+                                    duration_ms: reload_start.elapsed().as_millis(),
+                                    trigger: ReloadTrigger::Auto,
+                                });
+                            }
+                            Err(e) => error!("Failed to reload DuckDB with fresh data: {}", e),
                         }
                     } else {
                         error!("Failed to fetch fresh data from FalkorDB");
                     }
                 }
-                
-                last_node_count = current_node_count;
-                last_edge_count = current_edge_count;
-                last_centrality_sum = current_centrality_sum;
             }
         }
     });
 
     // Build router - cleaned up for React frontend only
     let app = Router::new()
+        .route("/api/health", get(health_check))
         .route("/api/stats", get(get_stats))
+        .route("/api/stats/timeseries", get(get_stats_timeseries))
+        .route("/api/stats/by-type", get(get_stats_by_type))
         .route("/api/queue/status", get(get_queue_status))
         .route("/api/visualize", get(visualize))
         .route("/api/search", get(search))
+        .route("/api/search/fulltext", get(search_fulltext))
         .route("/api/cache/clear", post(clear_cache))
         .route("/api/cache/stats", get(get_cache_stats))
         .route("/api/nodes/:id/summary", patch(update_node_summary))
+        .route("/api/nodes/summaries", patch(update_node_summaries))
+        .route("/api/nodes/exists", post(check_nodes_exist))
+        .route("/api/nodes/:id/properties", patch(update_node_properties))
         .route("/api/nodes/:id", get(get_node_by_id))
+        .route("/api/node/:id", get(get_node_by_id))
+        .route("/api/node/:id/edges", get(get_node_edges))
         // DuckDB endpoints
         .route("/api/duckdb/info", get(get_duckdb_info))
         .route("/api/arrow/nodes", get(get_nodes_arrow))
         .route("/api/arrow/edges", get(get_edges_arrow))
         .route("/api/duckdb/stats", get(get_duckdb_stats))
+        .route("/api/pending-edges", get(get_pending_edges))
         .route("/api/arrow/refresh", post(refresh_arrow_cache))
-        // Real-time update endpoints
-        .route("/api/updates/nodes", post(add_nodes))
-        .route("/api/updates/edges", post(add_edges))
-        .route("/api/updates/batch", post(batch_update))
+        .route("/api/config/colors", get(get_color_config).post(update_color_config))
+        .route("/api/config/load-limits", get(get_load_limits).post(update_load_limits))
+        .route("/api/snapshot", post(create_snapshot))
+        .route("/api/diff/:snapshot", get(get_snapshot_diff))
+        // Real-time update endpoints, accepting zstd or gzip compressed bodies
+        .merge(
+            Router::new()
+                .route("/api/updates/nodes", post(add_nodes))
+                .route("/api/updates/edges", post(add_edges))
+                .route("/api/updates/batch", post(batch_update))
+                .route_layer(middleware::from_fn(decompress_request_body))
+                .route_layer(middleware::from_fn_with_state(state.clone(), idempotent_updates))
+                .route_layer(DefaultBodyLimit::max(MAX_UPDATE_BODY_BYTES)),
+        )
         // Incremental query endpoints for notification-based updates
         .route("/api/graph/changes", get(get_changes_since))
         .route("/api/graph/nodes", get(get_nodes_by_ids))
@@ -647,8 +1370,10 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/centrality/betweenness", post(proxy_centrality_betweenness))
         .route("/api/centrality/all", post(proxy_centrality_all))
         .route("/ws", get(websocket_handler))
+        .layer(middleware::from_fn_with_state(state.clone(), require_api_token))
         .layer(CompressionLayer::new())  // Add gzip/brotli compression
         .layer(CorsLayer::permissive())
+        .layer(middleware::from_fn(propagate_request_id))
         .with_state(state);
 
     let addr = std::env::var("BIND_ADDR").unwrap_or_else(|_| "0.0.0.0:3000".to_string());
@@ -661,8 +1386,389 @@ async fn main() -> anyhow::Result<()> {
 }
 
 
+// Fetch the nodes above the degree threshold, and the edges between them, for the DuckDB load
+async fn fetch_limited_graph(
+    client: &FalkorAsyncClient,
+    graph_name: &str,
+    limits: &LoadLimits,
+    label_fields: &LabelFields,
+) -> anyhow::Result<(Vec<Node>, Vec<Edge>)> {
+    let LoadLimits { node_limit, edge_limit, min_degree } = *limits;
+    // Full properties are only needed to pick configured label fields
+    let props_column = if label_fields.is_empty() { "" } else { ", properties(n) as props" };
+    
+    // Step 1: Load nodes first (much more efficient)
+    // If min_degree is 0, load ALL nodes without filtering
+    let nodes_query = if min_degree <= 0.0 {
+        format!(
+            "MATCH (n) RETURN n.uuid as id, n.name as name, COALESCE(n.type, labels(n)[0]) as label, COALESCE(n.degree_centrality, 0.0) as degree, n.created_at as created_at, n.summary as summary, n.pagerank_centrality as pagerank, n.betweenness_centrality as betweenness, n.eigenvector_centrality as eigenvector{} ORDER BY COALESCE(n.degree_centrality, 0.0) DESC LIMIT {}",
+            props_column, node_limit
+        )
+    } else {
+        format!(
+            "MATCH (n) WHERE EXISTS(n.degree_centrality) AND n.degree_centrality > {} RETURN n.uuid as id, n.name as name, COALESCE(n.type, labels(n)[0]) as label, n.degree_centrality as degree, n.created_at as created_at, n.summary as summary, n.pagerank_centrality as pagerank, n.betweenness_centrality as betweenness, n.eigenvector_centrality as eigenvector{} ORDER BY n.degree_centrality DESC LIMIT {}",
+            min_degree, props_column, node_limit
+        )
+    };
+    
+    let mut graph = client.select_graph(graph_name);
+    let mut nodes_result = graph.query(&nodes_query).execute().await?;
+    let mut node_ids = Vec::new();
+    let mut nodes = Vec::new();
+    
+    for row in nodes_result.data.by_ref() {
+        if let Some(id) = row.first().and_then(|v| v.as_string()) {
+            node_ids.push(format!("'{}'", id));
+            
+            // Build properties map with real data
+            let mut properties = HashMap::new();
+            
+            // Add degree centrality
+            if let Some(degree) = row.get(3).and_then(|v| v.to_f64()) {
+                properties.insert("degree_centrality".to_string(), serde_json::Value::from(degree));
+            }
+            
+            // Add created_at timestamp
+            if let Some(created) = row.get(4).and_then(|v| v.as_string()) {
+                properties.insert("created_at".to_string(), serde_json::Value::String(created.to_string()));
+            }
+            
+            // Get summary from row index 5
+            let summary = row.get(5).and_then(|v| v.as_string()).map(|s| s.to_string());
+            
+            // Add pagerank centrality from row index 6
+            if let Some(pagerank) = row.get(6).and_then(|v| v.to_f64()) {
+                properties.insert("pagerank_centrality".to_string(), serde_json::Value::from(pagerank));
+            }
+            
+            // Add betweenness centrality from row index 7
+            if let Some(betweenness) = row.get(7).and_then(|v| v.to_f64()) {
+                properties.insert("betweenness_centrality".to_string(), serde_json::Value::from(betweenness));
+            }
+            
+            // Add eigenvector centrality from row index 8
+            if let Some(raw_eigenvector) = row.get(8) {
+                debug!("Raw eigenvector value: {:?}", raw_eigenvector);
+                if let Some(eigenvector) = raw_eigenvector.to_f64() {
+                    debug!("Converted eigenvector to f64: {}", eigenvector);
+                    properties.insert("eigenvector_centrality".to_string(), serde_json::Value::from(eigenvector));
+                } else {
+                    debug!("Failed to convert eigenvector to f64: {:?}", raw_eigenvector);
+                }
+            } else {
+                debug!("No eigenvector value at index 8");
+            }
+            
+            // Add name to properties so frontend can access it
+            let name = row.get(1).and_then(|v| v.as_string()).map_or("", |v| v).to_string();
+            properties.insert("name".to_string(), serde_json::Value::String(name.clone()));
+            
+            let node_type = row.get(2).and_then(|v| v.as_string()).map_or("Unknown", |v| v).to_string();
+            let label = row.get(9)
+                .and_then(|props| label_fields.label_for(&node_type, &value_to_properties(props)))
+                .unwrap_or(name);
+            
+            nodes.push(Node {
+                id: id.to_string(),
+                label,
+                node_type,
+                summary,
+                properties,
+            });
+        }
+    }
+    
+    // Step 2: Load edges only for loaded nodes - paginate to avoid FalkorDB's 10K result limit
+    let mut edges = Vec::new();
+    let batch_size: usize = 5000;
+    let mut offset: usize = 0;
+    
+    loop {
+        let edges_query = format!(
+            "MATCH (n)-[r]->(m) WHERE n.uuid IN [{}] AND m.uuid IN [{}] RETURN n.uuid, type(r), m.uuid, r.weight, properties(r) SKIP {} LIMIT {}",
+            node_ids.join(","),
+            node_ids.join(","),
+            offset,
+            batch_size.min(edge_limit.saturating_sub(offset))
+        );
+        
+        info!("Fetching edges batch: offset={}, limit={}", offset, batch_size);
+        
+        let mut graph = client.select_graph(graph_name);
+        let mut edges_result = graph.query(&edges_query).execute().await?;
+        let mut batch_count = 0;
+        
+        for row in edges_result.data.by_ref() {
+            batch_count += 1;
+            edges.push(Edge {
+                from: row.first().and_then(|v| v.as_string()).map_or("", |v| v).to_string(),
+                to: row.get(2).and_then(|v| v.as_string()).map_or("", |v| v).to_string(),
+                edge_type: row.get(1).and_then(|v| v.as_string()).map_or("", |v| v).to_string(),
+                weight: row.get(3).and_then(|v| v.to_f64()).unwrap_or(1.0),
+                properties: row.get(4).map(edge_properties).unwrap_or_default(),
+            });
+        }
+        
+        info!("Batch fetched {} edges", batch_count);
+        
+        if batch_count < batch_size || edges.len() >= edge_limit {
+            break;
+        }
+        
+        offset += batch_size;
+    }
+    
+    info!("Total fetched {} edges from FalkorDB", edges.len());
+    
+    Ok((nodes, edges))
+}
+
+// POST routes that only read, so they stay public like the GET routes
+const READ_ONLY_POST_PATHS: &[&str] = &["/api/nodes/exists"];
+
+// Reject mutating requests without the configured bearer token; read routes stay public
+async fn require_api_token(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(expected) = state.api_token.as_deref() else {
+        return next.run(request).await;
+    };
+    let read_only = match *request.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => true,
+        Method::POST => READ_ONLY_POST_PATHS.contains(&request.uri().path()),
+        _ => false,
+    };
+    if read_only {
+        return next.run(request).await;
+    }
+    
+    let provided = request.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match provided {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => next.run(request).await,
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(ErrorResponse {
+                error: "Missing or invalid bearer token".to_string(),
+            }),
+        ).into_response(),
+    }
+}
+
+// Largest update request body accepted, before and after decompression
+const MAX_UPDATE_BODY_BYTES: usize = 64 * 1024 * 1024;
+
+// Decode `Content-Encoding: zstd` or `gzip` request bodies so handlers see plain JSON
+async fn decompress_request_body(request: Request, next: Next) -> Response {
+    let encoding = request.headers()
+        .get(header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .filter(|v| !v.is_empty() && v != "identity");
+    let Some(encoding) = encoding else {
+        return next.run(request).await;
+    };
+    
+    let error = |status: StatusCode, error: String| (status, Json(ErrorResponse { error })).into_response();
+    if encoding != "zstd" && encoding != "gzip" {
+        return error(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!("Unsupported Content-Encoding '{}': expected zstd or gzip", encoding),
+        );
+    }
+    
+    let (mut parts, body) = request.into_parts();
+    let compressed = match axum::body::to_bytes(body, MAX_UPDATE_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => return error(StatusCode::PAYLOAD_TOO_LARGE, format!("Failed to read request body: {}", e)),
+    };
+    
+    let decoded = tokio::task::spawn_blocking(move || decode_body(&encoding, &compressed, MAX_UPDATE_BODY_BYTES)).await;
+    let decoded = match decoded {
+        Ok(Ok(bytes)) => bytes,
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::FileTooLarge => {
+            return error(StatusCode::PAYLOAD_TOO_LARGE, e.to_string());
+        }
+        Ok(Err(e)) => return error(StatusCode::BAD_REQUEST, format!("Failed to decompress request body: {}", e)),
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, format!("Decompression task failed: {}", e)),
+    };
+    
+    parts.headers.remove(header::CONTENT_ENCODING);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    next.run(Request::from_parts(parts, Body::from(decoded))).await
+}
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+// Answer retried update requests carrying a seen Idempotency-Key from the
+// recorded response instead of applying them again. Keys are scoped per path.
+async fn idempotent_updates(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let key = request.headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| format!("{} {}", request.uri().path(), v));
+    let Some(key) = key else {
+        return next.run(request).await;
+    };
+    
+    let guard = match state.idempotency.claim(key) {
+        Claim::New(guard) => guard,
+        Claim::Replay(stored) => {
+            debug!("Replaying recorded response for idempotent {}", request.uri().path());
+            let mut response = (stored.status, stored.body).into_response();
+            if let Some(content_type) = stored.content_type {
+                response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+            }
+            response.headers_mut().insert("idempotent-replayed", header::HeaderValue::from_static("true"));
+            return response;
+        }
+        Claim::InFlight => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse {
+                    error: "A request with this Idempotency-Key is still being processed".to_string(),
+                }),
+            ).into_response();
+        }
+    };
+    
+    let response = next.run(request).await;
+    if !response.status().is_success() {
+        return response;
+    }
+    
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Failed to buffer response for idempotency record: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: format!("Failed to read response: {}", e) }),
+            ).into_response();
+        }
+    };
+    guard.complete(StoredResponse {
+        status: parts.status,
+        content_type: parts.headers.get(header::CONTENT_TYPE).cloned(),
+        body: body.clone(),
+    });
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn decode_body(encoding: &str, data: &[u8], limit: usize) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+    
+    let reader: Box<dyn Read + '_> = match encoding {
+        "zstd" => Box::new(zstd::stream::read::Decoder::new(data)?),
+        _ => Box::new(flate2::read::GzDecoder::new(data)),
+    };
+    let mut decoded = Vec::new();
+    reader.take(limit as u64 + 1).read_to_end(&mut decoded)?;
+    if decoded.len() > limit {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::FileTooLarge,
+            format!("Decompressed request body exceeds {} bytes", limit),
+        ));
+    }
+    Ok(decoded)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// A pooled FalkorDB client for a handler, or 503 when none can be opened
+async fn falkor_client(state: &AppState) -> Result<FalkorConnection, (StatusCode, Json<ErrorResponse>)> {
+    state.falkor_pool.get().await.map_err(|e| {
+        error!("No FalkorDB connection available: {}", e);
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: format!("FalkorDB unavailable: {}", e),
+            }),
+        )
+    })
+}
+
+// Longest the health check waits on a FalkorDB or Redis ping, so a hung backend reports an error
+const HEALTH_PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+async fn ping_within<T>(
+    timeout: std::time::Duration,
+    ping: impl std::future::Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    tokio::time::timeout(timeout, ping)
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("ping timed out after {}ms", timeout.as_millis())))
+}
+
+// Dependency health: FalkorDB and DuckDB are critical, Redis is optional
+async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<serde_json::Value>) {
+    let falkor_start = std::time::Instant::now();
+    let ping = async {
+        let client = state.falkor_pool.get().await?;
+        client.select_graph(&state.graph_name).query("RETURN 1").execute().await?;
+        anyhow::Ok(())
+    };
+    let falkordb = match ping_within(HEALTH_PING_TIMEOUT, ping).await {
+        Ok(_) => serde_json::json!({
+            "status": "ok",
+            "latency_ms": falkor_start.elapsed().as_millis(),
+        }),
+        Err(e) => {
+            warn!("Health check: FalkorDB unreachable: {}", e);
+            serde_json::json!({ "status": "error", "error": e.to_string() })
+        }
+    };
+    
+    let duckdb = match state.duckdb_store.get_stats().await {
+        Ok((nodes, edges)) => serde_json::json!({
+            "status": "ok",
+            "nodes": nodes,
+            "edges": edges,
+        }),
+        Err(e) => {
+            warn!("Health check: DuckDB query failed: {}", e);
+            serde_json::json!({ "status": "error", "error": e.to_string() })
+        }
+    };
+    
+    let redis = match &state.enhanced_cache {
+        Some(cache) => match ping_within(HEALTH_PING_TIMEOUT, cache.ping()).await {
+            Ok(()) => serde_json::json!({ "status": "ok" }),
+            Err(e) => {
+                warn!("Health check: Redis ping failed: {}", e);
+                serde_json::json!({ "status": "error", "error": e.to_string() })
+            }
+        },
+        None => serde_json::json!({ "status": "disabled" }),
+    };
+    
+    let healthy = falkordb["status"] == "ok" && duckdb["status"] == "ok";
+    let status = if healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    
+    (status, Json(serde_json::json!({
+        "status": if healthy { "ok" } else { "degraded" },
+        "version": env!("CARGO_PKG_VERSION"),
+        "graph": state.graph_name,
+        "dependencies": {
+            "falkordb": falkordb,
+            "duckdb": duckdb,
+            "redis": redis,
+        },
+    })))
+}
+
 async fn get_stats(State(state): State<AppState>) -> Result<Json<GraphStats>, StatusCode> {
-    match calculate_graph_stats(&state.client, &state.graph_name).await {
+    let client = state.falkor_pool.get().await.map_err(|e| {
+        error!("No FalkorDB connection available for stats: {}", e);
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+    match calculate_graph_stats(&client, &state.graph_name).await {
         Ok(stats) => Ok(Json(stats)),
         Err(e) => {
             error!("Failed to get stats: {}", e);
@@ -674,9 +1780,17 @@ async fn get_stats(State(state): State<AppState>) -> Result<Json<GraphStats>, St
 async fn visualize(
     State(state): State<AppState>,
     Query(params): Query<QueryParams>,
-) -> Result<Json<QueryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     let start = std::time::Instant::now();
     
+    if let Err(e) = QueryFilters::from_params(&params).validate() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: e }),
+        ));
+    }
+    
     // Create cache key
     let cache_key = format!("{:?}", params);
     
@@ -684,13 +1798,17 @@ async fn visualize(
     if let Some(ref enhanced_cache) = state.enhanced_cache {
         if !state.cache_config.force_fresh {
             // Clone values needed for the closure
-            let client = state.client.clone();
+            let falkor_pool = state.falkor_pool.clone();
             let graph_name = state.graph_name.clone();
             let params_clone = params.clone();
+            let label_fields = state.label_fields.with_override(params.label_field.as_deref());
+            let entire_graph_limits = state.entire_graph_limits;
+            let query_timeout_ms = state.query_timeout_ms;
             
             // Use enhanced cache with all optimizations
             let cached_result = enhanced_cache
-                .get_or_compute::<GraphData, _, _>(&cache_key, || {
+                // "No results" goes stale as soon as data is ingested, so skip negative caching here
+                .get_or_compute_with::<GraphData, _, _>(&cache_key, false, move || {
                     Box::pin(async move {
                         let limit = if params_clone.query_type == "entire_graph" {
                             params_clone.limit.unwrap_or(50000).min(100000)
@@ -704,22 +1822,23 @@ async fn visualize(
                             limit,
                             offset,
                             params_clone.search.as_deref(),
+                            &QueryFilters::from_params(&params_clone),
                         );
                         
-                        execute_graph_query(&client, &graph_name, &query)
+                        let client = falkor_pool.get().await?;
+                        execute_graph_query(&client, &graph_name, &query, &entire_graph_limits, query_timeout_ms)
                             .await
-                            .map(Some)
+                            .map(|mut data| {
+                                label_fields.apply(&mut data);
+                                Some(project_node_properties(data, params_clone.fields.as_deref()))
+                            })
                     })
                 })
                 .await;
                 
             if let Ok(Some(data)) = cached_result {
                 let execution_time_ms = start.elapsed().as_millis();
-                return Ok(Json(QueryResponse {
-                    data,
-                    has_more: false,
-                    execution_time_ms,
-                }));
+                return Ok(graph_data_response(data, execution_time_ms, &headers, state.cache_config.ttl_seconds));
             }
         }
     }
@@ -729,11 +1848,9 @@ async fn visualize(
     
     if cache_enabled {
         if let Some(cached) = state.graph_cache.get(&cache_key) {
-            return Ok(Json(QueryResponse {
-                data: cached.value().clone(),
-                has_more: false,
-                execution_time_ms: 0,
-            }));
+            let data = cached.value().clone();
+            drop(cached);
+            return Ok(graph_data_response(data, 0, &headers, state.cache_config.ttl_seconds));
         }
     }
     
@@ -744,21 +1861,41 @@ async fn visualize(
     };
     let offset = params.offset.unwrap_or(0);
     
-    let query = build_query(&params.query_type, limit, offset, params.search.as_deref());
+    let query = build_query(
+        &params.query_type,
+        limit,
+        offset,
+        params.search.as_deref(),
+        &QueryFilters::from_params(&params),
+    );
     
-    match execute_graph_query(&state.client, &state.graph_name, &query).await {
-        Ok(data) => {
+    let client = falkor_client(&state).await?;
+    match execute_graph_query(&client, &state.graph_name, &query, &state.entire_graph_limits, state.query_timeout_ms).await {
+        Ok(mut data) => {
+            state.label_fields.with_override(params.label_field.as_deref()).apply(&mut data);
+            let data = project_node_properties(data, params.fields.as_deref());
+            
             // Cache the result only if cache is enabled
             if cache_enabled {
                 state.graph_cache.insert(cache_key, data.clone());
             }
             
             let execution_time_ms = start.elapsed().as_millis();
-            Ok(Json(QueryResponse {
-                data,
-                has_more: false, // TODO: Implement proper pagination check
-                execution_time_ms,
-            }))
+            Ok(graph_data_response(data, execution_time_ms, &headers, state.cache_config.ttl_seconds))
+        }
+        Err(e) if e.downcast_ref::<GraphTooLarge>().is_some() => {
+            warn!("Refused visualize query: {}", e);
+            Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ErrorResponse { error: e.to_string() }),
+            ))
+        }
+        Err(e) if e.downcast_ref::<QueryTimedOut>().is_some() => {
+            warn!("Visualize query timed out: {}", e);
+            Err((
+                StatusCode::GATEWAY_TIMEOUT,
+                Json(ErrorResponse { error: e.to_string() }),
+            ))
         }
         Err(e) => {
             error!("Query failed: {}", e);
@@ -772,18 +1909,90 @@ async fn visualize(
     }
 }
 
+// Keep only the requested properties on each node when `fields` is given.
+// `name` and `type` are always kept since the renderer labels and colors by them.
+fn project_node_properties(mut data: GraphData, fields: Option<&str>) -> GraphData {
+    let Some(fields) = fields else {
+        return data;
+    };
+    
+    let wanted: std::collections::HashSet<&str> = fields
+        .split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .chain(["name", "type"])
+        .collect();
+    
+    for node in &mut data.nodes {
+        node.properties.retain(|key, _| wanted.contains(key.as_str()));
+    }
+    
+    data
+}
+
+// Build the JSON visualize response, answering with 304 when the client already
+// holds the current data. The ETag covers only the GraphData so that timing
+// fields don't change it between otherwise identical responses.
+fn graph_data_response(
+    data: GraphData,
+    execution_time_ms: u128,
+    headers: &HeaderMap,
+    ttl_seconds: u64,
+) -> Response {
+    let etag = match serde_json::to_vec(&data) {
+        Ok(bytes) => ArrowCache::generate_etag(&bytes),
+        Err(e) => {
+            error!("Failed to serialize graph data for ETag: {}", e);
+            return Json(QueryResponse {
+                data,
+                has_more: false,
+                execution_time_ms,
+            }).into_response();
+        }
+    };
+    let cache_control = format!("public, max-age={}", ttl_seconds);
+    
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        if let Ok(client_etag) = if_none_match.to_str() {
+            if client_etag == etag {
+                debug!("Client has current graph data (ETag match)");
+                return Response::builder()
+                    .status(StatusCode::NOT_MODIFIED)
+                    .header(header::ETAG, &etag)
+                    .header("Cache-Control", cache_control)
+                    .body(Body::empty())
+                    .unwrap();
+            }
+        }
+    }
+    
+    let mut response = Json(QueryResponse {
+        data,
+        has_more: false, // TODO: Implement proper pagination check
+        execution_time_ms,
+    }).into_response();
+    let response_headers = response.headers_mut();
+    if let Ok(value) = header::HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = header::HeaderValue::from_str(&cache_control) {
+        response_headers.insert(header::CACHE_CONTROL, value);
+    }
+    response_headers.insert(header::VARY, header::HeaderValue::from_static("Accept-Encoding"));
+    response
+}
+
 async fn search(
     State(state): State<AppState>,
     Query(params): Query<QueryParams>,
-) -> Result<Json<QueryResponse>, (StatusCode, Json<ErrorResponse>)> {
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     if params.search.is_some() {
         let new_params = QueryParams {
             query_type: "search".to_string(),
-            limit: params.limit,
-            offset: params.offset,
-            search: params.search,
+            ..params
         };
-        visualize(State(state), Query(new_params)).await
+        visualize(State(state), Query(new_params), headers).await
     } else {
         Err((
             StatusCode::BAD_REQUEST,
@@ -795,78 +2004,362 @@ async fn search(
 }
 
 
-// Cache management endpoints
-async fn clear_cache(State(state): State<AppState>) -> Result<Json<CacheResponse>, StatusCode> {
-    let cleared_entries = state.graph_cache.len();
-    state.graph_cache.clear();
-    
-    // Also clear arrow cache
-    let mut arrow_cache = state.arrow_cache.write().await;
-    *arrow_cache = None;
-    drop(arrow_cache);
-    
-    info!("Cache cleared: {} entries removed, arrow cache cleared", cleared_entries);
-    
-    Ok(Json(CacheResponse {
-        message: "Cache cleared successfully".to_string(),
-        cleared_entries,
-    }))
+#[derive(Debug, Deserialize)]
+struct FulltextSearchParams {
+    search: String,
+    limit: Option<usize>,
+    node_type: Option<String>,
 }
 
-async fn get_cache_stats(State(state): State<AppState>) -> Result<Json<CacheStats>, StatusCode> {
-    let total_entries = state.graph_cache.len();
-    let cache_keys: Vec<String> = state.graph_cache.iter().map(|entry| entry.key().clone()).collect();
-    
-    Ok(Json(CacheStats {
-        total_entries,
-        cache_keys,
-    }))
+// Result shapes of graphiti-search-rs' `/search`, keeping only what GraphData needs
+#[derive(Debug, Deserialize)]
+struct SearchServiceResults {
+    #[serde(default)]
+    nodes: Vec<SearchServiceNode>,
+    #[serde(default)]
+    edges: Vec<SearchServiceEdge>,
 }
 
-fn build_query(query_type: &str, limit: usize, offset: usize, search: Option<&str>) -> String {
-    match query_type {
-        "entire_graph" => {
-            // For entire_graph, we need a special handling to get ALL nodes and edges
-            // The regular OPTIONAL MATCH approach misses some edges
-            // We'll handle this differently in execute_graph_query
-            "ENTIRE_GRAPH_SPECIAL".to_string()
-        },
-        
-        "high_degree" => format!(
-            r#"
-            MATCH (n) 
-            WHERE EXISTS(n.degree_centrality) AND n.degree_centrality > 0.001
-            WITH n ORDER BY n.degree_centrality DESC SKIP {} LIMIT {}
-            MATCH (n)-[r]->(m) 
-            WHERE EXISTS(m.degree_centrality) AND m.degree_centrality > 0.0005
-            RETURN DISTINCT 
-                n.uuid as source_id, n.name as source_name, 
-                type(r) as rel_type, 
-                m.uuid as target_id, m.name as target_name,
-                COALESCE(n.type, labels(n)[0]) as source_label, COALESCE(m.type, labels(m)[0]) as target_label,
-                n.degree_centrality as source_degree, m.degree_centrality as target_degree,
-                properties(n) as source_props, properties(m) as target_props
-            LIMIT {}
-            "#,
-            offset, limit / 2, limit
-        ),
-        
-        "agents" => format!(
-            r#"
-            MATCH (n) 
-            WHERE n.name CONTAINS 'Agent' 
-            WITH n SKIP {} LIMIT {}
+#[derive(Debug, Deserialize)]
+struct SearchServiceNode {
+    uuid: String,
+    name: String,
+    node_type: String,
+    summary: Option<String>,
+    created_at: Option<String>,
+    group_id: Option<String>,
+    centrality: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchServiceEdge {
+    source_node_uuid: String,
+    target_node_uuid: String,
+    #[serde(default)]
+    fact: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default = "default_edge_weight")]
+    weight: f64,
+}
+
+fn default_edge_weight() -> f64 {
+    1.0
+}
+
+// Ranked search through the search service at SEARCH_SERVICE_URL, falling back
+// to the local `/api/search` expansion when it's unset or unreachable
+async fn search_fulltext(
+    State(state): State<AppState>,
+    Query(params): Query<FulltextSearchParams>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let query = params.search.trim();
+    if query.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Search term required".to_string(),
+            }),
+        ));
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 1000);
+    let node_type = params.node_type.clone().filter(|t| !t.is_empty());
+    
+    if let Some(ref search_url) = state.search_url {
+        match query_search_service(&state, search_url, query, limit, node_type.as_deref()).await {
+            Ok(results) => {
+                let mut data = search_results_to_graph(&state, results).await;
+                state.label_fields.apply(&mut data);
+                let mut response = Json(data).into_response();
+                response.headers_mut().insert("x-search-source", header::HeaderValue::from_static("service"));
+                return Ok(response);
+            }
+            Err(e) => warn!("Search service unavailable, falling back to local search: {}", e),
+        }
+    }
+    
+    let local_params = QueryParams {
+        query_type: "search".to_string(),
+        limit: Some(limit),
+        offset: None,
+        search: Some(query.to_string()),
+        node_type,
+        created_after: None,
+        created_before: None,
+        fields: None,
+        label_field: None,
+        edge_types: None,
+        min_degree: None,
+    };
+    let mut response = visualize(State(state), Query(local_params), headers).await?;
+    response.headers_mut().insert("x-search-source", header::HeaderValue::from_static("local"));
+    Ok(response)
+}
+
+async fn query_search_service(
+    state: &AppState,
+    search_url: &str,
+    query: &str,
+    limit: usize,
+    node_type: Option<&str>,
+) -> anyhow::Result<SearchServiceResults> {
+    let methods = serde_json::json!(["fulltext", "similarity"]);
+    let request = serde_json::json!({
+        "query": query,
+        "config": {
+            "edge_config": {
+                "search_methods": methods,
+                "reranker": "rrf",
+                "bfs_max_depth": 1,
+                "sim_min_score": 0.5,
+                "mmr_lambda": 0.5
+            },
+            "node_config": {
+                "search_methods": methods,
+                "reranker": "rrf",
+                "bfs_max_depth": 1,
+                "sim_min_score": 0.5,
+                "mmr_lambda": 0.5,
+                "centrality_boost_factor": null
+            },
+            "episode_config": null,
+            "community_config": null,
+            "limit": limit,
+            "reranker_min_score": 0.0
+        },
+        "filters": {
+            "node_types": node_type.map(|t| vec![t]),
+        }
+    });
+    
+    let response = state.http_client
+        .post(format!("{}/search", search_url.trim_end_matches('/')))
+        .header("accept", "application/json")
+        .json(&request)
+        .with_request_id()
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response.json().await?)
+}
+
+// Map ranked search results into GraphData. Nodes keep the service's order
+// (`search_rank`); edge endpoints missing from the node results are loaded
+// from FalkorDB so every edge has both ends.
+async fn search_results_to_graph(state: &AppState, results: SearchServiceResults) -> GraphData {
+    let mut nodes: Vec<Node> = Vec::with_capacity(results.nodes.len());
+    let mut seen: std::collections::HashSet<String> = std::collections::HashSet::new();
+    
+    for (rank, node) in results.nodes.into_iter().enumerate() {
+        if !seen.insert(node.uuid.clone()) {
+            continue;
+        }
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), serde_json::json!(node.name));
+        properties.insert("type".to_string(), serde_json::json!(node.node_type));
+        properties.insert("search_rank".to_string(), serde_json::json!(rank));
+        if let Some(ref summary) = node.summary {
+            properties.insert("summary".to_string(), serde_json::json!(summary));
+        }
+        if let Some(created_at) = node.created_at {
+            properties.insert("created_at".to_string(), serde_json::json!(created_at));
+        }
+        if let Some(group_id) = node.group_id {
+            properties.insert("group_id".to_string(), serde_json::json!(group_id));
+        }
+        if let Some(centrality) = node.centrality {
+            properties.insert("degree_centrality".to_string(), serde_json::json!(centrality));
+        }
+        nodes.push(Node {
+            id: node.uuid,
+            label: node.name,
+            node_type: node.node_type,
+            summary: node.summary,
+            properties,
+        });
+    }
+    
+    let missing: Vec<String> = results.edges.iter()
+        .flat_map(|e| [&e.source_node_uuid, &e.target_node_uuid])
+        .filter(|uuid| !seen.contains(*uuid))
+        .cloned()
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+    if !missing.is_empty() {
+        match fetch_nodes_by_uuid(state, &missing).await {
+            Ok(fetched) => {
+                for node in fetched {
+                    seen.insert(node.id.clone());
+                    nodes.push(node);
+                }
+            }
+            Err(e) => warn!("Failed to load {} search edge endpoints: {}", missing.len(), e),
+        }
+    }
+    
+    let edges: Vec<Edge> = results.edges.into_iter()
+        .filter(|e| seen.contains(&e.source_node_uuid) && seen.contains(&e.target_node_uuid))
+        .map(|e| {
+            let mut properties = HashMap::new();
+            if let Some(fact) = e.fact {
+                properties.insert("fact".to_string(), serde_json::Value::String(fact));
+            }
+            if let Some(created_at) = e.created_at {
+                properties.insert("created_at".to_string(), serde_json::Value::String(created_at));
+            }
+            Edge {
+                from: e.source_node_uuid,
+                to: e.target_node_uuid,
+                edge_type: "RELATES_TO".to_string(), // the service only searches entity edges
+                weight: e.weight,
+                properties,
+            }
+        })
+        .collect();
+    
+    let stats = graph_data_stats(&nodes, &edges);
+    GraphData { nodes, edges, stats }
+}
+
+async fn fetch_nodes_by_uuid(state: &AppState, uuids: &[String]) -> anyhow::Result<Vec<Node>> {
+    let uuid_list = uuids.iter()
+        .map(|uuid| format!("'{}'", escape_cypher_string(uuid)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = format!(
+        "MATCH (n) WHERE n.uuid IN [{}] RETURN n.uuid, n.name, COALESCE(n.type, labels(n)[0]), properties(n)",
+        uuid_list
+    );
+    
+    let client = state.falkor_pool.get().await?;
+    let mut graph = client.select_graph(&state.graph_name);
+    let mut result = graph.query(&query).execute().await?;
+    let mut nodes = Vec::new();
+    for row in result.data.by_ref() {
+        if row.len() < 4 {
+            continue;
+        }
+        let name = value_to_string(&row[1]);
+        let properties = value_to_properties(&row[3]);
+        let summary = properties.get("summary").and_then(|v| v.as_str()).map(|s| s.to_string());
+        nodes.push(Node {
+            id: value_to_string(&row[0]),
+            label: name,
+            node_type: value_to_string(&row[2]),
+            summary,
+            properties,
+        });
+    }
+    Ok(nodes)
+}
+
+// Cache management endpoints
+async fn clear_cache(State(state): State<AppState>) -> Result<Json<CacheResponse>, StatusCode> {
+    let cleared_entries = state.graph_cache.len();
+    state.graph_cache.clear();
+    
+    // Also clear arrow cache
+    let mut arrow_cache = state.arrow_cache.write().await;
+    *arrow_cache = None;
+    drop(arrow_cache);
+    
+    info!("Cache cleared: {} entries removed, arrow cache cleared", cleared_entries);
+    
+    Ok(Json(CacheResponse {
+        message: "Cache cleared successfully".to_string(),
+        cleared_entries,
+    }))
+}
+
+async fn get_cache_stats(State(state): State<AppState>) -> Result<Json<CacheStats>, StatusCode> {
+    let total_entries = state.graph_cache.len();
+    let cache_keys: Vec<String> = state.graph_cache.iter().map(|entry| entry.key().clone()).collect();
+    
+    Ok(Json(CacheStats {
+        total_entries,
+        cache_keys,
+    }))
+}
+
+/// Paginated queries order by degree with the UUID as tiebreaker, so tied
+/// nodes land on exactly one page.
+fn build_query(query_type: &str, limit: usize, offset: usize, search: Option<&str>, filters: &QueryFilters) -> String {
+    let n_filter = filters.node_conditions("n");
+    let m_filter = filters.node_conditions("m") + &filters.edge_conditions("r");
+    
+    match query_type {
+        // Filtered entire_graph requests can't use the special path, fall through to node_type
+        "entire_graph" if !filters.is_empty() => build_query("node_type", limit, offset, search, filters),
+        
+        "entire_graph" => {
+            // For entire_graph, we need a special handling to get ALL nodes and edges
+            // The regular OPTIONAL MATCH approach misses some edges
+            // We'll handle this differently in execute_graph_query
+            "ENTIRE_GRAPH_SPECIAL".to_string()
+        },
+        
+        "high_degree" => format!(
+            r#"
+            MATCH (n) 
+            WHERE true{}{}
+            WITH n ORDER BY n.degree_centrality DESC, n.uuid ASC SKIP {} LIMIT {}
+            MATCH (n)-[r]->(m) 
+            WHERE true{}{}
+            RETURN DISTINCT 
+                n.uuid as source_id, n.name as source_name, 
+                type(r) as rel_type, 
+                m.uuid as target_id, m.name as target_name,
+                COALESCE(n.type, labels(n)[0]) as source_label, COALESCE(m.type, labels(m)[0]) as target_label,
+                n.degree_centrality as source_degree, m.degree_centrality as target_degree,
+                properties(n) as source_props, properties(m) as target_props,
+                properties(r) as edge_props
+            LIMIT {}
+            "#,
+            filters.default_degree_floor("n", 0.001), n_filter, offset, limit / 2,
+            filters.default_degree_floor("m", 0.0005), m_filter, limit
+        ),
+        
+        "agents" => format!(
+            r#"
+            MATCH (n) 
+            WHERE n.name CONTAINS 'Agent'{}
+            WITH n ORDER BY COALESCE(n.degree_centrality, 0.0) DESC, n.uuid ASC SKIP {} LIMIT {}
             MATCH (n)-[r]->(m)
+            WHERE true{}
             RETURN DISTINCT 
                 n.uuid as source_id, n.name as source_name, 
                 type(r) as rel_type, 
                 m.uuid as target_id, m.name as target_name,
                 COALESCE(n.type, labels(n)[0]) as source_label, COALESCE(m.type, labels(m)[0]) as target_label,
                 n.degree_centrality as source_degree, m.degree_centrality as target_degree,
-                properties(n) as source_props, properties(m) as target_props
+                properties(n) as source_props, properties(m) as target_props,
+                properties(r) as edge_props
             LIMIT {}
             "#,
-            offset, limit / 3, limit
+            n_filter, offset, limit / 3, m_filter, limit
+        ),
+        
+        // All nodes matching the filters, with the edges between them
+        "node_type" => format!(
+            r#"
+            MATCH (n)
+            WHERE true{}
+            WITH n ORDER BY COALESCE(n.degree_centrality, 0.0) DESC, n.uuid ASC SKIP {} LIMIT {}
+            OPTIONAL MATCH (n)-[r]->(m)
+            WHERE true{}
+            RETURN DISTINCT 
+                n.uuid as source_id, n.name as source_name, 
+                type(r) as rel_type, 
+                m.uuid as target_id, m.name as target_name,
+                COALESCE(n.type, labels(n)[0]) as source_label, COALESCE(m.type, labels(m)[0]) as target_label,
+                n.degree_centrality as source_degree, m.degree_centrality as target_degree,
+                properties(n) as source_props, properties(m) as target_props,
+                properties(r) as edge_props
+            LIMIT {}
+            "#,
+            n_filter, offset, limit, m_filter, limit
         ),
         
         "search" => {
@@ -874,35 +2367,47 @@ fn build_query(query_type: &str, limit: usize, offset: usize, search: Option<&st
                 format!(
                     r#"
                     MATCH (n) 
-                    WHERE n.name CONTAINS '{}'
+                    WHERE n.name CONTAINS '{}'{}
                     WITH n LIMIT 1
                     MATCH (n)-[r*1..2]-(m)
+                    WHERE ALL(rel IN r WHERE true{}){}
                     RETURN DISTINCT 
                         n.uuid as source_id, n.name as source_name, 
                         type(r[0]) as rel_type, 
                         m.uuid as target_id, m.name as target_name,
                         COALESCE(n.type, labels(n)[0]) as source_label, COALESCE(m.type, labels(m)[0]) as target_label,
                         n.degree_centrality as source_degree, m.degree_centrality as target_degree,
-                        properties(n) as source_props, properties(m) as target_props
+                        properties(n) as source_props, properties(m) as target_props,
+                        properties(r[0]) as edge_props
                     LIMIT {}
                     "#,
-                    term, limit
+                    term, n_filter, filters.edge_conditions("rel"), filters.node_conditions("m"), limit
                 )
             } else {
-                build_query("high_degree", limit, offset, None)
+                build_query("high_degree", limit, offset, None, filters)
             }
         }
         
-        _ => build_query("high_degree", limit, offset, None),
+        _ => build_query("high_degree", limit, offset, None, filters),
     }
 }
 
-async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query: &str) -> anyhow::Result<GraphData> {
+async fn execute_graph_query(
+    client: &FalkorAsyncClient,
+    graph_name: &str,
+    query: &str,
+    limits: &EntireGraphLimits,
+    timeout_ms: u64,
+) -> anyhow::Result<GraphData> {
     let mut nodes_map: HashMap<String, Node> = HashMap::new();
     let mut edges = Vec::new();
     
     // Special handling for entire_graph query
     if query == "ENTIRE_GRAPH_SPECIAL" {
+        // Refuse graphs too large to hold in memory before fetching anything
+        let stats = calculate_graph_stats(client, graph_name).await?;
+        limits.check(stats.total_nodes, stats.total_edges)?;
+        
         // Query 1: Get all nodes
         let nodes_query = r#"
             MATCH (n)
@@ -915,7 +2420,7 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
         "#;
         
         let mut graph = client.select_graph(graph_name);
-        let mut nodes_result = graph.query(nodes_query).execute().await?;
+        let mut nodes_result = run_query(&mut graph, nodes_query, timeout_ms).await?;
         
         // Process all nodes
         while let Some(row) = nodes_result.data.next() {
@@ -944,7 +2449,7 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
                 
                 nodes_map.insert(node_id.clone(), Node {
                     id: node_id,
-                    label: truncate_string(&node_name, 50),
+                    label: node_name.clone(),
                     node_type,
                     summary,
                     properties: node_props,
@@ -961,13 +2466,14 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
                 RETURN 
                     n.uuid as source_id,
                     m.uuid as target_id,
-                    type(r) as rel_type
+                    type(r) as rel_type,
+                    properties(r) as edge_props
                 SKIP {}
                 LIMIT {}
             "#, offset, batch_size);
             
             let mut graph = client.select_graph(graph_name);
-            let mut edges_result = graph.query(&edges_query).execute().await?;
+            let mut edges_result = run_query(&mut graph, &edges_query, timeout_ms).await?;
             
             let mut batch_count = 0;
             // Process edges in this batch
@@ -976,12 +2482,14 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
                     let source_id = value_to_string(&row[0]);
                     let target_id = value_to_string(&row[1]);
                     let rel_type = value_to_string(&row[2]);
+                    let edge_props = row.get(3).map(edge_properties).unwrap_or_default();
                     
                     edges.push(Edge {
                         from: source_id,
                         to: target_id,
                         edge_type: rel_type,
                         weight: 1.0,
+                        properties: edge_props,
                     });
                     batch_count += 1;
                 }
@@ -998,7 +2506,7 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
     } else {
         // Regular query processing
         let mut graph = client.select_graph(graph_name);
-        let mut result_set = graph.query(query).execute().await?;
+        let mut result_set = run_query(&mut graph, query, timeout_ms).await?;
     
         // Process results
         while let Some(row) = result_set.data.next() {
@@ -1032,7 +2540,7 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
                 
                 nodes_map.insert(source_id.clone(), Node {
                     id: source_id.clone(),
-                    label: truncate_string(&source_name, 50),
+                    label: source_name.clone(),
                     node_type: source_label.clone(),
                     summary,
                     properties: node_props,
@@ -1073,7 +2581,7 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
                     
                     nodes_map.insert(target_id.clone(), Node {
                         id: target_id.clone(),
-                        label: truncate_string(&target_name, 50),
+                        label: target_name.clone(),
                         node_type: target_label.clone(),
                         summary,
                         properties: node_props,
@@ -1081,11 +2589,13 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
                 }
                 
                 // Add edge
+                let edge_props = if row.len() > 11 { edge_properties(&row[11]) } else { HashMap::new() };
                 edges.push(Edge {
                     from: source_id,
                     to: target_id,
                     edge_type: rel_type,
                     weight: 1.0,
+                    properties: edge_props,
                 });
             }
         }
@@ -1093,9 +2603,13 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
     } // Close the else block
     
     let nodes: Vec<Node> = nodes_map.into_values().collect();
+    let stats = graph_data_stats(&nodes, &edges);
     
-    // Calculate stats
-    let stats = GraphStats {
+    Ok(GraphData { nodes, edges, stats })
+}
+
+fn graph_data_stats(nodes: &[Node], edges: &[Edge]) -> GraphStats {
+    GraphStats {
         total_nodes: nodes.len(),
         total_edges: edges.len(),
         node_types: nodes.iter()
@@ -1107,9 +2621,7 @@ async fn execute_graph_query(client: &FalkorAsyncClient, graph_name: &str, query
         max_degree: nodes.iter()
             .map(|n| n.properties.get("degree_centrality").and_then(|v| v.as_f64()).unwrap_or(0.0))
             .fold(0.0, f64::max),
-    };
-    
-    Ok(GraphData { nodes, edges, stats })
+    }
 }
 
 async fn calculate_graph_stats(client: &FalkorAsyncClient, graph_name: &str) -> anyhow::Result<GraphStats> {
@@ -1178,7 +2690,21 @@ fn value_to_string(value: &FalkorValue) -> String {
         FalkorValue::F64(f) => f.to_string(),
         FalkorValue::Bool(b) => b.to_string(),
         FalkorValue::None => String::new(),
-        _ => format!("{:?}", value),
+        // Lists render as their elements, e.g. an array-valued name
+        FalkorValue::Array(items) => items
+            .iter()
+            .map(value_to_string)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(", "),
+        FalkorValue::Map(map) => ["name", "value", "label", "title"]
+            .iter()
+            .find_map(|key| map.get(*key))
+            .map(value_to_string)
+            .unwrap_or_default(),
+        FalkorValue::Point(point) => format!("{}, {}", point.latitude, point.longitude),
+        // Nodes, edges, paths and vectors have no sensible text form; never leak debug output
+        _ => String::new(),
     }
 }
 
@@ -1201,11 +2727,16 @@ fn value_to_usize(value: &FalkorValue) -> usize {
 }
 
 
+// Cut to at most `max_len` characters, ending in "..." when shortened. Counts
+// chars rather than bytes so multi-byte names never split mid-character.
 fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len-3])
+    match s.char_indices().nth(max_len) {
+        None => s.to_string(),
+        Some(_) if max_len <= 3 => s.chars().take(max_len).collect(),
+        Some(_) => {
+            let keep = s.char_indices().nth(max_len - 3).map_or(s.len(), |(i, _)| i);
+            format!("{}...", &s[..keep])
+        }
     }
 }
 
@@ -1222,6 +2753,13 @@ fn value_to_properties(value: &FalkorValue) -> HashMap<String, serde_json::Value
     }
 }
 
+// Relationship properties without embeddings, which are large and never displayed
+fn edge_properties(value: &FalkorValue) -> HashMap<String, serde_json::Value> {
+    let mut props = value_to_properties(value);
+    props.retain(|key, _| !key.ends_with("_embedding"));
+    props
+}
+
 fn falkor_value_to_json(value: &FalkorValue) -> serde_json::Value {
     match value {
         FalkorValue::String(s) => serde_json::Value::String(s.clone()),
@@ -1255,10 +2793,28 @@ fn escape_cypher_string(s: &str) -> String {
 
 // ==================== Centrality Proxy Handlers ====================
 
-async fn proxy_centrality_health(State(state): State<AppState>) -> Response {
-    let url = format!("{}/health", state.centrality_url);
-    match state.http_client.get(&url).send().await {
+// Send a request to the centrality service through the circuit breaker and relay its response
+async fn forward_to_centrality(state: &AppState, request: reqwest::RequestBuilder, what: &str) -> Response {
+    if !state.centrality_breaker.allow_request() {
+        let retry_after = state.centrality_breaker.retry_after().unwrap_or(1);
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            Json(serde_json::json!({
+                "error": "Centrality service unavailable",
+                "details": "Circuit open after repeated failures",
+                "retry_after_secs": retry_after
+            }))
+        ).into_response();
+    }
+    
+    match request.with_request_id().send().await {
         Ok(resp) => {
+            if resp.status().is_server_error() {
+                state.centrality_breaker.record_failure();
+            } else {
+                state.centrality_breaker.record_success();
+            }
             let status = StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::OK);
             let body = resp.bytes().await.unwrap_or_default();
             Response::builder()
@@ -1268,9 +2824,15 @@ async fn proxy_centrality_health(State(state): State<AppState>) -> Response {
                 .unwrap()
         }
         Err(e) => {
-            error!("Failed to proxy health check: {}", e);
+            state.centrality_breaker.record_failure();
+            error!("Failed to proxy {}: {}", what, e);
+            let status = if e.is_timeout() {
+                StatusCode::GATEWAY_TIMEOUT
+            } else {
+                StatusCode::SERVICE_UNAVAILABLE
+            };
             (
-                StatusCode::SERVICE_UNAVAILABLE,
+                status,
                 Json(serde_json::json!({
                     "error": "Centrality service unavailable",
                     "details": e.to_string()
@@ -1280,29 +2842,14 @@ async fn proxy_centrality_health(State(state): State<AppState>) -> Response {
     }
 }
 
+async fn proxy_centrality_health(State(state): State<AppState>) -> Response {
+    let url = format!("{}/health", state.centrality_url);
+    forward_to_centrality(&state, state.http_client.get(&url), "health check").await
+}
+
 async fn proxy_centrality_stats(State(state): State<AppState>) -> Response {
     let url = format!("{}/stats", state.centrality_url);
-    match state.http_client.get(&url).send().await {
-        Ok(resp) => {
-            let status = StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::OK);
-            let body = resp.bytes().await.unwrap_or_default();
-            Response::builder()
-                .status(status)
-                .header("content-type", "application/json")
-                .body(Body::from(body))
-                .unwrap()
-        }
-        Err(e) => {
-            error!("Failed to proxy stats: {}", e);
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(serde_json::json!({
-                    "error": "Centrality service unavailable",
-                    "details": e.to_string()
-                }))
-            ).into_response()
-        }
-    }
+    forward_to_centrality(&state, state.http_client.get(&url), "stats").await
 }
 
 async fn proxy_centrality_pagerank(
@@ -1310,27 +2857,7 @@ async fn proxy_centrality_pagerank(
     Json(payload): Json<serde_json::Value>,
 ) -> Response {
     let url = format!("{}/centrality/pagerank", state.centrality_url);
-    match state.http_client.post(&url).json(&payload).send().await {
-        Ok(resp) => {
-            let status = StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::OK);
-            let body = resp.bytes().await.unwrap_or_default();
-            Response::builder()
-                .status(status)
-                .header("content-type", "application/json")
-                .body(Body::from(body))
-                .unwrap()
-        }
-        Err(e) => {
-            error!("Failed to proxy PageRank: {}", e);
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(serde_json::json!({
-                    "error": "Centrality service unavailable",
-                    "details": e.to_string()
-                }))
-            ).into_response()
-        }
-    }
+    forward_to_centrality(&state, state.http_client.post(&url).json(&payload), "PageRank").await
 }
 
 async fn proxy_centrality_degree(
@@ -1338,27 +2865,7 @@ async fn proxy_centrality_degree(
     Json(payload): Json<serde_json::Value>,
 ) -> Response {
     let url = format!("{}/centrality/degree", state.centrality_url);
-    match state.http_client.post(&url).json(&payload).send().await {
-        Ok(resp) => {
-            let status = StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::OK);
-            let body = resp.bytes().await.unwrap_or_default();
-            Response::builder()
-                .status(status)
-                .header("content-type", "application/json")
-                .body(Body::from(body))
-                .unwrap()
-        }
-        Err(e) => {
-            error!("Failed to proxy degree centrality: {}", e);
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(serde_json::json!({
-                    "error": "Centrality service unavailable",
-                    "details": e.to_string()
-                }))
-            ).into_response()
-        }
-    }
+    forward_to_centrality(&state, state.http_client.post(&url).json(&payload), "degree centrality").await
 }
 
 async fn proxy_centrality_betweenness(
@@ -1366,27 +2873,7 @@ async fn proxy_centrality_betweenness(
     Json(payload): Json<serde_json::Value>,
 ) -> Response {
     let url = format!("{}/centrality/betweenness", state.centrality_url);
-    match state.http_client.post(&url).json(&payload).send().await {
-        Ok(resp) => {
-            let status = StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::OK);
-            let body = resp.bytes().await.unwrap_or_default();
-            Response::builder()
-                .status(status)
-                .header("content-type", "application/json")
-                .body(Body::from(body))
-                .unwrap()
-        }
-        Err(e) => {
-            error!("Failed to proxy betweenness centrality: {}", e);
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(serde_json::json!({
-                    "error": "Centrality service unavailable",
-                    "details": e.to_string()
-                }))
-            ).into_response()
-        }
-    }
+    forward_to_centrality(&state, state.http_client.post(&url).json(&payload), "betweenness centrality").await
 }
 
 async fn proxy_centrality_all(
@@ -1394,27 +2881,8 @@ async fn proxy_centrality_all(
     Json(payload): Json<serde_json::Value>,
 ) -> Response {
     let url = format!("{}/centrality/all", state.centrality_url);
-    match state.http_client.post(&url).json(&payload).send().await {
-        Ok(resp) => {
-            let status = StatusCode::from_u16(resp.status().as_u16()).unwrap_or(StatusCode::OK);
-            let body = resp.bytes().await.unwrap_or_default();
-            Response::builder()
-                .status(status)
-                .header("content-type", "application/json")
-                .body(Body::from(body))
-                .unwrap()
-        }
-        Err(e) => {
-            error!("Failed to proxy all centralities: {}", e);
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(serde_json::json!({
-                    "error": "Centrality service unavailable",
-                    "details": e.to_string()
-                }))
-            ).into_response()
-        }
-    }
+    let request = state.http_client.post(&url).timeout(state.centrality_all_timeout).json(&payload);
+    forward_to_centrality(&state, request, "all centralities").await
 }
 
 async fn update_node_summary(
@@ -1433,7 +2901,8 @@ async fn update_node_summary(
         escape_cypher_string(&request.summary)
     );
     
-    let mut graph = state.client.select_graph(&state.graph_name);
+    let client = falkor_client(&state).await?;
+    let mut graph = client.select_graph(&state.graph_name);
     match graph.query(&query).execute().await {
         Ok(mut result) => {
             if let Some(row) = result.data.next() {
@@ -1444,6 +2913,7 @@ async fn update_node_summary(
                     
                     // Clear cache to ensure fresh data
                     state.graph_cache.clear();
+                    state.audit_log.record(AuditSource::Api, "update_summary", std::slice::from_ref(&uuid), &[]);
                     
                     Ok(Json(NodeUpdateResponse {
                         uuid,
@@ -1479,6 +2949,192 @@ async fn update_node_summary(
     }
 }
 
+// Maximum summaries written per UNWIND query
+const SUMMARY_BATCH_SIZE: usize = 500;
+
+async fn update_node_summaries(
+    State(state): State<AppState>,
+    Json(request): Json<BulkSummaryUpdateRequest>,
+) -> Json<serde_json::Value> {
+    info!("Bulk updating {} node summaries", request.updates.len());
+    let mut results = Vec::with_capacity(request.updates.len());
+    
+    for chunk in request.updates.chunks(SUMMARY_BATCH_SIZE) {
+        let rows: Vec<String> = chunk.iter()
+            .map(|u| format!(
+                "{{uuid: '{}', summary: '{}'}}",
+                escape_cypher_string(&u.uuid),
+                escape_cypher_string(&u.summary)
+            ))
+            .collect();
+        let query = format!(
+            r#"
+            UNWIND [{}] AS row
+            MATCH (n {{uuid: row.uuid}})
+            SET n.summary = row.summary
+            RETURN n.uuid as uuid
+            "#,
+            rows.join(", ")
+        );
+        
+        let batch = async {
+            let client = state.falkor_pool.get().await?;
+            let mut graph = client.select_graph(&state.graph_name);
+            let mut result = graph.query(&query).execute().await?;
+            let mut updated = std::collections::HashSet::new();
+            for row in result.data.by_ref() {
+                if let Some(value) = row.first() {
+                    updated.insert(value_to_string(value));
+                }
+            }
+            anyhow::Ok(updated)
+        };
+        match batch.await {
+            Ok(updated) => {
+                results.extend(chunk.iter().map(|u| {
+                    let success = updated.contains(&u.uuid);
+                    SummaryUpdateResult {
+                        uuid: u.uuid.clone(),
+                        success,
+                        error: (!success).then(|| "Node not found".to_string()),
+                    }
+                }));
+            }
+            Err(e) => {
+                error!("Failed to update node summaries batch: {}", e);
+                results.extend(chunk.iter().map(|u| SummaryUpdateResult {
+                    uuid: u.uuid.clone(),
+                    success: false,
+                    error: Some(format!("Failed to update summary: {}", e)),
+                }));
+            }
+        }
+    }
+    
+    let updated = results.iter().filter(|r| r.success).count();
+    if updated > 0 {
+        // Clear cache once for the whole batch
+        state.graph_cache.clear();
+        let updated_ids: Vec<String> = results.iter().filter(|r| r.success).map(|r| r.uuid.clone()).collect();
+        state.audit_log.record(AuditSource::Api, "update_summaries", &updated_ids, &[]);
+    }
+    
+    Json(serde_json::json!({
+        "updated": updated,
+        "failed": results.len() - updated,
+        "results": results,
+    }))
+}
+
+// Render a JSON value as a Cypher literal; maps and nested arrays can't be stored as properties
+fn cypher_literal(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(format!("'{}'", escape_cypher_string(s))),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        serde_json::Value::Null => Some("null".to_string()),
+        serde_json::Value::Array(items) => {
+            let literals = items.iter()
+                .map(|item| match item {
+                    serde_json::Value::Array(_) | serde_json::Value::Object(_) => None,
+                    other => cypher_literal(other),
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("[{}]", literals.join(", ")))
+        }
+        serde_json::Value::Object(_) => None,
+    }
+}
+
+fn is_valid_property_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+async fn update_node_properties(
+    Path(node_id): Path<String>,
+    State(state): State<AppState>,
+    Json(properties): Json<serde_json::Map<String, serde_json::Value>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let bad_request = |error: String| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }));
+    
+    if properties.is_empty() {
+        return Err(bad_request("No properties provided".to_string()));
+    }
+    
+    let mut assignments = Vec::with_capacity(properties.len());
+    for (key, value) in &properties {
+        if key == "uuid" {
+            return Err(bad_request("The uuid property cannot be changed".to_string()));
+        }
+        if !is_valid_property_key(key) {
+            return Err(bad_request(format!("Invalid property name: {}", key)));
+        }
+        let literal = cypher_literal(value)
+            .ok_or_else(|| bad_request(format!("Unsupported value for property {}", key)))?;
+        assignments.push(format!("n.{} = {}", key, literal));
+    }
+    
+    let query = format!(
+        r#"
+        MATCH (n {{uuid: '{}'}})
+        SET {}
+        RETURN properties(n) as props
+        "#,
+        escape_cypher_string(&node_id),
+        assignments.join(", ")
+    );
+    
+    let client = falkor_client(&state).await?;
+    let mut graph = client.select_graph(&state.graph_name);
+    match graph.query(&query).execute().await {
+        Ok(mut result) => {
+            let Some(props) = result.data.next().and_then(|row| row.first().map(value_to_properties)) else {
+                return Err((
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse {
+                        error: format!("Node with id {} not found", node_id),
+                    }),
+                ));
+            };
+            
+            // Clear caches to ensure fresh data
+            state.graph_cache.clear();
+            *state.arrow_cache.write().await = None;
+            state.audit_log.record(AuditSource::Api, "update_properties", std::slice::from_ref(&node_id), &[]);
+
+            // DuckDB keeps the name and summary, so mirror those
+            if let Ok(Some(mut node)) = state.duckdb_store.get_node_by_id(&node_id).await {
+                if let Some(name) = props.get("name").and_then(|v| v.as_str()) {
+                    node.label = name.to_string();
+                }
+                if let Some(summary) = props.get("summary").and_then(|v| v.as_str()) {
+                    node.summary = Some(summary.to_string());
+                }
+                state.duckdb_store.queue_node_update(node).await;
+                if let Err(e) = state.duckdb_store.process_updates().await {
+                    warn!("Failed to mirror property update for {} into DuckDB: {}", node_id, e);
+                }
+            }
+
+            Ok(Json(serde_json::json!({
+                "uuid": node_id,
+                "properties": props,
+            })))
+        }
+        Err(e) => {
+            error!("Failed to update node properties: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to update properties: {}", e),
+                }),
+            ))
+        }
+    }
+}
+
 // DuckDB endpoint handlers
 async fn get_duckdb_info(State(_state): State<AppState>) -> Result<Json<serde_json::Value>, StatusCode> {
     Ok(Json(serde_json::json!({
@@ -1493,48 +3149,113 @@ async fn get_duckdb_info(State(_state): State<AppState>) -> Result<Json<serde_js
     })))
 }
 
+#[derive(Debug, Deserialize)]
+struct ArrowQuery {
+    stream: Option<bool>,
+}
+
+// Rows per streamed Arrow batch
+const ARROW_STREAM_PAGE_SIZE: usize = 10_000;
+
+// Stream the nodes table as Arrow IPC, one DuckDB page of `page_size` rows per record batch
+fn stream_nodes_arrow(store: Arc<DuckDBStore>, page_size: usize) -> Response<Body> {
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<Bytes, std::io::Error>>(4);
+    
+    tokio::spawn(async move {
+        let to_io = |e: anyhow::Error| std::io::Error::other(e.to_string());
+        let (mut encoder, header) = match ArrowStreamEncoder::new(&store.nodes_schema()) {
+            Ok(encoder) => encoder,
+            Err(e) => {
+                let _ = tx.send(Err(to_io(e))).await;
+                return;
+            }
+        };
+        if tx.send(Ok(header)).await.is_err() {
+            return;
+        }
+        
+        let mut offset = 0;
+        loop {
+            let chunk = match store.get_nodes_as_arrow_page(offset, page_size).await {
+                Ok(batch) if batch.num_rows() == 0 => break,
+                Ok(batch) => {
+                    offset += batch.num_rows();
+                    encoder.encode(&batch).map(|bytes| (bytes, batch.num_rows()))
+                }
+                Err(e) => Err(e),
+            };
+            match chunk {
+                Ok((bytes, rows)) => {
+                    // Client went away
+                    if tx.send(Ok(bytes)).await.is_err() {
+                        return;
+                    }
+                    if rows < page_size {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to stream nodes page at offset {}: {}", offset, e);
+                    let _ = tx.send(Err(to_io(e))).await;
+                    return;
+                }
+            }
+        }
+        
+        match encoder.finish() {
+            Ok(footer) => { let _ = tx.send(Ok(footer)).await; }
+            Err(e) => { let _ = tx.send(Err(to_io(e))).await; }
+        }
+    });
+    
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    });
+    
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/vnd.apache.arrow.stream")
+        .header("X-Arrow-Schema", "nodes")
+        .header("X-Cache-Hit", "false")
+        .header("X-Arrow-Streamed", "true")
+        .header("Cache-Control", "no-cache")
+        .body(Body::from_stream(stream))
+        .unwrap()
+}
+
 async fn get_nodes_arrow(
     State(state): State<AppState>,
+    Query(params): Query<ArrowQuery>,
     headers: HeaderMap,
 ) -> Result<Response<Body>, (StatusCode, Json<ErrorResponse>)> {
     // Check cache first (unless disabled or force fresh)
     if state.cache_config.enabled && !state.cache_config.force_fresh {
         let cache = state.arrow_cache.read().await;
         if let Some(ref cached) = *cache {
-            // Check if client has the same version (ETag)
-            if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
-                if let Ok(client_etag) = if_none_match.to_str() {
-                    if client_etag == cached.nodes_etag {
-                        debug!("Client has current version (ETag match)");
-                        return Ok(Response::builder()
-                            .status(StatusCode::NOT_MODIFIED)
-                            .header(header::ETAG, &cached.nodes_etag)
-                            .header("Cache-Control", format!("public, max-age={}", state.cache_config.ttl_seconds))
-                            .body(Body::empty())
-                            .unwrap());
-                    }
-                }
-            }
-            
-            // Check cache TTL
-            if cached.timestamp.elapsed() < std::time::Duration::from_secs(state.cache_config.ttl_seconds) {
-            debug!("Serving nodes from Arrow cache");
-            return Ok(Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "application/vnd.apache.arrow.stream")
-                .header("X-Arrow-Schema", "nodes")
-                .header("X-Cache-Hit", "true")
-                .header(header::ETAG, &cached.nodes_etag)
-                .header("Cache-Control", format!("public, max-age={}", state.cache_config.ttl_seconds))
-                .header("Vary", "Accept-Encoding")
-                .body(Body::from(cached.nodes_bytes.clone()))
-                .unwrap());
+            // A client holding the current version gets a 304 even past the TTL
+            let fresh = cached.timestamp.elapsed() < std::time::Duration::from_secs(state.cache_config.ttl_seconds);
+            let etag = encoded_etag(&cached.nodes_etag, cached.nodes_bytes.encoding_for(&headers));
+            if fresh || client_has_etag(&headers, &etag) {
+                debug!("Serving nodes from Arrow cache");
+                return Ok(arrow_bytes_response(&cached.nodes_bytes, &cached.nodes_etag, "nodes", true, &headers, state.cache_config.ttl_seconds));
             }
         }
         drop(cache); // Release read lock
     } else {
         debug!("Cache disabled or force fresh data requested");
     }
+    
+    // Stream large exports page by page instead of buffering them
+    let stream = match params.stream {
+        Some(stream) => stream,
+        None => state.duckdb_store.get_stats().await
+            .map(|(nodes, _)| nodes > state.arrow_stream_threshold)
+            .unwrap_or(false),
+    };
+    if stream {
+        debug!("Streaming nodes as Arrow batches");
+        return Ok(stream_nodes_arrow(state.duckdb_store.clone(), ARROW_STREAM_PAGE_SIZE));
+    }
 
     match state.duckdb_store.get_nodes_as_arrow().await {
         Ok(batch) => {
@@ -1544,6 +3265,7 @@ async fn get_nodes_arrow(
                     
                     // Generate ETag for new data
                     let etag = ArrowCache::generate_etag(&bytes);
+                    let bytes = PrecompressedBytes::compress(bytes).await;
                     
                     // Update cache if we have edges too
                     let mut cache = state.arrow_cache.write().await;
@@ -1553,18 +3275,9 @@ async fn get_nodes_arrow(
                         cached.nodes_etag = etag.clone();
                         cached.timestamp = std::time::Instant::now();
                     }
+                    drop(cache);
                     
-                    // Note: Compression is handled by CompressionLayer middleware
-                    Ok(Response::builder()
-                        .status(StatusCode::OK)
-                        .header(header::CONTENT_TYPE, "application/vnd.apache.arrow.stream")
-                        .header("X-Arrow-Schema", "nodes")
-                        .header("X-Cache-Hit", "false")
-                        .header(header::ETAG, etag)
-                        .header("Cache-Control", format!("public, max-age={}", state.cache_config.ttl_seconds))
-                        .header("Vary", "Accept-Encoding")
-                        .body(Body::from(bytes))
-                        .unwrap())
+                    Ok(arrow_bytes_response(&bytes, &etag, "nodes", false, &headers, state.cache_config.ttl_seconds))
                 }
                 Err(e) => {
                     error!("Failed to convert nodes to Arrow: {}", e);
@@ -1597,34 +3310,12 @@ async fn get_edges_arrow(
     if state.cache_config.enabled && !state.cache_config.force_fresh {
         let cache = state.arrow_cache.read().await;
         if let Some(ref cached) = *cache {
-            // Check if client has the same version (ETag)
-            if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
-                if let Ok(client_etag) = if_none_match.to_str() {
-                    if client_etag == cached.edges_etag {
-                        debug!("Client has current version (ETag match)");
-                        return Ok(Response::builder()
-                            .status(StatusCode::NOT_MODIFIED)
-                            .header(header::ETAG, &cached.edges_etag)
-                            .header("Cache-Control", format!("public, max-age={}", state.cache_config.ttl_seconds))
-                            .body(Body::empty())
-                            .unwrap());
-                    }
-                }
-            }
-            
-            // Check cache TTL
-            if cached.timestamp.elapsed() < std::time::Duration::from_secs(state.cache_config.ttl_seconds) {
-            debug!("Serving edges from Arrow cache");
-            return Ok(Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "application/vnd.apache.arrow.stream")
-                .header("X-Arrow-Schema", "edges")
-                .header("X-Cache-Hit", "true")
-                .header(header::ETAG, &cached.edges_etag)
-                .header("Cache-Control", format!("public, max-age={}", state.cache_config.ttl_seconds))
-                .header("Vary", "Accept-Encoding")
-                .body(Body::from(cached.edges_bytes.clone()))
-                .unwrap());
+            // A client holding the current version gets a 304 even past the TTL
+            let fresh = cached.timestamp.elapsed() < std::time::Duration::from_secs(state.cache_config.ttl_seconds);
+            let etag = encoded_etag(&cached.edges_etag, cached.edges_bytes.encoding_for(&headers));
+            if fresh || client_has_etag(&headers, &etag) {
+                debug!("Serving edges from Arrow cache");
+                return Ok(arrow_bytes_response(&cached.edges_bytes, &cached.edges_etag, "edges", true, &headers, state.cache_config.ttl_seconds));
             }
         }
         drop(cache); // Release read lock
@@ -1640,6 +3331,7 @@ async fn get_edges_arrow(
                     
                     // Generate ETag for new data
                     let etag = ArrowCache::generate_etag(&bytes);
+                    let bytes = PrecompressedBytes::compress(bytes).await;
                     
                     // Update cache if we have nodes too
                     let mut cache = state.arrow_cache.write().await;
@@ -1649,18 +3341,9 @@ async fn get_edges_arrow(
                         cached.edges_etag = etag.clone();
                         cached.timestamp = std::time::Instant::now();
                     }
+                    drop(cache);
                     
-                    // Note: Compression is handled by CompressionLayer middleware
-                    Ok(Response::builder()
-                        .status(StatusCode::OK)
-                        .header(header::CONTENT_TYPE, "application/vnd.apache.arrow.stream")
-                        .header("X-Arrow-Schema", "edges")
-                        .header("X-Cache-Hit", "false")
-                        .header(header::ETAG, etag)
-                        .header("Cache-Control", format!("public, max-age={}", state.cache_config.ttl_seconds))
-                        .header("Vary", "Accept-Encoding")
-                        .body(Body::from(bytes))
-                        .unwrap())
+                    Ok(arrow_bytes_response(&bytes, &etag, "edges", false, &headers, state.cache_config.ttl_seconds))
                 }
                 Err(e) => {
                     error!("Failed to convert edges to Arrow: {}", e);
@@ -1691,6 +3374,7 @@ async fn get_duckdb_stats(State(state): State<AppState>) -> Result<Json<serde_js
             Ok(Json(serde_json::json!({
                 "nodes": node_count,
                 "edges": edge_count,
+                "dropped_pending_edges": state.duckdb_store.dropped_edge_count(),
                 "last_updated": std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
@@ -1704,6 +3388,65 @@ async fn get_duckdb_stats(State(state): State<AppState>) -> Result<Json<serde_js
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct TimeseriesQuery {
+    interval: Option<String>, // day (default), week, or month
+}
+
+async fn get_stats_timeseries(
+    Query(params): Query<TimeseriesQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<GrowthBucket>>, (StatusCode, Json<ErrorResponse>)> {
+    let interval = params.interval.as_deref().unwrap_or("day");
+    if !matches!(interval, "day" | "week" | "month") {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Invalid interval '{}': expected day, week, or month", interval),
+            }),
+        ));
+    }
+
+    match state.duckdb_store.get_node_growth(interval).await {
+        Ok(buckets) => Ok(Json(buckets)),
+        Err(e) => {
+            error!("Failed to compute node growth timeseries: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to compute timeseries: {}", e),
+                }),
+            ))
+        }
+    }
+}
+
+async fn get_stats_by_type(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<NodeTypeStats>>, (StatusCode, Json<ErrorResponse>)> {
+    match state.duckdb_store.get_node_type_stats().await {
+        Ok(stats) => Ok(Json(stats)),
+        Err(e) => {
+            error!("Failed to compute per-type stats: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to compute per-type stats: {}", e),
+                }),
+            ))
+        }
+    }
+}
+
+async fn get_pending_edges(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let pending = state.duckdb_store.get_pending_edges().await;
+    Json(serde_json::json!({
+        "count": pending.len(),
+        "dropped_total": state.duckdb_store.dropped_edge_count(),
+        "pending_edges": pending,
+    }))
+}
+
 async fn refresh_arrow_cache(State(state): State<AppState>) -> Result<Json<serde_json::Value>, StatusCode> {
     info!("Refreshing Arrow cache...");
     let start = std::time::Instant::now();
@@ -1725,8 +3468,8 @@ async fn refresh_arrow_cache(State(state): State<AppState>) -> Result<Json<serde
                         edges_batch,
                         nodes_etag: ArrowCache::generate_etag(&nodes_bytes),
                         edges_etag: ArrowCache::generate_etag(&edges_bytes),
-                        nodes_bytes,
-                        edges_bytes,
+                        nodes_bytes: PrecompressedBytes::compress(nodes_bytes).await,
+                        edges_bytes: PrecompressedBytes::compress(edges_bytes).await,
                         timestamp: std::time::Instant::now(),
                     };
                     
@@ -1756,6 +3499,173 @@ async fn refresh_arrow_cache(State(state): State<AppState>) -> Result<Json<serde
     }
 }
 
+async fn get_color_config(State(state): State<AppState>) -> Json<ColorMap> {
+    Json(state.duckdb_store.get_color_map())
+}
+
+async fn update_color_config(
+    State(state): State<AppState>,
+    Json(colors): Json<ColorMap>,
+) -> Result<Json<ColorMap>, (StatusCode, Json<ErrorResponse>)> {
+    match state.duckdb_store.set_color_map(colors).await {
+        Ok(color_map) => {
+            // Colors are baked into the Arrow output
+            *state.arrow_cache.write().await = None;
+            Ok(Json(color_map))
+        }
+        Err(e) => {
+            error!("Failed to update color map: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to update color map: {}", e),
+                }),
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotRequest {
+    name: String,
+}
+
+// Capture the current DuckDB graph under a name for later diffing
+async fn create_snapshot(
+    State(state): State<AppState>,
+    Json(request): Json<SnapshotRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    if request.name.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: "Snapshot name must not be empty".to_string() }),
+        ));
+    }
+    
+    match state.duckdb_store.create_snapshot(&request.name).await {
+        Ok((nodes, edges)) => {
+            info!("Captured snapshot '{}' with {} nodes and {} edges", request.name, nodes, edges);
+            Ok(Json(serde_json::json!({
+                "name": request.name,
+                "nodes": nodes,
+                "edges": edges,
+            })))
+        }
+        Err(e) => {
+            error!("Failed to capture snapshot '{}': {}", request.name, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to capture snapshot: {}", e),
+                }),
+            ))
+        }
+    }
+}
+
+// What changed in the current graph since the snapshot was captured
+async fn get_snapshot_diff(
+    State(state): State<AppState>,
+    Path(snapshot): Path<String>,
+) -> Result<Json<GraphDiff>, (StatusCode, Json<ErrorResponse>)> {
+    let internal_error = |e: anyhow::Error| {
+        error!("Failed to diff against snapshot '{}': {}", snapshot, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to diff against snapshot: {}", e),
+            }),
+        )
+    };
+    
+    let Some((old_nodes, old_edges)) = state.duckdb_store.get_snapshot(&snapshot).await.map_err(internal_error)? else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Snapshot {} not found", snapshot),
+            }),
+        ));
+    };
+    let (new_nodes, new_edges) = state.duckdb_store.get_graph().await.map_err(internal_error)?;
+    
+    let (old_nodes, old_edges) = index_graph(old_nodes, old_edges);
+    let (new_nodes, new_edges) = index_graph(new_nodes, new_edges);
+    Ok(Json(diff_graphs(&old_nodes, &old_edges, &new_nodes, &new_edges)))
+}
+
+async fn get_load_limits(State(state): State<AppState>) -> Json<LoadLimits> {
+    Json(*state.load_limits.read().await)
+}
+
+// Update the load limits and reload DuckDB with them
+async fn update_load_limits(
+    State(state): State<AppState>,
+    Json(request): Json<LoadLimitsUpdate>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    let bad_request = |error: &str| (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse { error: error.to_string() }),
+    );
+    if request.node_limit == Some(0) {
+        return Err(bad_request("node_limit must be positive"));
+    }
+    if request.edge_limit == Some(0) {
+        return Err(bad_request("edge_limit must be positive"));
+    }
+    if request.min_degree.is_some_and(|d| !d.is_finite() || d < 0.0) {
+        return Err(bad_request("min_degree must be non-negative"));
+    }
+    
+    let limits = {
+        let mut limits = state.load_limits.write().await;
+        if let Some(node_limit) = request.node_limit {
+            limits.node_limit = node_limit;
+        }
+        if let Some(edge_limit) = request.edge_limit {
+            limits.edge_limit = edge_limit;
+        }
+        if let Some(min_degree) = request.min_degree {
+            limits.min_degree = min_degree;
+        }
+        *limits
+    };
+    info!("Load limits updated to {:?}, reloading DuckDB", limits);
+    
+    let reload_start = std::time::Instant::now();
+    let reload = async {
+        let client = state.falkor_pool.get().await?;
+        let (nodes, edges) = fetch_limited_graph(&client, &state.graph_name, &limits, &state.label_fields).await?;
+        replace_graph(&state, &nodes, &edges, AuditSource::Api).await?;
+        anyhow::Ok((nodes.len(), edges.len()))
+    };
+    match reload.await {
+        Ok((node_count, edge_count)) => {
+            state.broadcast_event(ServerEvent::ReloadComplete {
+                nodes: node_count,
+                edges: edge_count,
+                duration_ms: reload_start.elapsed().as_millis(),
+                trigger: ReloadTrigger::Manual,
+            });
+            
+            Ok(Json(serde_json::json!({
+                "status": "success",
+                "limits": limits,
+                "nodes_loaded": node_count,
+                "edges_loaded": edge_count,
+            })))
+        }
+        Err(e) => {
+            error!("Failed to reload with new load limits: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to reload with new limits: {}", e),
+                }),
+            ))
+        }
+    }
+}
+
 // Real-time update handlers
 #[derive(Debug, Deserialize)]
 struct AddNodesRequest {
@@ -1779,22 +3689,18 @@ async fn add_nodes(
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
     info!("Adding {} new nodes", request.nodes.len());
     
-    // Queue nodes for processing
-    state.duckdb_store.queue_nodes(request.nodes.clone()).await;
-    
-    // Process updates immediately
-    match state.duckdb_store.process_updates().await {
+    // Queue and process in one step so the response only reflects this request
+    match state.duckdb_store.apply_updates(request.nodes.clone(), Vec::new()).await {
         Ok(Some(update)) => {
             // Broadcast update to WebSocket clients
             state.broadcast_update(update.clone());
             
-            // Clear caches to ensure fresh data
+            // Clear JSON caches and patch the Arrow cache with the new rows
             state.graph_cache.clear();
-            let mut arrow_cache = state.arrow_cache.write().await;
-            *arrow_cache = None;
-            drop(arrow_cache);
+            apply_update_to_arrow_cache(&state, &update).await;
             
-            info!("Nodes added successfully, caches cleared");
+            info!("Nodes added successfully, caches updated");
+            state.audit_log.record(AuditSource::Api, "add_nodes", &node_ids(&request.nodes), &[]);
             
             Ok(Json(serde_json::json!({
                 "status": "success",
@@ -1826,22 +3732,18 @@ async fn add_edges(
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
     info!("Adding {} new edges", request.edges.len());
     
-    // Queue edges for processing
-    state.duckdb_store.queue_edges(request.edges.clone()).await;
-    
-    // Process updates immediately
-    match state.duckdb_store.process_updates().await {
+    // Queue and process in one step so the response only reflects this request
+    match state.duckdb_store.apply_updates(Vec::new(), request.edges.clone()).await {
         Ok(Some(update)) => {
             // Broadcast update to WebSocket clients
             state.broadcast_update(update.clone());
             
-            // Clear caches to ensure fresh data
+            // Clear JSON caches and patch the Arrow cache with the new rows
             state.graph_cache.clear();
-            let mut arrow_cache = state.arrow_cache.write().await;
-            *arrow_cache = None;
-            drop(arrow_cache);
+            apply_update_to_arrow_cache(&state, &update).await;
             
-            info!("Edges added successfully, caches cleared");
+            info!("Edges added successfully, caches updated");
+            state.audit_log.record(AuditSource::Api, "add_edges", &[], &edge_ids(&request.edges));
             
             Ok(Json(serde_json::json!({
                 "status": "success",
@@ -1875,29 +3777,21 @@ async fn batch_update(
     let edge_count = request.edges.as_ref().map(|e| e.len()).unwrap_or(0);
     
     info!("Batch update: {} nodes, {} edges", node_count, edge_count);
+    let audit_node_ids = request.nodes.as_deref().map(node_ids).unwrap_or_default();
+    let audit_edge_ids = request.edges.as_deref().map(edge_ids).unwrap_or_default();
     
-    // Queue updates
-    if let Some(nodes) = request.nodes {
-        state.duckdb_store.queue_nodes(nodes).await;
-    }
-    
-    if let Some(edges) = request.edges {
-        state.duckdb_store.queue_edges(edges).await;
-    }
-    
-    // Process updates immediately
-    match state.duckdb_store.process_updates().await {
+    // Queue and process in one step so the response only reflects this request
+    match state.duckdb_store.apply_updates(request.nodes.unwrap_or_default(), request.edges.unwrap_or_default()).await {
         Ok(Some(update)) => {
             // Broadcast update to WebSocket clients
             state.broadcast_update(update.clone());
             
-            // Clear caches to ensure fresh data
+            // Clear JSON caches and patch the Arrow cache with the new rows
             state.graph_cache.clear();
-            let mut arrow_cache = state.arrow_cache.write().await;
-            *arrow_cache = None;
-            drop(arrow_cache);
+            apply_update_to_arrow_cache(&state, &update).await;
             
-            info!("Batch update successful, caches cleared");
+            info!("Batch update successful, caches updated");
+            state.audit_log.record(AuditSource::Api, "batch_update", &audit_node_ids, &audit_edge_ids);
             
             Ok(Json(serde_json::json!({
                 "status": "success",
@@ -1991,37 +3885,97 @@ fn transform_graphiti_nodes(graphiti_nodes: Vec<GraphitiNode>) -> Vec<Node> {
 }
 
 fn transform_graphiti_edges(graphiti_edges: Vec<GraphitiEdge>) -> Vec<Edge> {
-    graphiti_edges.into_iter().map(|ge| Edge {
-        from: ge.source_node_uuid,
-        to: ge.target_node_uuid,
-        edge_type: ge.name,
-        weight: 1.0, // Default weight, could be calculated from episodes count
+    graphiti_edges.into_iter().map(|ge| {
+        let mut properties = HashMap::new();
+        if let Some(fact) = ge.fact {
+            properties.insert("fact".to_string(), serde_json::Value::String(fact));
+        }
+        if let Some(created_at) = ge.created_at {
+            properties.insert("created_at".to_string(), serde_json::Value::String(created_at));
+        }
+        properties.insert("episodes".to_string(), serde_json::json!(ge.episodes));
+        
+        Edge {
+            from: ge.source_node_uuid,
+            to: ge.target_node_uuid,
+            edge_type: ge.name,
+            weight: 1.0, // Default weight, could be calculated from episodes count
+            properties,
+        }
     }).collect()
 }
 
-// Webhook receiver endpoint for data ingestion events
-async fn webhook_data_ingestion(
-    State(state): State<AppState>,
-    Json(webhook): Json<DataIngestionWebhook>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
-    info!("Received data ingestion webhook: operation={}, nodes={}, edges={}", 
+// HMAC-SHA256 (RFC 2104) over the raw request body
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let inner_hash = inner.finalize();
+    
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner_hash);
+    outer.finalize().into()
+}
+
+// Accepts the hex digest with or without a "sha256=" prefix
+fn verify_webhook_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let signature = signature.trim();
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let expected: String = hmac_sha256(secret.as_bytes(), body)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    constant_time_eq(signature.to_ascii_lowercase().as_bytes(), expected.as_bytes())
+}
+
+// Webhook receiver endpoint for data ingestion events
+async fn webhook_data_ingestion(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
+    if let Some(secret) = state.webhook_secret.as_deref() {
+        let signature = headers.get("x-signature").and_then(|v| v.to_str().ok());
+        if !signature.is_some_and(|sig| verify_webhook_signature(secret, &body, sig)) {
+            warn!("Rejected data ingestion webhook with missing or invalid signature");
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse {
+                    error: "Missing or invalid X-Signature".to_string(),
+                }),
+            ));
+        }
+    }
+    
+    let webhook: DataIngestionWebhook = serde_json::from_slice(&body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Invalid webhook payload: {}", e),
+            }),
+        )
+    })?;
+    
+    info!("Received data ingestion webhook: operation={}, nodes={}, edges={}", 
         webhook.operation, webhook.nodes.len(), webhook.edges.len());
     
     // Transform entities to Rust format
     let rust_nodes = transform_graphiti_nodes(webhook.nodes);
     let rust_edges = transform_graphiti_edges(webhook.edges);
+    let (rust_nodes, rust_edges, nodes_rejected, edges_rejected) =
+        state.type_allowlist.filter(rust_nodes, rust_edges);
     
-    // Queue updates for processing
-    if !rust_nodes.is_empty() {
-        state.duckdb_store.queue_nodes(rust_nodes.clone()).await;
-    }
-    
-    if !rust_edges.is_empty() {
-        state.duckdb_store.queue_edges(rust_edges.clone()).await;
-    }
-    
-    // Process updates immediately
-    match state.duckdb_store.process_updates().await {
+    // Queue and process in one step so the response only reflects this webhook
+    match state.duckdb_store.apply_updates(rust_nodes.clone(), rust_edges.clone()).await {
         Ok(Some(update)) => {
             // Broadcast update to WebSocket clients
             state.broadcast_update(update.clone());
@@ -2037,31 +3991,35 @@ async fn webhook_data_ingestion(
                 edges_updated: vec![],
                 edges_removed: vec![],
                 timestamp: update.timestamp,
-                sequence: 0, // Will be set by delta tracker if needed
+                sequence: 0, // Assigned by the delta tracker on broadcast
             };
             state.broadcast_delta(delta);
             
-            // Clear caches to ensure fresh data
+            // Clear JSON caches and patch the Arrow cache with the new rows
             state.graph_cache.clear();
-            let mut arrow_cache = state.arrow_cache.write().await;
-            *arrow_cache = None;
-            drop(arrow_cache);
+            apply_update_to_arrow_cache(&state, &update).await;
             
             info!("Webhook data processed: {} nodes, {} edges added", 
                 rust_nodes.len(), rust_edges.len());
+            state.audit_log.record(AuditSource::Webhook, &format!("ingest_{}", webhook.operation),
+                &node_ids(&rust_nodes), &edge_ids(&rust_edges));
             
             Ok(Json(serde_json::json!({
                 "status": "success",
                 "operation": webhook.operation,
                 "nodes_processed": rust_nodes.len(),
                 "edges_processed": rust_edges.len(),
+                "nodes_rejected": nodes_rejected,
+                "edges_rejected": edges_rejected,
                 "timestamp": update.timestamp
             })))
         }
         Ok(None) => {
             Ok(Json(serde_json::json!({
                 "status": "no_updates",
-                "message": "No updates to process"
+                "message": "No updates to process",
+                "nodes_rejected": nodes_rejected,
+                "edges_rejected": edges_rejected
             })))
         }
         Err(e) => {
@@ -2076,16 +4034,109 @@ async fn webhook_data_ingestion(
     }
 }
 
+// Identifiers of the nodes and edges a mutation touched, for the audit log
+fn node_ids(nodes: &[Node]) -> Vec<String> {
+    nodes.iter().map(|n| n.id.clone()).collect()
+}
+
+fn edge_ids(edges: &[Edge]) -> Vec<String> {
+    edges.iter().map(|e| audit::edge_id(&e.from, &e.to)).collect()
+}
+
+// Updates announcing the entities a reload removed, so clients can drop them
+fn deletion_updates(removed_nodes: Vec<Node>, removed_edges: Vec<Edge>) -> Vec<GraphUpdate> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let mut updates = Vec::new();
+    if !removed_edges.is_empty() {
+        updates.push(GraphUpdate {
+            operation: UpdateOperation::DeleteEdges,
+            nodes: None,
+            edges: Some(removed_edges),
+            timestamp,
+        });
+    }
+    if !removed_nodes.is_empty() {
+        updates.push(GraphUpdate {
+            operation: UpdateOperation::DeleteNodes,
+            nodes: Some(removed_nodes),
+            edges: None,
+            timestamp,
+        });
+    }
+    updates
+}
+
+// Replace the DuckDB contents with a freshly fetched graph and tell clients:
+// deletions and the full graph for update clients, the diff for delta clients
+// Replace the DuckDB contents with a freshly fetched graph; every reload path goes through here
+async fn replace_graph(state: &AppState, nodes: &[Node], edges: &[Edge], source: AuditSource) -> anyhow::Result<()> {
+    // Work out what the reload removes before the old contents are replaced
+    let (removed_nodes, removed_edges) = state.duckdb_store
+        .find_removed(nodes, edges)
+        .await
+        .unwrap_or_else(|e| {
+            error!("Failed to diff DuckDB contents before reload: {}", e);
+            (Vec::new(), Vec::new())
+        });
+    
+    // Atomically replace the DuckDB contents
+    state.duckdb_store.load_initial_data(nodes.to_vec(), edges.to_vec()).await?;
+    info!("Reload removed {} nodes and {} edges", removed_nodes.len(), removed_edges.len());
+    state.audit_log.record(source, "reload", &node_ids(&removed_nodes), &edge_ids(&removed_edges));
+    for update in deletion_updates(removed_nodes, removed_edges) {
+        state.broadcast_update(update);
+    }
+    
+    // Clear caches
+    state.graph_cache.clear();
+    let mut arrow_cache = state.arrow_cache.write().await;
+    *arrow_cache = None;
+    drop(arrow_cache);
+    
+    // Broadcast full reload event
+    let update = GraphUpdate {
+        operation: UpdateOperation::AddNodes, // Could add FullReload variant
+        nodes: Some(nodes.to_vec()),
+        edges: Some(edges.to_vec()),
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    };
+    state.broadcast_update(update);
+    
+    // Delta clients skip full updates, so they get the removals as a delta
+    let delta = state.delta_tracker.compute_delta(nodes.to_vec(), edges.to_vec()).await;
+    info!("Broadcasting delta: {} nodes added, {} nodes updated, {} nodes removed, {} edges added, {} edges updated, {} edges removed",
+        delta.nodes_added.len(),
+        delta.nodes_updated.len(),
+        delta.nodes_removed.len(),
+        delta.edges_added.len(),
+        delta.edges_updated.len(),
+        delta.edges_removed.len()
+    );
+    state.broadcast_delta(delta);
+    Ok(())
+}
+
 // Full reload endpoint - reload all data from FalkorDB
 async fn reload_duckdb_from_falkordb(
     State(state): State<AppState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
     info!("Starting full DuckDB reload from FalkorDB");
+    let reload_start = std::time::Instant::now();
     
     // Fetch fresh data from FalkorDB using the entire_graph query
-    let query = build_query("entire_graph", 100000, 0, None);
-    let graph_data = match execute_graph_query(&state.client, &state.graph_name, &query).await {
-        Ok(data) => data,
+    let query = build_query("entire_graph", 100000, 0, None, &QueryFilters::default());
+    let client = falkor_client(&state).await?;
+    let graph_data = match execute_graph_query(&client, &state.graph_name, &query, &state.entire_graph_limits, state.reload_timeout_ms).await {
+        Ok(mut data) => {
+            state.label_fields.apply(&mut data);
+            data
+        }
         Err(e) => {
             error!("Failed to fetch data from FalkorDB: {}", e);
             return Err((
@@ -2100,27 +4151,30 @@ async fn reload_duckdb_from_falkordb(
     info!("Fetched {} nodes and {} edges from FalkorDB", 
         graph_data.nodes.len(), graph_data.edges.len());
     
-    // Clear and reload DuckDB
-    // Note: This is a simplified version - in production you'd want atomic swap
-    match state.duckdb_store.load_initial_data(graph_data.nodes.clone(), graph_data.edges.clone()).await {
-        Ok(_) => {
-            // Clear caches
-            state.graph_cache.clear();
-            let mut arrow_cache = state.arrow_cache.write().await;
-            *arrow_cache = None;
-            drop(arrow_cache);
-            
-            // Broadcast full reload event
-            let update = GraphUpdate {
-                operation: UpdateOperation::AddNodes, // Could add FullReload variant
-                nodes: Some(graph_data.nodes.clone()),
-                edges: Some(graph_data.edges.clone()),
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64,
+    match replace_graph(&state, &graph_data.nodes, &graph_data.edges, AuditSource::Api).await {
+        Ok(()) => {
+            // Optionally refresh centralities so the reloaded graph doesn't render stale scores
+            let centrality_recomputed = if state.auto_recompute_centrality {
+                match recompute_centrality(&state).await {
+                    Ok(updated) => {
+                        info!("Refreshed centrality scores for {} nodes after reload", updated);
+                        true
+                    }
+                    Err(e) => {
+                        warn!("Centrality recompute after reload failed, keeping existing scores: {}", e);
+                        false
+                    }
+                }
+            } else {
+                false
             };
-            state.broadcast_update(update);
+            
+            state.broadcast_event(ServerEvent::ReloadComplete {
+                nodes: graph_data.nodes.len(),
+                edges: graph_data.edges.len(),
+                duration_ms: reload_start.elapsed().as_millis(),
+                trigger: ReloadTrigger::Manual,
+            });
             
             info!("DuckDB reload completed successfully");
             
@@ -2128,6 +4182,7 @@ async fn reload_duckdb_from_falkordb(
                 "status": "success",
                 "nodes_loaded": graph_data.nodes.len(),
                 "edges_loaded": graph_data.edges.len(),
+                "centrality_recomputed": centrality_recomputed,
                 "stats": graph_data.stats
             })))
         }
@@ -2143,6 +4198,61 @@ async fn reload_duckdb_from_falkordb(
     }
 }
 
+// Scores in the centrality service's `/centrality/all` response, per node and metric
+#[derive(Debug, Deserialize)]
+struct CentralityAllResponse {
+    scores: HashMap<String, HashMap<String, f64>>,
+}
+
+// Ask the centrality service to recompute and store all scores, then copy them into DuckDB
+async fn recompute_centrality(state: &AppState) -> anyhow::Result<usize> {
+    if !state.centrality_breaker.allow_request() {
+        anyhow::bail!("centrality service circuit is open");
+    }
+    let url = format!("{}/centrality/all", state.centrality_url);
+    let resp = match state.http_client
+        .post(&url)
+        .timeout(state.centrality_all_timeout)
+        .json(&serde_json::json!({ "store_results": true }))
+        .with_request_id()
+        .send()
+        .await
+    {
+        Ok(resp) => resp,
+        Err(e) => {
+            state.centrality_breaker.record_failure();
+            return Err(e.into());
+        }
+    };
+    if !resp.status().is_success() {
+        if resp.status().is_server_error() {
+            state.centrality_breaker.record_failure();
+        }
+        anyhow::bail!("centrality service returned {}", resp.status());
+    }
+    state.centrality_breaker.record_success();
+    
+    // The service answers with the scores it stored; a failed metric is
+    // missing from them and keeps its current value
+    let response: CentralityAllResponse = resp.json().await?;
+    let scores: Vec<CentralityScores> = response.scores.into_iter()
+        .map(|(id, metrics)| CentralityScores {
+            degree: metrics.get("degree").copied(),
+            pagerank: metrics.get("pagerank").copied(),
+            betweenness: metrics.get("betweenness").copied(),
+            eigenvector: metrics.get("eigenvector").copied(),
+            id,
+        })
+        .collect();
+    
+    let updated = state.duckdb_store.update_centrality_scores(&scores).await?;
+    
+    state.graph_cache.clear();
+    *state.arrow_cache.write().await = None;
+    
+    Ok(updated)
+}
+
 // Incremental query endpoints for notification-based architecture
 
 #[derive(Debug, Deserialize)]
@@ -2197,6 +4307,42 @@ async fn get_nodes_by_ids(
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct NodesExistRequest {
+    uuids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct NodesExistResponse {
+    existing: Vec<String>,
+    missing: Vec<String>,
+}
+
+// Partition node UUIDs by whether DuckDB holds them, in request order, so
+// ingestion clients can order node and edge updates themselves
+async fn check_nodes_exist(
+    State(state): State<AppState>,
+    Json(request): Json<NodesExistRequest>,
+) -> Result<Json<NodesExistResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let mut seen = std::collections::HashSet::new();
+    let uuids: Vec<String> = request.uuids.into_iter()
+        .filter(|uuid| seen.insert(uuid.clone()))
+        .collect();
+    
+    let present = state.duckdb_store.existing_node_ids(&uuids).await.map_err(|e| {
+        error!("Failed to check node existence: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to check nodes: {}", e),
+            }),
+        )
+    })?;
+    
+    let (existing, missing) = uuids.into_iter().partition(|uuid| present.contains(uuid));
+    Ok(Json(NodesExistResponse { existing, missing }))
+}
+
 #[derive(Debug, Deserialize)]
 struct EdgesByIdsQuery {
     ids: String, // Comma-separated list of edge IDs (format: source-target)
@@ -2279,6 +4425,44 @@ async fn get_node_by_id(
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct NodeEdgesQuery {
+    direction: Option<String>, // in, out, or both (default)
+}
+
+async fn get_node_edges(
+    Path(id): Path<String>,
+    Query(params): Query<NodeEdgesQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Edge>>, (StatusCode, Json<ErrorResponse>)> {
+    let (outgoing, incoming) = match params.direction.as_deref().unwrap_or("both") {
+        "out" => (true, false),
+        "in" => (false, true),
+        "both" => (true, true),
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Invalid direction '{}': expected in, out, or both", other),
+                }),
+            ));
+        }
+    };
+    
+    match state.duckdb_store.get_node_edges(&id, outgoing, incoming).await {
+        Ok(edges) => Ok(Json(edges)),
+        Err(e) => {
+            error!("Failed to get edges for node {}: {}", id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: format!("Failed to get edges: {}", e),
+                }),
+            ))
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct QueueStatus {
     status: String,
@@ -2296,6 +4480,7 @@ async fn get_queue_status(State(state): State<AppState>) -> Result<Json<QueueSta
     
     match state.http_client.get(&format!("{}/queue/{}/metrics", queue_url, queue_name))
         .header("Accept", "application/json")
+        .with_request_id()
         .send()
         .await
     {
@@ -2381,3 +4566,1237 @@ async fn get_queue_status(State(state): State<AppState>) -> Result<Json<QueueSta
 
 // Force rebuild for CI/CD workflow
 // Trigger rebuild Wed Aug  6 01:14:42 AM EDT 2025
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    // State backed by an in-memory DuckDB and a FalkorDB pool that is never connected
+    fn test_state() -> AppState {
+        let (update_tx, _) = broadcast::channel(100);
+        let (delta_tx, _) = broadcast::channel(100);
+        let (event_tx, _) = broadcast::channel(100);
+        AppState {
+            falkor_pool: falkor_pool::create_pool("falkor://127.0.0.1:1".to_string(), "test".to_string()).unwrap(),
+            graph_name: "test".to_string(),
+            graph_cache: Arc::new(DashMap::new()),
+            duckdb_store: Arc::new(DuckDBStore::new().unwrap()),
+            update_tx,
+            delta_tx,
+            event_tx,
+            arrow_cache: Arc::new(RwLock::new(None)),
+            delta_tracker: Arc::new(DeltaTracker::new()),
+            http_client: Arc::new(reqwest::Client::new()),
+            centrality_breaker: Arc::new(CircuitBreaker::new(3, std::time::Duration::from_secs(30))),
+            centrality_url: "http://127.0.0.1:1".to_string(),
+            centrality_all_timeout: std::time::Duration::from_secs(300),
+            search_url: None,
+            auto_recompute_centrality: false,
+            cache_config: CacheConfig {
+                enabled: false,
+                ttl_seconds: 0,
+                strategy: CacheStrategy::Disabled,
+                force_fresh: true,
+            },
+            enhanced_cache: None,
+            arrow_stream_threshold: usize::MAX,
+            ws_max_message_bytes: 1 << 20,
+            load_limits: Arc::new(RwLock::new(LoadLimits { node_limit: 100, edge_limit: 100, min_degree: 0.0 })),
+            api_token: None,
+            webhook_secret: None,
+            label_fields: LabelFields { default_field: None, per_type: HashMap::new(), max_len: DEFAULT_LABEL_MAX_LENGTH },
+            type_allowlist: TypeAllowlist::default(),
+            audit_log: Arc::new(AuditLog::disabled()),
+            entire_graph_limits: EntireGraphLimits { max_nodes: 1000, max_edges: 1000 },
+            query_timeout_ms: 0,
+            reload_timeout_ms: 0,
+            idempotency: Arc::new(IdempotencyStore::new(std::time::Duration::from_secs(60))),
+        }
+    }
+
+    #[test]
+    fn test_truncate_string_cuts_on_char_boundaries() {
+        assert_eq!(truncate_string("short", 10), "short");
+        assert_eq!(truncate_string("exactly10!", 10), "exactly10!");
+        // The cut lands inside multi-byte characters
+        assert_eq!(truncate_string("ééééééé", 6), "ééé...");
+        assert_eq!(truncate_string("日本語のラベル", 5), "日本...");
+        assert_eq!(truncate_string("🦀🦀🦀🦀", 3), "🦀🦀🦀");
+        assert_eq!(truncate_string("🦀🦀🦀🦀", 4), "🦀🦀🦀🦀");
+    }
+
+    #[test]
+    fn test_labels_truncated_to_configured_length() {
+        let long_name = "x".repeat(80);
+        let mut data = GraphData {
+            nodes: vec![node(&long_name, "Entity")],
+            edges: vec![],
+            stats: GraphStats::default(),
+        };
+        let fields = LabelFields { default_field: None, per_type: HashMap::new(), max_len: 60 };
+        fields.apply(&mut data);
+        assert_eq!(data.nodes[0].label, format!("{}...", "x".repeat(57)));
+    }
+
+    #[test]
+    fn test_encoded_etag_marks_the_encoding() {
+        assert_eq!(encoded_etag("W/\"abc\"", Some("zstd")), "W/\"abc-zstd\"");
+        assert_eq!(encoded_etag("W/\"abc\"", None), "W/\"abc\"");
+    }
+
+    // Accepts connections and never answers
+    async fn stalled_backend() -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_stalled_centrality_service_times_out_then_trips_breaker() {
+        let mut state = test_state();
+        state.centrality_url = format!("http://{}", stalled_backend().await);
+        state.http_client = Arc::new(reqwest::Client::builder()
+            .timeout(std::time::Duration::from_millis(200))
+            .build()
+            .unwrap());
+        state.centrality_breaker = Arc::new(CircuitBreaker::new(2, std::time::Duration::from_secs(30)));
+
+        for _ in 0..2 {
+            let start = std::time::Instant::now();
+            let response = proxy_centrality_stats(State(state.clone())).await;
+            assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+            assert!(start.elapsed() < std::time::Duration::from_secs(2));
+        }
+
+        // Open now, so the service isn't contacted at all
+        let start = std::time::Instant::now();
+        let response = proxy_centrality_stats(State(state.clone())).await;
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let retry_after: u64 = response.headers()[header::RETRY_AFTER].to_str().unwrap().parse().unwrap();
+        assert!(retry_after > 0 && retry_after <= 30);
+    }
+
+    #[tokio::test]
+    async fn test_recompute_centrality_stores_returned_scores() {
+        let centrality = serve(Router::new().route("/centrality/all", post(|| async {
+            Json(serde_json::json!({
+                "scores": {
+                    "a": {"degree": 0.9, "pagerank": 0.4, "betweenness": 0.1, "eigenvector": 0.2, "importance": 0.5},
+                    // Betweenness failed, so it's missing
+                    "b": {"degree": 0.3, "pagerank": 0.2, "eigenvector": 0.1},
+                    "not-in-duckdb": {"degree": 1.0, "pagerank": 1.0, "betweenness": 1.0, "eigenvector": 1.0}
+                },
+                "nodes_processed": 3
+            }))
+        }))).await;
+        let mut state = test_state();
+        state.centrality_url = format!("http://{}", centrality);
+        let mut b = node("b", "Entity");
+        b.properties.insert("betweenness_centrality".to_string(), serde_json::json!(0.7));
+        state.duckdb_store.load_initial_data(vec![node("a", "Entity"), b], vec![]).await.unwrap();
+        state.graph_cache.insert("visualize".to_string(), GraphData { nodes: vec![], edges: vec![], stats: GraphStats::default() });
+
+        assert_eq!(recompute_centrality(&state).await.unwrap(), 2);
+
+        let scores = |id: &str| {
+            let store = state.duckdb_store.clone();
+            let id = id.to_string();
+            async move {
+                let node = store.get_node_by_id(&id).await.unwrap().unwrap();
+                ["degree_centrality", "pagerank_centrality", "betweenness_centrality", "eigenvector_centrality"]
+                    .map(|metric| node.properties[metric].as_f64().unwrap())
+            }
+        };
+        assert_eq!(scores("a").await, [0.9, 0.4, 0.1, 0.2]);
+        assert_eq!(scores("b").await, [0.3, 0.2, 0.7, 0.1]);
+        assert!(state.graph_cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reports_hung_falkordb() {
+        let addr = stalled_backend().await;
+        let mut state = test_state();
+        state.falkor_pool = falkor_pool::create_pool(format!("falkor://{}", addr), "test".to_string()).unwrap();
+
+        let start = std::time::Instant::now();
+        let (status, Json(body)) = health_check(State(state)).await;
+        assert!(start.elapsed() < HEALTH_PING_TIMEOUT + std::time::Duration::from_secs(1));
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["dependencies"]["falkordb"]["status"], "error");
+        assert!(body["dependencies"]["falkordb"]["error"].as_str().unwrap().contains("timed out"));
+        assert_eq!(body["dependencies"]["duckdb"]["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn test_health_check_reports_refused_falkordb() {
+        // Nothing listens on port 1
+        let state = test_state();
+
+        let start = std::time::Instant::now();
+        let (status, Json(body)) = health_check(State(state)).await;
+        assert!(start.elapsed() < HEALTH_PING_TIMEOUT);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["dependencies"]["falkordb"]["status"], "error");
+        assert!(!body["dependencies"]["falkordb"]["error"].as_str().unwrap().contains("timed out"));
+        assert_eq!(body["dependencies"]["duckdb"]["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn test_health_check_all_healthy() {
+        let server = mock_redis::MockRedis::start().await;
+        let mut state = test_state();
+        state.falkor_pool = falkor_pool::create_pool(format!("falkor://{}", server.addr), "test".to_string()).unwrap();
+        let redis_pool = RedisConfig::from_url(format!("redis://{}", server.addr))
+            .create_pool(Some(Runtime::Tokio1))
+            .unwrap();
+        state.enhanced_cache = Some(Arc::new(EnhancedCache::new(redis_pool, NegativeCacheConfig::default())));
+
+        let (status, Json(body)) = health_check(State(state)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["status"], "ok");
+        for component in ["falkordb", "duckdb", "redis"] {
+            assert_eq!(body["dependencies"][component]["status"], "ok", "{}", component);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_arrow_variants_have_their_own_etags() {
+        let mut state = test_state();
+        state.cache_config = CacheConfig { enabled: true, ttl_seconds: 60, strategy: CacheStrategy::Moderate, force_fresh: false };
+        state.duckdb_store.load_initial_data(vec![node("a", "Entity"), node("b", "Entity")], vec![edge("a", "b")]).await.unwrap();
+        assert!(refresh_arrow_cache(State(state.clone())).await.is_ok());
+        let raw = ArrowConverter::record_batch_to_bytes(&state.duckdb_store.get_nodes_as_arrow().await.unwrap()).unwrap();
+
+        let request = |accept: &str, if_none_match: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT_ENCODING, accept.parse().unwrap());
+            if let Some(etag) = if_none_match {
+                headers.insert(header::IF_NONE_MATCH, etag.parse().unwrap());
+            }
+            get_nodes_arrow(State(state.clone()), Query(ArrowQuery { stream: Some(false) }), headers)
+        };
+        let etag = |response: &Response<Body>| response.headers()[header::ETAG].to_str().unwrap().to_string();
+
+        let zstd = request("zstd, gzip", None).await.unwrap();
+        assert_eq!(zstd.headers()[header::CONTENT_ENCODING], "zstd");
+        assert_eq!(zstd.headers()[header::VARY], "Accept-Encoding");
+        let zstd_etag = etag(&zstd);
+        assert!(zstd_etag.ends_with("-zstd\""));
+        let body = axum::body::to_bytes(zstd.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(zstd::decode_all(&body[..]).unwrap(), raw);
+
+        let gzip = request("gzip", None).await.unwrap();
+        assert_eq!(gzip.headers()[header::CONTENT_ENCODING], "gzip");
+        let gzip_etag = etag(&gzip);
+        let body = axum::body::to_bytes(gzip.into_body(), usize::MAX).await.unwrap();
+        let mut decoded = Vec::new();
+        std::io::Read::read_to_end(&mut flate2::read::GzDecoder::new(&body[..]), &mut decoded).unwrap();
+        assert_eq!(decoded, raw);
+
+        let identity = request("identity", None).await.unwrap();
+        assert!(identity.headers().get(header::CONTENT_ENCODING).is_none());
+        let identity_etag = etag(&identity);
+        assert_ne!(zstd_etag, gzip_etag);
+        assert_ne!(identity_etag, gzip_etag);
+
+        // A client holding one variant still gets the other encoding in full
+        assert_eq!(request("gzip", Some(&zstd_etag)).await.unwrap().status(), StatusCode::OK);
+        let not_modified = request("gzip", Some(&gzip_etag)).await.unwrap();
+        assert_eq!(not_modified.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(not_modified.headers()[header::VARY], "Accept-Encoding");
+    }
+
+    #[tokio::test]
+    async fn test_incremental_arrow_update_is_compressed_after_release() {
+        let mut state = test_state();
+        state.cache_config = CacheConfig { enabled: true, ttl_seconds: 60, strategy: CacheStrategy::Moderate, force_fresh: false };
+        let nodes: Vec<Node> = (0..20).map(|i| node(&format!("n{}", i), "Entity")).collect();
+        state.duckdb_store.load_initial_data(nodes, vec![]).await.unwrap();
+        assert!(refresh_arrow_cache(State(state.clone())).await.is_ok());
+
+        let update = state.duckdb_store.apply_updates(vec![node("new", "Entity")], vec![]).await.unwrap().unwrap();
+        assert!(matches!(update.operation, UpdateOperation::AddNodes));
+        apply_update_to_arrow_cache(&state, &update).await;
+
+        let cache = state.arrow_cache.read().await;
+        let cached = cache.as_ref().expect("cache kept after incremental update");
+        assert_eq!(cached.nodes_batch.num_rows(), 21);
+        assert!(cached.nodes_bytes.is_compressed());
+        assert_eq!(zstd::decode_all(&cached.nodes_bytes.zstd.as_ref().unwrap()[..]).unwrap(), cached.nodes_bytes.raw);
+    }
+
+    fn node(id: &str, node_type: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            label: id.to_string(),
+            node_type: node_type.to_string(),
+            summary: None,
+            properties: HashMap::new(),
+        }
+    }
+
+    fn edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            edge_type: "RELATES_TO".to_string(),
+            weight: 1.0,
+            properties: HashMap::new(),
+        }
+    }
+
+    // Serve `app` on an ephemeral port, returning its address
+    async fn serve(app: Router) -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        addr
+    }
+
+    // RESP result set of compact rows, as FalkorDB answers GRAPH.QUERY --compact
+    fn falkor_rows(columns: &[&str], rows: Vec<Vec<String>>) -> String {
+        use mock_redis::{array, bulk, int};
+        let header: Vec<String> = columns.iter().map(|c| array(&[int(1), bulk(c)])).collect();
+        let rows: Vec<String> = rows.iter().map(|row| array(row)).collect();
+        array(&[array(&header), array(&rows), array(&[bulk("Query internal execution time: 0.1 milliseconds")])])
+    }
+
+    fn falkor_string(value: &str) -> String {
+        mock_redis::array(&[mock_redis::int(2), mock_redis::bulk(value)])
+    }
+
+    #[tokio::test]
+    async fn test_manual_reload_sends_reload_complete_event() {
+        let server = mock_redis::MockRedis::start().await;
+        let mut state = test_state();
+        state.falkor_pool = falkor_pool::create_pool(format!("falkor://{}", server.addr), "test".to_string()).unwrap();
+        let empty_map = mock_redis::array(&[mock_redis::int(10), mock_redis::array(&[])]);
+        let degree = mock_redis::array(&[mock_redis::int(3), mock_redis::int(1)]);
+        server.reply_to_graph_queries_containing("n.uuid as id", falkor_rows(
+            &["id", "name", "node_type", "degree_centrality", "props"],
+            ["a", "b", "c"].iter()
+                .map(|id| vec![falkor_string(id), falkor_string(id), falkor_string("Entity"), degree.clone(), empty_map.clone()])
+                .collect(),
+        ));
+        server.reply_to_graph_queries_containing("n.uuid as source_id", falkor_rows(
+            &["source_id", "target_id", "rel_type", "edge_props"],
+            vec![
+                vec![falkor_string("a"), falkor_string("b"), falkor_string("RELATES_TO"), empty_map.clone()],
+                vec![falkor_string("b"), falkor_string("c"), falkor_string("RELATES_TO"), empty_map.clone()],
+            ],
+        ));
+
+        let addr = serve(Router::new().route("/ws", get(websocket_handler)).with_state(state.clone())).await;
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+        // The welcome message shows the socket is subscribed before the reload
+        ws.next().await.unwrap().unwrap();
+
+        let start = std::time::Instant::now();
+        let Json(response) = reload_duckdb_from_falkordb(State(state.clone())).await.unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!((response["nodes_loaded"].as_u64(), response["edges_loaded"].as_u64()), (Some(3), Some(2)));
+
+        let event = loop {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(5), ws.next())
+                .await
+                .expect("no reload_complete event")
+                .unwrap()
+                .unwrap();
+            let value: serde_json::Value = serde_json::from_str(msg.to_text().unwrap()).unwrap();
+            if value["type"] == "reload_complete" {
+                break value;
+            }
+        };
+        assert_eq!(event["nodes"], 3);
+        assert_eq!(event["edges"], 2);
+        assert_eq!(event["trigger"], "manual");
+        assert!(event["duration_ms"].as_u64().unwrap() <= elapsed.as_millis() as u64);
+        assert_eq!(state.duckdb_store.get_stats().await.unwrap(), (3, 2));
+    }
+
+    #[tokio::test]
+    async fn test_reload_removals_reach_delta_clients() {
+        let state = test_state();
+        let nodes = vec![node("a", "Entity"), node("b", "Entity")];
+        let edges = vec![edge("a", "b")];
+        state.duckdb_store.load_initial_data(nodes.clone(), edges.clone()).await.unwrap();
+        state.delta_tracker.compute_delta(nodes, edges).await;
+
+        let addr = serve(Router::new().route("/ws", get(websocket_handler)).with_state(state.clone())).await;
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+        ws.send(WsMessage::Text(r#"{"type": "subscribe:deltas"}"#.to_string())).await.unwrap();
+
+        let mut next_of_type = async |wanted: &str| loop {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(5), ws.next())
+                .await
+                .expect("no message from server")
+                .unwrap()
+                .unwrap();
+            let value: serde_json::Value = serde_json::from_str(msg.to_text().unwrap()).unwrap();
+            if value["type"] == wanted {
+                return value;
+            }
+        };
+        next_of_type("subscribed:deltas").await;
+
+        replace_graph(&state, &[node("a", "Entity")], &[], AuditSource::Api).await.unwrap();
+
+        let delta = next_of_type("graph:delta").await;
+        assert_eq!(delta["data"]["nodes_removed"], serde_json::json!(["b"]));
+        assert_eq!(delta["data"]["edges_removed"], serde_json::json!([["a", "b"]]));
+    }
+
+    #[tokio::test]
+    async fn test_replace_graph_clears_caches_and_records_its_source() {
+        let path = std::env::temp_dir().join(format!("audit-{}.jsonl", uuid::Uuid::new_v4()));
+        let mut state = test_state();
+        state.audit_log = Arc::new(AuditLog::to_writer(Box::new(std::fs::File::create(&path).unwrap())));
+        state.cache_config = CacheConfig { enabled: true, ttl_seconds: 60, strategy: CacheStrategy::Moderate, force_fresh: false };
+        state.duckdb_store.load_initial_data(vec![node("a", "Entity"), node("b", "Entity")], vec![edge("a", "b")]).await.unwrap();
+        assert!(refresh_arrow_cache(State(state.clone())).await.is_ok());
+        state.graph_cache.insert("visualize".to_string(), GraphData { nodes: vec![], edges: vec![], stats: GraphStats::default() });
+        let mut updates = state.update_tx.subscribe();
+
+        replace_graph(&state, &[node("a", "Entity")], &[], AuditSource::Monitor).await.unwrap();
+
+        assert!(state.graph_cache.is_empty());
+        assert!(state.arrow_cache.read().await.is_none());
+        let mut last = None;
+        while let Ok(update) = updates.try_recv() {
+            last = Some(update);
+        }
+        let full = last.expect("no full reload update");
+        assert_eq!(full.nodes.map(|nodes| nodes.len()), Some(1));
+
+        // Dropping the state drops the log, which writes out its records
+        drop(state);
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let record: serde_json::Value = serde_json::from_str(written.lines().last().unwrap()).unwrap();
+        assert_eq!(record["source"], "monitor");
+        assert_eq!(record["action"], "reload");
+        assert_eq!(record["node_ids"], serde_json::json!(["b"]));
+        assert_eq!(record["edge_ids"], serde_json::json!(["a->b"]));
+    }
+
+    #[tokio::test]
+    async fn test_request_id_forwarded_to_centrality_and_search() {
+        // Upstream recording the path and request id of every call it receives
+        type Seen = Arc<std::sync::Mutex<Vec<(String, Option<String>)>>>;
+        let seen = Seen::default();
+        let record = |seen: Seen, path: &'static str| {
+            move |headers: HeaderMap| async move {
+                let request_id = headers.get(request_id::REQUEST_ID_HEADER)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                seen.lock().unwrap().push((path.to_string(), request_id));
+                Json(serde_json::json!({"nodes": [], "edges": []}))
+            }
+        };
+        let upstream = serve(Router::new()
+            .route("/stats", get(record(seen.clone(), "/stats")))
+            .route("/search", post(record(seen.clone(), "/search")))).await;
+
+        let mut state = test_state();
+        state.centrality_url = format!("http://{}", upstream);
+        state.search_url = Some(format!("http://{}", upstream));
+        let app = serve(Router::new()
+            .route("/api/centrality/stats", get(proxy_centrality_stats))
+            .route("/api/search/fulltext", get(search_fulltext))
+            .layer(middleware::from_fn(propagate_request_id))
+            .with_state(state)).await;
+
+        let client = reqwest::Client::new();
+        let stats = client.get(format!("http://{}/api/centrality/stats", app))
+            .header("X-Request-Id", "req-stats")
+            .send().await.unwrap();
+        assert_eq!(stats.status(), 200);
+        assert_eq!(stats.headers()[request_id::REQUEST_ID_HEADER], "req-stats");
+        let search = client.get(format!("http://{}/api/search/fulltext?search=alice", app))
+            .header("X-Request-Id", "req-search")
+            .send().await.unwrap();
+        assert_eq!(search.headers()["x-search-source"], "service");
+
+        assert_eq!(*seen.lock().unwrap(), vec![
+            ("/stats".to_string(), Some("req-stats".to_string())),
+            ("/search".to_string(), Some("req-search".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn test_entire_graph_limits_refuse_oversized_graphs() {
+        let limits = EntireGraphLimits { max_nodes: 10, max_edges: 20 };
+        assert!(limits.check(10, 20).is_ok());
+        assert!(limits.check(0, 0).is_ok());
+        let too_many_nodes = limits.check(11, 0).unwrap_err();
+        assert_eq!((too_many_nodes.nodes, too_many_nodes.edges), (11, 0));
+        assert!(limits.check(10, 21).is_err());
+
+        // Surfaced through anyhow and recognised by the handlers
+        let err: anyhow::Error = limits.check(11, 21).unwrap_err().into();
+        let too_large = err.downcast_ref::<GraphTooLarge>().unwrap();
+        assert_eq!((too_large.limits.max_nodes, too_large.limits.max_edges), (10, 20));
+        assert!(err.to_string().contains("11 nodes and 21 edges"), "{}", err);
+    }
+
+    #[test]
+    fn test_decode_body_round_trips_zstd_and_gzip() {
+        use std::io::Write;
+        let body = br#"{"nodes": [{"id": "a"}], "edges": []}"#.repeat(50);
+        let zstd_body = zstd::encode_all(&body[..], 3).unwrap();
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(&body).unwrap();
+        let gzip_body = gzip.finish().unwrap();
+
+        assert_eq!(decode_body("zstd", &zstd_body, body.len()).unwrap(), body);
+        assert_eq!(decode_body("gzip", &gzip_body, body.len()).unwrap(), body);
+        // Over the limit once decompressed, and not compressed at all
+        let too_large = decode_body("zstd", &zstd_body, body.len() - 1).unwrap_err();
+        assert_eq!(too_large.kind(), std::io::ErrorKind::FileTooLarge);
+        assert!(decode_body("gzip", &body, body.len()).is_err());
+    }
+
+    #[test]
+    fn test_allowlist_drops_unlisted_types_and_their_edges() {
+        let allowlist = TypeAllowlist {
+            node_types: Some(["Entity".to_string()].into()),
+            edge_types: Some(["RELATES_TO".to_string()].into()),
+        };
+        let mut mentions = edge("a", "b");
+        mentions.edge_type = "MENTIONS".to_string();
+        let (nodes, edges, rejected_nodes, rejected_edges) = allowlist.filter(
+            vec![node("a", "Entity"), node("b", "Entity"), node("e", "Episodic")],
+            vec![edge("a", "b"), mentions, edge("e", "a")],
+        );
+
+        assert_eq!(nodes.iter().map(|n| n.id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(edges.len(), 1);
+        assert_eq!((edges[0].from.as_str(), edges[0].edge_type.as_str()), ("a", "RELATES_TO"));
+        assert_eq!((rejected_nodes, rejected_edges), (1, 2));
+
+        // Without lists everything is accepted
+        let (nodes, edges, rejected_nodes, rejected_edges) = TypeAllowlist::default()
+            .filter(vec![node("e", "Episodic")], vec![edge("e", "a")]);
+        assert_eq!((nodes.len(), edges.len(), rejected_nodes, rejected_edges), (1, 1, 0, 0));
+    }
+
+    #[test]
+    fn test_label_field_per_type_and_request_override() {
+        let fields = LabelFields {
+            default_field: None,
+            per_type: [("Document".to_string(), "title".to_string())].into(),
+            max_len: DEFAULT_LABEL_MAX_LENGTH,
+        };
+        let with = |props: serde_json::Value| -> HashMap<String, serde_json::Value> {
+            serde_json::from_value(props).unwrap()
+        };
+        let doc = with(serde_json::json!({"title": "Roadmap", "version": 3, "summary": "Plans"}));
+
+        assert_eq!(fields.label_for("Document", &doc).as_deref(), Some("Roadmap"));
+        assert_eq!(fields.label_for("Entity", &doc), None);
+        assert_eq!(fields.label_for("Document", &with(serde_json::json!({"title": ""}))), None);
+
+        // `label_field` applies to every type and beats the per-type field
+        let overridden = fields.with_override(Some(" version "));
+        assert_eq!(overridden.label_for("Document", &doc).as_deref(), Some("3"));
+        assert_eq!(overridden.label_for("Entity", &doc).as_deref(), Some("3"));
+        assert!(fields.with_override(Some("")).default_field.is_none());
+
+        let mut data = GraphData {
+            nodes: vec![Node { properties: doc.clone(), ..node("d", "Document") }, node("e", "Entity")],
+            edges: vec![],
+            stats: GraphStats::default(),
+        };
+        fields.apply(&mut data);
+        assert_eq!(data.nodes[0].label, "Roadmap");
+        assert_eq!(data.nodes[1].label, "e");
+    }
+
+    // Filters parsed from a visualize query string
+    fn filters(query: &str) -> QueryFilters {
+        let uri: axum::http::Uri = format!("/api/visualize?{}", query).parse().unwrap();
+        let Query(params) = Query::<QueryParams>::try_from_uri(&uri).unwrap();
+        QueryFilters::from_params(&params)
+    }
+
+    #[test]
+    fn test_min_degree_replaces_the_default_floors() {
+        let unfiltered = build_query("high_degree", 100, 0, None, &filters("query_type=high_degree"));
+        assert!(unfiltered.contains("n.degree_centrality > 0.001"));
+        assert!(unfiltered.contains("m.degree_centrality > 0.0005"));
+
+        let min_degree = filters("query_type=high_degree&min_degree=0.25");
+        assert!(!min_degree.is_empty());
+        assert!(min_degree.validate().is_ok());
+        let query = build_query("high_degree", 100, 0, None, &min_degree);
+        assert!(!query.contains("> 0.001") && !query.contains("> 0.0005"), "{}", query);
+        assert!(query.contains("COALESCE(n.degree_centrality, 0.0) >= 0.25"), "{}", query);
+        assert!(query.contains("COALESCE(m.degree_centrality, 0.0) >= 0.25"), "{}", query);
+
+        assert!(filters("query_type=high_degree&min_degree=-1").validate().is_err());
+        assert!(filters("query_type=high_degree&min_degree=NaN").validate().is_err());
+    }
+
+    #[test]
+    fn test_node_type_filter_restricts_both_endpoints() {
+        let episodic = filters("query_type=node_type&node_type=EpisodicNode");
+        assert!(!episodic.is_empty());
+        assert_eq!(episodic.node_conditions("n"), " AND COALESCE(n.type, labels(n)[0]) = 'EpisodicNode'");
+        // Edges are only followed to nodes of the same type
+        for query_type in ["node_type", "high_degree", "entire_graph"] {
+            let query = build_query(query_type, 100, 0, None, &episodic);
+            assert!(query.contains("COALESCE(n.type, labels(n)[0]) = 'EpisodicNode'"), "{}", query);
+            assert!(query.contains("COALESCE(m.type, labels(m)[0]) = 'EpisodicNode'"), "{}", query);
+        }
+
+        let quoted = filters("query_type=node_type&node_type=it's");
+        assert_eq!(quoted.node_conditions("n"), " AND COALESCE(n.type, labels(n)[0]) = 'it\\'s'");
+        assert!(filters("query_type=node_type&node_type=").is_empty());
+
+        // Each type is cached separately
+        let params = |query: &str| {
+            let uri: axum::http::Uri = format!("/api/visualize?{}", query).parse().unwrap();
+            format!("{:?}", Query::<QueryParams>::try_from_uri(&uri).unwrap().0)
+        };
+        assert_ne!(params("query_type=node_type&node_type=EpisodicNode"), params("query_type=node_type&node_type=EntityNode"));
+    }
+
+    #[tokio::test]
+    async fn test_created_range_filters_nodes_and_rejects_bad_timestamps() {
+        let range = filters("query_type=node_type&created_after=2024-01-01T00:00:00Z&created_before=2024-06-30T23:59:59%2B02:00");
+        assert!(range.validate().is_ok());
+        assert_eq!(
+            range.node_conditions("n"),
+            " AND n.created_at >= '2024-01-01T00:00:00Z' AND n.created_at <= '2024-06-30T23:59:59+02:00'"
+        );
+        let query = build_query("high_degree", 100, 0, None, &range);
+        assert!(query.contains("m.created_at >= '2024-01-01T00:00:00Z' AND m.created_at <= '2024-06-30T23:59:59+02:00'"), "{}", query);
+
+        // Only the bound that was given
+        assert_eq!(filters("query_type=node_type&created_after=2024-01-01T00:00:00Z").node_conditions("n"), " AND n.created_at >= '2024-01-01T00:00:00Z'");
+
+        for bad in ["created_after=2024-01-01", "created_before=yesterday", "created_after=2024-01-01T00:00:00Z'%20OR%20true"] {
+            let uri: axum::http::Uri = format!("/api/visualize?query_type=node_type&{}", bad).parse().unwrap();
+            let Query(params) = Query::<QueryParams>::try_from_uri(&uri).unwrap();
+            let (status, Json(error)) = visualize(State(test_state()), Query(params), HeaderMap::new()).await.unwrap_err();
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", bad);
+            assert!(error.error.contains("RFC3339"), "{}", error.error);
+        }
+    }
+
+    #[test]
+    fn test_edge_types_filter_restricts_relationships() {
+        let edge_types = filters("query_type=high_degree&edge_types=RELATES_TO,%20,MENTIONS%20,it's");
+        assert_eq!(edge_types.edge_types, vec!["RELATES_TO", "MENTIONS", "it's"]);
+        assert!(!edge_types.is_empty());
+        assert_eq!(
+            edge_types.edge_conditions("r"),
+            " AND type(r) IN ['RELATES_TO', 'MENTIONS', 'it\\'s']"
+        );
+        assert!(build_query("high_degree", 100, 0, None, &edge_types)
+            .contains("AND type(r) IN ['RELATES_TO', 'MENTIONS', 'it\\'s']"));
+        // A filtered entire_graph goes through the regular query path
+        assert!(build_query("entire_graph", 100, 0, None, &edge_types).contains("type(r) IN"));
+
+        let unfiltered = filters("query_type=high_degree&edge_types=");
+        assert!(unfiltered.is_empty());
+        assert_eq!(unfiltered.edge_conditions("r"), "");
+    }
+
+    #[test]
+    fn test_visualize_etag_answers_matching_requests_with_304() {
+        let data = || GraphData { nodes: vec![node("a", "Entity")], edges: vec![], stats: GraphStats::default() };
+        let first = graph_data_response(data(), 12, &HeaderMap::new(), 60);
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers()[header::ETAG].clone();
+        assert_eq!(first.headers()[header::CACHE_CONTROL], "public, max-age=60");
+
+        // Timing differs but the data doesn't, so the ETag is the same
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag.clone());
+        let repeat = graph_data_response(data(), 99, &headers, 60);
+        assert_eq!(repeat.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(repeat.headers()[header::ETAG], etag);
+
+        let mut changed = data();
+        changed.nodes.push(node("b", "Entity"));
+        let changed = graph_data_response(changed, 12, &headers, 60);
+        assert_eq!(changed.status(), StatusCode::OK);
+        assert_ne!(changed.headers()[header::ETAG], etag);
+    }
+
+    #[tokio::test]
+    async fn test_small_adds_patch_the_arrow_cache_and_large_ones_invalidate_it() {
+        let state = test_state();
+        let nodes: Vec<Node> = (0..20).map(|i| node(&format!("n{}", i), "Entity")).collect();
+        let edges: Vec<Edge> = (1..20).map(|i| edge("n0", &format!("n{}", i))).collect();
+        state.duckdb_store.load_initial_data(nodes, edges).await.unwrap();
+        assert!(refresh_arrow_cache(State(state.clone())).await.is_ok());
+        let (nodes_etag, edges_etag) = {
+            let cache = state.arrow_cache.read().await;
+            let cached = cache.as_ref().unwrap();
+            (cached.nodes_etag.clone(), cached.edges_etag.clone())
+        };
+
+        // The patched batches match a fresh export of the store
+        let update = state.duckdb_store.apply_updates(vec![node("new", "Entity")], vec![edge("new", "n1")]).await.unwrap().unwrap();
+        apply_update_to_arrow_cache(&state, &update).await;
+        {
+            let cache = state.arrow_cache.read().await;
+            let cached = cache.as_ref().expect("cache kept after a small add");
+            assert_eq!(cached.nodes_batch, state.duckdb_store.get_nodes_as_arrow().await.unwrap());
+            assert_eq!(cached.edges_batch, state.duckdb_store.get_edges_as_arrow().await.unwrap());
+            assert_ne!(cached.nodes_etag, nodes_etag);
+            assert_ne!(cached.edges_etag, edges_etag);
+        }
+
+        // More than a tenth of the cached rows at once is rebuilt from scratch
+        let many: Vec<Node> = (0..10).map(|i| node(&format!("bulk{}", i), "Entity")).collect();
+        let update = state.duckdb_store.apply_updates(many, vec![]).await.unwrap().unwrap();
+        apply_update_to_arrow_cache(&state, &update).await;
+        assert!(state.arrow_cache.read().await.is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "Benchmark; run with --ignored --nocapture"]
+    async fn bench_single_node_add_incremental_vs_full_rebuild() {
+        const NODES: usize = 5_000;
+        const ROUNDS: u32 = 5;
+        let state = test_state();
+        let nodes: Vec<Node> = (0..NODES).map(|i| node(&format!("n{}", i), "Entity")).collect();
+        let edges: Vec<Edge> = (1..NODES).map(|i| edge(&format!("n{}", i / 2), &format!("n{}", i))).collect();
+        state.duckdb_store.load_initial_data(nodes, edges).await.unwrap();
+        assert!(refresh_arrow_cache(State(state.clone())).await.is_ok());
+
+        let mut full = std::time::Duration::ZERO;
+        let mut incremental = std::time::Duration::ZERO;
+        for round in 0..ROUNDS {
+            let id = format!("added{}", round);
+            let update = state.duckdb_store.apply_updates(vec![node(&id, "Entity")], vec![edge(&id, "n0")]).await.unwrap().unwrap();
+
+            let start = std::time::Instant::now();
+            apply_update_to_arrow_cache(&state, &update).await;
+            incremental += start.elapsed();
+            assert!(state.arrow_cache.read().await.is_some());
+
+            // What every add used to cost: invalidate, then rebuild on the next request
+            let start = std::time::Instant::now();
+            *state.arrow_cache.write().await = None;
+            assert!(refresh_arrow_cache(State(state.clone())).await.is_ok());
+            full += start.elapsed();
+        }
+        println!("single-node add over {} nodes: incremental {:?}, full rebuild {:?}", NODES, incremental / ROUNDS, full / ROUNDS);
+        assert!(incremental < full, "incremental ({:?}) no faster than a full rebuild ({:?})", incremental / ROUNDS, full / ROUNDS);
+    }
+
+    #[tokio::test]
+    async fn test_streamed_node_export_matches_the_full_batch() {
+        let state = test_state();
+        let nodes: Vec<Node> = (0..25).map(|i| node(&format!("n{}", i), "Entity")).collect();
+        state.duckdb_store.load_initial_data(nodes, vec![]).await.unwrap();
+
+        let response = stream_nodes_arrow(state.duckdb_store.clone(), 10);
+        assert_eq!(response.headers()["X-Arrow-Streamed"], "true");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let batches = arrow::ipc::reader::StreamReader::try_new(std::io::Cursor::new(body), None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(), vec![10, 10, 5]);
+        let streamed = arrow::compute::concat_batches(&batches[0].schema(), &batches).unwrap();
+        assert_eq!(streamed, state.duckdb_store.get_nodes_as_arrow().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_api_token_gates_only_mutating_routes() {
+        let mut state = test_state();
+        state.api_token = Some("secret".to_string());
+        let app = serve(Router::new()
+            .route("/api/graph/sequence", get(get_current_sequence))
+            .route("/api/nodes/exists", post(check_nodes_exist))
+            .route("/api/cache/clear", post(clear_cache))
+            .layer(middleware::from_fn_with_state(state.clone(), require_api_token))
+            .with_state(state)).await;
+        let client = reqwest::Client::new();
+        let url = |path: &str| format!("http://{}{}", app, path);
+
+        assert_eq!(client.get(url("/api/graph/sequence")).send().await.unwrap().status(), 200);
+        let exists = client.post(url("/api/nodes/exists"))
+            .json(&serde_json::json!({"uuids": ["a"]}))
+            .send().await.unwrap();
+        assert_eq!(exists.status(), 200);
+
+        let anonymous = client.post(url("/api/cache/clear")).send().await.unwrap();
+        assert_eq!(anonymous.status(), 401);
+        assert_eq!(anonymous.headers()["www-authenticate"], "Bearer");
+        let wrong = client.post(url("/api/cache/clear")).bearer_auth("guess").send().await.unwrap();
+        assert_eq!(wrong.status(), 401);
+        let authorized = client.post(url("/api/cache/clear")).bearer_auth("secret").send().await.unwrap();
+        assert_eq!(authorized.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_mutations_open_without_api_token() {
+        // API_TOKEN unset
+        let state = test_state();
+        assert!(state.api_token.is_none());
+        let app = serve(Router::new()
+            .route("/api/cache/clear", post(clear_cache))
+            .route("/api/snapshot", post(create_snapshot))
+            .layer(middleware::from_fn_with_state(state.clone(), require_api_token))
+            .with_state(state)).await;
+        let client = reqwest::Client::new();
+
+        let cleared = client.post(format!("http://{}/api/cache/clear", app)).send().await.unwrap();
+        assert_eq!(cleared.status(), 200);
+        let snapshot = client.post(format!("http://{}/api/snapshot", app))
+            .json(&serde_json::json!({"name": "before"}))
+            .send().await.unwrap();
+        assert_eq!(snapshot.status(), 200);
+    }
+
+    #[test]
+    fn test_webhook_signature_matches_rfc_4231_vectors() {
+        let hex = |bytes: [u8; 32]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        assert_eq!(
+            hex(hmac_sha256(&[0x0b; 20], b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        // Keys longer than the block size are hashed first
+        assert_eq!(
+            hex(hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First")),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+
+        let body = br#"{"nodes": []}"#;
+        let signature = hex(hmac_sha256(b"secret", body));
+        assert!(verify_webhook_signature("secret", body, &signature));
+        assert!(verify_webhook_signature("secret", body, &format!("sha256={}", signature.to_uppercase())));
+        assert!(!verify_webhook_signature("other", body, &signature));
+        assert!(!verify_webhook_signature("secret", br#"{"nodes": [1]}"#, &signature));
+        assert!(!verify_webhook_signature("secret", body, ""));
+    }
+
+    #[test]
+    fn test_falkordb_credentials_are_encoded_into_the_url() {
+        assert_eq!(falkordb_connection_string("db", "6379", None, None), "redis://db:6379");
+        assert_eq!(falkordb_connection_string("db", "6379", Some("graph"), None), "redis://graph@db:6379");
+        assert_eq!(falkordb_connection_string("db", "6379", None, Some("pw")), "redis://:pw@db:6379");
+        assert_eq!(
+            falkordb_connection_string("db", "6379", Some("user@corp"), Some("p:ss/w rd~")),
+            "redis://user%40corp:p%3Ass%2Fw%20rd~@db:6379"
+        );
+        // Encoded credentials still give a valid connection string
+        let info: falkordb::FalkorConnectionInfo =
+            falkordb_connection_string("db", "6379", Some("user@corp"), Some("p:ss")).as_str().try_into().unwrap();
+        assert!(matches!(info, falkordb::FalkorConnectionInfo::Redis(_)));
+    }
+
+    #[test]
+    fn test_fields_projection_keeps_requested_properties_and_name_and_type() {
+        let properties: HashMap<String, serde_json::Value> = serde_json::from_value(serde_json::json!({
+            "name": "Alice", "type": "Entity", "summary": "Engineer", "degree_centrality": 0.5, "group_id": "g"
+        })).unwrap();
+        let data = || GraphData {
+            nodes: vec![Node { properties: properties.clone(), ..node("a", "Entity") }],
+            edges: vec![edge("a", "a")],
+            stats: GraphStats::default(),
+        };
+
+        let projected = project_node_properties(data(), Some(" summary, ,degree_centrality"));
+        let mut keys: Vec<&str> = projected.nodes[0].properties.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["degree_centrality", "name", "summary", "type"]);
+        assert_eq!(projected.edges.len(), 1);
+
+        assert_eq!(project_node_properties(data(), None).nodes[0].properties, properties);
+    }
+
+    #[test]
+    fn test_falkor_values_render_without_debug_formatting() {
+        let string = |s: &str| FalkorValue::String(s.to_string());
+        assert_eq!(value_to_string(&FalkorValue::Array(vec![string("Alice"), FalkorValue::None, FalkorValue::I64(3)])), "Alice, 3");
+        let map = FalkorValue::Map([("title".to_string(), string("Roadmap")), ("id".to_string(), FalkorValue::I64(1))].into());
+        assert_eq!(value_to_string(&map), "Roadmap");
+        assert_eq!(value_to_string(&FalkorValue::Map(HashMap::new())), "");
+        let point = FalkorValue::Point(falkordb::Point { latitude: 52.5, longitude: 13.25 });
+        assert_eq!(value_to_string(&point), "52.5, 13.25");
+        assert_eq!(value_to_string(&FalkorValue::Bool(true)), "true");
+    }
+
+    #[test]
+    fn test_paginated_queries_break_degree_ties_by_uuid() {
+        let filters = QueryFilters::default();
+        for query_type in ["high_degree", "agents", "node_type"] {
+            let query = build_query(query_type, 100, 200, None, &filters);
+            let order = query.split("ORDER BY").nth(1).unwrap_or_else(|| panic!("{} has no ORDER BY", query_type));
+            let order = order.split("SKIP").next().unwrap();
+            assert!(order.contains("degree_centrality") && order.trim_end().ends_with("n.uuid ASC"), "{}: {}", query_type, order);
+            assert!(query.contains("SKIP 200"), "{}", query_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nodes_exist_partitions_uuids_in_request_order() {
+        let state = test_state();
+        state.duckdb_store.load_initial_data(vec![node("a", "Entity"), node("c", "Entity")], vec![]).await.unwrap();
+
+        let request = NodesExistRequest { uuids: ["c", "b", "a", "c", "d"].map(String::from).to_vec() };
+        let Json(response) = check_nodes_exist(State(state), Json(request)).await.unwrap();
+        assert_eq!(response.existing, vec!["c", "a"]);
+        assert_eq!(response.missing, vec!["b", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_fulltext_search_maps_ranked_service_results() {
+        let results = serde_json::json!({
+            "nodes": [
+                {"uuid": "b", "name": "Bob", "node_type": "Entity", "summary": "Second", "centrality": 0.4},
+                {"uuid": "a", "name": "Alice", "node_type": "Entity"},
+                {"uuid": "b", "name": "Bob again", "node_type": "Entity"}
+            ],
+            "edges": [
+                {"source_node_uuid": "a", "target_node_uuid": "b", "fact": "Alice knows Bob", "weight": 2.0},
+                {"source_node_uuid": "a", "target_node_uuid": "unreachable"}
+            ]
+        });
+        let search = serve(Router::new().route("/search", post(move || async move { Json(results) }))).await;
+        let mut state = test_state();
+        state.search_url = Some(format!("http://{}", search));
+
+        let params = FulltextSearchParams { search: " alice ".to_string(), limit: None, node_type: None };
+        let response = search_fulltext(State(state), Query(params), HeaderMap::new()).await.unwrap();
+        assert_eq!(response.headers()["x-search-source"], "service");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let data: GraphData = serde_json::from_slice(&body).unwrap();
+
+        // Service order is kept, duplicates dropped
+        let ranked: Vec<(&str, i64)> = data.nodes.iter()
+            .map(|n| (n.id.as_str(), n.properties["search_rank"].as_i64().unwrap()))
+            .collect();
+        assert_eq!(ranked, vec![("b", 0), ("a", 1)]);
+        assert_eq!(data.nodes[0].properties["degree_centrality"], 0.4);
+        // The edge whose endpoint couldn't be loaded is left out
+        assert_eq!(data.edges.len(), 1);
+        assert_eq!((data.edges[0].from.as_str(), data.edges[0].weight), ("a", 2.0));
+        assert_eq!(data.edges[0].properties["fact"], "Alice knows Bob");
+    }
+
+    #[tokio::test]
+    async fn test_edge_properties_survive_without_embeddings() {
+        let relationship = FalkorValue::Map([
+            ("fact".to_string(), FalkorValue::String("Alice knows Bob".to_string())),
+            ("fact_embedding".to_string(), FalkorValue::Array(vec![FalkorValue::F64(0.5)])),
+        ].into());
+        let properties = edge_properties(&relationship);
+        assert_eq!(properties.keys().collect::<Vec<_>>(), vec!["fact"]);
+
+        // Empty properties are left out of the JSON
+        let plain = serde_json::to_value(edge("a", "b")).unwrap();
+        assert!(plain.get("properties").is_none());
+
+        // Stored in DuckDB and exported as a JSON column
+        let state = test_state();
+        let with_fact = Edge { properties, ..edge("a", "b") };
+        state.duckdb_store.load_initial_data(vec![node("a", "Entity"), node("b", "Entity")], vec![with_fact]).await.unwrap();
+        let (_, edges) = state.duckdb_store.get_graph().await.unwrap();
+        assert_eq!(edges[0].properties["fact"], "Alice knows Bob");
+        let batch = state.duckdb_store.get_edges_as_arrow().await.unwrap();
+        let column = batch.column_by_name("properties").unwrap()
+            .as_any().downcast_ref::<arrow::array::StringArray>().unwrap();
+        let exported: serde_json::Value = serde_json::from_str(column.value(0)).unwrap();
+        assert_eq!(exported, serde_json::json!({"fact": "Alice knows Bob"}));
+    }
+
+    #[test]
+    fn test_cypher_literal_escapes_and_types_values() {
+        assert_eq!(cypher_literal(&serde_json::json!(r"it's a \ path")).unwrap(), r"'it\'s a \\ path'");
+        assert_eq!(cypher_literal(&serde_json::json!("say \"hi\"")).unwrap(), r#"'say \"hi\"'"#);
+        assert_eq!(cypher_literal(&serde_json::json!(3.5)).unwrap(), "3.5");
+        assert_eq!(cypher_literal(&serde_json::json!(true)).unwrap(), "true");
+        assert_eq!(cypher_literal(&serde_json::json!(null)).unwrap(), "null");
+        assert_eq!(cypher_literal(&serde_json::json!(["a'", 1])).unwrap(), r"['a\'', 1]");
+        assert!(cypher_literal(&serde_json::json!({"nested": 1})).is_none());
+        assert!(cypher_literal(&serde_json::json!([[1]])).is_none());
+    }
+
+    #[test]
+    fn test_property_keys_must_be_identifiers() {
+        for key in ["name", "_private", "score2"] {
+            assert!(is_valid_property_key(key), "{}", key);
+        }
+        for key in ["", "2nd", "a-b", "n.name", "a}) DETACH DELETE n //", "a` = 1"] {
+            assert!(!is_valid_property_key(key), "{}", key);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_node_properties_rejects_injected_keys() {
+        let server = mock_redis::MockRedis::start().await;
+        let mut state = test_state();
+        state.falkor_pool = falkor_pool::create_pool(format!("falkor://{}", server.addr), "test".to_string()).unwrap();
+
+        for body in [serde_json::json!({"a}) DETACH DELETE n //": 1}), serde_json::json!({"uuid": "other"})] {
+            let properties = body.as_object().unwrap().clone();
+            let (status, _) = update_node_properties(Path("a".to_string()), State(state.clone()), Json(properties))
+                .await
+                .unwrap_err();
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+        }
+        assert!(server.graph_queries().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_node_properties_reaches_duckdb() {
+        use mock_redis::{array, bulk, int};
+        let server = mock_redis::MockRedis::start().await;
+        let mut state = test_state();
+        state.falkor_pool = falkor_pool::create_pool(format!("falkor://{}", server.addr), "test".to_string()).unwrap();
+        state.duckdb_store.load_initial_data(vec![node("a", "Entity")], vec![]).await.unwrap();
+        state.graph_cache.insert("visualize".to_string(), GraphData { nodes: vec![], edges: vec![], stats: GraphStats::default() });
+
+        // FalkorDB answers with properties(n) after the SET
+        let string = |s: &str| array(&[int(2), bulk(s)]);
+        let props = array(&[
+            bulk("uuid"), string("a"),
+            bulk("name"), string("Renamed"),
+            bulk("summary"), string("New summary"),
+            bulk("rank"), array(&[int(3), int(7)]),
+        ]);
+        server.reply_to_graph_queries(array(&[
+            array(&[array(&[int(1), bulk("props")])]),
+            array(&[array(&[array(&[int(10), props])])]),
+            array(&[bulk("Properties set: 3")]),
+        ]));
+
+        let body = serde_json::json!({"name": "Renamed", "summary": "New summary", "rank": 7});
+        let Json(response) = update_node_properties(Path("a".to_string()), State(state.clone()), Json(body.as_object().unwrap().clone()))
+            .await
+            .unwrap();
+        assert_eq!(response["properties"]["name"], "Renamed");
+        assert_eq!(response["properties"]["rank"], 7);
+
+        let queries = server.graph_queries();
+        assert_eq!(queries.len(), 1);
+        assert!(queries[0].contains("n.name = 'Renamed'") && queries[0].contains("n.rank = 7"), "{}", queries[0]);
+
+        let stored = state.duckdb_store.get_node_by_id("a").await.unwrap().unwrap();
+        assert_eq!(stored.label, "Renamed");
+        assert_eq!(stored.summary.as_deref(), Some("New summary"));
+        assert!(state.graph_cache.is_empty());
+    }
+
+    #[test]
+    fn test_load_limits_read_from_env() {
+        std::env::set_var("NODE_LIMIT", "42");
+        std::env::set_var("EDGE_LIMIT", "84");
+        std::env::set_var("MIN_DEGREE_CENTRALITY", "0.25");
+        let limits = LoadLimits::from_env();
+        for var in ["NODE_LIMIT", "EDGE_LIMIT", "MIN_DEGREE_CENTRALITY"] {
+            std::env::remove_var(var);
+        }
+        assert_eq!((limits.node_limit, limits.edge_limit, limits.min_degree), (42, 84, 0.25));
+    }
+
+    #[tokio::test]
+    async fn test_updated_load_limits_bound_the_reload() {
+        let server = mock_redis::MockRedis::start().await;
+        let mut state = test_state();
+        state.falkor_pool = falkor_pool::create_pool(format!("falkor://{}", server.addr), "test".to_string()).unwrap();
+
+        let update = |node_limit, min_degree| update_load_limits(
+            State(state.clone()),
+            Json(LoadLimitsUpdate { node_limit: Some(node_limit), edge_limit: Some(30), min_degree: Some(min_degree) }),
+        );
+        let (status, _) = update(0, 0.5).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(server.graph_queries().is_empty());
+        assert_eq!(get_load_limits(State(state.clone())).await.0.node_limit, 100);
+
+        let Json(response) = update(2, 0.5).await.unwrap();
+        assert_eq!(response["limits"]["node_limit"], 2);
+        let Json(limits) = get_load_limits(State(state.clone())).await;
+        assert_eq!((limits.node_limit, limits.edge_limit, limits.min_degree), (2, 30, 0.5));
+
+        // Both reload queries carry the new limits
+        let queries = server.graph_queries();
+        assert_eq!(queries.len(), 2);
+        assert!(queries[0].contains("n.degree_centrality > 0.5") && queries[0].ends_with("LIMIT 2"), "{}", queries[0]);
+        assert!(queries[1].ends_with("LIMIT 30"), "{}", queries[1]);
+    }
+
+    #[tokio::test]
+    async fn test_custom_colors_reach_the_arrow_output() {
+        let state = test_state();
+        state.duckdb_store.load_initial_data(
+            vec![node("a", "EntityNode"), node("b", "EpisodicNode")],
+            vec![edge("a", "b")],
+        ).await.unwrap();
+        assert!(refresh_arrow_cache(State(state.clone())).await.is_ok());
+
+        let colors: ColorMap = serde_json::from_value(serde_json::json!({
+            "nodes": {"EntityNode": "#123456"},
+            "edges": {"RELATES_TO": "#abcdef"},
+        })).unwrap();
+        let Json(merged) = update_color_config(State(state.clone()), Json(colors)).await.unwrap();
+        assert_eq!(merged.nodes["EntityNode"], "#123456");
+        assert!(state.arrow_cache.read().await.is_none());
+
+        let column = |batch: &RecordBatch, name: &str| -> Vec<String> {
+            let values = batch.column_by_name(name).unwrap().as_any().downcast_ref::<arrow::array::StringArray>().unwrap();
+            values.iter().map(|v| v.unwrap().to_string()).collect()
+        };
+        // Stored rows are re-colored; unlisted types keep the default palette
+        let nodes = state.duckdb_store.get_nodes_as_arrow().await.unwrap();
+        assert_eq!(column(&nodes, "id"), vec!["a", "b"]);
+        assert_eq!(column(&nodes, "color"), vec!["#123456", "#2196F3"]);
+        assert_eq!(column(&state.duckdb_store.get_edges_as_arrow().await.unwrap(), "color"), vec!["#abcdef"]);
+
+        // And so are nodes added afterwards
+        state.duckdb_store.apply_updates(vec![node("c", "EntityNode")], vec![]).await.unwrap();
+        let nodes = state.duckdb_store.get_nodes_as_arrow().await.unwrap();
+        assert_eq!(column(&nodes, "color"), vec!["#123456", "#2196F3", "#123456"]);
+    }
+
+    #[tokio::test]
+    async fn test_node_details_found_and_missing() {
+        let state = test_state();
+        let mut known = node("a", "EntityNode");
+        known.summary = Some("Alice".to_string());
+        known.properties.insert("created_at".to_string(), serde_json::json!("2024-01-15T10:30:00+00:00"));
+        known.properties.insert("degree_centrality".to_string(), serde_json::json!(0.5));
+        state.duckdb_store.load_initial_data(vec![known, node("b", "EntityNode")], vec![edge("a", "b")]).await.unwrap();
+        let app = serve(Router::new().route("/api/node/:id", get(get_node_by_id)).with_state(state)).await;
+
+        let found = reqwest::get(format!("http://{}/api/node/a", app)).await.unwrap();
+        assert_eq!(found.status(), 200);
+        let body: serde_json::Value = found.json().await.unwrap();
+        assert_eq!(body["id"], "a");
+        assert_eq!(body["summary"], "Alice");
+        let properties = &body["properties"];
+        assert_eq!(properties["degree_centrality"], 0.5);
+        assert_eq!(properties["created_at"], "2024-01-15T10:30:00+00:00");
+        assert_eq!(properties["color"], "#4CAF50");
+        for key in ["pagerank_centrality", "betweenness_centrality", "eigenvector_centrality", "size"] {
+            assert!(properties[key].is_number(), "{} missing from {}", key, properties);
+        }
+
+        let missing = reqwest::get(format!("http://{}/api/node/nope", app)).await.unwrap();
+        assert_eq!(missing.status(), 404);
+        let body: serde_json::Value = missing.json().await.unwrap();
+        assert!(body["error"].as_str().unwrap().contains("nope"));
+    }
+
+    #[tokio::test]
+    async fn test_node_edges_filtered_by_direction() {
+        let state = test_state();
+        let nodes = ["hub", "in1", "in2", "out1", "other"].map(|id| node(id, "Entity")).to_vec();
+        let edges = vec![edge("in1", "hub"), edge("in2", "hub"), edge("hub", "out1"), edge("in1", "other")];
+        state.duckdb_store.load_initial_data(nodes, edges).await.unwrap();
+        let app = serve(Router::new().route("/api/node/:id/edges", get(get_node_edges)).with_state(state)).await;
+
+        let fetch = |query: &str| {
+            let url = format!("http://{}/api/node/hub/edges{}", app, query);
+            async move {
+                let response = reqwest::get(url).await.unwrap();
+                let status = response.status();
+                let body: serde_json::Value = response.json().await.unwrap();
+                (status, body)
+            }
+        };
+        let pairs = |body: &serde_json::Value| {
+            let mut pairs: Vec<(String, String)> = body.as_array().unwrap().iter()
+                .map(|e| (e["from"].as_str().unwrap().to_string(), e["to"].as_str().unwrap().to_string()))
+                .collect();
+            pairs.sort();
+            pairs
+        };
+        let pair = |from: &str, to: &str| (from.to_string(), to.to_string());
+
+        let (status, body) = fetch("?direction=in").await;
+        assert_eq!(status, 200);
+        assert_eq!(pairs(&body), vec![pair("in1", "hub"), pair("in2", "hub")]);
+        let (_, body) = fetch("?direction=out").await;
+        assert_eq!(pairs(&body), vec![pair("hub", "out1")]);
+        let (_, both) = fetch("?direction=both").await;
+        assert_eq!(pairs(&both), vec![pair("hub", "out1"), pair("in1", "hub"), pair("in2", "hub")]);
+        // Both directions is the default
+        let (_, default) = fetch("").await;
+        assert_eq!(pairs(&default), pairs(&both));
+
+        let (status, body) = fetch("?direction=sideways").await;
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("sideways"));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_summaries_written_in_one_query_per_batch() {
+        let server = mock_redis::MockRedis::start().await;
+        let mut state = test_state();
+        state.falkor_pool = falkor_pool::create_pool(format!("falkor://{}", server.addr), "test".to_string()).unwrap();
+        state.graph_cache.insert("visualize".to_string(), GraphData { nodes: vec![], edges: vec![], stats: GraphStats::default() });
+        // FalkorDB returns the uuids it matched; "ghost" doesn't exist
+        server.reply_to_graph_queries(falkor_rows(&["uuid"], vec![vec![falkor_string("a")], vec![falkor_string("b")]]));
+
+        let request: BulkSummaryUpdateRequest = serde_json::from_value(serde_json::json!({"updates": [
+            {"uuid": "a", "summary": "Alice's summary"},
+            {"uuid": "b", "summary": "Line one\nline two"},
+            {"uuid": "ghost", "summary": "Nobody"},
+        ]})).unwrap();
+        let Json(response) = update_node_summaries(State(state.clone()), Json(request)).await;
+        assert_eq!((response["updated"].as_u64(), response["failed"].as_u64()), (Some(2), Some(1)));
+        let results: Vec<(&str, bool)> = response["results"].as_array().unwrap().iter()
+            .map(|r| (r["uuid"].as_str().unwrap(), r["success"].as_bool().unwrap()))
+            .collect();
+        assert_eq!(results, vec![("a", true), ("b", true), ("ghost", false)]);
+        assert_eq!(response["results"][2]["error"], "Node not found");
+        assert!(state.graph_cache.is_empty());
+
+        // Every summary goes out escaped in a single UNWIND
+        let queries = server.graph_queries();
+        assert_eq!(queries.len(), 1);
+        for row in [r"{uuid: 'a', summary: 'Alice\'s summary'}", r"{uuid: 'b', summary: 'Line one\nline two'}", "{uuid: 'ghost', summary: 'Nobody'}"] {
+            assert!(queries[0].contains(row), "{} not in {}", row, queries[0]);
+        }
+
+        // Larger requests are split into batches
+        let updates: Vec<serde_json::Value> = (0..SUMMARY_BATCH_SIZE + 1)
+            .map(|i| serde_json::json!({"uuid": format!("n{}", i), "summary": "s"}))
+            .collect();
+        let request: BulkSummaryUpdateRequest = serde_json::from_value(serde_json::json!({"updates": updates})).unwrap();
+        let Json(batched) = update_node_summaries(State(state.clone()), Json(request)).await;
+        assert_eq!(batched["results"].as_array().unwrap().len(), SUMMARY_BATCH_SIZE + 1);
+        assert_eq!(server.graph_queries().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_pending_edges_endpoint_lists_buffered_edges() {
+        let state = test_state();
+        state.duckdb_store.load_initial_data(vec![node("a", "Entity")], vec![]).await.unwrap();
+        let before = chrono::Utc::now();
+        state.duckdb_store.apply_updates(vec![], vec![edge("a", "missing")]).await.unwrap();
+        // One retry while the node is still missing
+        state.duckdb_store.process_updates().await.unwrap();
+        let app = serve(Router::new().route("/api/pending-edges", get(get_pending_edges)).with_state(state)).await;
+
+        let body: serde_json::Value = reqwest::get(format!("http://{}/api/pending-edges", app)).await.unwrap().json().await.unwrap();
+        assert_eq!(body["count"], 1);
+        assert_eq!(body["dropped_total"], 0);
+        let pending = &body["pending_edges"][0];
+        assert_eq!((pending["edge"]["from"].as_str(), pending["edge"]["to"].as_str()), (Some("a"), Some("missing")));
+        assert_eq!(pending["retry_count"], 1);
+        let first_seen = chrono::DateTime::parse_from_rfc3339(pending["first_seen"].as_str().unwrap()).unwrap();
+        assert!(first_seen >= before && first_seen <= chrono::Utc::now());
+    }
+}
@@ -0,0 +1,139 @@
+//! In-process stand-in for Redis and FalkorDB, for tests that need a server
+//! that answers. Speaks enough RESP for PING, GET, SET, SETEX and INFO.
+//! GRAPH.QUERY is recorded and answered with an empty result unless a reply
+//! has been set for it with `reply_to_graph_queries[_containing]`.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+#[derive(Clone, Default)]
+struct Shared {
+    values: Arc<Mutex<HashMap<String, String>>>,
+    graph_replies: Arc<Mutex<Vec<(String, String)>>>,
+    graph_queries: Arc<Mutex<Vec<String>>>,
+}
+
+#[derive(Clone)]
+pub struct MockRedis {
+    pub addr: SocketAddr,
+    shared: Shared,
+}
+
+impl MockRedis {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = Self {
+            addr: listener.local_addr().unwrap(),
+            shared: Shared::default(),
+        };
+        let shared = server.shared.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(serve_connection(socket, shared.clone()));
+            }
+        });
+        server
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.shared.values.lock().unwrap().get(key).cloned()
+    }
+
+    pub fn set(&self, key: &str, value: String) {
+        self.shared.values.lock().unwrap().insert(key.to_string(), value);
+    }
+
+    /// Answer every following GRAPH.QUERY with `reply`, already RESP-encoded
+    pub fn reply_to_graph_queries(&self, reply: String) {
+        self.reply_to_graph_queries_containing("", reply);
+    }
+
+    /// Answer queries containing `pattern` with `reply`; the first pattern set wins
+    pub fn reply_to_graph_queries_containing(&self, pattern: &str, reply: String) {
+        self.shared.graph_replies.lock().unwrap().push((pattern.to_string(), reply));
+    }
+
+    /// Query strings received so far, oldest first
+    pub fn graph_queries(&self) -> Vec<String> {
+        self.shared.graph_queries.lock().unwrap().clone()
+    }
+}
+
+async fn serve_connection(socket: TcpStream, shared: Shared) {
+    let (reader, mut writer) = socket.into_split();
+    let mut reader = BufReader::new(reader);
+    while let Some(command) = read_command(&mut reader).await {
+        let reply = respond(&command, &shared);
+        if writer.write_all(reply.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
+
+// Read one command, sent as an array of bulk strings
+async fn read_command(reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>) -> Option<Vec<String>> {
+    let count = read_length(reader, '*').await?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = read_length(reader, '$').await?;
+        let mut arg = vec![0; len + 2];
+        reader.read_exact(&mut arg).await.ok()?;
+        arg.truncate(len);
+        args.push(String::from_utf8(arg).ok()?);
+    }
+    Some(args)
+}
+
+async fn read_length(reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>, prefix: char) -> Option<usize> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    line.strip_prefix(prefix)?.trim_end().parse().ok()
+}
+
+pub fn bulk(value: &str) -> String {
+    format!("${}\r\n{}\r\n", value.len(), value)
+}
+
+pub fn int(value: i64) -> String {
+    format!(":{}\r\n", value)
+}
+
+pub fn array(items: &[String]) -> String {
+    format!("*{}\r\n{}", items.len(), items.concat())
+}
+
+fn respond(command: &[String], shared: &Shared) -> String {
+    let values = &shared.values;
+    match command[0].to_ascii_uppercase().as_str() {
+        "PING" => "+PONG\r\n".to_string(),
+        "GET" => match values.lock().unwrap().get(&command[1]) {
+            Some(value) => bulk(value),
+            None => "$-1\r\n".to_string(),
+        },
+        "SET" => {
+            values.lock().unwrap().insert(command[1].clone(), command[2].clone());
+            "+OK\r\n".to_string()
+        }
+        "SETEX" => {
+            values.lock().unwrap().insert(command[1].clone(), command[3].clone());
+            "+OK\r\n".to_string()
+        }
+        "INFO" => bulk("# Server\r\nredis_mode:standalone\r\n"),
+        // GRAPH.QUERY <graph> <query> --compact
+        "GRAPH.QUERY" | "GRAPH.RO_QUERY" => {
+            let query = &command[2];
+            shared.graph_queries.lock().unwrap().push(query.clone());
+            let replies = shared.graph_replies.lock().unwrap();
+            match replies.iter().find(|(pattern, _)| query.contains(pattern.as_str())) {
+                Some((_, reply)) => reply.clone(),
+                // A result set holding only statistics
+                None => array(&[array(&[bulk("Query internal execution time: 0.1 milliseconds")])]),
+            }
+        }
+        // CLIENT SETINFO and anything else the clients send on connect
+        _ => "+OK\r\n".to_string(),
+    }
+}
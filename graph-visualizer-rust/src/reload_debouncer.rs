@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// What the change monitor samples from FalkorDB on every tick
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GraphCounts {
+    pub nodes: usize,
+    pub edges: usize,
+    pub centrality_sum: f64,
+}
+
+/// Decides when the change monitor reloads DuckDB.
+///
+/// A change only marks a reload as pending; it runs on the first sample
+/// whose counts match the previous one, so a burst of writes is reloaded
+/// once. Reloads are at least `cooldown` apart, and a pending one waits
+/// the cooldown out rather than being dropped.
+pub struct ReloadDebouncer {
+    cooldown: Duration,
+    last: GraphCounts,
+    pending: bool,
+    last_reload: Option<Instant>,
+}
+
+impl ReloadDebouncer {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            last: GraphCounts::default(),
+            pending: false,
+            last_reload: None,
+        }
+    }
+
+    /// Cooldown from `RELOAD_COOLDOWN_SECS` (default 30)
+    pub fn from_env() -> Self {
+        let cooldown = std::env::var("RELOAD_COOLDOWN_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(30);
+        Self::new(Duration::from_secs(cooldown))
+    }
+
+    /// Record a sample taken at `now`; true when a reload should run now
+    pub fn observe(&mut self, counts: GraphCounts, now: Instant) -> bool {
+        let last = self.last;
+        self.last = counts;
+
+        // Centrality updates change the sum without changing any count
+        let centrality_changed = (last.centrality_sum > 0.0 && (counts.centrality_sum - last.centrality_sum).abs() > 0.001) ||
+                                 (last.centrality_sum == 0.0 && counts.centrality_sum > 0.0);
+        // The first sample only sets the baseline
+        let graph_changed = last.nodes > 0 && (counts.nodes != last.nodes ||
+                                               counts.edges != last.edges ||
+                                               centrality_changed);
+        let cooled_down = self.last_reload
+            .is_none_or(|t| now.saturating_duration_since(t) >= self.cooldown);
+
+        if graph_changed {
+            info!("Graph changed: nodes {} -> {}, edges {} -> {}, waiting for counts to stabilize",
+                  last.nodes, counts.nodes, last.edges, counts.edges);
+            self.pending = true;
+            false
+        } else if self.pending && !cooled_down {
+            debug!("Graph stable but reload cooldown still active, deferring reload");
+            false
+        } else if self.pending {
+            self.pending = false;
+            self.last_reload = Some(now);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(30);
+    const TICK: Duration = Duration::from_secs(5);
+
+    fn counts(nodes: usize) -> GraphCounts {
+        GraphCounts { nodes, edges: nodes, centrality_sum: 1.0 }
+    }
+
+    // Feed one sample per tick, returning the ticks that triggered a reload
+    fn reloads(debouncer: &mut ReloadDebouncer, start: Instant, samples: &[GraphCounts]) -> Vec<usize> {
+        samples.iter()
+            .enumerate()
+            .filter(|(tick, sample)| debouncer.observe(**sample, start + TICK * *tick as u32))
+            .map(|(tick, _)| tick)
+            .collect()
+    }
+
+    #[test]
+    fn test_burst_of_changes_reloads_once() {
+        let mut debouncer = ReloadDebouncer::new(COOLDOWN);
+        let samples = [counts(10), counts(11), counts(12), counts(13), counts(13), counts(13)];
+        assert_eq!(reloads(&mut debouncer, Instant::now(), &samples), vec![4]);
+    }
+
+    #[test]
+    fn test_centrality_change_alone_triggers_reload() {
+        let mut debouncer = ReloadDebouncer::new(COOLDOWN);
+        let rescored = GraphCounts { centrality_sum: 2.0, ..counts(10) };
+        assert_eq!(reloads(&mut debouncer, Instant::now(), &[counts(10), rescored, rescored]), vec![2]);
+    }
+
+    #[test]
+    fn test_no_reload_during_cooldown() {
+        let mut debouncer = ReloadDebouncer::new(COOLDOWN);
+        let start = Instant::now();
+        // Reload at tick 2, then change again and settle well inside the cooldown
+        let samples = [counts(10), counts(11), counts(11), counts(12), counts(12), counts(12)];
+        assert_eq!(reloads(&mut debouncer, start, &samples), vec![2]);
+    }
+
+    #[test]
+    fn test_deferred_reload_fires_once_cooled_down() {
+        let mut debouncer = ReloadDebouncer::new(COOLDOWN);
+        let start = Instant::now();
+        let mut samples = vec![counts(10), counts(11), counts(11), counts(12)];
+        // Stable from tick 4 on; the cooldown since the tick 2 reload ends at tick 8
+        samples.extend(std::iter::repeat_n(counts(12), 6));
+        assert_eq!(reloads(&mut debouncer, start, &samples), vec![2, 8]);
+    }
+}
@@ -2,11 +2,34 @@ use axum::{
     extract::{State, WebSocketUpgrade, ws::{WebSocket, Message}},
     response::IntoResponse,
 };
+use serde::Serialize;
 use serde_json::{json, Value};
 use tracing::{info, error, debug};
 use uuid::Uuid;
 
-use crate::{AppState, duckdb_store::GraphUpdate, delta_tracker::GraphDelta};
+use crate::{AppState, duckdb_store::GraphUpdate, delta_tracker::{Backfill, GraphDelta}};
+
+/// Structured server events sent to every connected client, e.g.
+/// `{"type": "reload_complete", "nodes": 10, "edges": 20, "duration_ms": 150, "trigger": "manual"}`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    /// `nodes` and `edges` count what was loaded into DuckDB
+    ReloadComplete {
+        nodes: usize,
+        edges: usize,
+        duration_ms: u128,
+        trigger: ReloadTrigger,
+    },
+}
+
+/// What caused a DuckDB reload
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReloadTrigger {
+    Manual,
+    Auto,
+}
 
 /// Main WebSocket handler that upgrades HTTP connections to WebSocket
 pub async fn websocket_handler(
@@ -23,13 +46,14 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
     // Subscribe to broadcast channels
     let mut update_rx = state.update_tx.subscribe();
     let mut delta_rx = state.delta_tx.subscribe();
+    let mut event_rx = state.event_tx.subscribe();
     
     // Track client for logging
     let client_id = Uuid::new_v4().to_string();
     info!("Client {} connected", client_id);
     
     // Send initial connection confirmation
-    if let Err(e) = send_connection_message(&mut socket).await {
+    if let Err(e) = send_connection_message(&mut socket, state.delta_tracker.last_sequence()).await {
         error!("Failed to send connection message: {}", e);
         return;
     }
@@ -61,7 +85,7 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
             Ok(delta) = delta_rx.recv() => {
                 info!("Received delta broadcast for client {}", client_id);
                 if use_deltas {
-                    if let Err(e) = send_delta_update(&mut socket, delta).await {
+                    if let Err(e) = send_delta_update(&mut socket, delta, state.ws_max_message_bytes).await {
                         error!("Failed to send delta: {}", e);
                         break;
                     } else {
@@ -74,7 +98,7 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
             Ok(update) = update_rx.recv() => {
                 info!("Received update broadcast for client {}", client_id);
                 if !use_deltas {
-                    if let Err(e) = send_full_update(&mut socket, update).await {
+                    if let Err(e) = send_full_update(&mut socket, update, state.delta_tracker.last_sequence(), state.ws_max_message_bytes).await {
                         error!("Failed to send update: {}", e);
                         break;
                     } else {
@@ -85,6 +109,14 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
                     debug!("Skipping full update for client {} (deltas enabled)", client_id);
                 }
             }
+            
+            // Server events go to every client
+            Ok(event) = event_rx.recv() => {
+                if let Err(e) = send_event(&mut socket, &event, state.delta_tracker.last_sequence()).await {
+                    error!("Failed to send event: {}", e);
+                    break;
+                }
+            }
         }
     }
     
@@ -98,10 +130,11 @@ enum MessageResult {
 }
 
 /// Send initial connection confirmation
-async fn send_connection_message(socket: &mut WebSocket) -> Result<(), axum::Error> {
+async fn send_connection_message(socket: &mut WebSocket, sequence: u64) -> Result<(), axum::Error> {
     let message = json!({
         "type": "connected",
         "timestamp": get_timestamp(),
+        "sequence": sequence,
         "features": {
             "delta_updates": true,
             "compression": true,
@@ -124,7 +157,7 @@ async fn handle_client_message(
         Ok(Message::Text(text)) => {
             if let Ok(cmd) = serde_json::from_str::<Value>(&text) {
                 if let Some(cmd_type) = cmd.get("type").and_then(|t| t.as_str()) {
-                    handle_command(cmd_type, socket, use_deltas, client_id, state).await
+                    handle_command(cmd_type, &cmd, socket, use_deltas, client_id, state).await
                 } else {
                     MessageResult::Continue
                 }
@@ -147,6 +180,7 @@ async fn handle_client_message(
 /// Handle specific client commands
 async fn handle_command(
     cmd_type: &str,
+    cmd: &Value,
     socket: &mut WebSocket,
     use_deltas: &mut bool,
     client_id: &str,
@@ -175,6 +209,39 @@ async fn handle_command(
                 }).to_string()
             )).await;
         }
+        "backfill" => {
+            // {"type": "backfill", "last_sequence": N} from a reconnecting client
+            let Some(last_sequence) = cmd.get("last_sequence").and_then(|s| s.as_u64()) else {
+                debug!("Backfill from client {} without last_sequence", client_id);
+                return MessageResult::Continue;
+            };
+            match state.delta_tracker.backfill(last_sequence) {
+                Backfill::Deltas(deltas) => {
+                    info!("Replaying {} deltas to client {} after sequence {}", deltas.len(), client_id, last_sequence);
+                    for delta in deltas {
+                        if let Err(e) = send_delta_update(socket, delta, state.ws_max_message_bytes).await {
+                            error!("Failed to send backfill delta: {}", e);
+                            return MessageResult::Close;
+                        }
+                    }
+                    let _ = socket.send(Message::Text(
+                        json!({
+                            "type": "backfill_complete",
+                            "sequence": state.delta_tracker.last_sequence()
+                        }).to_string()
+                    )).await;
+                }
+                Backfill::ResyncRequired => {
+                    info!("Client {} is too far behind (sequence {}), requesting resync", client_id, last_sequence);
+                    let _ = socket.send(Message::Text(
+                        json!({
+                            "type": "resync_required",
+                            "sequence": state.delta_tracker.last_sequence()
+                        }).to_string()
+                    )).await;
+                }
+            }
+        }
         "clear_cache" => {
             info!("Client {} requested cache clear", client_id);
             clear_caches(state).await;
@@ -200,23 +267,155 @@ async fn clear_caches(state: &AppState) {
     *arrow_cache = None;
 }
 
-/// Send delta update to client
-async fn send_delta_update(socket: &mut WebSocket, delta: GraphDelta) -> Result<(), axum::Error> {
+/// Send delta update to client. Deltas arrive with consecutive sequences; a
+/// client seeing a gap has missed some and should resync with a full reload.
+/// Deltas larger than `max_message_bytes` are split like full updates, every
+/// chunk keeping the delta's sequence. Added and updated nodes come first and
+/// removed nodes last, so no chunk refers to a node the client lacks.
+async fn send_delta_update(socket: &mut WebSocket, delta: GraphDelta, max_message_bytes: usize) -> Result<(), axum::Error> {
+    for msg in delta_messages(&delta, max_message_bytes) {
+        socket.send(Message::Text(msg)).await?;
+    }
+    Ok(())
+}
+
+fn delta_messages(delta: &GraphDelta, max_message_bytes: usize) -> Vec<String> {
     let msg = json!({
         "type": "graph:delta",
+        "sequence": delta.sequence,
         "data": delta
-    });
+    }).to_string();
+    if msg.len() <= max_message_bytes {
+        return vec![msg];
+    }
     
-    socket.send(Message::Text(msg.to_string())).await
+    let chunks = chunk_delta(delta, max_message_bytes.saturating_sub(CHUNK_ENVELOPE_BYTES).max(1));
+    let total = chunks.len();
+    debug!("Splitting {} byte delta into {} chunks", msg.len(), total);
+    chunks.iter()
+        .enumerate()
+        .map(|(chunk, data)| json!({
+            "type": "graph:delta",
+            "sequence": delta.sequence,
+            "chunk": chunk,
+            "total": total,
+            "data": data
+        }).to_string())
+        .collect()
+}
+
+/// Split a delta's lists into deltas of roughly `budget` bytes of items each
+fn chunk_delta(delta: &GraphDelta, budget: usize) -> Vec<GraphDelta> {
+    let empty = || GraphDelta {
+        operation: delta.operation.clone(),
+        nodes_added: Vec::new(),
+        nodes_updated: Vec::new(),
+        nodes_removed: Vec::new(),
+        edges_added: Vec::new(),
+        edges_updated: Vec::new(),
+        edges_removed: Vec::new(),
+        timestamp: delta.timestamp,
+        sequence: delta.sequence,
+    };
+    let mut chunks = vec![empty()];
+    let mut used = 0;
+    push_chunked(&mut chunks, &mut used, budget, &delta.nodes_added, &empty, |c| &mut c.nodes_added);
+    push_chunked(&mut chunks, &mut used, budget, &delta.nodes_updated, &empty, |c| &mut c.nodes_updated);
+    push_chunked(&mut chunks, &mut used, budget, &delta.edges_added, &empty, |c| &mut c.edges_added);
+    push_chunked(&mut chunks, &mut used, budget, &delta.edges_updated, &empty, |c| &mut c.edges_updated);
+    push_chunked(&mut chunks, &mut used, budget, &delta.edges_removed, &empty, |c| &mut c.edges_removed);
+    push_chunked(&mut chunks, &mut used, budget, &delta.nodes_removed, &empty, |c| &mut c.nodes_removed);
+    chunks
+}
+
+/// Send full update to client. Updates larger than `max_message_bytes` go out
+/// as several `graph:update` messages numbered by `chunk` (from 0) out of
+/// `total`, each carrying a slice of the nodes and edges. Nodes come before
+/// edges, so every edge arrives after its endpoints.
+async fn send_full_update(socket: &mut WebSocket, update: GraphUpdate, sequence: u64, max_message_bytes: usize) -> Result<(), axum::Error> {
+    for msg in full_update_messages(&update, sequence, max_message_bytes) {
+        socket.send(Message::Text(msg)).await?;
+    }
+    Ok(())
 }
 
-/// Send full update to client
-async fn send_full_update(socket: &mut WebSocket, update: GraphUpdate) -> Result<(), axum::Error> {
+/// Room left in each chunk for the message envelope around the items
+const CHUNK_ENVELOPE_BYTES: usize = 256;
+
+fn full_update_messages(update: &GraphUpdate, sequence: u64, max_message_bytes: usize) -> Vec<String> {
     let msg = json!({
         "type": "graph:update",
+        "sequence": sequence,
         "data": update
-    });
+    }).to_string();
+    if msg.len() <= max_message_bytes {
+        return vec![msg];
+    }
     
+    let chunks = chunk_update(update, max_message_bytes.saturating_sub(CHUNK_ENVELOPE_BYTES).max(1));
+    let total = chunks.len();
+    debug!("Splitting {} byte update into {} chunks", msg.len(), total);
+    chunks.iter()
+        .enumerate()
+        .map(|(chunk, data)| json!({
+            "type": "graph:update",
+            "sequence": sequence,
+            "chunk": chunk,
+            "total": total,
+            "data": data
+        }).to_string())
+        .collect()
+}
+
+/// Split an update's nodes and edges into updates of roughly `budget` bytes of
+/// items each. An item bigger than the budget gets a chunk of its own.
+fn chunk_update(update: &GraphUpdate, budget: usize) -> Vec<GraphUpdate> {
+    let empty = || GraphUpdate {
+        operation: update.operation.clone(),
+        nodes: None,
+        edges: None,
+        timestamp: update.timestamp,
+    };
+    let mut chunks = vec![empty()];
+    let mut used = 0;
+    push_chunked(&mut chunks, &mut used, budget, update.nodes.as_deref().unwrap_or(&[]), &empty, |c| c.nodes.get_or_insert_with(Vec::new));
+    push_chunked(&mut chunks, &mut used, budget, update.edges.as_deref().unwrap_or(&[]), &empty, |c| c.edges.get_or_insert_with(Vec::new));
+    chunks
+}
+
+/// Append `items` to the list `list` picks out of the last chunk, starting a
+/// new chunk whenever the `used` bytes would go over `budget`
+fn push_chunked<C, T: Serialize + Clone>(
+    chunks: &mut Vec<C>,
+    used: &mut usize,
+    budget: usize,
+    items: &[T],
+    empty: &impl Fn() -> C,
+    list: impl Fn(&mut C) -> &mut Vec<T>,
+) {
+    for item in items {
+        let size = json_len(item) + 1;
+        if *used > 0 && *used + size > budget {
+            chunks.push(empty());
+            *used = 0;
+        }
+        if let Some(current) = chunks.last_mut() {
+            list(current).push(item.clone());
+        }
+        *used += size;
+    }
+}
+
+fn json_len<T: Serialize>(item: &T) -> usize {
+    serde_json::to_vec(item).map_or(0, |bytes| bytes.len())
+}
+
+/// Send a server event to client
+async fn send_event(socket: &mut WebSocket, event: &ServerEvent, sequence: u64) -> Result<(), axum::Error> {
+    let mut msg = serde_json::to_value(event).unwrap_or_default();
+    if let Some(fields) = msg.as_object_mut() {
+        fields.insert("sequence".to_string(), json!(sequence));
+    }
     socket.send(Message::Text(msg.to_string())).await
 }
 
@@ -235,6 +434,9 @@ pub trait BroadcastExt {
     
     /// Broadcast a delta update to all connected WebSocket clients
     fn broadcast_delta(&self, delta: GraphDelta);
+    
+    /// Broadcast a server event to all connected WebSocket clients
+    fn broadcast_event(&self, event: ServerEvent);
 }
 
 impl BroadcastExt for AppState {
@@ -244,7 +446,129 @@ impl BroadcastExt for AppState {
     }
     
     fn broadcast_delta(&self, delta: GraphDelta) {
+        // Bursts are merged and sent by the flush task instead
+        if let Some(delta) = self.delta_tracker.coalesce(delta) {
+            // Ignore send errors - it just means no clients are connected
+            let _ = self.delta_tx.send(delta);
+        }
+    }
+    
+    fn broadcast_event(&self, event: ServerEvent) {
         // Ignore send errors - it just means no clients are connected
-        let _ = self.delta_tx.send(delta);
+        let _ = self.event_tx.send(event);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::{Node, Edge, delta_tracker::DeltaOperation, duckdb_store::UpdateOperation};
+    
+    fn node(id: &str) -> Node {
+        Node {
+            id: id.to_string(),
+            label: id.to_string(),
+            node_type: "Entity".to_string(),
+            summary: Some("x".repeat(100)),
+            properties: HashMap::new(),
+        }
+    }
+    
+    fn edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            edge_type: "RELATES_TO".to_string(),
+            weight: 1.0,
+            properties: HashMap::new(),
+        }
+    }
+    
+    fn ids(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("n{}", i)).collect()
+    }
+    
+    #[test]
+    fn test_chunk_update_keeps_chunks_within_budget_and_nodes_first() {
+        let ids = ids(20);
+        let update = GraphUpdate {
+            operation: UpdateOperation::AddNodes,
+            nodes: Some(ids.iter().map(|id| node(id)).collect()),
+            edges: Some(ids.windows(2).map(|pair| edge(&pair[0], &pair[1])).collect()),
+            timestamp: 0,
+        };
+        let budget = 600;
+        let chunks = chunk_update(&update, budget);
+        
+        assert!(chunks.len() > 1);
+        let mut seen_edges = false;
+        let (mut nodes, mut edges) = (0, 0);
+        for chunk in &chunks {
+            let items: usize = chunk.nodes.iter().flatten().map(|n| json_len(n) + 1).sum::<usize>()
+                + chunk.edges.iter().flatten().map(|e| json_len(e) + 1).sum::<usize>();
+            assert!(items <= budget, "chunk of {} bytes", items);
+            assert!(!seen_edges || chunk.nodes.is_none(), "node sent after an edge");
+            seen_edges |= chunk.edges.is_some();
+            nodes += chunk.nodes.as_ref().map_or(0, Vec::len);
+            edges += chunk.edges.as_ref().map_or(0, Vec::len);
+        }
+        assert_eq!((nodes, edges), (20, 19));
+    }
+    
+    #[test]
+    fn test_large_delta_split_into_numbered_chunks() {
+        let ids = ids(50);
+        let delta = GraphDelta {
+            operation: DeltaOperation::Update,
+            nodes_added: ids[..25].iter().map(|id| node(id)).collect(),
+            nodes_updated: Vec::new(),
+            nodes_removed: ids[25..].to_vec(),
+            edges_added: ids[..25].windows(2).map(|pair| edge(&pair[0], &pair[1])).collect(),
+            edges_updated: Vec::new(),
+            edges_removed: vec![("n25".to_string(), "n26".to_string())],
+            timestamp: 0,
+            sequence: 7,
+        };
+        let max_message_bytes = 1024;
+        let messages = delta_messages(&delta, max_message_bytes);
+        
+        assert!(messages.len() > 1);
+        let mut removed_started = false;
+        let (mut added, mut removed) = (0, 0);
+        for (i, msg) in messages.iter().enumerate() {
+            assert!(msg.len() <= max_message_bytes, "message of {} bytes", msg.len());
+            let msg: Value = serde_json::from_str(msg).unwrap();
+            assert_eq!(msg["type"], "graph:delta");
+            assert_eq!(msg["sequence"], 7);
+            assert_eq!(msg["chunk"], i);
+            assert_eq!(msg["total"], messages.len());
+            let chunk: GraphDelta = serde_json::from_value(msg["data"].clone()).unwrap();
+            assert!(!removed_started || chunk.nodes_added.is_empty(), "node added after a removal");
+            removed_started |= !chunk.nodes_removed.is_empty();
+            added += chunk.nodes_added.len();
+            removed += chunk.nodes_removed.len();
+        }
+        assert_eq!((added, removed), (25, 25));
+    }
+    
+    #[test]
+    fn test_small_delta_sent_whole() {
+        let delta = GraphDelta {
+            operation: DeltaOperation::Update,
+            nodes_added: vec![node("a")],
+            nodes_updated: Vec::new(),
+            nodes_removed: Vec::new(),
+            edges_added: Vec::new(),
+            edges_updated: Vec::new(),
+            edges_removed: Vec::new(),
+            timestamp: 0,
+            sequence: 1,
+        };
+        let messages = delta_messages(&delta, 1 << 20);
+        
+        assert_eq!(messages.len(), 1);
+        let msg: Value = serde_json::from_str(&messages[0]).unwrap();
+        assert!(msg.get("chunk").is_none());
+    }
+}
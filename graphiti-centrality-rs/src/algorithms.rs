@@ -1,23 +1,97 @@
 use crate::client::{
-    falkor_value_to_f64, falkor_value_to_i64, falkor_value_to_string, FalkorClient,
+    falkor_value_to_f64, falkor_value_to_i64, falkor_value_to_string, score_property_name,
+    validate_property_suffix, FalkorClient,
 };
 use crate::error::{CentralityError, Result};
-use crate::models::CentralityScores;
+use crate::models::{
+    AllCentralityScores, BetweennessSampling, CentralityScores, DegreeUpdate, EigenvectorScores,
+    GraphPath, ImportanceWeights, NeighborSimilarity, PathEdge, PathNode, StoredScore,
+};
 use falkordb::FalkorValue;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::time::Instant;
 use tracing::{debug, info, warn};
 
-/// Calculate PageRank centrality using FalkorDB's native algorithm or custom fallback
+/// Upper bound on iterations for the iterative algorithms
+pub const MAX_ITERATIONS: u32 = 1000;
+
+/// Upper bound on the hop limit of path searches
+pub const MAX_PATH_DEPTH: u32 = 15;
+
+/// Upper bound on the number of paths a k-shortest-paths request may ask for
+pub const MAX_K_PATHS: usize = 100;
+
+/// Candidate paths a k-shortest-paths search reads before giving up
+pub const MAX_PATHS_EXAMINED: usize = 10_000;
+
+/// Upper bound on the number of nodes a neighbor-similarity request may ask for
+pub const MAX_SIMILAR_NODES: usize = 1000;
+
+/// Metrics whose scores `store_centrality_scores` writes to nodes
+pub const STORED_METRICS: &[&str] = &[
+    "pagerank",
+    "degree",
+    "betweenness",
+    "eigenvector",
+    "importance",
+];
+
+/// Largest `top_k` accepted when reading stored scores
+pub const MAX_STORED_SCORES: usize = 10_000;
+
+/// Damping factor must lie strictly within (0, 1) and iterations within 1..=MAX_ITERATIONS
+pub fn validate_pagerank_params(damping_factor: f64, iterations: u32) -> Result<()> {
+    if !(damping_factor > 0.0 && damping_factor < 1.0) {
+        return Err(CentralityError::invalid_parameter(format!(
+            "damping_factor must be between 0 and 1 (exclusive), got {}",
+            damping_factor
+        )));
+    }
+    validate_iterations(iterations)
+}
+
+/// Tolerance must be a positive number and iterations within 1..=MAX_ITERATIONS
+pub fn validate_eigenvector_params(max_iterations: u32, tolerance: f64) -> Result<()> {
+    if !(tolerance.is_finite() && tolerance > 0.0) {
+        return Err(CentralityError::invalid_parameter(format!(
+            "tolerance must be a positive number, got {}",
+            tolerance
+        )));
+    }
+    validate_iterations(max_iterations)
+}
+
+fn validate_iterations(iterations: u32) -> Result<()> {
+    if !(1..=MAX_ITERATIONS).contains(&iterations) {
+        return Err(CentralityError::invalid_parameter(format!(
+            "iterations must be between 1 and {}, got {}",
+            MAX_ITERATIONS, iterations
+        )));
+    }
+    Ok(())
+}
+
+/// Calculate PageRank centrality using FalkorDB's native algorithm or custom fallback.
+///
+/// With `directed` false every edge counts in both directions. The native
+/// algorithm only follows out-links, so undirected runs use the custom implementation.
 pub async fn calculate_pagerank(
     client: &FalkorClient,
     group_id: Option<&str>,
     damping_factor: f64,
     iterations: u32,
+    directed: bool,
 ) -> Result<CentralityScores> {
+    validate_pagerank_params(damping_factor, iterations)?;
     let start = Instant::now();
     info!("Starting PageRank calculation");
 
+    if !directed {
+        info!("Undirected PageRank requested, using custom implementation");
+        return calculate_pagerank_custom(client, group_id, damping_factor, iterations, false)
+            .await;
+    }
+
     // Use FalkorDB's native PageRank algorithm
     let graph_name = client.graph_name();
     let native_algorithm = format!(
@@ -28,7 +102,7 @@ pub async fn calculate_pagerank(
     debug!("Running native PageRank: {}", native_algorithm);
 
     // Execute the algorithm (stores results in node properties)
-    if let Ok(_) = client.execute_query(&native_algorithm, None).await {
+    if client.execute_query(&native_algorithm, None).await.is_ok() {
         info!("FalkorDB native PageRank completed, retrieving results");
 
         // Now retrieve the stored results
@@ -53,7 +127,7 @@ pub async fn calculate_pagerank(
     }
 
     info!("Native PageRank not available, using custom implementation");
-    calculate_pagerank_custom(client, group_id, damping_factor, iterations).await
+    calculate_pagerank_custom(client, group_id, damping_factor, iterations, true).await
 }
 
 /// Process PageRank results from either native or custom implementation
@@ -98,10 +172,11 @@ async fn calculate_pagerank_custom(
     group_id: Option<&str>,
     damping_factor: f64,
     max_iterations: u32,
+    directed: bool,
 ) -> Result<CentralityScores> {
     info!(
-        "Starting custom PageRank calculation with damping_factor={}, iterations={}",
-        damping_factor, max_iterations
+        "Starting custom PageRank calculation with damping_factor={}, iterations={}, directed={}",
+        damping_factor, max_iterations, directed
     );
 
     // Get all nodes and their connections
@@ -136,36 +211,70 @@ async fn calculate_pagerank_custom(
     // Build node list and adjacency information
     let nodes: Vec<String> = node_results
         .iter()
-        .filter_map(|record| record.get("uuid").map(|v| falkor_value_to_string(v)))
+        .filter_map(|record| record.get("uuid").map(falkor_value_to_string))
         .collect();
 
     if nodes.is_empty() {
         return Err(CentralityError::NoNodesFound);
     }
 
+    info!("Processing {} nodes for PageRank", nodes.len());
+
+    let edges: Vec<(String, String)> = edge_results
+        .iter()
+        .filter_map(|record| {
+            let source = record.get("source").map(falkor_value_to_string)?;
+            let target = record.get("target").map(falkor_value_to_string)?;
+            Some((source, target))
+        })
+        .collect();
+
+    let scores = pagerank_from_edges(&nodes, &edges, directed, damping_factor, max_iterations);
+
+    let processed = scores.len();
+    info!(
+        "Custom PageRank calculation completed for {} nodes",
+        processed
+    );
+
+    Ok(CentralityScores {
+        scores,
+        nodes_processed: processed,
+    })
+}
+
+/// Iterative PageRank over an explicit edge list. With `directed` false each
+/// edge is followed in both directions.
+pub fn pagerank_from_edges(
+    nodes: &[String],
+    edges: &[(String, String)],
+    directed: bool,
+    damping_factor: f64,
+    max_iterations: u32,
+) -> HashMap<String, f64> {
     let node_count = nodes.len();
-    info!("Processing {} nodes for PageRank", node_count);
 
     // Build adjacency lists and out-degree counts
     let mut out_links: HashMap<String, Vec<String>> = HashMap::new();
     let mut out_degree: HashMap<String, usize> = HashMap::new();
 
     // Initialize structures
-    for node in &nodes {
+    for node in nodes {
         out_links.insert(node.clone(), Vec::new());
         out_degree.insert(node.clone(), 0);
     }
 
-    // Process edges
-    for record in edge_results {
-        if let (Some(source_val), Some(target_val)) = (record.get("source"), record.get("target")) {
-            let source = falkor_value_to_string(source_val);
-            let target = falkor_value_to_string(target_val);
-
-            if let Some(links) = out_links.get_mut(&source) {
-                links.push(target);
-                *out_degree.get_mut(&source).unwrap() += 1;
-            }
+    // Process edges, each one counting both ways when undirected
+    let mut add_link = |from: &String, to: &String| {
+        if let Some(links) = out_links.get_mut(from) {
+            links.push(to.clone());
+            *out_degree.get_mut(from).unwrap() += 1;
+        }
+    };
+    for (source, target) in edges {
+        add_link(source, target);
+        if !directed {
+            add_link(target, source);
         }
     }
 
@@ -182,11 +291,11 @@ async fn calculate_pagerank_custom(
     for iteration in 0..max_iterations {
         let mut total_diff = 0.0;
 
-        for node in &nodes {
+        for node in nodes {
             let mut rank = (1.0 - damping_factor) / node_count as f64;
 
             // Sum contributions from incoming links
-            for other_node in &nodes {
+            for other_node in nodes {
                 if let Some(links) = out_links.get(other_node) {
                     if links.contains(node) {
                         let out_deg = *out_degree.get(other_node).unwrap() as f64;
@@ -220,16 +329,7 @@ async fn calculate_pagerank_custom(
         }
     }
 
-    let processed = scores.len();
-    info!(
-        "Custom PageRank calculation completed for {} nodes",
-        processed
-    );
-
-    Ok(CentralityScores {
-        scores,
-        nodes_processed: processed,
-    })
+    scores
 }
 
 /// Calculate degree centrality with optimized single queries
@@ -330,12 +430,512 @@ pub async fn calculate_degree_centrality(
     })
 }
 
+/// Raw degree of each changed node and its neighbors, counted the same way as
+/// `calculate_degree_centrality`. Only these nodes' degrees can change when
+/// edges touching `changed_uuids` are added or removed.
+pub async fn calculate_incremental_degree(
+    client: &FalkorClient,
+    changed_uuids: &[String],
+    direction: &str,
+) -> Result<CentralityScores> {
+    let start = Instant::now();
+
+    let pattern = match direction {
+        "both" => "(n)-[r]-()",
+        "in" => "()-[r]->(n)",
+        "out" => "(n)-[r]->()",
+        _ => {
+            return Err(CentralityError::invalid_parameter(format!(
+                "Invalid direction: {}. Must be 'in', 'out', or 'both'",
+                direction
+            )))
+        }
+    };
+    if changed_uuids.is_empty() {
+        return Err(CentralityError::invalid_parameter(
+            "node_uuids must contain at least one node",
+        ));
+    }
+
+    let uuid_list = changed_uuids
+        .iter()
+        .map(|uuid| format!("'{}'", uuid.replace('\'', "\\'")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = format!(
+        "MATCH (c) WHERE c.uuid IN [{}]
+         OPTIONAL MATCH (c)-[]-(m)
+         WITH collect(DISTINCT c) + collect(DISTINCT m) AS affected
+         UNWIND affected AS n
+         WITH DISTINCT n
+         OPTIONAL MATCH {}
+         RETURN n.uuid as uuid, count(r) as degree",
+        uuid_list, pattern
+    );
+
+    debug!("Executing incremental degree query: {}", query);
+
+    let results = client.execute_query(&query, None).await?;
+
+    let mut scores = HashMap::new();
+    for record in results {
+        if let (Some(uuid_val), Some(degree_val)) = (record.get("uuid"), record.get("degree")) {
+            if let Some(degree) = falkor_value_to_i64(degree_val) {
+                scores.insert(falkor_value_to_string(uuid_val), degree as f64);
+            }
+        }
+    }
+
+    info!(
+        "Incremental degree computed for {} nodes ({} changed) in {:?}",
+        scores.len(),
+        changed_uuids.len(),
+        start.elapsed()
+    );
+
+    if scores.is_empty() {
+        return Err(CentralityError::NoNodesFound);
+    }
+
+    let nodes_processed = scores.len();
+    Ok(CentralityScores {
+        scores,
+        nodes_processed,
+    })
+}
+
+/// Largest raw degree in the graph, used to normalize incremental results on
+/// the same scale as a full degree calculation
+pub async fn max_degree(client: &FalkorClient, direction: &str) -> Result<f64> {
+    let pattern = match direction {
+        "in" => "()-[r]->(n)",
+        "out" => "(n)-[r]->()",
+        _ => "(n)-[r]-()",
+    };
+    let query = format!(
+        "MATCH (n) OPTIONAL MATCH {} WITH n, count(r) AS degree RETURN max(degree) as max_degree",
+        pattern
+    );
+
+    let results = client.execute_query(&query, None).await?;
+    Ok(results
+        .first()
+        .and_then(|record| record.get("max_degree"))
+        .and_then(falkor_value_to_i64)
+        .map_or(0.0, |max| max as f64))
+}
+
+/// Recompute degree centrality after edges touching `changed_uuids` changed.
+///
+/// Scores are normalized by the graph's max degree, so only the changed nodes
+/// and their neighbors need recomputing while that max stays the same. When it
+/// differs from `previous_max` (or that is unknown) every stored score is on
+/// the old scale, so all nodes are recomputed instead.
+pub async fn update_degree_centrality(
+    client: &FalkorClient,
+    changed_uuids: &[String],
+    direction: &str,
+    previous_max: Option<f64>,
+) -> Result<DegreeUpdate> {
+    let max = max_degree(client, direction).await?;
+    let full_recompute = previous_max != Some(max);
+    let mut scores = if full_recompute {
+        info!(
+            "Max degree changed from {:?} to {}, recomputing all degrees",
+            previous_max, max
+        );
+        calculate_degree_centrality(client, direction, None).await?
+    } else {
+        calculate_incremental_degree(client, changed_uuids, direction).await?
+    };
+
+    for degree in scores.scores.values_mut() {
+        *degree = if max > 0.0 { *degree / max } else { 0.0 };
+    }
+
+    Ok(DegreeUpdate {
+        scores,
+        max_degree: max,
+        full_recompute,
+    })
+}
+
+/// Shortest undirected path of at most `max_depth` hops between two nodes,
+/// or `None` if they aren't connected within that many hops
+pub async fn shortest_path(
+    client: &FalkorClient,
+    source_uuid: &str,
+    target_uuid: &str,
+    max_depth: u32,
+) -> Result<Option<GraphPath>> {
+    validate_path_depth(max_depth)?;
+
+    // The hop limit can't be a parameter; it's a validated integer
+    let query = format!(
+        "MATCH (a {{uuid: $source}}), (b {{uuid: $target}})
+         WITH a, b
+         MATCH path = shortestPath((a)-[*..{}]-(b))
+         {}
+         LIMIT 1",
+        max_depth, PATH_RETURN
+    );
+
+    let results = client
+        .execute_query(&query, Some(path_endpoint_params(source_uuid, target_uuid)))
+        .await?;
+    Ok(results.first().map(path_from_record))
+}
+
+/// The `k` shortest distinct simple paths of at most `max_depth` hops between
+/// two nodes, shortest first, and how many candidate paths were examined.
+///
+/// Paths are enumerated one length at a time, shortest first, and the search
+/// stops once `MAX_PATHS_EXAMINED` candidates have been read, so dense graphs
+/// can't trigger an exponential enumeration.
+pub async fn k_shortest_paths(
+    client: &FalkorClient,
+    source_uuid: &str,
+    target_uuid: &str,
+    k: usize,
+    max_depth: u32,
+) -> Result<(Vec<GraphPath>, usize)> {
+    validate_path_depth(max_depth)?;
+    if !(1..=MAX_K_PATHS).contains(&k) {
+        return Err(CentralityError::invalid_parameter(format!(
+            "k must be between 1 and {}, got {}",
+            MAX_K_PATHS, k
+        )));
+    }
+
+    let mut paths: Vec<GraphPath> = Vec::new();
+    let mut seen_routes: HashSet<Vec<String>> = HashSet::new();
+    let mut examined = 0;
+
+    for depth in 1..=max_depth {
+        if paths.len() >= k || examined >= MAX_PATHS_EXAMINED {
+            break;
+        }
+
+        let query = format!(
+            "MATCH (a {{uuid: $source}}), (b {{uuid: $target}})
+             WITH a, b
+             MATCH path = (a)-[*{0}..{0}]-(b)
+             {1}
+             LIMIT {2}",
+            depth,
+            PATH_RETURN,
+            MAX_PATHS_EXAMINED - examined
+        );
+        let results = client
+            .execute_query(&query, Some(path_endpoint_params(source_uuid, target_uuid)))
+            .await?;
+        examined += results.len();
+
+        for path in results.iter().map(path_from_record) {
+            if paths.len() >= k {
+                break;
+            }
+            // Relationship-unique matches can still revisit a node; keep simple paths only
+            let route: Vec<String> = path.nodes.iter().map(|n| n.uuid.clone()).collect();
+            let simple = route.iter().collect::<HashSet<_>>().len() == route.len();
+            if simple && seen_routes.insert(route) {
+                paths.push(path);
+            }
+        }
+    }
+
+    debug!(
+        "Found {} of {} requested paths after examining {} candidates",
+        paths.len(),
+        k,
+        examined
+    );
+    Ok((paths, examined))
+}
+
+/// Projection of a matched `path` into the columns read by `path_from_record`
+const PATH_RETURN: &str = "RETURN [n IN nodes(path) | {uuid: n.uuid, name: n.name}] AS nodes,
+                [r IN relationships(path) | {type: type(r), source: startNode(r).uuid, target: endNode(r).uuid}] AS edges";
+
+fn validate_path_depth(max_depth: u32) -> Result<()> {
+    if !(1..=MAX_PATH_DEPTH).contains(&max_depth) {
+        return Err(CentralityError::invalid_parameter(format!(
+            "max_depth must be between 1 and {}, got {}",
+            MAX_PATH_DEPTH, max_depth
+        )));
+    }
+    Ok(())
+}
+
+fn path_endpoint_params(source_uuid: &str, target_uuid: &str) -> HashMap<String, FalkorValue> {
+    HashMap::from([
+        (
+            "source".to_string(),
+            FalkorValue::String(source_uuid.to_string()),
+        ),
+        (
+            "target".to_string(),
+            FalkorValue::String(target_uuid.to_string()),
+        ),
+    ])
+}
+
+fn path_from_record(record: &HashMap<String, FalkorValue>) -> GraphPath {
+    let entries = |column: &str| match record.get(column) {
+        Some(FalkorValue::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                FalkorValue::Map(map) => Some(map.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let field = |map: &HashMap<String, FalkorValue>, key: &str| {
+        map.get(key).map(falkor_value_to_string).unwrap_or_default()
+    };
+
+    let nodes: Vec<PathNode> = entries("nodes")
+        .iter()
+        .map(|map| PathNode {
+            uuid: field(map, "uuid"),
+            name: map
+                .get("name")
+                .filter(|name| !matches!(name, FalkorValue::None))
+                .map(falkor_value_to_string),
+        })
+        .collect();
+    let edges: Vec<PathEdge> = entries("edges")
+        .iter()
+        .map(|map| PathEdge {
+            relationship_type: field(map, "type"),
+            source_uuid: field(map, "source"),
+            target_uuid: field(map, "target"),
+        })
+        .collect();
+
+    GraphPath {
+        length: edges.len(),
+        nodes,
+        edges,
+    }
+}
+
+/// Jaccard and overlap similarity of the undirected neighbor sets of two nodes,
+/// or `None` if either node doesn't exist
+pub async fn neighbor_similarity(
+    client: &FalkorClient,
+    source_uuid: &str,
+    target_uuid: &str,
+) -> Result<Option<NeighborSimilarity>> {
+    let query = "MATCH (a {uuid: $source}), (b {uuid: $target})
+                 OPTIONAL MATCH (a)--(an)
+                 WITH a, b, collect(DISTINCT an) AS a_neighbors
+                 OPTIONAL MATCH (b)--(bn)
+                 WITH b, a_neighbors, collect(DISTINCT bn) AS b_neighbors
+                 RETURN b.uuid AS uuid, b.name AS name,
+                        size([n IN a_neighbors WHERE n IN b_neighbors]) AS shared,
+                        size(a_neighbors) AS source_degree, size(b_neighbors) AS degree
+                 LIMIT 1";
+
+    let results = client
+        .execute_query(query, Some(path_endpoint_params(source_uuid, target_uuid)))
+        .await?;
+    Ok(results.first().map(similarity_from_record))
+}
+
+/// The `limit` nodes sharing the largest fraction of their neighbors with
+/// `source_uuid` by Jaccard similarity, most similar first. Only nodes two
+/// hops away can share a neighbor, so those are the only candidates.
+pub async fn most_similar_by_neighbors(
+    client: &FalkorClient,
+    source_uuid: &str,
+    limit: usize,
+) -> Result<Vec<NeighborSimilarity>> {
+    if !(1..=MAX_SIMILAR_NODES).contains(&limit) {
+        return Err(CentralityError::invalid_parameter(format!(
+            "limit must be between 1 and {}, got {}",
+            MAX_SIMILAR_NODES, limit
+        )));
+    }
+
+    let query = format!(
+        "MATCH (a {{uuid: $source}})
+         OPTIONAL MATCH (a)--(an)
+         WITH a, count(DISTINCT an) AS source_degree
+         MATCH (a)--(shared)--(b)
+         WHERE b <> a
+         WITH source_degree, b, count(DISTINCT shared) AS shared
+         MATCH (b)--(bn)
+         WITH source_degree, b, shared, count(DISTINCT bn) AS degree
+         RETURN b.uuid AS uuid, b.name AS name, shared, source_degree, degree
+         ORDER BY toFloat(shared) / (source_degree + degree - shared) DESC, uuid ASC
+         LIMIT {}",
+        limit
+    );
+    let params = HashMap::from([(
+        "source".to_string(),
+        FalkorValue::String(source_uuid.to_string()),
+    )]);
+
+    let results = client.execute_query(&query, Some(params)).await?;
+    Ok(results.iter().map(similarity_from_record).collect())
+}
+
+/// The `top_k` highest scores previously stored for `metric`, read straight
+/// from the node properties without recomputing anything. Nodes that never
+/// had the metric stored are left out.
+pub async fn stored_scores(
+    client: &FalkorClient,
+    metric: &str,
+    group_id: Option<&str>,
+    top_k: usize,
+    property_suffix: Option<&str>,
+) -> Result<Vec<StoredScore>> {
+    if !STORED_METRICS.contains(&metric) {
+        return Err(CentralityError::invalid_parameter(format!(
+            "metric must be one of {}, got {}",
+            STORED_METRICS.join(", "),
+            metric
+        )));
+    }
+    if !(1..=MAX_STORED_SCORES).contains(&top_k) {
+        return Err(CentralityError::invalid_parameter(format!(
+            "top_k must be between 1 and {}, got {}",
+            MAX_STORED_SCORES, top_k
+        )));
+    }
+    validate_property_suffix(property_suffix)?;
+
+    let property = score_property_name(metric, property_suffix);
+    let group_filter = if group_id.is_some() {
+        " AND n.group_id = $group_id"
+    } else {
+        ""
+    };
+    let query = format!(
+        "MATCH (n) WHERE EXISTS(n.{property}){group_filter}
+         RETURN n.uuid AS uuid, n.name AS name, n.{property} AS score
+         ORDER BY score DESC, uuid ASC
+         LIMIT {top_k}"
+    );
+    let params = group_id.map(|group_id| {
+        HashMap::from([(
+            "group_id".to_string(),
+            FalkorValue::String(group_id.to_string()),
+        )])
+    });
+
+    let results = client.execute_query(&query, params).await?;
+    Ok(results
+        .iter()
+        .filter_map(|record| {
+            Some(StoredScore {
+                uuid: falkor_value_to_string(record.get("uuid")?),
+                name: record.get("name").and_then(|name| match name {
+                    FalkorValue::String(name) => Some(name.clone()),
+                    _ => None,
+                }),
+                score: record.get("score").and_then(falkor_value_to_f64)?,
+            })
+        })
+        .collect())
+}
+
+/// Every score previously stored for `metric`, keyed by node uuid, e.g. to
+/// warm-start eigenvector centrality from the last run
+pub async fn stored_score_map(
+    client: &FalkorClient,
+    metric: &str,
+    group_id: Option<&str>,
+    property_suffix: Option<&str>,
+) -> Result<HashMap<String, f64>> {
+    validate_property_suffix(property_suffix)?;
+
+    let property = score_property_name(metric, property_suffix);
+    let group_filter = if group_id.is_some() {
+        " AND n.group_id = $group_id"
+    } else {
+        ""
+    };
+    let query = format!(
+        "MATCH (n) WHERE EXISTS(n.{property}){group_filter}
+         RETURN n.uuid AS uuid, n.{property} AS score"
+    );
+    let params = group_id.map(|group_id| {
+        HashMap::from([(
+            "group_id".to_string(),
+            FalkorValue::String(group_id.to_string()),
+        )])
+    });
+
+    let results = client.execute_query(&query, params).await?;
+    Ok(results
+        .iter()
+        .filter_map(|record| {
+            Some((
+                falkor_value_to_string(record.get("uuid")?),
+                record.get("score").and_then(falkor_value_to_f64)?,
+            ))
+        })
+        .collect())
+}
+
+fn similarity_from_record(record: &HashMap<String, FalkorValue>) -> NeighborSimilarity {
+    let count = |column: &str| {
+        record
+            .get(column)
+            .and_then(falkor_value_to_i64)
+            .unwrap_or(0)
+            .max(0) as usize
+    };
+    let (shared, source_degree, degree) =
+        (count("shared"), count("source_degree"), count("degree"));
+    let union = (source_degree + degree).saturating_sub(shared);
+    let smaller = source_degree.min(degree);
+
+    NeighborSimilarity {
+        uuid: record
+            .get("uuid")
+            .map(falkor_value_to_string)
+            .unwrap_or_default(),
+        name: record
+            .get("name")
+            .filter(|name| !matches!(name, FalkorValue::None))
+            .map(falkor_value_to_string),
+        shared_neighbors: shared,
+        jaccard: if union > 0 {
+            shared as f64 / union as f64
+        } else {
+            0.0
+        },
+        overlap: if smaller > 0 {
+            shared as f64 / smaller as f64
+        } else {
+            0.0
+        },
+    }
+}
+
 /// Calculate betweenness centrality (simplified version with sampling)
 pub async fn calculate_betweenness_centrality(
     client: &FalkorClient,
     group_id: Option<&str>,
     sample_size: Option<u32>,
 ) -> Result<CentralityScores> {
+    calculate_betweenness_with_sample(client, group_id, sample_size)
+        .await
+        .map(|(scores, _)| scores)
+}
+
+/// Betweenness centrality along with the number of nodes actually sampled,
+/// which is `None` when every node was used (including FalkorDB's native
+/// algorithm, which never samples)
+pub async fn calculate_betweenness_with_sample(
+    client: &FalkorClient,
+    group_id: Option<&str>,
+    sample_size: Option<u32>,
+) -> Result<(CentralityScores, Option<u32>)> {
     let _start = Instant::now();
     info!("Starting betweenness centrality calculation");
 
@@ -345,9 +945,9 @@ pub async fn calculate_betweenness_centrality(
 
     debug!("Running native betweenness: {}", native_algorithm);
 
-    if let Ok(_) = client.execute_query(&native_algorithm, None).await {
+    if client.execute_query(&native_algorithm, None).await.is_ok() {
         info!("FalkorDB native betweenness completed, retrieving results");
-        return calculate_betweenness_native(client, group_id).await;
+        return Ok((calculate_betweenness_native(client, group_id).await?, None));
     }
 
     info!("Using simplified betweenness centrality calculation");
@@ -408,7 +1008,7 @@ async fn calculate_betweenness_approximation(
     client: &FalkorClient,
     group_id: Option<&str>,
     sample_size: Option<u32>,
-) -> Result<CentralityScores> {
+) -> Result<(CentralityScores, Option<u32>)> {
     // Get all nodes first
     let nodes_query = if let Some(group_id) = group_id {
         format!(
@@ -422,15 +1022,17 @@ async fn calculate_betweenness_approximation(
     let node_results = client.execute_query(&nodes_query, None).await?;
     let mut node_uuids: Vec<String> = node_results
         .iter()
-        .filter_map(|record| record.get("uuid").map(|v| falkor_value_to_string(v)))
+        .filter_map(|record| record.get("uuid").map(falkor_value_to_string))
         .collect();
 
     // Apply sampling if requested
+    let mut sampled = None;
     if let Some(sample_size) = sample_size {
         if node_uuids.len() > sample_size as usize {
             // Simple sampling - take every nth node
             let step = node_uuids.len() / sample_size as usize;
             node_uuids = node_uuids.into_iter().step_by(step.max(1)).collect();
+            sampled = Some(node_uuids.len() as u32);
         }
     }
 
@@ -466,7 +1068,7 @@ async fn calculate_betweenness_approximation(
     let sample_results = client.execute_query(&sample_nodes_query, None).await?;
     let sample_uuids: Vec<String> = sample_results
         .iter()
-        .filter_map(|record| record.get("uuid").map(|v| falkor_value_to_string(v)))
+        .filter_map(|record| record.get("uuid").map(falkor_value_to_string))
         .collect();
 
     // Now find shortest paths between all pairs in our sample
@@ -483,8 +1085,9 @@ async fn calculate_betweenness_approximation(
                 for record in path_results {
                     if let Some(FalkorValue::Array(path_nodes)) = record.get("path_nodes") {
                         // Count intermediate nodes in the path (exclude source and target)
-                        for k in 1..path_nodes.len().saturating_sub(1) {
-                            if let Some(node_map) = path_nodes[k].as_map() {
+                        let intermediate = path_nodes.len().saturating_sub(2);
+                        for node in path_nodes.iter().skip(1).take(intermediate) {
+                            if let Some(node_map) = node.as_map() {
                                 if let Some(uuid_val) = node_map.get("uuid") {
                                     let uuid = falkor_value_to_string(uuid_val);
                                     if let Some(score) = betweenness.get_mut(&uuid) {
@@ -509,10 +1112,13 @@ async fn calculate_betweenness_approximation(
 
     let processed = betweenness.len();
 
-    Ok(CentralityScores {
-        scores: betweenness,
-        nodes_processed: processed,
-    })
+    Ok((
+        CentralityScores {
+            scores: betweenness,
+            nodes_processed: processed,
+        },
+        sampled,
+    ))
 }
 
 /// Graph connectivity types for choosing appropriate centrality algorithm
@@ -567,30 +1173,40 @@ async fn calculate_damped_eigenvector_centrality(
     max_iterations: u32,
     tolerance: f64,
     damping_factor: f64,
-) -> Result<CentralityScores> {
+    warm_start: Option<&HashMap<String, f64>>,
+    directed: bool,
+) -> Result<EigenvectorScores> {
     let start = Instant::now();
     info!(
-        "Starting damped eigenvector centrality calculation with damping={}",
-        damping_factor
+        "Starting damped eigenvector centrality calculation with damping={}, directed={}",
+        damping_factor, directed
     );
 
-    // Get nodes with both in-neighbors and out-degree
+    // Get nodes with both in-neighbors and out-degree; undirected, every
+    // neighbor counts as both
+    let (in_pattern, out_pattern) = if directed {
+        ("<-[r_in]-", "-[r_out]->")
+    } else {
+        ("-[r_in]-", "-[r_out]-")
+    };
     let query = if let Some(group_id) = group_id {
         format!(
             "MATCH (n) WHERE n.group_id = '{}'
-             OPTIONAL MATCH (n)<-[r_in]-(m_in) WHERE m_in.group_id = '{}'
+             OPTIONAL MATCH (n){}(m_in) WHERE m_in.group_id = '{}'
              WITH n, collect(DISTINCT m_in.uuid) as in_neighbors
-             OPTIONAL MATCH (n)-[r_out]->(m_out) WHERE m_out.group_id = '{}'
+             OPTIONAL MATCH (n){}(m_out) WHERE m_out.group_id = '{}'
              RETURN n.uuid as node, in_neighbors, count(m_out) as out_degree",
-            group_id, group_id, group_id
+            group_id, in_pattern, group_id, out_pattern, group_id
         )
     } else {
-        "MATCH (n)
-         OPTIONAL MATCH (n)<-[r_in]-(m_in)
-         WITH n, collect(DISTINCT m_in.uuid) as in_neighbors
-         OPTIONAL MATCH (n)-[r_out]->(m_out)
-         RETURN n.uuid as node, in_neighbors, count(m_out) as out_degree"
-            .to_string()
+        format!(
+            "MATCH (n)
+             OPTIONAL MATCH (n){}(m_in)
+             WITH n, collect(DISTINCT m_in.uuid) as in_neighbors
+             OPTIONAL MATCH (n){}(m_out)
+             RETURN n.uuid as node, in_neighbors, count(m_out) as out_degree",
+            in_pattern, out_pattern
+        )
     };
 
     let results = client.execute_query(&query, None).await?;
@@ -640,14 +1256,21 @@ async fn calculate_damped_eigenvector_centrality(
     let node_count = all_nodes.len();
     let uniform_value = (1.0 - damping_factor) / node_count as f64;
 
-    // Initialize scores uniformly
-    let mut scores: HashMap<String, f64> = HashMap::new();
-    for node in &all_nodes {
-        scores.insert(node.clone(), 1.0 / node_count as f64);
+    // Initialize scores uniformly, or from the warm start rescaled to sum to 1
+    let mut scores = initial_scores(&all_nodes, 1.0 / node_count as f64, warm_start);
+    let total: f64 = scores.values().sum();
+    if total > 0.0 {
+        for score in scores.values_mut() {
+            *score /= total;
+        }
     }
 
+    let mut iterations = 0;
+    let mut converged = false;
+
     // Power iteration with damping
     for iteration in 0..max_iterations {
+        iterations = iteration + 1;
         let mut new_scores: HashMap<String, f64> = HashMap::new();
 
         for node in &all_nodes {
@@ -694,29 +1317,54 @@ async fn calculate_damped_eigenvector_centrality(
                 "Damped eigenvector centrality converged after {} iterations",
                 iteration + 1
             );
+            converged = true;
             break;
         }
     }
 
+    if !converged {
+        warn!(
+            "Damped eigenvector centrality did not converge within {} iterations",
+            max_iterations
+        );
+    }
+
     let duration = start.elapsed();
     info!(
         "Damped eigenvector centrality calculation completed in {:?} for {} nodes",
         duration, node_count
     );
 
-    Ok(CentralityScores {
-        scores,
-        nodes_processed: node_count,
+    Ok(EigenvectorScores {
+        centrality: CentralityScores {
+            scores,
+            nodes_processed: node_count,
+        },
+        iterations,
+        converged,
     })
 }
 
 /// Calculate eigenvector centrality using power iteration method
+///
+/// `warm_start` seeds the iteration with previously converged scores (e.g. the
+/// stored `eigenvector_centrality` values); after a small graph change this
+/// converges in far fewer iterations than starting from a uniform vector.
+/// Nodes missing from it start at the uniform value.
+///
+/// With `directed` a node scores from its in-edges, giving the principal
+/// eigenvector of the transposed adjacency; otherwise edges count both ways.
+/// Directed power iteration often fails to settle (e.g. on DAGs), so it stops
+/// after `max_iterations` and the result reports whether it `converged`.
 pub async fn calculate_eigenvector_centrality(
     client: &FalkorClient,
     group_id: Option<&str>,
     max_iterations: u32,
     tolerance: f64,
-) -> Result<CentralityScores> {
+    warm_start: Option<&HashMap<String, f64>>,
+    directed: bool,
+) -> Result<EigenvectorScores> {
+    validate_eigenvector_params(max_iterations, tolerance)?;
     let _start = Instant::now();
     info!("Starting eigenvector centrality calculation");
 
@@ -726,7 +1374,15 @@ pub async fn calculate_eigenvector_centrality(
     match connectivity {
         GraphConnectivity::WeaklyConnected => {
             info!("Graph is weakly connected, using pure eigenvector centrality");
-            calculate_pure_eigenvector_centrality(client, group_id, max_iterations, tolerance).await
+            calculate_pure_eigenvector_centrality(
+                client,
+                group_id,
+                max_iterations,
+                tolerance,
+                warm_start,
+                directed,
+            )
+            .await
         }
         GraphConnectivity::Disconnected => {
             info!("Graph has multiple components, using damped eigenvector centrality");
@@ -736,6 +1392,8 @@ pub async fn calculate_eigenvector_centrality(
                 max_iterations,
                 tolerance,
                 0.85,
+                warm_start,
+                directed,
             )
             .await
         }
@@ -748,24 +1406,33 @@ async fn calculate_pure_eigenvector_centrality(
     group_id: Option<&str>,
     max_iterations: u32,
     tolerance: f64,
-) -> Result<CentralityScores> {
+    warm_start: Option<&HashMap<String, f64>>,
+    directed: bool,
+) -> Result<EigenvectorScores> {
     let start = Instant::now();
-    info!("Starting pure eigenvector centrality calculation");
+    info!(
+        "Starting pure eigenvector centrality calculation, directed={}",
+        directed
+    );
 
-    // First, get all nodes and their incoming connections (for eigenvector centrality)
+    // First, get all nodes and their incoming connections (for eigenvector
+    // centrality), or all their neighbors when undirected
+    let pattern = if directed { "<-[r]-" } else { "-[r]-" };
     let adjacency_query = if let Some(group_id) = group_id {
         format!(
             "MATCH (n) WHERE n.group_id = '{}' 
-             OPTIONAL MATCH (n)<-[r]-(m)
+             OPTIONAL MATCH (n){}(m)
              WHERE m.group_id = '{}'
              RETURN n.uuid as node, collect(DISTINCT m.uuid) as in_neighbors",
-            group_id, group_id
+            group_id, pattern, group_id
         )
     } else {
-        "MATCH (n)
-         OPTIONAL MATCH (n)<-[r]-(m)
-         RETURN n.uuid as node, collect(DISTINCT m.uuid) as in_neighbors"
-            .to_string()
+        format!(
+            "MATCH (n)
+             OPTIONAL MATCH (n){}(m)
+             RETURN n.uuid as node, collect(DISTINCT m.uuid) as in_neighbors",
+            pattern
+        )
     };
 
     debug!("Fetching adjacency list for eigenvector centrality");
@@ -808,21 +1475,95 @@ async fn calculate_pure_eigenvector_centrality(
     let node_count = all_nodes.len();
     info!("Computing eigenvector centrality for {} nodes", node_count);
 
-    // Initialize scores to 1/sqrt(n)
+    let (scores, iterations, converged) =
+        eigenvector_from_in_neighbors(&adjacency, max_iterations, tolerance, warm_start);
+
+    let duration = start.elapsed();
+    info!(
+        "Eigenvector centrality calculation completed in {:?} for {} nodes",
+        duration, node_count
+    );
+
+    Ok(EigenvectorScores {
+        centrality: CentralityScores {
+            scores,
+            nodes_processed: node_count,
+        },
+        iterations,
+        converged,
+    })
+}
+
+/// Eigenvector centrality over an explicit edge list. With `directed` each
+/// node scores from its in-edges; otherwise each edge is followed both ways.
+/// Returns the scores, the iterations run and whether the iteration converged.
+pub fn eigenvector_from_edges(
+    nodes: &[String],
+    edges: &[(String, String)],
+    directed: bool,
+    max_iterations: u32,
+    tolerance: f64,
+) -> (HashMap<String, f64>, u32, bool) {
+    let mut in_neighbors: HashMap<String, Vec<String>> = nodes
+        .iter()
+        .map(|node| (node.clone(), Vec::new()))
+        .collect();
+    let mut add_link = |from: &String, to: &String| {
+        if let Some(sources) = in_neighbors.get_mut(to) {
+            if !sources.contains(from) {
+                sources.push(from.clone());
+            }
+        }
+    };
+    for (source, target) in edges {
+        add_link(source, target);
+        if !directed {
+            add_link(target, source);
+        }
+    }
+
+    eigenvector_from_in_neighbors(&in_neighbors, max_iterations, tolerance, None)
+}
+
+/// Power iteration for eigenvector centrality over each node's in-neighbors,
+/// returning the L2-normalized scores, the number of iterations run and
+/// whether it converged before `max_iterations`.
+///
+/// The iteration starts from `warm_start` where given (missing nodes get the
+/// uniform `1/sqrt(n)`) and from the uniform vector otherwise.
+pub fn eigenvector_from_in_neighbors(
+    in_neighbors: &HashMap<String, Vec<String>>,
+    max_iterations: u32,
+    tolerance: f64,
+    warm_start: Option<&HashMap<String, f64>>,
+) -> (HashMap<String, f64>, u32, bool) {
+    let all_nodes: HashSet<String> = in_neighbors.keys().cloned().collect();
+    let node_count = all_nodes.len();
+    if node_count == 0 {
+        return (HashMap::new(), 0, true);
+    }
+
     let initial_value = 1.0 / (node_count as f64).sqrt();
-    let mut scores: HashMap<String, f64> = HashMap::new();
-    for node in &all_nodes {
-        scores.insert(node.clone(), initial_value);
+    let mut scores = initial_scores(&all_nodes, initial_value, warm_start);
+    let norm: f64 = scores.values().map(|s| s * s).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for score in scores.values_mut() {
+            *score /= norm;
+        }
     }
 
+    let mut iterations = 0;
+    let mut converged = false;
+
     // Power iteration
     for iteration in 0..max_iterations {
+        iterations = iteration + 1;
         let mut new_scores: HashMap<String, f64> = HashMap::new();
 
         // Calculate new scores: score[v] = sum of neighbors' scores
         for node in &all_nodes {
             let mut score = 0.0;
-            if let Some(neighbors) = adjacency.get(node) {
+            if let Some(neighbors) = in_neighbors.get(node) {
                 for neighbor in neighbors {
                     if let Some(neighbor_score) = scores.get(neighbor) {
                         score += neighbor_score;
@@ -877,100 +1618,448 @@ async fn calculate_pure_eigenvector_centrality(
                 "Pure eigenvector centrality converged after {} iterations",
                 iteration + 1
             );
+            converged = true;
             break;
         }
     }
 
-    let duration = start.elapsed();
-    info!(
-        "Eigenvector centrality calculation completed in {:?} for {} nodes",
-        duration, node_count
-    );
+    if !converged {
+        warn!(
+            "Pure eigenvector centrality did not converge within {} iterations",
+            max_iterations
+        );
+    }
 
-    Ok(CentralityScores {
-        scores,
-        nodes_processed: node_count,
-    })
+    (scores, iterations, converged)
 }
 
-/// Calculate all centrality metrics efficiently
+/// Starting vector for a power iteration: prior scores where known, `default` elsewhere
+fn initial_scores(
+    nodes: &HashSet<String>,
+    default: f64,
+    warm_start: Option<&HashMap<String, f64>>,
+) -> HashMap<String, f64> {
+    nodes
+        .iter()
+        .map(|node| {
+            let prior = warm_start
+                .and_then(|prior| prior.get(node))
+                .copied()
+                .filter(|score| score.is_finite() && *score >= 0.0);
+            (node.clone(), prior.unwrap_or(default))
+        })
+        .collect()
+}
+
+/// Calculate all centrality metrics efficiently.
+///
+/// Each metric is calculated independently: one that fails is listed in
+/// `failed_metrics` and the others are still returned. Only when every
+/// metric fails is an error returned.
+///
+/// `eigenvector_warm_start` seeds eigenvector centrality, normally with the
+/// scores stored by the previous run.
 pub async fn calculate_all_centralities(
     client: &FalkorClient,
     group_id: Option<&str>,
-) -> Result<HashMap<String, HashMap<String, f64>>> {
+    weights: &ImportanceWeights,
+    sampling: &BetweennessSampling,
+    eigenvector_directed: bool,
+    eigenvector_warm_start: Option<&HashMap<String, f64>>,
+) -> Result<AllCentralityScores> {
+    weights.validate()?;
+    sampling.validate()?;
     let start = Instant::now();
     info!("Starting calculation of all centrality metrics");
 
     // Calculate each metric
-    let pagerank = calculate_pagerank(client, group_id, 0.85, 20).await?;
-    let degree = calculate_degree_centrality(client, "both", group_id).await?;
+    let pagerank = calculate_pagerank(client, group_id, 0.85, 20, true).await;
+    let degree = calculate_degree_centrality(client, "both", group_id).await;
 
     // For betweenness, use sampling for large graphs
-    let stats = client.get_graph_stats().await?;
-    let node_count = stats.get("nodes").unwrap_or(&0);
-    let sample_size = if *node_count > 100 { Some(50) } else { None };
-
-    let betweenness = calculate_betweenness_centrality(client, group_id, sample_size).await?;
+    let betweenness = async {
+        let stats = client.get_graph_stats().await?;
+        let node_count = stats.get("nodes").copied().unwrap_or(0);
+        let sample_size = sampling.sample_size_for(node_count as usize);
+        calculate_betweenness_with_sample(client, group_id, sample_size).await
+    }
+    .await;
+    let (betweenness, betweenness_sample_size) = match betweenness {
+        Ok((scores, sample_size)) => (Ok(scores), sample_size),
+        Err(e) => (Err(e), None),
+    };
 
     // Calculate true eigenvector centrality
-    let eigenvector = calculate_eigenvector_centrality(client, group_id, 100, 1e-6).await?;
+    let eigenvector = calculate_eigenvector_centrality(
+        client,
+        group_id,
+        100,
+        1e-6,
+        eigenvector_warm_start,
+        eigenvector_directed,
+    )
+    .await;
+    let (eigenvector, eigenvector_converged) = match eigenvector {
+        Ok(EigenvectorScores {
+            centrality,
+            converged,
+            ..
+        }) => (Ok(centrality), converged),
+        Err(e) => (Err(e), false),
+    };
+
+    let (all_scores, failed_metrics) =
+        combine_centralities(pagerank, degree, betweenness, eigenvector, weights);
+    if failed_metrics.len() == 4 {
+        return Err(CentralityError::algorithm_failed(format!(
+            "All centrality metrics failed: {:?}",
+            failed_metrics
+        )));
+    }
+
+    let duration = start.elapsed();
+    info!(
+        "All centrality calculations completed in {:?} for {} nodes",
+        duration,
+        all_scores.len()
+    );
+
+    Ok(AllCentralityScores {
+        scores: all_scores,
+        betweenness_sample_size,
+        eigenvector_converged,
+        failed_metrics,
+    })
+}
+
+/// Combine per-metric results into per-node scores, normalizing degree.
+///
+/// Returns the scores along with each failed metric's error. A failed metric
+/// is left out of every node's scores, and so is `importance`, since the
+/// weighted combination needs all four metrics.
+pub fn combine_centralities(
+    pagerank: Result<CentralityScores>,
+    degree: Result<CentralityScores>,
+    betweenness: Result<CentralityScores>,
+    eigenvector: Result<CentralityScores>,
+    weights: &ImportanceWeights,
+) -> (
+    HashMap<String, HashMap<String, f64>>,
+    BTreeMap<String, String>,
+) {
+    let mut failed_metrics = BTreeMap::new();
+    let mut succeeded = |metric: &str, result: Result<CentralityScores>| match result {
+        Ok(scores) => Some(scores),
+        Err(e) => {
+            warn!("{} calculation failed: {}", metric, e);
+            failed_metrics.insert(metric.to_string(), e.to_string());
+            None
+        }
+    };
+    let pagerank = succeeded("pagerank", pagerank);
+    let degree = succeeded("degree", degree);
+    let betweenness = succeeded("betweenness", betweenness);
+    let eigenvector = succeeded("eigenvector", eigenvector);
 
     // Find max degree for normalization
-    let max_degree = degree.scores.values().fold(0.0_f64, |a, &b| a.max(b));
+    let max_degree = degree
+        .iter()
+        .flat_map(|degree| degree.scores.values())
+        .fold(0.0_f64, |a, &b| a.max(b));
 
-    // Combine all scores
-    let mut all_scores = HashMap::new();
-    let all_nodes: std::collections::HashSet<String> = pagerank
-        .scores
-        .keys()
-        .chain(degree.scores.keys())
-        .chain(betweenness.scores.keys())
-        .chain(eigenvector.scores.keys())
-        .cloned()
+    let all_nodes: HashSet<&String> = [&pagerank, &degree, &betweenness, &eigenvector]
+        .into_iter()
+        .flatten()
+        .flat_map(|metric| metric.scores.keys())
         .collect();
+    let score = |metric: &Option<CentralityScores>, node_id: &str| {
+        metric
+            .as_ref()
+            .map(|metric| metric.scores.get(node_id).copied().unwrap_or(0.0))
+    };
 
+    // Combine all scores
+    let mut all_scores = HashMap::new();
     for node_id in all_nodes {
         let mut node_scores = HashMap::new();
 
         // PageRank is already normalized by the algorithm
-        let pagerank_score = pagerank.scores.get(&node_id).copied().unwrap_or(0.0);
-        node_scores.insert("pagerank".to_string(), pagerank_score);
+        let pagerank_score = score(&pagerank, node_id);
 
         // Normalize degree centrality to [0,1] by dividing by max degree
-        let degree_raw = degree.scores.get(&node_id).copied().unwrap_or(0.0);
-        let degree_normalized = if max_degree > 0.0 {
-            degree_raw / max_degree
-        } else {
-            0.0
-        };
-        node_scores.insert("degree".to_string(), degree_normalized);
+        let degree_normalized = score(&degree, node_id).map(|degree_raw| {
+            if max_degree > 0.0 {
+                degree_raw / max_degree
+            } else {
+                0.0
+            }
+        });
 
         // Betweenness is already normalized in the approximation function
-        let betweenness_score = betweenness.scores.get(&node_id).copied().unwrap_or(0.0);
-        node_scores.insert("betweenness".to_string(), betweenness_score);
+        let betweenness_score = score(&betweenness, node_id);
 
         // True eigenvector centrality (already normalized by power iteration)
-        let eigenvector_score = eigenvector.scores.get(&node_id).copied().unwrap_or(0.0);
-        node_scores.insert("eigenvector".to_string(), eigenvector_score);
+        let eigenvector_score = score(&eigenvector, node_id);
+
+        for (metric, value) in [
+            ("pagerank", pagerank_score),
+            ("degree", degree_normalized),
+            ("betweenness", betweenness_score),
+            ("eigenvector", eigenvector_score),
+        ] {
+            if let Some(value) = value {
+                node_scores.insert(metric.to_string(), value);
+            }
+        }
 
         // Calculate importance as a weighted combination
         // This is a composite metric, not eigenvector centrality
-        let importance = (0.4 * pagerank_score
-            + 0.3 * eigenvector_score
-            + 0.2 * degree_normalized
-            + 0.1 * betweenness_score)
-            .min(1.0);
-        node_scores.insert("importance".to_string(), importance);
+        if let (Some(pr), Some(ev), Some(deg), Some(btw)) = (
+            pagerank_score,
+            eigenvector_score,
+            degree_normalized,
+            betweenness_score,
+        ) {
+            node_scores.insert(
+                "importance".to_string(),
+                weights.importance(pr, ev, deg, btw),
+            );
+        }
 
-        all_scores.insert(node_id, node_scores);
+        all_scores.insert(node_id.clone(), node_scores);
     }
 
-    let duration = start.elapsed();
-    info!(
-        "All centrality calculations completed in {:?} for {} nodes",
-        duration,
-        all_scores.len()
+    (all_scores, failed_metrics)
+}
+
+/// Calculate the requested metrics for a single node.
+///
+/// `importance` combines the node's pagerank, degree and betweenness with the
+/// same weighted formula as `calculate_all_centralities`. Eigenvector
+/// centrality has no local approximation, so the node's stored score is used.
+pub async fn calculate_single_node_centralities(
+    client: &FalkorClient,
+    node_uuid: &str,
+    metrics: &[String],
+    weights: &ImportanceWeights,
+) -> Result<HashMap<String, f64>> {
+    let wants = |metric: &str| metrics.iter().any(|m| m == metric);
+    let importance = wants("importance");
+    if importance {
+        weights.validate()?;
+    }
+
+    let mut scores = HashMap::new();
+
+    if wants("degree") || importance {
+        let degree = calculate_single_node_degree(client, node_uuid).await?;
+        scores.insert("degree".to_string(), degree);
+    }
+
+    // Both localized metrics work on the same bounded 2-hop neighborhood
+    if wants("pagerank") || wants("betweenness") || importance {
+        let neighborhood = fetch_neighborhood(client, node_uuid).await?;
+        if wants("pagerank") || importance {
+            let pagerank = personalized_pagerank(&neighborhood, node_uuid, 0.85, 50);
+            scores.insert("pagerank".to_string(), pagerank);
+        }
+        if wants("betweenness") || importance {
+            scores.insert(
+                "betweenness".to_string(),
+                ego_betweenness(&neighborhood, node_uuid),
+            );
+        }
+    }
+
+    if wants("eigenvector") || importance {
+        let eigenvector = stored_single_node_eigenvector(client, node_uuid).await?;
+        scores.insert("eigenvector".to_string(), eigenvector);
+    }
+
+    if importance {
+        let score = |metric: &str| scores.get(metric).copied().unwrap_or(0.0);
+        let value = weights.importance(
+            score("pagerank"),
+            score("eigenvector"),
+            score("degree"),
+            score("betweenness"),
+        );
+        scores.insert("importance".to_string(), value);
+    }
+
+    // Components computed only for importance are not part of the response
+    scores.retain(|metric, _| wants(metric));
+    Ok(scores)
+}
+
+// Helper functions for single node calculations
+async fn calculate_single_node_degree(client: &FalkorClient, node_uuid: &str) -> Result<f64> {
+    let query = format!(
+        r#"
+        MATCH (n {{uuid: '{}'}})
+        OPTIONAL MATCH (n)-[r]-(m)
+        RETURN COUNT(DISTINCT m) as degree
+        "#,
+        node_uuid
     );
 
-    Ok(all_scores)
+    let result = client.execute_query(&query, None).await?;
+
+    if let Some(row) = result.first() {
+        if let Some(degree_value) = row.get("degree") {
+            if let Some(degree) = falkor_value_to_i64(degree_value) {
+                // Normalize degree (simple normalization by dividing by 10)
+                return Ok(degree as f64 / 10.0);
+            }
+        }
+    }
+
+    Ok(0.0)
+}
+
+/// Upper bound on nodes fetched for a single node's neighborhood
+const MAX_NEIGHBORHOOD_NODES: usize = 1000;
+
+/// Undirected adjacency of the subgraph within two hops of `node_uuid`
+async fn fetch_neighborhood(
+    client: &FalkorClient,
+    node_uuid: &str,
+) -> Result<HashMap<String, HashSet<String>>> {
+    let query = format!(
+        r#"
+        MATCH (n {{uuid: $uuid}})-[*1..2]-(m)
+        WITH DISTINCT n, m LIMIT {}
+        WITH n, collect(m) AS neighbours
+        WITH neighbours + [n] AS members
+        UNWIND members AS a
+        MATCH (a)-[]-(b)
+        WHERE b IN members AND a <> b
+        RETURN DISTINCT a.uuid AS source, b.uuid AS target
+        "#,
+        MAX_NEIGHBORHOOD_NODES
+    );
+    let params = HashMap::from([(
+        "uuid".to_string(),
+        FalkorValue::String(node_uuid.to_string()),
+    )]);
+
+    let result = client.execute_query(&query, Some(params)).await?;
+
+    let mut adjacency: HashMap<String, HashSet<String>> = HashMap::new();
+    for row in result {
+        if let (Some(FalkorValue::String(source)), Some(FalkorValue::String(target))) =
+            (row.get("source"), row.get("target"))
+        {
+            adjacency
+                .entry(source.clone())
+                .or_default()
+                .insert(target.clone());
+            adjacency
+                .entry(target.clone())
+                .or_default()
+                .insert(source.clone());
+        }
+    }
+
+    debug!(
+        "Fetched neighborhood of {} with {} nodes",
+        node_uuid,
+        adjacency.len()
+    );
+    Ok(adjacency)
+}
+
+/// Personalized PageRank seeded at `seed` over an undirected neighborhood,
+/// returning the seed's own stationary probability. Walks from well-connected
+/// nodes keep returning to them, so hubs score higher than leaves.
+pub fn personalized_pagerank(
+    adjacency: &HashMap<String, HashSet<String>>,
+    seed: &str,
+    damping_factor: f64,
+    iterations: u32,
+) -> f64 {
+    if adjacency
+        .get(seed)
+        .is_none_or(|neighbours| neighbours.is_empty())
+    {
+        return 0.0;
+    }
+
+    let mut scores: HashMap<&str, f64> = adjacency.keys().map(|n| (n.as_str(), 0.0)).collect();
+    scores.insert(seed, 1.0);
+
+    for _ in 0..iterations {
+        let mut next: HashMap<&str, f64> = adjacency.keys().map(|n| (n.as_str(), 0.0)).collect();
+        let mut restart = 1.0 - damping_factor;
+
+        for (node, neighbours) in adjacency {
+            let score = scores[node.as_str()];
+            if neighbours.is_empty() {
+                // Dangling mass returns to the seed
+                restart += damping_factor * score;
+                continue;
+            }
+            let share = damping_factor * score / neighbours.len() as f64;
+            for neighbour in neighbours {
+                if let Some(value) = next.get_mut(neighbour.as_str()) {
+                    *value += share;
+                }
+            }
+        }
+
+        *next.entry(seed).or_default() += restart;
+        scores = next;
+    }
+
+    scores[seed]
+}
+
+/// Betweenness of `node` within its ego network (the node, its neighbours and
+/// the edges among them), normalized by the number of neighbour pairs.
+///
+/// Each pair of unconnected neighbours contributes `1 / k`, where `k` is the
+/// number of ego members adjacent to both.
+pub fn ego_betweenness(adjacency: &HashMap<String, HashSet<String>>, node: &str) -> f64 {
+    let neighbours: Vec<&String> = match adjacency.get(node) {
+        Some(neighbours) if neighbours.len() >= 2 => neighbours.iter().collect(),
+        _ => return 0.0,
+    };
+    let empty = HashSet::new();
+    let neighbours_of = |n: &str| adjacency.get(n).unwrap_or(&empty);
+
+    let mut betweenness = 0.0;
+    for (i, u) in neighbours.iter().enumerate() {
+        let u_neighbours = neighbours_of(u);
+        for v in &neighbours[i + 1..] {
+            if u_neighbours.contains(*v) {
+                continue;
+            }
+            let v_neighbours = neighbours_of(v);
+            // The center always bridges u and v; other neighbours may as well
+            let bridges = 1 + neighbours
+                .iter()
+                .filter(|w| u_neighbours.contains(**w) && v_neighbours.contains(**w))
+                .count();
+            betweenness += 1.0 / bridges as f64;
+        }
+    }
+
+    let pairs = neighbours.len() * (neighbours.len() - 1) / 2;
+    betweenness / pairs as f64
+}
+
+async fn stored_single_node_eigenvector(client: &FalkorClient, node_uuid: &str) -> Result<f64> {
+    let query = "MATCH (n {uuid: $uuid}) RETURN n.eigenvector_centrality AS eigenvector";
+    let params = HashMap::from([(
+        "uuid".to_string(),
+        FalkorValue::String(node_uuid.to_string()),
+    )]);
+
+    let result = client.execute_query(query, Some(params)).await?;
+
+    Ok(result
+        .first()
+        .and_then(|row| row.get("eigenvector"))
+        .and_then(falkor_value_to_f64)
+        .unwrap_or(0.0))
 }
@@ -14,13 +14,39 @@ pub struct FalkorClient {
 
 impl FalkorClient {
     /// Create a new FalkorDB client with optimized settings
+    ///
+    /// Endpoints from `config.endpoints()` are tried in order and the first one
+    /// that answers a query is used.
     pub async fn new(config: DatabaseConfig) -> Result<Self> {
-        info!(
-            "Connecting to FalkorDB at {}:{}, graph: {}",
-            config.host, config.port, config.graph_name
-        );
+        let mut last_error = None;
+
+        for endpoint in config.endpoints() {
+            info!(
+                "Connecting to FalkorDB at {}, graph: {}",
+                endpoint, config.graph_name
+            );
+            let client = match Self::connect(&endpoint, &config.graph_name).await {
+                Ok(client) => client,
+                Err(e) => {
+                    warn!("FalkorDB host {} unavailable: {}", endpoint, e);
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            match client.test_connection().await {
+                Ok(()) => return Ok(client),
+                Err(e) => {
+                    warn!("FalkorDB host {} failed connection test: {}", endpoint, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| CentralityError::internal("No FalkorDB hosts configured")))
+    }
 
-        let connection_string = format!("falkor://{}:{}", config.host, config.port);
+    async fn connect(endpoint: &str, graph_name: &str) -> Result<Self> {
+        let connection_string = format!("falkor://{}", endpoint);
         let connection_info: FalkorConnectionInfo = connection_string
             .as_str()
             .try_into()
@@ -33,20 +59,34 @@ impl FalkorClient {
 
         Ok(Self {
             client: Arc::new(client),
-            graph_name: config.graph_name,
+            graph_name: graph_name.to_string(),
         })
     }
 
     /// Execute a query and return results as a vector of hash maps
+    ///
+    /// `params` are bound as `$name` query parameters.
     pub async fn execute_query(
         &self,
         query: &str,
-        _params: Option<HashMap<String, FalkorValue>>,
+        params: Option<HashMap<String, FalkorValue>>,
     ) -> Result<Vec<HashMap<String, FalkorValue>>> {
         debug!("Executing query: {}", query);
 
+        let params = params
+            .map(|params| {
+                params
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), falkor_value_to_cypher(value)?)))
+                    .collect::<Result<HashMap<String, String>>>()
+            })
+            .transpose()?;
+
         let mut graph = self.client.select_graph(&self.graph_name);
-        let result = graph.query(query).execute().await?;
+        let result = match &params {
+            Some(params) => graph.query(query).with_params(params).execute().await?,
+            None => graph.query(query).execute().await?,
+        };
 
         let mut records = Vec::new();
 
@@ -107,54 +147,75 @@ impl FalkorClient {
     }
 
     /// Store centrality scores back to the database using batch updates
+    ///
+    /// Each metric is written to its canonical property (e.g. `pagerank_centrality`),
+    /// or to `{property}_{suffix}` when a suffix is given so experimental runs don't
+    /// overwrite the values the visualizer reads. Properties of metrics missing
+    /// from a node's scores are left untouched.
+    ///
+    /// Returns the number of queries sent: one per batch of 1000 nodes,
+    /// plus one per node of any batch that had to be retried node by node.
     pub async fn store_centrality_scores(
         &self,
         scores: &HashMap<String, HashMap<String, f64>>,
-    ) -> Result<()> {
+        property_suffix: Option<&str>,
+    ) -> Result<usize> {
+        validate_property_suffix(property_suffix)?;
         info!(
             "Storing centrality scores for {} nodes using batch updates",
             scores.len()
         );
 
-        // Process in batches of 100 nodes for reliable FalkorDB updates
-        const BATCH_SIZE: usize = 100;
+        let mut metrics: Vec<&str> = scores
+            .values()
+            .flat_map(|node_scores| node_scores.keys().map(String::as_str))
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+            .collect();
+        metrics.sort_unstable();
+        if metrics.is_empty() {
+            return Ok(0);
+        }
+        if let Some(metric) = metrics.iter().find(|m| !is_property_identifier(m)) {
+            return Err(CentralityError::invalid_parameter(format!(
+                "Invalid metric name: {}",
+                metric
+            )));
+        }
+
+        // One UNWIND round trip per batch instead of one query per node
+        const BATCH_SIZE: usize = 1000;
+        let set_clauses: Vec<String> = metrics
+            .iter()
+            .map(|metric| {
+                let property = score_property_name(metric, property_suffix);
+                format!("n.{0} = coalesce(row.{1}, n.{0})", property, metric)
+            })
+            .collect();
+        let store_query = format!(
+            "UNWIND $rows AS row MATCH (n {{uuid: row.uuid}}) SET {}",
+            set_clauses.join(", ")
+        );
+        let store_query = store_query.as_str();
+
+        let start = std::time::Instant::now();
         let mut processed = 0;
+        let mut round_trips = 0;
 
         // Convert scores to a vector for easier batching
         let score_entries: Vec<(&String, &HashMap<String, f64>)> = scores.iter().collect();
 
         for chunk in score_entries.chunks(BATCH_SIZE) {
-            // Build batch update data
-            let mut batch_data = Vec::new();
-
-            for (node_uuid, node_scores) in chunk {
-                // Extract all centrality values with defaults
-                let pagerank = node_scores.get("pagerank").copied().unwrap_or(0.0);
-                let degree = node_scores.get("degree").copied().unwrap_or(0.0);
-                let betweenness = node_scores.get("betweenness").copied().unwrap_or(0.0);
-                let eigenvector = node_scores.get("eigenvector").copied().unwrap_or(0.0);
-                let importance = node_scores.get("importance").copied().unwrap_or(0.0);
-
-                batch_data.push(format!(
-                    "{{uuid: '{}', pagerank: {}, degree: {}, betweenness: {}, eigenvector: {}, importance: {}}}",
-                    node_uuid, pagerank, degree, betweenness, eigenvector, importance
-                ));
-            }
-
-            // Use UNWIND for batch updates - much more efficient than individual queries
-            let batch_query = format!(
-                "UNWIND [{}] AS nodeData
-                 MATCH (n {{uuid: nodeData.uuid}})
-                 SET n.pagerank_centrality = nodeData.pagerank,
-                     n.degree_centrality = nodeData.degree,
-                     n.betweenness_centrality = nodeData.betweenness,
-                     n.eigenvector_centrality = nodeData.eigenvector,
-                     n.importance_score = nodeData.importance",
-                batch_data.join(", ")
-            );
-
-            // Execute batch update
-            match self.execute_query(&batch_query, None).await {
+            let rows: Vec<FalkorValue> = chunk
+                .iter()
+                .map(|(node_uuid, node_scores)| score_row(node_uuid, node_scores))
+                .collect();
+
+            round_trips += 1;
+            match self
+                .execute_query(store_query, Some(rows_param(rows)))
+                .await
+            {
                 Ok(_) => {
                     processed += chunk.len();
                     debug!(
@@ -167,38 +228,22 @@ impl FalkorClient {
                     warn!("Failed to store batch of {} scores: {}", chunk.len(), e);
                     // Try individual updates as fallback for this batch
                     for (node_uuid, node_scores) in chunk {
-                        let mut set_clauses = Vec::new();
-                        for (score_name, score_value) in node_scores.iter() {
-                            let property_name = match score_name.as_str() {
-                                "pagerank" => "pagerank_centrality",
-                                "degree" => "degree_centrality",
-                                "betweenness" => "betweenness_centrality",
-                                "eigenvector" => "eigenvector_centrality",
-                                "importance" => "importance_score",
-                                _ => &format!("{}_centrality", score_name),
-                            };
-                            set_clauses.push(format!("n.{} = {}", property_name, score_value));
-                        }
-
-                        if !set_clauses.is_empty() {
-                            let fallback_query = format!(
-                                "MATCH (n {{uuid: '{}'}}) SET {}",
-                                node_uuid,
-                                set_clauses.join(", ")
-                            );
-
-                            if let Err(e) = self.execute_query(&fallback_query, None).await {
-                                warn!("Fallback update also failed for node {}: {}", node_uuid, e);
-                            } else {
-                                processed += 1;
-                            }
+                        round_trips += 1;
+                        let row = score_row(node_uuid, node_scores);
+                        if let Err(e) = self
+                            .execute_query(store_query, Some(rows_param(vec![row])))
+                            .await
+                        {
+                            warn!("Fallback update also failed for node {}: {}", node_uuid, e);
+                        } else {
+                            processed += 1;
                         }
                     }
                 }
             }
 
             // Log progress for large datasets
-            if scores.len() > 1000 && processed % 1000 == 0 {
+            if scores.len() > BATCH_SIZE {
                 info!(
                     "Stored centrality scores for {}/{} nodes",
                     processed,
@@ -208,10 +253,20 @@ impl FalkorClient {
         }
 
         info!(
-            "Centrality scores stored successfully for {} nodes",
-            processed
+            "Centrality scores stored for {} nodes in {:?} ({} round trips instead of {})",
+            processed,
+            start.elapsed(),
+            round_trips,
+            scores.len()
         );
-        Ok(())
+        if processed < scores.len() {
+            return Err(CentralityError::internal(format!(
+                "Stored centrality scores for only {} of {} nodes",
+                processed,
+                scores.len()
+            )));
+        }
+        Ok(round_trips)
     }
 
     /// Get the graph name this client is connected to
@@ -220,6 +275,95 @@ impl FalkorClient {
     }
 }
 
+/// Build the UNWIND row for one node's scores; missing metrics become null
+fn score_row(node_uuid: &str, node_scores: &HashMap<String, f64>) -> FalkorValue {
+    let mut row: HashMap<String, FalkorValue> = node_scores
+        .iter()
+        .map(|(metric, value)| (metric.clone(), FalkorValue::F64(*value)))
+        .collect();
+    row.insert(
+        "uuid".to_string(),
+        FalkorValue::String(node_uuid.to_string()),
+    );
+    FalkorValue::Map(row)
+}
+
+/// Node property a metric's score is stored under
+pub fn score_property_name(metric: &str, property_suffix: Option<&str>) -> String {
+    let base = match metric {
+        "importance" => "importance_score".to_string(),
+        other => format!("{}_centrality", other),
+    };
+    match property_suffix {
+        Some(suffix) => format!("{}_{}", base, suffix),
+        None => base,
+    }
+}
+
+/// Suffixes become part of a property name, so only identifier characters are allowed
+pub fn validate_property_suffix(property_suffix: Option<&str>) -> Result<()> {
+    match property_suffix {
+        Some(suffix)
+            if suffix.is_empty()
+                || !suffix
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+        {
+            Err(CentralityError::invalid_parameter(format!(
+                "property_suffix must be non-empty and contain only letters, digits or '_': {}",
+                suffix
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn is_property_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn rows_param(rows: Vec<FalkorValue>) -> HashMap<String, FalkorValue> {
+    HashMap::from([("rows".to_string(), FalkorValue::Array(rows))])
+}
+
+/// Render a value as a Cypher literal for use as a query parameter
+pub fn falkor_value_to_cypher(value: &FalkorValue) -> Result<String> {
+    Ok(match value {
+        FalkorValue::String(s) => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+        FalkorValue::I64(i) => i.to_string(),
+        // Cypher has no literal for NaN/infinity
+        FalkorValue::F64(f) if f.is_finite() => format!("{:?}", f),
+        FalkorValue::F64(_) => "0.0".to_string(),
+        FalkorValue::Bool(b) => b.to_string(),
+        FalkorValue::None => "null".to_string(),
+        FalkorValue::Array(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(falkor_value_to_cypher)
+                .collect::<Result<Vec<_>>>()?
+                .join(", ")
+        ),
+        FalkorValue::Map(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(key, value)| Ok(format!("{}: {}", key, falkor_value_to_cypher(value)?)))
+                .collect::<Result<Vec<_>>>()?
+                .join(", ")
+        ),
+        other => {
+            return Err(CentralityError::internal(format!(
+                "Unsupported query parameter value: {:?}",
+                other
+            )))
+        }
+    })
+}
+
 /// Utility functions for converting FalkorValue types
 pub fn falkor_value_to_string(value: &FalkorValue) -> String {
     match value {
@@ -0,0 +1,111 @@
+use crate::error::{CentralityError, Result};
+use crate::models::AllCentralitiesResponse;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+/// How long finished jobs stay available for polling
+const FINISHED_JOB_RETENTION: Duration = Duration::from_secs(3600);
+
+/// Lifecycle of a background centrality job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending,
+    Complete,
+    Failed,
+}
+
+/// Pollable state of a background job
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRecord {
+    pub job_id: String,
+    pub status: JobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<AllCentralitiesResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip)]
+    finished_at: Option<Instant>,
+}
+
+/// In-memory registry of background centrality computations.
+///
+/// Jobs run on the tokio runtime so long calculations are not tied to the
+/// lifetime of an HTTP request. Finished jobs are kept for an hour.
+#[derive(Clone, Default)]
+pub struct JobStore {
+    jobs: Arc<DashMap<String, JobRecord>>,
+}
+
+impl JobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start `work` in the background and return its job id
+    pub fn spawn<F>(&self, work: F) -> String
+    where
+        F: Future<Output = Result<AllCentralitiesResponse>> + Send + 'static,
+    {
+        self.prune_finished();
+
+        let job_id = uuid::Uuid::new_v4().to_string();
+        self.jobs.insert(
+            job_id.clone(),
+            JobRecord {
+                job_id: job_id.clone(),
+                status: JobStatus::Pending,
+                result: None,
+                error: None,
+                finished_at: None,
+            },
+        );
+
+        let jobs = self.jobs.clone();
+        let id = job_id.clone();
+        tokio::spawn(async move {
+            // Run the work on its own task so a panic fails the job
+            // instead of leaving it pending forever
+            let outcome = match tokio::spawn(work).await {
+                Ok(outcome) => outcome,
+                Err(e) => Err(CentralityError::internal(format!(
+                    "Centrality job aborted: {}",
+                    e
+                ))),
+            };
+            if let Some(mut record) = jobs.get_mut(&id) {
+                record.finished_at = Some(Instant::now());
+                match outcome {
+                    Ok(result) => {
+                        info!("Centrality job {} complete", id);
+                        record.status = JobStatus::Complete;
+                        record.result = Some(result);
+                    }
+                    Err(e) => {
+                        error!("Centrality job {} failed: {}", id, e);
+                        record.status = JobStatus::Failed;
+                        record.error = Some(e.to_string());
+                    }
+                }
+            }
+        });
+
+        job_id
+    }
+
+    pub fn get(&self, job_id: &str) -> Option<JobRecord> {
+        self.jobs.get(job_id).map(|record| record.clone())
+    }
+
+    fn prune_finished(&self) {
+        self.jobs.retain(|_, record| {
+            record
+                .finished_at
+                .is_none_or(|finished| finished.elapsed() < FINISHED_JOB_RETENTION)
+        });
+    }
+}
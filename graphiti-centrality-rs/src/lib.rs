@@ -15,7 +15,9 @@ Performance target: 100-1000x faster than Python implementation.
 pub mod algorithms;
 pub mod client;
 pub mod error;
+pub mod jobs;
 pub mod models;
+pub mod request_id;
 pub mod server;
 
 pub use algorithms::*;
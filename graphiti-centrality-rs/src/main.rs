@@ -1,4 +1,4 @@
-use graphiti_centrality::models::DatabaseConfig;
+use graphiti_centrality::models::{parse_hosts, DatabaseConfig};
 use graphiti_centrality::server;
 use std::env;
 use tracing::info;
@@ -23,20 +23,25 @@ async fn main() -> anyhow::Result<()> {
     info!("Starting Graphiti Centrality Service");
 
     // Load configuration from environment variables
+    let port = env::var("FALKORDB_PORT")
+        .unwrap_or_else(|_| "6379".to_string())
+        .parse()
+        .unwrap_or(6379);
     let config = DatabaseConfig {
         host: env::var("FALKORDB_HOST").unwrap_or_else(|_| "falkordb".to_string()),
-        port: env::var("FALKORDB_PORT")
-            .unwrap_or_else(|_| "6379".to_string())
-            .parse()
-            .unwrap_or(6379),
+        port,
+        hosts: env::var("FALKORDB_HOSTS")
+            .map(|hosts| parse_hosts(&hosts, port))
+            .unwrap_or_default(),
         graph_name: env::var("GRAPH_NAME").unwrap_or_else(|_| "graphiti_migration".to_string()),
         username: env::var("FALKORDB_USERNAME").ok(),
         password: env::var("FALKORDB_PASSWORD").ok(),
     };
 
     info!(
-        "Connecting to FalkorDB at {}:{}, graph: {}",
-        config.host, config.port, config.graph_name
+        "Connecting to FalkorDB at {}, graph: {}",
+        config.endpoints().join(", "),
+        config.graph_name
     );
 
     // Create application state
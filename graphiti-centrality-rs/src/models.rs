@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Request for PageRank calculation
 #[derive(Debug, Deserialize)]
@@ -9,8 +9,12 @@ pub struct PageRankRequest {
     pub damping_factor: f64,
     #[serde(default = "default_iterations")]
     pub iterations: u32,
+    /// Follow edges only in their stored direction; false treats every edge as bidirectional
+    #[serde(default = "default_directed")]
+    pub directed: bool,
     #[serde(default = "default_store_results")]
     pub store_results: bool,
+    pub property_suffix: Option<String>,
 }
 
 /// Request for degree centrality calculation
@@ -21,6 +25,50 @@ pub struct DegreeRequest {
     pub direction: String,
     #[serde(default = "default_store_results")]
     pub store_results: bool,
+    pub property_suffix: Option<String>,
+}
+
+/// Request for recomputing degree centrality around changed nodes
+#[derive(Debug, Deserialize)]
+pub struct IncrementalDegreeRequest {
+    pub node_uuids: Vec<String>,
+    #[serde(default = "default_direction")]
+    pub direction: String,
+    #[serde(default = "default_store_results")]
+    pub store_results: bool,
+    pub property_suffix: Option<String>,
+}
+
+/// Request for the shortest path between two nodes
+#[derive(Debug, Deserialize)]
+pub struct ShortestPathRequest {
+    pub source_uuid: String,
+    pub target_uuid: String,
+    /// Longest path, in hops, to search for
+    #[serde(default = "default_max_depth")]
+    pub max_depth: u32,
+}
+
+/// Request for the k shortest simple paths between two nodes
+#[derive(Debug, Deserialize)]
+pub struct KShortestPathsRequest {
+    pub source_uuid: String,
+    pub target_uuid: String,
+    #[serde(default = "default_k_paths")]
+    pub k: usize,
+    /// Longest path, in hops, to search for
+    #[serde(default = "default_max_depth")]
+    pub max_depth: u32,
+}
+
+/// Request for neighbor-set similarity: against `target_uuid` when given,
+/// otherwise the `limit` most similar nodes
+#[derive(Debug, Deserialize)]
+pub struct NeighborSimilarityRequest {
+    pub source_uuid: String,
+    pub target_uuid: Option<String>,
+    #[serde(default = "default_similarity_limit")]
+    pub limit: usize,
 }
 
 /// Request for betweenness centrality calculation
@@ -30,6 +78,7 @@ pub struct BetweennessRequest {
     pub sample_size: Option<u32>,
     #[serde(default = "default_store_results")]
     pub store_results: bool,
+    pub property_suffix: Option<String>,
 }
 
 /// Request for all centralities calculation
@@ -38,6 +87,116 @@ pub struct AllCentralitiesRequest {
     pub group_id: Option<String>,
     #[serde(default = "default_store_results")]
     pub store_results: bool,
+    #[serde(default)]
+    pub weights: ImportanceWeights,
+    #[serde(default)]
+    pub betweenness_sampling: BetweennessSampling,
+    /// Score eigenvector centrality from in-edges only; false treats edges as undirected
+    #[serde(default = "default_directed")]
+    pub eigenvector_directed: bool,
+    pub property_suffix: Option<String>,
+    /// Compute and return scores without writing them, even if `store_results` is set
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// When betweenness is approximated from a sample of nodes rather than all of them
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BetweennessSampling {
+    /// Sample once the graph has more nodes than this
+    pub node_threshold: usize,
+    /// Nodes to sample
+    pub sample_size: u32,
+}
+
+impl Default for BetweennessSampling {
+    fn default() -> Self {
+        Self {
+            node_threshold: 100,
+            sample_size: 50,
+        }
+    }
+}
+
+impl BetweennessSampling {
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if self.sample_size == 0 {
+            return Err(crate::error::CentralityError::invalid_parameter(
+                "Betweenness sample_size must be at least 1",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sample size to use for a graph of `node_count` nodes, if it should be sampled
+    pub fn sample_size_for(&self, node_count: usize) -> Option<u32> {
+        (node_count > self.node_threshold).then_some(self.sample_size)
+    }
+}
+
+/// Weights of each metric in the composite importance score
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportanceWeights {
+    pub pagerank: f64,
+    pub eigenvector: f64,
+    pub degree: f64,
+    pub betweenness: f64,
+}
+
+impl Default for ImportanceWeights {
+    fn default() -> Self {
+        Self {
+            pagerank: 0.4,
+            eigenvector: 0.3,
+            degree: 0.2,
+            betweenness: 0.1,
+        }
+    }
+}
+
+impl ImportanceWeights {
+    /// Allowed deviation of the weight sum from 1.0
+    pub const SUM_TOLERANCE: f64 = 0.01;
+
+    /// Weights must be non-negative and sum to ~1.0
+    pub fn validate(&self) -> crate::error::Result<()> {
+        let weights = [
+            self.pagerank,
+            self.eigenvector,
+            self.degree,
+            self.betweenness,
+        ];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err(crate::error::CentralityError::invalid_parameter(
+                "Importance weights must be non-negative numbers",
+            ));
+        }
+        let sum: f64 = weights.iter().sum();
+        if (sum - 1.0).abs() > Self::SUM_TOLERANCE {
+            return Err(crate::error::CentralityError::invalid_parameter(format!(
+                "Importance weights must sum to 1.0, got {}",
+                sum
+            )));
+        }
+        Ok(())
+    }
+
+    /// Composite importance from normalized metric scores, capped at 1.0
+    pub fn importance(
+        &self,
+        pagerank: f64,
+        eigenvector: f64,
+        degree: f64,
+        betweenness: f64,
+    ) -> f64 {
+        (self.pagerank * pagerank
+            + self.eigenvector * eigenvector
+            + self.degree * degree
+            + self.betweenness * betweenness)
+            .min(1.0)
+    }
 }
 
 /// Request for single node centrality calculation
@@ -47,6 +206,10 @@ pub struct SingleNodeRequest {
     pub store_results: bool,
     #[serde(default = "default_metrics")]
     pub metrics: Vec<String>,
+    /// Weights for the `importance` metric
+    #[serde(default)]
+    pub weights: ImportanceWeights,
+    pub property_suffix: Option<String>,
 }
 
 /// Response for single node centrality
@@ -67,11 +230,143 @@ pub struct CentralityResponse {
 }
 
 /// Response for all centralities
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AllCentralitiesResponse {
     pub scores: HashMap<String, HashMap<String, f64>>,
     pub nodes_processed: usize,
     pub execution_time_ms: u128,
+    /// Nodes a dry run would have written scores for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub would_store: Option<usize>,
+    /// Whether betweenness was approximated from a sample of nodes
+    pub betweenness_sampled: bool,
+    /// Nodes actually sampled for betweenness, when it was sampled
+    pub betweenness_sample_size: Option<u32>,
+    /// Whether eigenvector centrality converged within its iteration cap
+    pub eigenvector_converged: bool,
+    /// Metrics that failed, with their errors; the others are still returned
+    pub failed_metrics: BTreeMap<String, String>,
+}
+
+/// Node on a path, in path order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathNode {
+    pub uuid: String,
+    pub name: Option<String>,
+}
+
+/// Relationship on a path, joining consecutive path nodes
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathEdge {
+    pub relationship_type: String,
+    pub source_uuid: String,
+    pub target_uuid: String,
+}
+
+/// An ordered path from source to target; `edges[i]` joins `nodes[i]` and `nodes[i + 1]`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphPath {
+    pub nodes: Vec<PathNode>,
+    pub edges: Vec<PathEdge>,
+    pub length: usize,
+}
+
+/// Response for the shortest path endpoint
+#[derive(Debug, Serialize)]
+pub struct ShortestPathResponse {
+    #[serde(flatten)]
+    pub path: GraphPath,
+    pub execution_time_ms: u128,
+}
+
+/// Response for the k-shortest-paths endpoint
+#[derive(Debug, Serialize)]
+pub struct KShortestPathsResponse {
+    /// Distinct simple paths, shortest first
+    pub paths: Vec<GraphPath>,
+    /// Candidate paths read; reaching the cap means longer paths may be missing
+    pub paths_examined: usize,
+    pub execution_time_ms: u128,
+}
+
+/// How much of its neighborhood a node shares with the source node
+#[derive(Debug, Clone, Serialize)]
+pub struct NeighborSimilarity {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub shared_neighbors: usize,
+    /// Shared neighbors over the union of both neighbor sets
+    pub jaccard: f64,
+    /// Shared neighbors over the smaller neighbor set
+    pub overlap: f64,
+}
+
+/// Response for the neighbor-similarity endpoint
+#[derive(Debug, Serialize)]
+pub struct NeighborSimilarityResponse {
+    pub source_uuid: String,
+    /// Most similar first
+    pub results: Vec<NeighborSimilarity>,
+    pub execution_time_ms: u128,
+}
+
+/// Query for reading back previously stored scores of one metric
+#[derive(Debug, Deserialize)]
+pub struct StoredScoresQuery {
+    pub metric: String,
+    pub group_id: Option<String>,
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+    pub property_suffix: Option<String>,
+}
+
+/// A node's stored score for one metric
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoredScore {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub score: f64,
+}
+
+/// Response for the stored-scores endpoint
+#[derive(Debug, Serialize)]
+pub struct StoredScoresResponse {
+    pub metric: String,
+    /// Node property the scores were read from
+    pub property: String,
+    /// Highest score first
+    pub scores: Vec<StoredScore>,
+    pub execution_time_ms: u128,
+}
+
+/// Per-node scores from `calculate_all_centralities`
+#[derive(Debug, Clone)]
+pub struct AllCentralityScores {
+    pub scores: HashMap<String, HashMap<String, f64>>,
+    /// Nodes actually sampled for betweenness, when it was sampled
+    pub betweenness_sample_size: Option<u32>,
+    pub eigenvector_converged: bool,
+    /// Error message for each metric that failed to calculate
+    pub failed_metrics: BTreeMap<String, String>,
+}
+
+/// Scores from `calculate_eigenvector_centrality`
+#[derive(Debug, Clone)]
+pub struct EigenvectorScores {
+    pub centrality: CentralityScores,
+    pub iterations: u32,
+    /// False when the iteration stopped at its cap without settling
+    pub converged: bool,
+}
+
+/// Normalized degree scores after `update_degree_centrality`
+#[derive(Debug, Clone)]
+pub struct DegreeUpdate {
+    pub scores: CentralityScores,
+    /// Largest raw degree the scores were normalized by
+    pub max_degree: f64,
+    /// Whether every node was recomputed because the max degree changed
+    pub full_recompute: bool,
 }
 
 /// Internal representation of centrality scores
@@ -86,6 +381,8 @@ pub struct CentralityScores {
 pub struct DatabaseConfig {
     pub host: String,
     pub port: u16,
+    /// Candidate `host:port` endpoints tried in order; empty means just `host:port`
+    pub hosts: Vec<String>,
     pub graph_name: String,
     pub username: Option<String>,
     pub password: Option<String>,
@@ -96,6 +393,7 @@ impl Default for DatabaseConfig {
         Self {
             host: "falkordb".to_string(),
             port: 6379,
+            hosts: Vec::new(),
             graph_name: "graphiti_migration".to_string(),
             username: None,
             password: None,
@@ -103,6 +401,34 @@ impl Default for DatabaseConfig {
     }
 }
 
+impl DatabaseConfig {
+    /// Endpoints to try when connecting, in order
+    pub fn endpoints(&self) -> Vec<String> {
+        if self.hosts.is_empty() {
+            vec![format!("{}:{}", self.host, self.port)]
+        } else {
+            self.hosts.clone()
+        }
+    }
+}
+
+/// Parse a comma-separated `FALKORDB_HOSTS` value into `host:port` endpoints,
+/// using `default_port` for entries without one
+pub fn parse_hosts(hosts: &str, default_port: u16) -> Vec<String> {
+    hosts
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            if entry.contains(':') {
+                entry.to_string()
+            } else {
+                format!("{}:{}", entry, default_port)
+            }
+        })
+        .collect()
+}
+
 // Default values for serde
 fn default_damping_factor() -> f64 {
     0.85
@@ -116,6 +442,26 @@ fn default_direction() -> String {
     "both".to_string()
 }
 
+fn default_directed() -> bool {
+    true
+}
+
+fn default_k_paths() -> usize {
+    3
+}
+
+fn default_similarity_limit() -> usize {
+    10
+}
+
+fn default_top_k() -> usize {
+    100
+}
+
+fn default_max_depth() -> u32 {
+    6
+}
+
 fn default_store_results() -> bool {
     true
 }
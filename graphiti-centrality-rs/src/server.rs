@@ -1,33 +1,47 @@
 use crate::algorithms::{
     calculate_all_centralities, calculate_betweenness_centrality, calculate_degree_centrality,
-    calculate_pagerank,
+    calculate_pagerank, calculate_single_node_centralities, k_shortest_paths,
+    most_similar_by_neighbors, neighbor_similarity, shortest_path, stored_score_map, stored_scores,
+    update_degree_centrality, validate_pagerank_params,
 };
-use crate::client::FalkorClient;
+use crate::client::{score_property_name, validate_property_suffix, FalkorClient};
 use crate::error::{CentralityError, Result};
+use crate::jobs::JobStore;
 use crate::models::{
-    AllCentralitiesRequest, AllCentralitiesResponse, BetweennessRequest, CentralityResponse,
-    DatabaseConfig, DegreeRequest, PageRankRequest, SingleNodeRequest, SingleNodeResponse,
+    AllCentralitiesRequest, AllCentralitiesResponse, AllCentralityScores, BetweennessRequest,
+    CentralityResponse, DatabaseConfig, DegreeRequest, IncrementalDegreeRequest,
+    KShortestPathsRequest, KShortestPathsResponse, NeighborSimilarityRequest,
+    NeighborSimilarityResponse, PageRankRequest, ShortestPathRequest, ShortestPathResponse,
+    SingleNodeRequest, SingleNodeResponse, StoredScoresQuery, StoredScoresResponse,
 };
+use crate::request_id::propagate_request_id;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
+    middleware,
     response::{IntoResponse, Json},
     routing::{get, post},
     Router,
 };
 use serde_json::json;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tower_http::cors::CorsLayer;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 /// Application state containing the FalkorDB client
 #[derive(Clone)]
 pub struct AppState {
     client: Arc<FalkorClient>,
+    jobs: JobStore,
+    /// Max degree the stored degree scores were last normalized by, per
+    /// direction and property suffix
+    degree_max: Arc<Mutex<DegreeMaxes>>,
 }
 
+type DegreeMaxes = HashMap<(String, Option<String>), f64>;
+
 impl AppState {
     pub async fn new(config: DatabaseConfig) -> Result<Self> {
         let client = FalkorClient::new(config).await?;
@@ -35,6 +49,8 @@ impl AppState {
 
         Ok(Self {
             client: Arc::new(client),
+            jobs: JobStore::new(),
+            degree_max: Arc::default(),
         })
     }
 }
@@ -46,10 +62,27 @@ pub fn create_router(state: AppState) -> Router {
         .route("/stats", get(get_stats))
         .route("/centrality/pagerank", post(pagerank_endpoint))
         .route("/centrality/degree", post(degree_endpoint))
+        .route(
+            "/centrality/degree/incremental",
+            post(incremental_degree_endpoint),
+        )
         .route("/centrality/betweenness", post(betweenness_endpoint))
         .route("/centrality/all", post(all_centralities_endpoint))
+        .route(
+            "/centrality/all/async",
+            post(all_centralities_async_endpoint),
+        )
+        .route("/centrality/jobs/:id", get(job_status_endpoint))
         .route("/centrality/node/:uuid", post(single_node_endpoint))
+        .route("/centrality/stored", get(stored_scores_endpoint))
+        .route("/path/shortest", post(shortest_path_endpoint))
+        .route("/path/k-shortest", post(k_shortest_paths_endpoint))
+        .route(
+            "/centrality/similarity/neighbors",
+            post(neighbor_similarity_endpoint),
+        )
         .layer(CorsLayer::permissive())
+        .layer(middleware::from_fn(propagate_request_id))
         .with_state(state)
 }
 
@@ -89,11 +122,18 @@ async fn pagerank_endpoint(
 ) -> impl IntoResponse {
     let start = Instant::now();
 
+    if let Err(e) = validate_property_suffix(request.property_suffix.as_deref())
+        .and_then(|_| validate_pagerank_params(request.damping_factor, request.iterations))
+    {
+        return handle_error(e).into_response();
+    }
+
     match calculate_pagerank(
         &state.client,
         request.group_id.as_deref(),
         request.damping_factor,
         request.iterations,
+        request.directed,
     )
     .await
     {
@@ -114,7 +154,7 @@ async fn pagerank_endpoint(
 
                 if let Err(e) = state
                     .client
-                    .store_centrality_scores(&formatted_scores)
+                    .store_centrality_scores(&formatted_scores, request.property_suffix.as_deref())
                     .await
                 {
                     error!("Failed to store PageRank scores: {}", e);
@@ -143,6 +183,10 @@ async fn degree_endpoint(
 ) -> impl IntoResponse {
     let start = Instant::now();
 
+    if let Err(e) = validate_property_suffix(request.property_suffix.as_deref()) {
+        return handle_error(e).into_response();
+    }
+
     match calculate_degree_centrality(
         &state.client,
         &request.direction,
@@ -175,10 +219,20 @@ async fn degree_endpoint(
 
                 if let Err(e) = state
                     .client
-                    .store_centrality_scores(&formatted_scores)
+                    .store_centrality_scores(&formatted_scores, request.property_suffix.as_deref())
                     .await
                 {
                     error!("Failed to store degree centrality scores: {}", e);
+                } else {
+                    // A group's scores are on that group's scale, so the next
+                    // incremental update has to recompute everything
+                    let key = (request.direction.clone(), request.property_suffix.clone());
+                    let mut degree_max = state.degree_max.lock().unwrap();
+                    if request.group_id.is_none() {
+                        degree_max.insert(key, max_degree);
+                    } else {
+                        degree_max.remove(&key);
+                    }
                 }
             }
 
@@ -197,6 +251,72 @@ async fn degree_endpoint(
     }
 }
 
+/// Incremental degree centrality endpoint: recompute only the changed nodes and
+/// their neighbors and merge them into the stored scores
+async fn incremental_degree_endpoint(
+    State(state): State<AppState>,
+    Json(request): Json<IncrementalDegreeRequest>,
+) -> impl IntoResponse {
+    let start = Instant::now();
+
+    if let Err(e) = validate_property_suffix(request.property_suffix.as_deref()) {
+        return handle_error(e).into_response();
+    }
+
+    let key = (request.direction.clone(), request.property_suffix.clone());
+    let previous_max = state.degree_max.lock().unwrap().get(&key).copied();
+    let update = match update_degree_centrality(
+        &state.client,
+        &request.node_uuids,
+        &request.direction,
+        previous_max,
+    )
+    .await
+    {
+        Ok(update) => update,
+        Err(e) => {
+            error!("Incremental degree calculation failed: {}", e);
+            return handle_error(e).into_response();
+        }
+    };
+    let result = update.scores;
+    let scores = result.scores;
+
+    if request.store_results {
+        let formatted_scores: HashMap<String, HashMap<String, f64>> = scores
+            .iter()
+            .map(|(uuid, score)| {
+                (
+                    uuid.clone(),
+                    HashMap::from([("degree".to_string(), *score)]),
+                )
+            })
+            .collect();
+
+        if let Err(e) = state
+            .client
+            .store_centrality_scores(&formatted_scores, request.property_suffix.as_deref())
+            .await
+        {
+            error!("Failed to store incremental degree scores: {}", e);
+        } else {
+            state
+                .degree_max
+                .lock()
+                .unwrap()
+                .insert(key, update.max_degree);
+        }
+    }
+
+    Json(CentralityResponse {
+        scores,
+        metric: format!("degree_{}", request.direction),
+        nodes_processed: result.nodes_processed,
+        execution_time_ms: start.elapsed().as_millis(),
+    })
+    .into_response()
+}
+
 /// Betweenness centrality endpoint
 async fn betweenness_endpoint(
     State(state): State<AppState>,
@@ -204,6 +324,10 @@ async fn betweenness_endpoint(
 ) -> impl IntoResponse {
     let start = Instant::now();
 
+    if let Err(e) = validate_property_suffix(request.property_suffix.as_deref()) {
+        return handle_error(e).into_response();
+    }
+
     match calculate_betweenness_centrality(
         &state.client,
         request.group_id.as_deref(),
@@ -228,7 +352,7 @@ async fn betweenness_endpoint(
 
                 if let Err(e) = state
                     .client
-                    .store_centrality_scores(&formatted_scores)
+                    .store_centrality_scores(&formatted_scores, request.property_suffix.as_deref())
                     .await
                 {
                     error!("Failed to store betweenness centrality scores: {}", e);
@@ -255,31 +379,267 @@ async fn all_centralities_endpoint(
     State(state): State<AppState>,
     Json(request): Json<AllCentralitiesRequest>,
 ) -> impl IntoResponse {
+    if let Err(e) = validate_property_suffix(request.property_suffix.as_deref()) {
+        return handle_error(e).into_response();
+    }
+
+    match run_all_centralities(&state.client, &request).await {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => {
+            error!("All centralities calculation failed: {}", e);
+            handle_error(e).into_response()
+        }
+    }
+}
+
+/// Start an all-centralities calculation in the background and return its job id
+async fn all_centralities_async_endpoint(
+    State(state): State<AppState>,
+    Json(request): Json<AllCentralitiesRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = validate_property_suffix(request.property_suffix.as_deref()) {
+        return handle_error(e).into_response();
+    }
+
+    let client = state.client.clone();
+    let job_id = state
+        .jobs
+        .spawn(async move { run_all_centralities(&client, &request).await });
+    info!("Started all centralities job {}", job_id);
+
+    (StatusCode::ACCEPTED, Json(json!({ "job_id": job_id }))).into_response()
+}
+
+/// Poll a background job
+async fn job_status_endpoint(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> impl IntoResponse {
+    match state.jobs.get(&job_id) {
+        Some(record) => Json(record).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "Job not found",
+                "details": format!("No job with id {job_id}")
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Calculate all centralities and store them if requested, unless this is a dry run
+pub async fn run_all_centralities(
+    client: &FalkorClient,
+    request: &AllCentralitiesRequest,
+) -> Result<AllCentralitiesResponse> {
     let start = Instant::now();
 
-    match calculate_all_centralities(&state.client, request.group_id.as_deref()).await {
-        Ok(result) => {
-            let execution_time_ms = start.elapsed().as_millis();
-            let nodes_processed = result.len();
+    // Seed eigenvector centrality with the last stored run; a small change
+    // since then converges in far fewer iterations
+    let warm_start = match stored_score_map(
+        client,
+        "eigenvector",
+        request.group_id.as_deref(),
+        request.property_suffix.as_deref(),
+    )
+    .await
+    {
+        Ok(scores) if !scores.is_empty() => Some(scores),
+        Ok(_) => None,
+        Err(e) => {
+            warn!(
+                "Failed to load stored eigenvector scores, starting cold: {}",
+                e
+            );
+            None
+        }
+    };
 
-            // Store results if requested
-            if request.store_results {
-                if let Err(e) = state.client.store_centrality_scores(&result).await {
-                    error!("Failed to store all centrality scores: {}", e);
-                } else {
-                    info!("✅ Centrality scores stored successfully. Visualization server should reload data from http://localhost:3000/api/data/reload");
+    let AllCentralityScores {
+        scores: result,
+        betweenness_sample_size,
+        eigenvector_converged,
+        failed_metrics,
+    } = calculate_all_centralities(
+        client,
+        request.group_id.as_deref(),
+        &request.weights,
+        &request.betweenness_sampling,
+        request.eigenvector_directed,
+        warm_start.as_ref(),
+    )
+    .await?;
+    let execution_time_ms = start.elapsed().as_millis();
+    let nodes_processed = result.len();
+    let mut would_store = None;
+
+    if request.store_results && request.dry_run {
+        info!(
+            "Dry run: skipping store of centrality scores for {} nodes",
+            nodes_processed
+        );
+        would_store = Some(nodes_processed);
+    } else if request.store_results {
+        if let Err(e) = client
+            .store_centrality_scores(&result, request.property_suffix.as_deref())
+            .await
+        {
+            error!("Failed to store all centrality scores: {}", e);
+            return Err(e);
+        }
+        info!("✅ Centrality scores stored successfully. Visualization server should reload data from http://localhost:3000/api/data/reload");
+    }
+
+    Ok(AllCentralitiesResponse {
+        scores: result,
+        nodes_processed,
+        execution_time_ms,
+        would_store,
+        betweenness_sampled: betweenness_sample_size.is_some(),
+        betweenness_sample_size,
+        eigenvector_converged,
+        failed_metrics,
+    })
+}
+
+/// Shortest path between two nodes, for explaining how they're related
+async fn shortest_path_endpoint(
+    State(state): State<AppState>,
+    Json(request): Json<ShortestPathRequest>,
+) -> impl IntoResponse {
+    let start = Instant::now();
+
+    match shortest_path(
+        &state.client,
+        &request.source_uuid,
+        &request.target_uuid,
+        request.max_depth,
+    )
+    .await
+    {
+        Ok(Some(path)) => Json(ShortestPathResponse {
+            path,
+            execution_time_ms: start.elapsed().as_millis(),
+        })
+        .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "No path found",
+                "details": format!(
+                    "No path of at most {} hops between {} and {}",
+                    request.max_depth, request.source_uuid, request.target_uuid
+                )
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Shortest path query failed: {}", e);
+            handle_error(e).into_response()
+        }
+    }
+}
+
+/// Up to k alternative paths between two nodes, shortest first
+async fn k_shortest_paths_endpoint(
+    State(state): State<AppState>,
+    Json(request): Json<KShortestPathsRequest>,
+) -> impl IntoResponse {
+    let start = Instant::now();
+
+    match k_shortest_paths(
+        &state.client,
+        &request.source_uuid,
+        &request.target_uuid,
+        request.k,
+        request.max_depth,
+    )
+    .await
+    {
+        Ok((paths, paths_examined)) => Json(KShortestPathsResponse {
+            paths,
+            paths_examined,
+            execution_time_ms: start.elapsed().as_millis(),
+        })
+        .into_response(),
+        Err(e) => {
+            error!("k-shortest paths query failed: {}", e);
+            handle_error(e).into_response()
+        }
+    }
+}
+
+/// Structural similarity by shared neighbors, for a pair of nodes or the
+/// top matches of one node
+async fn neighbor_similarity_endpoint(
+    State(state): State<AppState>,
+    Json(request): Json<NeighborSimilarityRequest>,
+) -> impl IntoResponse {
+    let start = Instant::now();
+
+    let results = match &request.target_uuid {
+        Some(target_uuid) => {
+            match neighbor_similarity(&state.client, &request.source_uuid, target_uuid).await {
+                Ok(Some(similarity)) => Ok(vec![similarity]),
+                Ok(None) => {
+                    return (
+                        StatusCode::NOT_FOUND,
+                        Json(json!({
+                            "error": "Node not found",
+                            "details": format!(
+                                "No node with uuid {} or {}",
+                                request.source_uuid, target_uuid
+                            )
+                        })),
+                    )
+                        .into_response()
                 }
+                Err(e) => Err(e),
             }
+        }
+        None => most_similar_by_neighbors(&state.client, &request.source_uuid, request.limit).await,
+    };
 
-            Json(AllCentralitiesResponse {
-                scores: result,
-                nodes_processed,
-                execution_time_ms,
-            })
-            .into_response()
+    match results {
+        Ok(results) => Json(NeighborSimilarityResponse {
+            source_uuid: request.source_uuid,
+            results,
+            execution_time_ms: start.elapsed().as_millis(),
+        })
+        .into_response(),
+        Err(e) => {
+            error!("Neighbor similarity query failed: {}", e);
+            handle_error(e).into_response()
         }
+    }
+}
+
+/// Read back stored scores for one metric without recomputing
+async fn stored_scores_endpoint(
+    State(state): State<AppState>,
+    Query(query): Query<StoredScoresQuery>,
+) -> impl IntoResponse {
+    let start = Instant::now();
+
+    match stored_scores(
+        &state.client,
+        &query.metric,
+        query.group_id.as_deref(),
+        query.top_k,
+        query.property_suffix.as_deref(),
+    )
+    .await
+    {
+        Ok(scores) => Json(StoredScoresResponse {
+            property: score_property_name(&query.metric, query.property_suffix.as_deref()),
+            metric: query.metric,
+            scores,
+            execution_time_ms: start.elapsed().as_millis(),
+        })
+        .into_response(),
         Err(e) => {
-            error!("All centralities calculation failed: {}", e);
+            error!("Stored scores query failed: {}", e);
             handle_error(e).into_response()
         }
     }
@@ -323,57 +683,35 @@ async fn single_node_endpoint(
 ) -> impl IntoResponse {
     let start = Instant::now();
 
-    // Calculate requested metrics for the single node
-    let mut metrics = HashMap::new();
-
-    // Calculate degree centrality if requested
-    if request.metrics.contains(&"degree".to_string()) {
-        match calculate_single_node_degree(&state.client, &node_uuid).await {
-            Ok(degree) => {
-                metrics.insert("degree".to_string(), degree);
-            }
-            Err(e) => {
-                error!(
-                    "Failed to calculate degree centrality for {}: {}",
-                    node_uuid, e
-                );
-                return handle_error(e).into_response();
-            }
-        }
-    }
-
-    // Calculate PageRank if requested (simplified local calculation)
-    if request.metrics.contains(&"pagerank".to_string()) {
-        match calculate_single_node_pagerank(&state.client, &node_uuid).await {
-            Ok(pagerank) => {
-                metrics.insert("pagerank".to_string(), pagerank);
-            }
-            Err(e) => {
-                error!("Failed to calculate PageRank for {}: {}", node_uuid, e);
-                return handle_error(e).into_response();
-            }
-        }
+    if let Err(e) = validate_property_suffix(request.property_suffix.as_deref()) {
+        return handle_error(e).into_response();
     }
 
-    // Calculate betweenness if requested (simplified local calculation)
-    if request.metrics.contains(&"betweenness".to_string()) {
-        match calculate_single_node_betweenness(&state.client, &node_uuid).await {
-            Ok(betweenness) => {
-                metrics.insert("betweenness".to_string(), betweenness);
-            }
-            Err(e) => {
-                error!("Failed to calculate betweenness for {}: {}", node_uuid, e);
-                return handle_error(e).into_response();
-            }
+    let metrics = match calculate_single_node_centralities(
+        &state.client,
+        &node_uuid,
+        &request.metrics,
+        &request.weights,
+    )
+    .await
+    {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            error!("Failed to calculate centrality for {}: {}", node_uuid, e);
+            return handle_error(e).into_response();
         }
-    }
+    };
 
     // Store results if requested
     if request.store_results && !metrics.is_empty() {
         let mut scores_map = HashMap::new();
         scores_map.insert(node_uuid.clone(), metrics.clone());
 
-        if let Err(e) = state.client.store_centrality_scores(&scores_map).await {
+        if let Err(e) = state
+            .client
+            .store_centrality_scores(&scores_map, request.property_suffix.as_deref())
+            .await
+        {
             error!("Failed to store centrality scores for {}: {}", node_uuid, e);
         }
     }
@@ -387,44 +725,3 @@ async fn single_node_endpoint(
     })
     .into_response()
 }
-
-// Helper functions for single node calculations
-async fn calculate_single_node_degree(client: &FalkorClient, node_uuid: &str) -> Result<f64> {
-    let query = format!(
-        r#"
-        MATCH (n {{uuid: '{}'}})
-        OPTIONAL MATCH (n)-[r]-(m)
-        RETURN COUNT(DISTINCT m) as degree
-        "#,
-        node_uuid
-    );
-
-    let result = client.execute_query(&query, None).await?;
-
-    if let Some(row) = result.first() {
-        if let Some(degree_value) = row.get("degree") {
-            if let Some(degree) = crate::client::falkor_value_to_i64(degree_value) {
-                // Normalize degree (simple normalization by dividing by 10)
-                return Ok(degree as f64 / 10.0);
-            }
-        }
-    }
-
-    Ok(0.0)
-}
-
-async fn calculate_single_node_pagerank(client: &FalkorClient, node_uuid: &str) -> Result<f64> {
-    // Simplified PageRank calculation based on degree
-    let degree = calculate_single_node_degree(client, node_uuid).await?;
-
-    // Simple formula: base pagerank + scaled degree contribution
-    Ok(0.15 + 0.85 * (degree / 100.0).min(1.0))
-}
-
-async fn calculate_single_node_betweenness(client: &FalkorClient, node_uuid: &str) -> Result<f64> {
-    // Simplified betweenness calculation based on degree
-    let degree = calculate_single_node_degree(client, node_uuid).await?;
-
-    // Simple approximation
-    Ok((degree / 20.0).min(1.0))
-}
@@ -1,25 +1,44 @@
+use falkordb::FalkorValue;
 use graphiti_centrality::{
-    algorithms::{calculate_all_centralities, calculate_degree_centrality, calculate_pagerank},
-    client::FalkorClient,
-    models::DatabaseConfig,
+    algorithms::{
+        calculate_all_centralities, calculate_degree_centrality, calculate_incremental_degree,
+        calculate_pagerank, calculate_single_node_centralities, combine_centralities,
+        ego_betweenness, eigenvector_from_edges, eigenvector_from_in_neighbors, k_shortest_paths,
+        most_similar_by_neighbors, neighbor_similarity, pagerank_from_edges, personalized_pagerank,
+        shortest_path, stored_score_map, stored_scores, update_degree_centrality,
+        validate_eigenvector_params, validate_pagerank_params, MAX_ITERATIONS,
+    },
+    client::{falkor_value_to_f64, falkor_value_to_i64, score_property_name, FalkorClient},
+    jobs::{JobStatus, JobStore},
+    models::{
+        parse_hosts, AllCentralitiesRequest, AllCentralitiesResponse, BetweennessSampling,
+        CentralityScores, DatabaseConfig, ImportanceWeights,
+    },
+    request_id::{current_request_id, propagate_request_id, REQUEST_ID_HEADER},
+    server::run_all_centralities,
+    CentralityError,
 };
+use std::collections::{BTreeMap, HashMap};
 use std::env;
-use tokio_test;
+use tokio_test::assert_ok;
 
 /// Create a test client for integration tests
-async fn create_test_client() -> FalkorClient {
-    let config = DatabaseConfig {
+fn test_config() -> DatabaseConfig {
+    DatabaseConfig {
         host: env::var("FALKORDB_HOST").unwrap_or_else(|_| "falkordb".to_string()),
         port: env::var("FALKORDB_PORT")
             .unwrap_or_else(|_| "6379".to_string())
             .parse()
             .unwrap_or(6379),
+        hosts: Vec::new(),
         graph_name: env::var("GRAPH_NAME").unwrap_or_else(|_| "graphiti_migration".to_string()),
         username: env::var("FALKORDB_USERNAME").ok(),
         password: env::var("FALKORDB_PASSWORD").ok(),
-    };
+    }
+}
 
-    FalkorClient::new(config)
+async fn create_test_client() -> FalkorClient {
+    FalkorClient::new(test_config())
         .await
         .expect("Failed to create test client")
 }
@@ -33,6 +52,27 @@ async fn test_connection() {
         .expect("Database connection failed");
 }
 
+#[tokio::test]
+#[ignore] // Requires a running FalkorDB
+async fn test_connection_falls_back_to_next_host() {
+    let mut config = test_config();
+    let good_host = format!("{}:{}", config.host, config.port);
+    config.hosts = vec!["127.0.0.1:1".to_string(), good_host];
+
+    // `new` only returns a client whose host passed a connection test
+    FalkorClient::new(config)
+        .await
+        .expect("Should connect through the fallback host");
+}
+
+#[test]
+fn test_parse_hosts() {
+    assert_eq!(
+        parse_hosts("primary, replica:6380,", 6379),
+        vec!["primary:6379", "replica:6380"]
+    );
+}
+
 #[tokio::test]
 async fn test_graph_stats() {
     let client = create_test_client().await;
@@ -58,7 +98,7 @@ async fn test_pagerank_calculation() {
     let client = create_test_client().await;
 
     let start = std::time::Instant::now();
-    let result = calculate_pagerank(&client, None, 0.85, 20)
+    let result = calculate_pagerank(&client, None, 0.85, 20, true)
         .await
         .expect("PageRank calculation failed");
     let duration = start.elapsed();
@@ -129,14 +169,153 @@ async fn test_degree_centrality_calculation() {
     }
 }
 
+#[tokio::test]
+async fn test_incremental_degree_matches_full_recompute() {
+    let client = create_test_client().await;
+    let group_id = format!("incremental-degree-test-{}", std::process::id());
+
+    // hub - a, hub - b, with only the hub reported as changed
+    client
+        .execute_query(
+            &format!(
+                "CREATE (h:IncrementalDegreeTest {{uuid: '{0}-hub', group_id: '{0}'}}), \
+                 (a:IncrementalDegreeTest {{uuid: '{0}-a', group_id: '{0}'}}), \
+                 (b:IncrementalDegreeTest {{uuid: '{0}-b', group_id: '{0}'}}), \
+                 (h)-[:RELATES_TO]->(a), (b)-[:RELATES_TO]->(h)",
+                group_id
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    let incremental =
+        calculate_incremental_degree(&client, &[format!("{}-hub", group_id)], "both").await;
+    let full = calculate_degree_centrality(&client, "both", Some(&group_id)).await;
+
+    client
+        .execute_query("MATCH (n:IncrementalDegreeTest) DETACH DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    let incremental = incremental.expect("Incremental degree calculation failed");
+    let full = full.expect("Full degree calculation failed");
+
+    assert_eq!(incremental.nodes_processed, 3, "Hub and both neighbors");
+    for (uuid, degree) in &incremental.scores {
+        assert_eq!(
+            Some(degree),
+            full.scores.get(uuid),
+            "Degree mismatch for {}",
+            uuid
+        );
+    }
+    assert_eq!(incremental.scores[&format!("{}-hub", group_id)], 2.0);
+
+    assert!(calculate_incremental_degree(&client, &[], "both")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_degree_update_recomputes_all_when_max_changes() {
+    let client = create_test_client().await;
+    let group_id = format!("degree-update-test-{}", std::process::id());
+    let hub = format!("{}-hub", group_id);
+    let changed = vec![hub.clone()];
+
+    // a - hub - b, then give the hub enough new edges to become the max
+    client
+        .execute_query(
+            &format!(
+                "CREATE (h:DegreeUpdateTest {{uuid: '{0}-hub', group_id: '{0}'}}), \
+                 (a:DegreeUpdateTest {{uuid: '{0}-a', group_id: '{0}'}}), \
+                 (b:DegreeUpdateTest {{uuid: '{0}-b', group_id: '{0}'}}), \
+                 (h)-[:RELATES_TO]->(a), (b)-[:RELATES_TO]->(h)",
+                group_id
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+    let before = update_degree_centrality(&client, &changed, "both", None).await;
+    let unchanged = match &before {
+        Ok(before) => {
+            update_degree_centrality(&client, &changed, "both", Some(before.max_degree)).await
+        }
+        Err(_) => Err(CentralityError::NoNodesFound),
+    };
+    let mut create_edges = String::from("MATCH (h:DegreeUpdateTest {uuid: $hub}) ");
+    let previous_max = before.as_ref().ok().map(|before| before.max_degree);
+    for i in 0..previous_max.unwrap_or(0.0) as usize + 1 {
+        create_edges.push_str(&format!(
+            "CREATE (h)-[:RELATES_TO]->(:DegreeUpdateTest {{uuid: '{0}-leaf{1}', group_id: '{0}'}}) ",
+            group_id, i
+        ));
+    }
+    client
+        .execute_query(
+            &create_edges,
+            Some(HashMap::from([(
+                "hub".to_string(),
+                FalkorValue::String(hub.clone()),
+            )])),
+        )
+        .await
+        .expect("Failed to add hub edges");
+
+    let after = update_degree_centrality(&client, &changed, "both", previous_max).await;
+    let full = calculate_degree_centrality(&client, "both", None).await;
+
+    client
+        .execute_query("MATCH (n:DegreeUpdateTest) DETACH DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    let before = before.expect("Initial degree update failed");
+    assert!(before.full_recompute, "No previous max to build on");
+    let unchanged = unchanged.expect("Unchanged degree update failed");
+    assert!(
+        !unchanged.full_recompute,
+        "Same max, so only the hub is recomputed"
+    );
+    assert_eq!(unchanged.scores.nodes_processed, 3);
+
+    let after = after.expect("Degree update failed");
+    let full = full.expect("Full degree calculation failed");
+    assert!(after.full_recompute, "Max degree changed");
+    assert_eq!(after.max_degree, previous_max.unwrap() + 3.0);
+    assert_eq!(after.scores.scores.len(), full.scores.len());
+    for (uuid, degree) in &full.scores {
+        assert_eq!(
+            after.scores.scores.get(uuid),
+            Some(&(degree / after.max_degree)),
+            "Degree mismatch for {}",
+            uuid
+        );
+    }
+    // a is untouched since the first update but still moves onto the new scale
+    let a = format!("{}-a", group_id);
+    assert_eq!(after.scores.scores[&a], 1.0 / after.max_degree);
+    assert_ne!(after.scores.scores[&a], before.scores.scores[&a]);
+}
+
 #[tokio::test]
 async fn test_all_centralities_calculation() {
     let client = create_test_client().await;
 
     let start = std::time::Instant::now();
-    let result = calculate_all_centralities(&client, None)
-        .await
-        .expect("All centralities calculation failed");
+    let result = calculate_all_centralities(
+        &client,
+        None,
+        &ImportanceWeights::default(),
+        &BetweennessSampling::default(),
+        true,
+        None,
+    )
+    .await
+    .expect("All centralities calculation failed")
+    .scores;
     let duration = start.elapsed();
 
     println!(
@@ -189,13 +368,1017 @@ async fn test_all_centralities_calculation() {
     }
 }
 
+#[tokio::test]
+async fn test_all_centralities_reports_betweenness_sampling() {
+    let client = create_test_client().await;
+    let group_id = format!("sampling-test-{}", std::process::id());
+
+    // A 120-node chain, over the 100-node sampling threshold
+    client
+        .execute_query(
+            &format!(
+                "UNWIND range(0, 119) AS i
+                 CREATE (:SamplingTest {{uuid: '{0}-' + toString(i), group_id: '{0}', idx: i}})",
+                group_id
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+    client
+        .execute_query(
+            "MATCH (a:SamplingTest), (b:SamplingTest) WHERE b.idx = a.idx + 1
+             CREATE (a)-[:RELATES_TO]->(b)",
+            None,
+        )
+        .await
+        .expect("Failed to create test edges");
+
+    let request = AllCentralitiesRequest {
+        group_id: Some(group_id.clone()),
+        store_results: false,
+        weights: ImportanceWeights::default(),
+        betweenness_sampling: BetweennessSampling {
+            node_threshold: 100,
+            sample_size: 20,
+        },
+        eigenvector_directed: true,
+        property_suffix: None,
+        dry_run: false,
+    };
+    let response = run_all_centralities(&client, &request).await;
+
+    client
+        .execute_query("MATCH (n:SamplingTest) DETACH DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    let response = response.expect("All centralities calculation failed");
+    assert!(response.betweenness_sampled);
+    // Every 6th of the 120 group nodes
+    assert_eq!(response.betweenness_sample_size, Some(20));
+}
+
+#[tokio::test]
+async fn test_store_centrality_scores_batched() {
+    let client = create_test_client().await;
+    let prefix = format!("store-batch-test-{}", std::process::id());
+    let node_count = 2000;
+
+    client
+        .execute_query(
+            &format!(
+                "UNWIND range(0, {}) AS i CREATE (:StoreBatchTest {{uuid: '{}-' + toString(i)}})",
+                node_count - 1,
+                prefix
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    let scores: HashMap<String, HashMap<String, f64>> = (0..node_count)
+        .map(|i| {
+            let value = i as f64 / node_count as f64;
+            let node_scores = HashMap::from([
+                ("pagerank".to_string(), value),
+                ("degree".to_string(), value),
+                ("importance".to_string(), value),
+            ]);
+            (format!("{}-{}", prefix, i), node_scores)
+        })
+        .collect();
+
+    let start = std::time::Instant::now();
+    let store_result = client.store_centrality_scores(&scores, None).await;
+    println!("Stored {} scores in {:?}", scores.len(), start.elapsed());
+
+    let stored = client
+        .execute_query(
+            "MATCH (n:StoreBatchTest) WHERE n.uuid STARTS WITH $prefix AND n.pagerank_centrality IS NOT NULL \
+             RETURN count(n) AS count, sum(n.importance_score) AS total",
+            Some(HashMap::from([(
+                "prefix".to_string(),
+                FalkorValue::String(format!("{}-", prefix)),
+            )])),
+        )
+        .await;
+
+    client
+        .execute_query("MATCH (n:StoreBatchTest) DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    store_result.expect("Storing scores failed");
+    let stored = stored.expect("Failed to read back stored scores");
+    let record = stored.first().expect("Count query returned no rows");
+    assert_eq!(
+        record.get("count").and_then(falkor_value_to_i64),
+        Some(node_count as i64),
+        "All scores should be stored"
+    );
+
+    let expected_total: f64 = scores.values().map(|s| s["importance"]).sum();
+    let total = record.get("total").and_then(falkor_value_to_f64).unwrap();
+    assert!((total - expected_total).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn test_store_centrality_scores_one_round_trip_per_batch() {
+    let client = create_test_client().await;
+    let prefix = format!("store-round-trip-test-{}", std::process::id());
+    // Not a multiple of the batch size, so the last batch is a partial one
+    let node_count: usize = 2500;
+
+    client
+        .execute_query(
+            &format!(
+                "UNWIND range(0, {}) AS i CREATE (:StoreRoundTripTest {{uuid: '{}-' + toString(i)}})",
+                node_count - 1,
+                prefix
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    let scores: HashMap<String, HashMap<String, f64>> = (0..node_count)
+        .map(|i| {
+            let node_scores = HashMap::from([("pagerank".to_string(), 0.5)]);
+            (format!("{}-{}", prefix, i), node_scores)
+        })
+        .collect();
+
+    let round_trips = client.store_centrality_scores(&scores, None).await;
+
+    client
+        .execute_query("MATCH (n:StoreRoundTripTest) DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    assert_eq!(
+        round_trips.expect("Storing scores failed"),
+        node_count.div_ceil(1000),
+        "Each batch of 1000 scores should be one UNWIND query"
+    );
+}
+
+#[tokio::test]
+async fn test_store_centrality_scores_merges_per_metric() {
+    let client = create_test_client().await;
+    let prefix = format!("store-merge-test-{}", std::process::id());
+
+    client
+        .execute_query(
+            &format!(
+                "CREATE (:StoreMergeTest {{uuid: '{0}-a', pagerank_centrality: 0.1, degree_centrality: 0.5}}), \
+                 (:StoreMergeTest {{uuid: '{0}-b', pagerank_centrality: 0.1, degree_centrality: 0.5}})",
+                prefix
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    // Only `b` has a new degree score, so `a` keeps its stored one
+    let scores = HashMap::from([
+        (
+            format!("{}-a", prefix),
+            HashMap::from([("pagerank".to_string(), 0.9)]),
+        ),
+        (
+            format!("{}-b", prefix),
+            HashMap::from([("pagerank".to_string(), 0.8), ("degree".to_string(), 0.2)]),
+        ),
+    ]);
+    let store_result = client.store_centrality_scores(&scores, None).await;
+
+    let stored = client
+        .execute_query(
+            "MATCH (n:StoreMergeTest) RETURN n.uuid AS uuid, \
+             n.pagerank_centrality AS pagerank, n.degree_centrality AS degree",
+            None,
+        )
+        .await;
+
+    client
+        .execute_query("MATCH (n:StoreMergeTest) DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    assert_ok!(store_result);
+    let stored: HashMap<String, (Option<f64>, Option<f64>)> = assert_ok!(stored)
+        .into_iter()
+        .map(|record| {
+            let uuid = match record.get("uuid") {
+                Some(FalkorValue::String(uuid)) => uuid.clone(),
+                other => panic!("unexpected uuid {:?}", other),
+            };
+            let score = |key: &str| record.get(key).and_then(falkor_value_to_f64);
+            (uuid, (score("pagerank"), score("degree")))
+        })
+        .collect();
+    assert_eq!(
+        stored[&format!("{}-a", prefix)],
+        (Some(0.9), Some(0.5)),
+        "Metrics missing from a node's scores should keep their stored value"
+    );
+    assert_eq!(stored[&format!("{}-b", prefix)], (Some(0.8), Some(0.2)));
+}
+
+#[tokio::test]
+async fn test_store_centrality_scores_with_property_suffix() {
+    let client = create_test_client().await;
+    let uuid = format!("store-suffix-test-{}", std::process::id());
+
+    client
+        .execute_query(
+            &format!(
+                "CREATE (:StoreSuffixTest {{uuid: '{}', pagerank_centrality: 0.25}})",
+                uuid
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test node");
+
+    let scores = HashMap::from([(
+        uuid.clone(),
+        HashMap::from([("pagerank".to_string(), 0.75)]),
+    )]);
+    let store_result = client.store_centrality_scores(&scores, Some("v2")).await;
+
+    let stored = client
+        .execute_query(
+            &format!(
+                "MATCH (n:StoreSuffixTest {{uuid: '{}'}}) \
+                 RETURN n.pagerank_centrality AS canonical, n.pagerank_centrality_v2 AS suffixed",
+                uuid
+            ),
+            None,
+        )
+        .await;
+
+    client
+        .execute_query("MATCH (n:StoreSuffixTest) DELETE n", None)
+        .await
+        .expect("Failed to clean up test node");
+
+    store_result.expect("Storing suffixed scores failed");
+    let stored = stored.expect("Failed to read back stored scores");
+    let record = stored.first().expect("Test node not found");
+    assert_eq!(
+        record.get("suffixed").and_then(falkor_value_to_f64),
+        Some(0.75)
+    );
+    assert_eq!(
+        record.get("canonical").and_then(falkor_value_to_f64),
+        Some(0.25),
+        "Canonical property should be unchanged"
+    );
+
+    assert!(client
+        .store_centrality_scores(&scores, Some("bad suffix"))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_stored_scores_read_back_without_recompute() {
+    let client = create_test_client().await;
+    let group_id = format!("stored-scores-test-{}", std::process::id());
+
+    client
+        .execute_query(
+            &format!(
+                "CREATE (:StoredScoresTest {{uuid: '{g}-a', name: 'a', group_id: '{g}'}}),
+                        (:StoredScoresTest {{uuid: '{g}-b', name: 'b', group_id: '{g}'}}),
+                        (:StoredScoresTest {{uuid: '{g}-c', name: 'c', group_id: '{g}'}})",
+                g = group_id
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    // c never gets a score and must not be returned
+    let scores = HashMap::from([
+        (
+            format!("{}-a", group_id),
+            HashMap::from([("pagerank".to_string(), 0.2)]),
+        ),
+        (
+            format!("{}-b", group_id),
+            HashMap::from([("pagerank".to_string(), 0.7)]),
+        ),
+    ]);
+    let store_result = client.store_centrality_scores(&scores, None).await;
+    let read = stored_scores(&client, "pagerank", Some(&group_id), 10, None).await;
+
+    client
+        .execute_query("MATCH (n:StoredScoresTest) DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    store_result.expect("Storing scores failed");
+    let read = read.expect("Reading stored scores failed");
+    let read: Vec<(String, f64)> = read.into_iter().map(|s| (s.uuid, s.score)).collect();
+    assert_eq!(
+        read,
+        vec![
+            (format!("{}-b", group_id), 0.7),
+            (format!("{}-a", group_id), 0.2),
+        ]
+    );
+
+    assert!(stored_scores(&client, "closeness", None, 10, None)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_stored_score_map_seeds_eigenvector_warm_start() {
+    let client = create_test_client().await;
+    let group_id = format!("stored-score-map-test-{}", std::process::id());
+
+    client
+        .execute_query(
+            &format!(
+                "CREATE (:StoredScoreMapTest {{uuid: '{g}-a', group_id: '{g}'}}),
+                        (:StoredScoreMapTest {{uuid: '{g}-b', group_id: '{g}'}})",
+                g = group_id
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    // b has no stored eigenvector score and starts from the uniform value
+    let scores = HashMap::from([(
+        format!("{}-a", group_id),
+        HashMap::from([("eigenvector".to_string(), 0.4)]),
+    )]);
+    let store_result = client.store_centrality_scores(&scores, None).await;
+    let read = stored_score_map(&client, "eigenvector", Some(&group_id), None).await;
+
+    client
+        .execute_query("MATCH (n:StoredScoreMapTest) DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    store_result.expect("Storing scores failed");
+    assert_eq!(
+        read.expect("Reading stored scores failed"),
+        HashMap::from([(format!("{}-a", group_id), 0.4)])
+    );
+}
+
+#[tokio::test]
+async fn test_single_node_importance_matches_formula() {
+    let client = create_test_client().await;
+    let rows = client
+        .execute_query(
+            "MATCH (n) WHERE n.uuid IS NOT NULL RETURN n.uuid AS uuid LIMIT 1",
+            None,
+        )
+        .await
+        .expect("Failed to find a node");
+    let node_uuid = match rows.first().and_then(|row| row.get("uuid")) {
+        Some(FalkorValue::String(uuid)) => uuid.clone(),
+        _ => return, // Empty graph
+    };
+
+    let weights = ImportanceWeights {
+        pagerank: 0.25,
+        eigenvector: 0.25,
+        degree: 0.25,
+        betweenness: 0.25,
+    };
+    let metrics: Vec<String> = [
+        "degree",
+        "pagerank",
+        "betweenness",
+        "eigenvector",
+        "importance",
+    ]
+    .iter()
+    .map(|m| m.to_string())
+    .collect();
+    let scores = calculate_single_node_centralities(&client, &node_uuid, &metrics, &weights)
+        .await
+        .expect("Single node calculation failed");
+
+    let expected = weights.importance(
+        scores["pagerank"],
+        scores["eigenvector"],
+        scores["degree"],
+        scores["betweenness"],
+    );
+    assert!((scores["importance"] - expected).abs() < 1e-12);
+
+    // Only the requested metrics are returned
+    let importance_only = calculate_single_node_centralities(
+        &client,
+        &node_uuid,
+        &["importance".to_string()],
+        &weights,
+    )
+    .await
+    .unwrap();
+    assert_eq!(importance_only.len(), 1);
+    assert!((importance_only["importance"] - expected).abs() < 1e-12);
+}
+
+#[test]
+fn test_local_metrics_rank_hub_above_leaf() {
+    // A hub with five spokes, one spoke extended by a further leaf
+    let edges = [
+        ("hub", "a"),
+        ("hub", "b"),
+        ("hub", "c"),
+        ("hub", "d"),
+        ("hub", "e"),
+        ("a", "b"),
+        ("e", "leaf"),
+    ];
+    let mut adjacency: HashMap<String, std::collections::HashSet<String>> = HashMap::new();
+    for (a, b) in edges {
+        adjacency
+            .entry(a.to_string())
+            .or_default()
+            .insert(b.to_string());
+        adjacency
+            .entry(b.to_string())
+            .or_default()
+            .insert(a.to_string());
+    }
+
+    let hub_pagerank = personalized_pagerank(&adjacency, "hub", 0.85, 50);
+    let leaf_pagerank = personalized_pagerank(&adjacency, "leaf", 0.85, 50);
+    assert!(
+        hub_pagerank > leaf_pagerank,
+        "hub {hub_pagerank} should outrank leaf {leaf_pagerank}"
+    );
+
+    let hub_betweenness = ego_betweenness(&adjacency, "hub");
+    let leaf_betweenness = ego_betweenness(&adjacency, "leaf");
+    assert!(hub_betweenness > leaf_betweenness);
+    assert_eq!(leaf_betweenness, 0.0);
+    // Only the a-b pair of the hub's 10 neighbour pairs is connected directly
+    assert!((hub_betweenness - 0.9).abs() < 1e-9);
+}
+
+#[test]
+fn test_undirected_pagerank_lifts_sources_and_lowers_sinks() {
+    // a -> b -> c -> d: directed, rank flows toward the sink d and it ranks first
+    let nodes: Vec<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+    let edges: Vec<(String, String)> = [("a", "b"), ("b", "c"), ("c", "d")]
+        .iter()
+        .map(|(s, t)| (s.to_string(), t.to_string()))
+        .collect();
+
+    let directed = pagerank_from_edges(&nodes, &edges, true, 0.85, 100);
+    let undirected = pagerank_from_edges(&nodes, &edges, false, 0.85, 100);
+
+    assert!(directed["d"] > directed["a"]);
+    assert!(directed["d"] > directed["b"]);
+
+    // Undirected, the chain is symmetric: the ends tie and the middle leads
+    assert!((undirected["a"] - undirected["d"]).abs() < 1e-6);
+    assert!(undirected["b"] > undirected["d"]);
+}
+
+#[test]
+fn test_directed_eigenvector_differs_from_undirected() {
+    // Cycle a -> b -> c -> a with a shortcut a -> c: c has two in-edges
+    let nodes: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    let edges: Vec<(String, String)> = [("a", "b"), ("b", "c"), ("c", "a"), ("a", "c")]
+        .iter()
+        .map(|(s, t)| (s.to_string(), t.to_string()))
+        .collect();
+
+    let (directed, _, directed_converged) =
+        eigenvector_from_edges(&nodes, &edges, true, MAX_ITERATIONS, 1e-10);
+    let (undirected, _, undirected_converged) =
+        eigenvector_from_edges(&nodes, &edges, false, MAX_ITERATIONS, 1e-10);
+    assert!(directed_converged);
+    assert!(undirected_converged);
+
+    // Directed, c collects from a and b, and a only from c
+    assert!(directed["c"] > directed["a"]);
+    assert!(directed["a"] > directed["b"]);
+
+    // Undirected, the graph is a triangle and every node ties
+    assert!((undirected["a"] - undirected["b"]).abs() < 1e-6);
+    assert!((undirected["b"] - undirected["c"]).abs() < 1e-6);
+}
+
+#[test]
+fn test_directed_eigenvector_reports_non_convergence_on_dag() {
+    let nodes: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    let edges: Vec<(String, String)> = [("a", "b"), ("b", "c")]
+        .iter()
+        .map(|(s, t)| (s.to_string(), t.to_string()))
+        .collect();
+
+    let (_, iterations, converged) = eigenvector_from_edges(&nodes, &edges, true, 50, 1e-10);
+    assert!(!converged);
+    assert_eq!(iterations, 50);
+}
+
+#[test]
+fn test_eigenvector_warm_start_converges_faster() {
+    // Pseudo-random graph: each node gets in-edges from its ring predecessor
+    // (keeping it strongly connected) and a few scattered nodes
+    let n = 40;
+    let mut seed: u64 = 42;
+    let mut next = |bound: usize| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize % bound
+    };
+    let mut in_neighbors: HashMap<String, Vec<String>> = HashMap::new();
+    for i in 0..n {
+        let mut sources = vec![((i + n - 1) % n).to_string()];
+        for _ in 0..(1 + next(4)) {
+            sources.push(next(n).to_string());
+        }
+        in_neighbors.insert(i.to_string(), sources);
+    }
+
+    let (previous, _, _) =
+        eigenvector_from_in_neighbors(&in_neighbors, MAX_ITERATIONS, 1e-10, None);
+
+    // A small change: one extra edge 0 -> 15
+    in_neighbors.get_mut("15").unwrap().push("0".to_string());
+
+    let (cold, cold_iterations, _) =
+        eigenvector_from_in_neighbors(&in_neighbors, MAX_ITERATIONS, 1e-10, None);
+    let (warm, warm_iterations, _) =
+        eigenvector_from_in_neighbors(&in_neighbors, MAX_ITERATIONS, 1e-10, Some(&previous));
+
+    assert!(
+        warm_iterations < cold_iterations,
+        "warm start took {} iterations, cold start {}",
+        warm_iterations,
+        cold_iterations
+    );
+    for (node, score) in &cold {
+        assert!((score - warm[node]).abs() < 1e-6, "{} diverged", node);
+    }
+}
+
+#[test]
+fn test_score_property_names() {
+    assert_eq!(score_property_name("pagerank", None), "pagerank_centrality");
+    assert_eq!(score_property_name("importance", None), "importance_score");
+    assert_eq!(
+        score_property_name("pagerank", Some("v2")),
+        "pagerank_centrality_v2"
+    );
+}
+
+#[test]
+fn test_pagerank_params_validation() {
+    let rejected = [
+        (2.0, 20),
+        (0.0, 20),
+        (1.0, 20),
+        (-0.5, 20),
+        (f64::NAN, 20),
+        (0.85, 0),
+        (0.85, 1_000_000),
+    ];
+    for (damping_factor, iterations) in rejected {
+        assert!(
+            matches!(
+                validate_pagerank_params(damping_factor, iterations),
+                Err(CentralityError::InvalidParameter { .. })
+            ),
+            "damping_factor={damping_factor} iterations={iterations} should be rejected"
+        );
+    }
+
+    assert!(validate_pagerank_params(0.85, 20).is_ok());
+    assert!(validate_pagerank_params(0.5, MAX_ITERATIONS).is_ok());
+}
+
+#[test]
+fn test_eigenvector_params_validation() {
+    assert!(matches!(
+        validate_eigenvector_params(100, 0.0),
+        Err(CentralityError::InvalidParameter { .. })
+    ));
+    assert!(matches!(
+        validate_eigenvector_params(100, f64::INFINITY),
+        Err(CentralityError::InvalidParameter { .. })
+    ));
+    assert!(matches!(
+        validate_eigenvector_params(MAX_ITERATIONS + 1, 1e-6),
+        Err(CentralityError::InvalidParameter { .. })
+    ));
+    assert!(validate_eigenvector_params(100, 1e-6).is_ok());
+}
+
+#[tokio::test]
+async fn test_dry_run_leaves_properties_untouched() {
+    let client = create_test_client().await;
+    let group_id = format!("dry-run-test-{}", std::process::id());
+
+    client
+        .execute_query(
+            &format!(
+                "CREATE (:DryRunTest {{uuid: '{0}-a', group_id: '{0}', pagerank_centrality: 0.25}})\
+                 -[:RELATES_TO]->(:DryRunTest {{uuid: '{0}-b', group_id: '{0}', pagerank_centrality: 0.25}})",
+                group_id
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    let request = AllCentralitiesRequest {
+        group_id: Some(group_id.clone()),
+        store_results: true,
+        weights: ImportanceWeights::default(),
+        betweenness_sampling: BetweennessSampling::default(),
+        eigenvector_directed: true,
+        property_suffix: None,
+        dry_run: true,
+    };
+    let response = run_all_centralities(&client, &request).await;
+
+    let stored = client
+        .execute_query(
+            &format!(
+                "MATCH (n:DryRunTest {{group_id: '{}'}}) RETURN n.pagerank_centrality AS pagerank",
+                group_id
+            ),
+            None,
+        )
+        .await;
+
+    client
+        .execute_query("MATCH (n:DryRunTest) DETACH DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    let response = response.expect("Dry run calculation failed");
+    assert_eq!(response.nodes_processed, 2);
+    assert_eq!(response.would_store, Some(2));
+    assert!(response.scores.values().all(|s| s.contains_key("pagerank")));
+
+    let stored = stored.expect("Failed to read back node properties");
+    assert_eq!(stored.len(), 2);
+    for record in &stored {
+        assert_eq!(
+            record.get("pagerank").and_then(falkor_value_to_f64),
+            Some(0.25),
+            "Dry run must not overwrite stored scores"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_shortest_path_on_known_graph() {
+    let client = create_test_client().await;
+    let prefix = format!("path-test-{}", std::process::id());
+
+    // a-b-c-d plus a longer detour a-x-y-z-d; the shortest a..d path is 3 hops
+    client
+        .execute_query(
+            &format!(
+                "CREATE (a:PathTest {{uuid: '{0}-a', name: 'a'}}), (b:PathTest {{uuid: '{0}-b'}}),
+                        (c:PathTest {{uuid: '{0}-c'}}), (d:PathTest {{uuid: '{0}-d', name: 'd'}}),
+                        (x:PathTest {{uuid: '{0}-x'}}), (y:PathTest {{uuid: '{0}-y'}}),
+                        (z:PathTest {{uuid: '{0}-z'}}), (lone:PathTest {{uuid: '{0}-lone'}}),
+                        (a)-[:RELATES_TO]->(b), (c)-[:RELATES_TO]->(b), (c)-[:RELATES_TO]->(d),
+                        (a)-[:RELATES_TO]->(x), (x)-[:RELATES_TO]->(y), (y)-[:RELATES_TO]->(z),
+                        (z)-[:RELATES_TO]->(d)",
+                prefix
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    let source = format!("{}-a", prefix);
+    let found = shortest_path(&client, &source, &format!("{}-d", prefix), 6).await;
+    let too_short = shortest_path(&client, &source, &format!("{}-d", prefix), 2).await;
+    let unreachable = shortest_path(&client, &source, &format!("{}-lone", prefix), 6).await;
+    let quoted = shortest_path(&client, "a' OR 1=1 //", &source, 6).await;
+
+    client
+        .execute_query("MATCH (n:PathTest) DETACH DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    let path = found
+        .expect("Shortest path query failed")
+        .expect("Expected a path");
+    assert_eq!(path.length, 3);
+    let uuids: Vec<_> = path.nodes.iter().map(|n| n.uuid.as_str()).collect();
+    assert_eq!(
+        uuids,
+        ["a", "b", "c", "d"].map(|suffix| format!("{}-{}", prefix, suffix))
+    );
+    assert_eq!(path.nodes[0].name.as_deref(), Some("a"));
+    assert_eq!(path.nodes[1].name, None);
+    // The c->b edge is traversed against its direction
+    assert_eq!(path.edges[1].source_uuid, format!("{}-c", prefix));
+    assert_eq!(path.edges[1].target_uuid, format!("{}-b", prefix));
+
+    assert!(too_short.expect("Depth-limited query failed").is_none());
+    assert!(unreachable.expect("Unreachable query failed").is_none());
+    assert!(quoted.expect("Quoted uuid query failed").is_none());
+}
+
+#[tokio::test]
+async fn test_k_shortest_paths_ordered_by_length() {
+    let client = create_test_client().await;
+    let prefix = format!("kpath-test-{}", std::process::id());
+
+    // Two routes from a to d: a-b-d (2 hops) and a-x-y-d (3 hops)
+    client
+        .execute_query(
+            &format!(
+                "CREATE (a:KPathTest {{uuid: '{0}-a'}}), (b:KPathTest {{uuid: '{0}-b'}}),
+                        (x:KPathTest {{uuid: '{0}-x'}}), (y:KPathTest {{uuid: '{0}-y'}}),
+                        (d:KPathTest {{uuid: '{0}-d'}}),
+                        (a)-[:RELATES_TO]->(b), (b)-[:RELATES_TO]->(d),
+                        (a)-[:RELATES_TO]->(x), (y)-[:RELATES_TO]->(x), (y)-[:RELATES_TO]->(d)",
+                prefix
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    let source = format!("{}-a", prefix);
+    let target = format!("{}-d", prefix);
+    let all = k_shortest_paths(&client, &source, &target, 5, 6).await;
+    let top = k_shortest_paths(&client, &source, &target, 1, 6).await;
+
+    client
+        .execute_query("MATCH (n:KPathTest) DETACH DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    let (paths, examined) = all.expect("k-shortest paths query failed");
+    let routes: Vec<Vec<String>> = paths
+        .iter()
+        .map(|path| path.nodes.iter().map(|n| n.uuid.clone()).collect())
+        .collect();
+    let route = |suffixes: &[&str]| -> Vec<String> {
+        suffixes
+            .iter()
+            .map(|suffix| format!("{}-{}", prefix, suffix))
+            .collect()
+    };
+    assert_eq!(
+        routes,
+        vec![route(&["a", "b", "d"]), route(&["a", "x", "y", "d"])]
+    );
+    assert_eq!(
+        paths.iter().map(|p| p.length).collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    assert!(examined >= 2);
+
+    let (top, _) = top.expect("k=1 query failed");
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].length, 2);
+}
+
+#[tokio::test]
+async fn test_neighbor_similarity_of_nodes_sharing_neighbors() {
+    let client = create_test_client().await;
+    let prefix = format!("similarity-test-{}", std::process::id());
+
+    // a and b share n1..n4 and b also has n5; c only shares n1 with a
+    client
+        .execute_query(
+            &format!(
+                "CREATE (a:SimilarityTest {{uuid: '{0}-a'}}), (b:SimilarityTest {{uuid: '{0}-b', name: 'b'}}),
+                        (c:SimilarityTest {{uuid: '{0}-c'}}),
+                        (n1:SimilarityTest {{uuid: '{0}-n1'}}), (n2:SimilarityTest {{uuid: '{0}-n2'}}),
+                        (n3:SimilarityTest {{uuid: '{0}-n3'}}), (n4:SimilarityTest {{uuid: '{0}-n4'}}),
+                        (n5:SimilarityTest {{uuid: '{0}-n5'}}),
+                        (a)-[:RELATES_TO]->(n1), (a)-[:RELATES_TO]->(n2),
+                        (a)-[:RELATES_TO]->(n3), (a)-[:RELATES_TO]->(n4),
+                        (n1)-[:RELATES_TO]->(b), (n2)-[:RELATES_TO]->(b), (b)-[:RELATES_TO]->(n3),
+                        (b)-[:RELATES_TO]->(n4), (b)-[:RELATES_TO]->(n5),
+                        (c)-[:RELATES_TO]->(n1)",
+                prefix
+            ),
+            None,
+        )
+        .await
+        .expect("Failed to create test nodes");
+
+    let source = format!("{}-a", prefix);
+    let pair = neighbor_similarity(&client, &source, &format!("{}-b", prefix)).await;
+    let missing = neighbor_similarity(&client, &source, &format!("{}-missing", prefix)).await;
+    let top = most_similar_by_neighbors(&client, &source, 5).await;
+
+    client
+        .execute_query("MATCH (n:SimilarityTest) DETACH DELETE n", None)
+        .await
+        .expect("Failed to clean up test nodes");
+
+    let pair = pair
+        .expect("Pair similarity query failed")
+        .expect("Expected both nodes to exist");
+    assert_eq!(pair.shared_neighbors, 4);
+    assert!((pair.jaccard - 0.8).abs() < 1e-9);
+    assert!((pair.overlap - 1.0).abs() < 1e-9);
+    assert_eq!(pair.name.as_deref(), Some("b"));
+
+    assert!(missing.expect("Missing node query failed").is_none());
+
+    let top = top.expect("Top-k similarity query failed");
+    let uuids: Vec<_> = top.iter().map(|s| s.uuid.clone()).collect();
+    assert_eq!(
+        uuids,
+        vec![format!("{}-b", prefix), format!("{}-c", prefix)]
+    );
+    assert!((top[1].jaccard - 0.25).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_job_polled_to_completion() {
+    let jobs = JobStore::new();
+
+    let job_id = jobs.spawn(async {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let mut scores = HashMap::new();
+        scores.insert(
+            "node-1".to_string(),
+            HashMap::from([("degree".to_string(), 2.0)]),
+        );
+        Ok(AllCentralitiesResponse {
+            scores,
+            nodes_processed: 1,
+            execution_time_ms: 50,
+            would_store: None,
+            betweenness_sampled: false,
+            betweenness_sample_size: None,
+            eigenvector_converged: true,
+            failed_metrics: BTreeMap::new(),
+        })
+    });
+    assert_eq!(jobs.get(&job_id).unwrap().status, JobStatus::Pending);
+
+    let mut record = jobs.get(&job_id).unwrap();
+    for _ in 0..100 {
+        if record.status != JobStatus::Pending {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        record = jobs.get(&job_id).unwrap();
+    }
+
+    assert_eq!(record.status, JobStatus::Complete);
+    assert_eq!(record.result.unwrap().nodes_processed, 1);
+
+    let failed_id = jobs.spawn(async { Err(CentralityError::internal("boom")) });
+    for _ in 0..100 {
+        if jobs.get(&failed_id).unwrap().status != JobStatus::Pending {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    let failed = jobs.get(&failed_id).unwrap();
+    assert_eq!(failed.status, JobStatus::Failed);
+    assert!(failed.error.unwrap().contains("boom"));
+    assert!(jobs.get("missing").is_none());
+}
+
+#[tokio::test]
+async fn test_panicking_job_reported_failed() {
+    let jobs = JobStore::new();
+
+    let job_id = jobs.spawn(async { panic!("calculation blew up") });
+    let mut record = jobs.get(&job_id).unwrap();
+    for _ in 0..100 {
+        if record.status != JobStatus::Pending {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        record = jobs.get(&job_id).unwrap();
+    }
+
+    assert_eq!(record.status, JobStatus::Failed);
+    assert!(record.error.unwrap().contains("panicked"));
+}
+
+#[test]
+fn test_importance_weights_change_ranking() {
+    // (pagerank, eigenvector, degree, betweenness)
+    let hub = (0.9, 0.8, 0.7, 0.05);
+    let bridge = (0.2, 0.1, 0.2, 0.95);
+    let importance = |w: &ImportanceWeights, (pr, ev, deg, btw): (f64, f64, f64, f64)| {
+        w.importance(pr, ev, deg, btw)
+    };
+
+    let default_weights = ImportanceWeights::default();
+    default_weights
+        .validate()
+        .expect("Default weights should be valid");
+    assert!(importance(&default_weights, hub) > importance(&default_weights, bridge));
+
+    let routing_weights = ImportanceWeights {
+        pagerank: 0.1,
+        eigenvector: 0.05,
+        degree: 0.05,
+        betweenness: 0.8,
+    };
+    routing_weights
+        .validate()
+        .expect("Routing weights should be valid");
+    assert!(importance(&routing_weights, bridge) > importance(&routing_weights, hub));
+
+    let unnormalized = ImportanceWeights {
+        betweenness: 0.5,
+        ..ImportanceWeights::default()
+    };
+    assert!(unnormalized.validate().is_err());
+}
+
+#[tokio::test]
+async fn test_request_id_reaches_handler_and_response() {
+    use axum::{body::Body, http::Request, middleware, routing::get, Router};
+    use tower::Service;
+
+    let mut app = Router::new()
+        .route(
+            "/",
+            get(|| async { current_request_id().unwrap_or_default() }),
+        )
+        .layer(middleware::from_fn(propagate_request_id));
+
+    let request = Request::get("/")
+        .header(REQUEST_ID_HEADER, "req-123")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.call(request).await.unwrap();
+    assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-123");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"req-123");
+
+    // Without one, an id is generated
+    let response = app
+        .call(Request::get("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert!(!response.headers()[REQUEST_ID_HEADER].is_empty());
+}
+
+#[test]
+fn test_failed_metric_reported_alongside_others() {
+    let metric = |scores: &[(&str, f64)]| {
+        Ok(CentralityScores {
+            scores: scores.iter().map(|(n, s)| (n.to_string(), *s)).collect(),
+            nodes_processed: scores.len(),
+        })
+    };
+    let pagerank = metric(&[("a", 0.6), ("b", 0.4)]);
+    let degree = metric(&[("a", 2.0), ("b", 1.0)]);
+    let eigenvector = metric(&[("a", 0.7), ("b", 0.3)]);
+    let betweenness = Err(CentralityError::algorithm_failed("sampling timed out"));
+
+    let (scores, failed_metrics) = combine_centralities(
+        pagerank,
+        degree,
+        betweenness,
+        eigenvector,
+        &ImportanceWeights::default(),
+    );
+
+    assert_eq!(failed_metrics.len(), 1);
+    assert!(failed_metrics["betweenness"].contains("sampling timed out"));
+    assert_eq!(scores.len(), 2);
+    for node_scores in scores.values() {
+        for metric in ["pagerank", "degree", "eigenvector"] {
+            assert!(node_scores.contains_key(metric), "missing {metric}");
+        }
+        assert!(!node_scores.contains_key("betweenness"));
+        assert!(!node_scores.contains_key("importance"));
+    }
+    assert_eq!(scores["a"]["degree"], 1.0);
+    assert_eq!(scores["b"]["degree"], 0.5);
+}
+
 #[tokio::test]
 #[ignore] // This test requires specific data setup
 async fn test_group_id_filtering() {
     let client = create_test_client().await;
 
     // Test with a group_id filter (this assumes test data exists)
-    let result = calculate_pagerank(&client, Some("test_group"), 0.85, 20).await;
+    let result = calculate_pagerank(&client, Some("test_group"), 0.85, 20, true).await;
 
     match result {
         Ok(scores) => {
@@ -225,7 +1408,9 @@ async fn test_performance_comparison() {
 
     // Test PageRank performance
     let start = std::time::Instant::now();
-    let pagerank_result = calculate_pagerank(&client, None, 0.85, 20).await.unwrap();
+    let pagerank_result = calculate_pagerank(&client, None, 0.85, 20, true)
+        .await
+        .unwrap();
     let pagerank_duration = start.elapsed();
 
     // Test degree centrality performance
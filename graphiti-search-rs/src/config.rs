@@ -7,30 +7,53 @@ pub struct Config {
     pub port: u16,
     pub falkor_host: String,
     pub falkor_port: u16,
+    /// Candidate FalkorDB endpoints (`host:port`), tried in order
+    pub falkor_hosts: Vec<String>,
     pub graph_name: String,
     pub redis_url: String,
     pub max_connections: usize,
+    /// Connections opened and warmed up at startup
+    pub min_idle_connections: usize,
     pub cache_ttl: u64,
     pub enable_simd: bool,
     pub parallel_threshold: usize,
+    /// Per-query FalkorDB timeout in milliseconds; 0 disables it
+    pub query_timeout_ms: u64,
+    /// Use the `name_embedding` vector index for cosine node similarity
+    pub use_vector_index: bool,
 }
 
 impl Config {
     pub fn from_env() -> Result<Self> {
+        let falkor_host = env::var("FALKORDB_HOST").unwrap_or_else(|_| "localhost".to_string());
+        let falkor_port = env::var("FALKORDB_PORT")
+            .unwrap_or_else(|_| "6379".to_string())
+            .parse()?;
+        let falkor_hosts = parse_falkor_hosts(
+            env::var("FALKORDB_HOSTS").ok().as_deref(),
+            &falkor_host,
+            falkor_port,
+        );
+
+        let max_connections: usize = env::var("MAX_CONNECTIONS")
+            .unwrap_or_else(|_| "200".to_string()) // Increased from 32 for better throughput
+            .parse()?;
+        let min_idle_connections: usize = env::var("MIN_IDLE_CONNECTIONS")
+            .unwrap_or_else(|_| "8".to_string())
+            .parse()?;
+
         Ok(Config {
             port: env::var("PORT")
                 .unwrap_or_else(|_| "3004".to_string())
                 .parse()?,
-            falkor_host: env::var("FALKORDB_HOST").unwrap_or_else(|_| "localhost".to_string()),
-            falkor_port: env::var("FALKORDB_PORT")
-                .unwrap_or_else(|_| "6379".to_string())
-                .parse()?,
+            falkor_host,
+            falkor_port,
+            falkor_hosts,
             graph_name: env::var("GRAPH_NAME").unwrap_or_else(|_| "graphiti_migration".to_string()),
             redis_url: env::var("REDIS_URL")
                 .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
-            max_connections: env::var("MAX_CONNECTIONS")
-                .unwrap_or_else(|_| "200".to_string()) // Increased from 32 for better throughput
-                .parse()?,
+            max_connections,
+            min_idle_connections: min_idle_connections.min(max_connections),
             cache_ttl: env::var("CACHE_TTL")
                 .unwrap_or_else(|_| "300".to_string())
                 .parse()?,
@@ -40,6 +63,58 @@ impl Config {
             parallel_threshold: env::var("PARALLEL_THRESHOLD")
                 .unwrap_or_else(|_| "100".to_string())
                 .parse()?,
+            query_timeout_ms: env::var("FALKORDB_QUERY_TIMEOUT_MS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()?,
+            use_vector_index: env::var("USE_VECTOR_INDEX")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()?,
         })
     }
 }
+
+/// Parse a comma-separated `FALKORDB_HOSTS` list into `host:port` endpoints.
+/// Entries without a port use `default_port`; an unset or empty list falls
+/// back to the single `host`.
+pub fn parse_falkor_hosts(hosts: Option<&str>, host: &str, default_port: u16) -> Vec<String> {
+    let endpoints: Vec<String> = hosts
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            if entry.contains(':') {
+                entry.to_string()
+            } else {
+                format!("{entry}:{default_port}")
+            }
+        })
+        .collect();
+
+    if endpoints.is_empty() {
+        vec![format!("{host}:{default_port}")]
+    } else {
+        endpoints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_falkor_hosts() {
+        assert_eq!(
+            parse_falkor_hosts(Some("primary, replica:6380,,"), "localhost", 6379),
+            vec!["primary:6379", "replica:6380"]
+        );
+        assert_eq!(
+            parse_falkor_hosts(None, "falkordb", 6379),
+            vec!["falkordb:6379"]
+        );
+        assert_eq!(
+            parse_falkor_hosts(Some(" "), "falkordb", 6379),
+            vec!["falkordb:6379"]
+        );
+    }
+}
@@ -2,8 +2,11 @@ use anyhow::Result;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::time::Duration;
 use tracing::{debug, warn};
 
+use crate::request_id::RequestIdExt;
+
 #[derive(Debug, Serialize)]
 struct EmbeddingRequest {
     input: String,
@@ -32,20 +35,41 @@ impl Default for OllamaEmbedder {
     }
 }
 
+/// Default bound on a single embedding request, overridable via `EMBEDDING_TIMEOUT_MS`
+const DEFAULT_EMBEDDING_TIMEOUT_MS: u64 = 5000;
+
 impl OllamaEmbedder {
     pub fn new() -> Self {
         let base_url = env::var("OLLAMA_BASE_URL")
             .unwrap_or_else(|_| "http://192.168.50.80:11434/v1".to_string());
         let model = env::var("OLLAMA_EMBEDDING_MODEL")
             .unwrap_or_else(|_| "mxbai-embed-large:latest".to_string());
+        let timeout_ms = env::var("EMBEDDING_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_EMBEDDING_TIMEOUT_MS);
 
+        Self::with_timeout(base_url, model, Duration::from_millis(timeout_ms))
+    }
+
+    /// Embedder whose requests give up after `timeout`, so a slow backend
+    /// can't stall the search waiting on it
+    pub fn with_timeout(base_url: String, model: String, timeout: Duration) -> Self {
         debug!(
-            "Ollama embedder initialized with URL: {}, Model: {}",
-            base_url, model
+            "Ollama embedder initialized with URL: {}, Model: {}, Timeout: {:?}",
+            base_url, model, timeout
         );
 
+        let client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_else(|e| {
+                warn!("Failed to build embedding client with timeout: {}", e);
+                Client::new()
+            });
+
         Self {
-            client: Client::new(),
+            client,
             base_url,
             model,
         }
@@ -66,6 +90,7 @@ impl OllamaEmbedder {
             .post(&url)
             .header("Authorization", "Bearer ollama")
             .json(&request)
+            .with_request_id()
             .send()
             .await?;
 
@@ -6,11 +6,16 @@ use axum::{
 use serde_json::json;
 use thiserror::Error;
 
+use crate::falkor::client_v2::QueryTimeout;
+
 #[derive(Error, Debug)]
 pub enum SearchError {
     #[error("Database error: {0}")]
     Database(String),
 
+    #[error("Query timed out: {0}")]
+    QueryTimeout(String),
+
     #[error("Cache error: {0}")]
     Cache(String),
 
@@ -29,28 +34,71 @@ pub enum SearchError {
     #[error("Configuration error: {0}")]
     Configuration(String),
 
+    #[error("Embedding unavailable: {0}")]
+    Embedding(String),
+
     #[error("Internal error: {0}")]
     Internal(#[from] anyhow::Error),
 }
 
+impl SearchError {
+    /// Wrap a FalkorDB client failure, keeping query timeouts distinct
+    pub fn database(error: anyhow::Error) -> Self {
+        match error.downcast::<QueryTimeout>() {
+            Ok(timeout) => SearchError::QueryTimeout(timeout.to_string()),
+            Err(e) => SearchError::Database(e.to_string()),
+        }
+    }
+
+    /// Stable machine-readable code for clients
+    pub fn code(&self) -> &'static str {
+        match self {
+            SearchError::Database(_) => "DATABASE_UNAVAILABLE",
+            SearchError::QueryTimeout(_) => "QUERY_TIMEOUT",
+            SearchError::Cache(_) => "CACHE_ERROR",
+            SearchError::InvalidQuery(_) => "INVALID_QUERY",
+            SearchError::Serialization(_) => "SERIALIZATION_ERROR",
+            SearchError::VectorOperation(_) => "VECTOR_OPERATION_FAILED",
+            SearchError::Reranking(_) => "RERANKING_FAILED",
+            SearchError::Configuration(_) => "CONFIGURATION_ERROR",
+            SearchError::Embedding(_) => "EMBEDDING_UNAVAILABLE",
+            SearchError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// Whether the same request may succeed if retried later
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self,
+            SearchError::Database(_) | SearchError::Cache(_) | SearchError::Embedding(_)
+        )
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            SearchError::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+            SearchError::QueryTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            SearchError::Database(_) | SearchError::Embedding(_) => StatusCode::SERVICE_UNAVAILABLE,
+            // Cache failures are non-fatal
+            SearchError::Cache(_) => StatusCode::OK,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
 impl IntoResponse for SearchError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            SearchError::InvalidQuery(msg) => (StatusCode::BAD_REQUEST, msg),
-            SearchError::Database(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
-            SearchError::Cache(msg) => {
-                tracing::warn!("Cache error (non-fatal): {}", msg);
-                (
-                    StatusCode::OK,
-                    "Cache miss, proceeding without cache".to_string(),
-                )
-            }
-            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
-        };
+        if let SearchError::Cache(msg) = &self {
+            tracing::warn!("Cache error (non-fatal): {}", msg);
+        }
 
+        let status = self.status();
         let body = Json(json!({
-            "error": error_message,
-            "status": status.as_u16(),
+            "error": {
+                "code": self.code(),
+                "message": self.to_string(),
+                "retryable": self.retryable(),
+            }
         }));
 
         (status, body).into_response()
@@ -58,3 +106,44 @@ impl IntoResponse for SearchError {
 }
 
 pub type SearchResult<T> = Result<T, SearchError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_database_error_response() {
+        let response = SearchError::Database("connection refused".to_string()).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "DATABASE_UNAVAILABLE");
+        assert_eq!(body["error"]["retryable"], true);
+        assert!(body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("connection refused"));
+    }
+
+    #[test]
+    fn test_query_timeout_kept_distinct() {
+        let error = SearchError::database(QueryTimeout { timeout_ms: 5 }.into());
+        assert_eq!(error.code(), "QUERY_TIMEOUT");
+        assert_eq!(error.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert!(!error.retryable());
+
+        let error = SearchError::database(anyhow::anyhow!("connection refused"));
+        assert_eq!(error.code(), "DATABASE_UNAVAILABLE");
+    }
+
+    #[test]
+    fn test_invalid_query_not_retryable() {
+        let error = SearchError::InvalidQuery("empty".to_string());
+        assert_eq!(error.code(), "INVALID_QUERY");
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+        assert!(!error.retryable());
+    }
+}
@@ -1,23 +1,57 @@
 #![allow(dead_code)]
 
 use anyhow::Result;
-use falkordb::{AsyncGraph, FalkorAsyncClient, FalkorClientBuilder, FalkorConnectionInfo};
-use tracing::instrument;
+use chrono::{DateTime, Utc};
+use falkordb::{
+    AsyncGraph, FalkorAsyncClient, FalkorClientBuilder, FalkorConnectionInfo, LazyResultSet,
+    QueryResult,
+};
+use tracing::{instrument, warn};
 
 use crate::config::Config;
 use crate::falkor::parser_v2;
-use crate::models::{Edge, Episode, Node};
+use crate::models::{Community, DistanceMetric, Edge, Episode, Node};
+
+/// Query embedding, minimum score and metric for a similarity count condition
+pub type SimilarityThreshold<'a> = (&'a [f32], f32, DistanceMetric);
+
+/// An entity's stored name embedding, as scanned by the re-embed job
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityEmbedding {
+    pub uuid: String,
+    pub name: String,
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// A query aborted by FalkorDB after exceeding `FALKORDB_QUERY_TIMEOUT_MS`
+#[derive(Debug, thiserror::Error)]
+#[error("query exceeded the {timeout_ms} ms timeout")]
+pub struct QueryTimeout {
+    pub timeout_ms: u64,
+}
 
 pub struct FalkorClientV2 {
     #[allow(dead_code)]
     client: FalkorAsyncClient,
     graph: AsyncGraph,
+    /// Per-query timeout passed to FalkorDB; 0 leaves queries unbounded
+    query_timeout_ms: u64,
+    /// Search node embeddings through the `name_embedding` vector index
+    use_vector_index: bool,
+    /// Set once the vector index query fails, so this connection scans from then on
+    vector_index_missing: bool,
 }
 
 impl FalkorClientV2 {
     pub async fn new(config: &Config) -> Result<Self> {
+        let endpoint = format!("{}:{}", config.falkor_host, config.falkor_port);
+        Self::connect(config, &endpoint).await
+    }
+
+    /// Connect to a specific `host:port` endpoint
+    pub async fn connect(config: &Config, endpoint: &str) -> Result<Self> {
         // Build connection URL
-        let conn_url = format!("redis://{}:{}", config.falkor_host, config.falkor_port);
+        let conn_url = format!("redis://{endpoint}");
         let conn_info: FalkorConnectionInfo = conn_url.try_into()?;
 
         // Create async client
@@ -29,12 +63,35 @@ impl FalkorClientV2 {
         // Select the graph
         let graph = client.select_graph(&config.graph_name);
 
-        Ok(Self { client, graph })
+        Ok(Self {
+            client,
+            graph,
+            query_timeout_ms: config.query_timeout_ms,
+            use_vector_index: config.use_vector_index,
+            vector_index_missing: false,
+        })
+    }
+
+    /// Run `cypher` under the configured timeout. A query FalkorDB aborts for
+    /// running too long fails with [`QueryTimeout`].
+    async fn run<'a>(&'a mut self, cypher: &'a str) -> Result<QueryResult<LazyResultSet<'a>>> {
+        let timeout_ms = self.query_timeout_ms;
+        let mut query = self.graph.query(cypher);
+        if timeout_ms > 0 {
+            query = query.with_timeout(timeout_ms as i64);
+        }
+        query.execute().await.map_err(|e| {
+            if is_timeout_error(&e.to_string()) {
+                QueryTimeout { timeout_ms }.into()
+            } else {
+                e.into()
+            }
+        })
     }
 
     pub async fn ping(&mut self) -> Result<()> {
         // Test connection by running a simple query
-        let _result = self.graph.query("RETURN 1").execute().await?;
+        let _result = self.run("RETURN 1").await?;
         Ok(())
     }
 
@@ -73,7 +130,7 @@ impl FalkorClientV2 {
             escaped_query, escaped_query, group_filter, limit
         );
 
-        let result = self.graph.query(&cypher).execute().await?;
+        let result = self.run(&cypher).await?;
 
         parser_v2::parse_nodes_from_falkor_v2(result.data)
     }
@@ -84,14 +141,21 @@ impl FalkorClientV2 {
         embedding: &[f32],
         limit: usize,
         min_score: f32,
+        metric: DistanceMetric,
         group_ids: Option<&[String]>,
     ) -> Result<Vec<Node>> {
-        // Build the vector string inline since FalkorDB params only support strings
-        let embedding_str = embedding
-            .iter()
-            .map(|v| v.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
+        // The index ranks by cosine distance, so other metrics always scan
+        if self.use_vector_index && !self.vector_index_missing && metric == DistanceMetric::Cosine {
+            let cypher = indexed_similarity_query(embedding, limit, min_score, group_ids);
+            match self.run(&cypher).await {
+                Ok(result) => return parser_v2::parse_nodes_from_falkor_v2(result.data),
+                Err(e) if e.downcast_ref::<QueryTimeout>().is_some() => return Err(e),
+                Err(e) => {
+                    warn!("Vector index query failed, falling back to a full scan: {e}");
+                    self.vector_index_missing = true;
+                }
+            }
+        }
 
         // Build group filter clause
         let group_filter = if let Some(groups) = group_ids {
@@ -113,15 +177,18 @@ impl FalkorClientV2 {
         let cypher = format!(
             "MATCH (n:Entity) 
              WHERE n.name_embedding IS NOT NULL{}
-             WITH n, (2 - vec.cosineDistance(n.name_embedding, vecf32([{}])))/2 AS score
+             WITH n, {} AS score
              WHERE score >= {}
              RETURN n, score 
              ORDER BY score DESC 
              LIMIT {}",
-            group_filter, embedding_str, min_score, limit
+            group_filter,
+            similarity_score(metric, "n.name_embedding", embedding),
+            min_score,
+            limit
         );
 
-        let result = self.graph.query(&cypher).execute().await?;
+        let result = self.run(&cypher).await?;
 
         parser_v2::parse_nodes_from_falkor_v2(result.data)
     }
@@ -151,7 +218,7 @@ impl FalkorClientV2 {
             uuid_list, max_depth, limit
         );
 
-        let result = self.graph.query(&cypher).execute().await?;
+        let result = self.run(&cypher).await?;
 
         parser_v2::parse_nodes_from_falkor_v2(result.data)
     }
@@ -191,7 +258,7 @@ impl FalkorClientV2 {
             escaped_query, escaped_query, group_filter, limit
         );
 
-        let result = self.graph.query(&cypher).execute().await?;
+        let result = self.run(&cypher).await?;
 
         parser_v2::parse_edges_from_falkor_v2(result.data)
     }
@@ -202,14 +269,10 @@ impl FalkorClientV2 {
         embedding: &[f32],
         limit: usize,
         min_score: f32,
+        metric: DistanceMetric,
         group_ids: Option<&[String]>,
     ) -> Result<Vec<Edge>> {
-        // Build the vector string inline
-        let embedding_str = embedding
-            .iter()
-            .map(|v| v.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
+        let score_expr = similarity_score(metric, "r.fact_embedding", embedding);
 
         // Build group filter clause
         let group_filter = if let Some(groups) = group_ids {
@@ -234,86 +297,33 @@ impl FalkorClientV2 {
         //
         // Solution: Get all scores in one query (LIMIT 1), then sort in Rust
 
-        let mut edge_scores = Vec::new();
-
-        // First, get all edges with scores above threshold
-        // We have to get them one at a time due to the LIMIT > 1 bug
-        // But we can't use ORDER BY or SKIP, so we'll collect all and sort later
-        for i in 0..100 {
-            // Max 100 iterations to prevent infinite loop
-            let cypher = if i == 0 {
-                // First query - no exclusions
-                format!(
-                    "MATCH ()-[r:RELATES_TO]->()
-                     WHERE r.fact_embedding IS NOT NULL{}
-                     WITH r.uuid AS uuid_str, (2 - vec.cosineDistance(r.fact_embedding, vecf32([{}])))/2 AS score
-                     WHERE score >= {}
-                     RETURN uuid_str, score
-                     LIMIT 1",
-                    group_filter, embedding_str, min_score
-                )
-            } else {
-                // Subsequent queries - exclude already found UUIDs
-                let exclude_list = edge_scores
-                    .iter()
-                    .map(|(uuid, _): &(String, f32)| format!("'{}'", uuid))
-                    .collect::<Vec<_>>()
-                    .join(",");
+        let mut collector = OneAtATimeScores::new(limit, min_score);
 
-                format!(
-                    "MATCH ()-[r:RELATES_TO]->()
-                     WHERE r.fact_embedding IS NOT NULL AND r.uuid NOT IN [{}]{}
-                     WITH r.uuid AS uuid_str, (2 - vec.cosineDistance(r.fact_embedding, vecf32([{}])))/2 AS score
-                     WHERE score >= {}
-                     RETURN uuid_str, score
-                     LIMIT 1",
-                    exclude_list, group_filter, embedding_str, min_score
-                )
-            };
+        // Query one edge at a time due to the LIMIT > 1 bug, excluding the ones
+        // already found, and stop as soon as we have enough
+        while collector.wants_more() {
+            let cypher = format!(
+                "MATCH ()-[r:RELATES_TO]->()
+                 WHERE r.fact_embedding IS NOT NULL{}{}
+                 WITH r.uuid AS uuid_str, {} AS score
+                 WHERE score >= {}
+                 RETURN uuid_str, score
+                 LIMIT 1",
+                collector.exclusion_filter("r"),
+                group_filter,
+                score_expr,
+                min_score
+            );
 
-            match self.graph.query(&cypher).execute().await {
-                Ok(result) => {
-                    // Extract UUID and score from the single result
-                    let mut found = false;
-                    for row in result.data {
-                        if row.len() >= 2 {
-                            if let (Some(falkordb::FalkorValue::String(uuid)), Some(score_val)) =
-                                (row.first(), row.get(1))
-                            {
-                                let score = match score_val {
-                                    falkordb::FalkorValue::F64(f) => *f as f32,
-                                    falkordb::FalkorValue::I64(i) => *i as f32,
-                                    _ => 0.0,
-                                };
-                                edge_scores.push((uuid.clone(), score));
-                                found = true;
-                                break;
-                            }
-                        }
-                    }
-                    // If no result found, we've exhausted all matches
-                    if !found {
-                        break;
-                    }
-
-                    // If we've collected enough results, stop
-                    if edge_scores.len() >= limit {
-                        break;
-                    }
-                }
-                Err(_) => {
-                    // Query failed, stop trying
-                    break;
-                }
-            }
+            // A failed query ends the search with what we have so far
+            let hit = match self.run(&cypher).await {
+                Ok(result) => first_scored_uuid(result.data),
+                Err(_) => None,
+            };
+            collector.record(hit);
         }
 
-        // Sort by score descending and take only the requested limit
-        edge_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        edge_scores.truncate(limit);
-
-        // Extract just the UUIDs
-        let edge_uuids: Vec<String> = edge_scores.into_iter().map(|(uuid, _)| uuid).collect();
+        let edge_uuids = collector.into_ranked_uuids();
 
         if edge_uuids.is_empty() {
             return Ok(Vec::new());
@@ -333,7 +343,7 @@ impl FalkorClientV2 {
             uuid_list
         );
 
-        let fetch_result = self.graph.query(&fetch_cypher).execute().await?;
+        let fetch_result = self.run(&fetch_cypher).await?;
 
         // Parse edges using the standard parser
         parser_v2::parse_edges_from_falkor_v2(fetch_result.data)
@@ -344,38 +354,837 @@ impl FalkorClientV2 {
         &mut self,
         query: &str,
         group_ids: Option<&[String]>,
+        created_after: Option<DateTime<Utc>>,
+        created_before: Option<DateTime<Utc>>,
+        order_by_time: bool,
         limit: usize,
     ) -> Result<Vec<Episode>> {
         // FalkorDB SDK doesn't support parameters well, use direct string interpolation
         let escaped_query = query.replace('\'', "\\'").to_lowercase();
 
-        // Build group filter clause
-        let group_filter = if let Some(groups) = group_ids {
-            if !groups.is_empty() {
-                let group_list = groups
-                    .iter()
-                    .map(|g| format!("'{}'", g.replace('\'', "\\'")))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                format!(" AND e.group_id IN [{}]", group_list)
-            } else {
-                String::new()
-            }
+        // Most recent first when requested
+        let order_clause = if order_by_time {
+            "\n             ORDER BY e.created_at DESC"
         } else {
-            String::new()
+            ""
         };
 
         let cypher = format!(
             "MATCH (e:Episode)
              WHERE (toLower(e.content) CONTAINS '{}' 
-                OR toLower(e.name) CONTAINS '{}'){}
-             RETURN e
+                OR toLower(e.name) CONTAINS '{}'){}{}
+             RETURN e{}
              LIMIT {}",
-            escaped_query, escaped_query, group_filter, limit
+            escaped_query,
+            escaped_query,
+            group_filter("e", group_ids),
+            created_range_filter("e", created_after, created_before),
+            order_clause,
+            limit
         );
 
-        let result = self.graph.query(&cypher).execute().await?;
+        let result = self.run(&cypher).await?;
 
         parser_v2::parse_episodes_from_falkor_v2(result.data)
     }
+
+    /// Entities linked to any of the given episodes by `MENTIONS` or `HAS_MEMBER`
+    #[instrument(skip(self))]
+    pub async fn episode_entities(
+        &mut self,
+        episode_uuids: &[String],
+        limit: usize,
+    ) -> Result<Vec<Node>> {
+        let uuid_list = episode_uuids
+            .iter()
+            .map(|u| format!("'{}'", u.replace('\'', "\\'")))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let cypher = format!(
+            "MATCH (e:Episode)-[:MENTIONS|HAS_MEMBER]-(n:Entity)
+             WHERE e.uuid IN [{}]
+             RETURN DISTINCT n
+             LIMIT {}",
+            uuid_list, limit
+        );
+
+        let result = self.run(&cypher).await?;
+
+        parser_v2::parse_nodes_from_falkor_v2(result.data)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn fulltext_search_communities(
+        &mut self,
+        query: &str,
+        group_ids: Option<&[String]>,
+        limit: usize,
+    ) -> Result<Vec<Community>> {
+        // FalkorDB SDK doesn't support parameters well, use direct string interpolation
+        let escaped_query = query.replace('\'', "\\'").to_lowercase();
+
+        let cypher = format!(
+            "MATCH (c:Community)
+             WHERE (toLower(c.name) CONTAINS '{}'
+                OR toLower(c.summary) CONTAINS '{}'){}
+             RETURN c
+             LIMIT {}",
+            escaped_query,
+            escaped_query,
+            group_filter("c", group_ids),
+            limit
+        );
+
+        let result = self.run(&cypher).await?;
+
+        parser_v2::parse_communities_from_falkor_v2(result.data)
+    }
+
+    #[instrument(skip(self, embedding))]
+    pub async fn similarity_search_communities(
+        &mut self,
+        embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+        metric: DistanceMetric,
+        group_ids: Option<&[String]>,
+    ) -> Result<Vec<Community>> {
+        let cypher = format!(
+            "MATCH (c:Community)
+             WHERE c.name_embedding IS NOT NULL{}
+             WITH c, {} AS score
+             WHERE score >= {}
+             RETURN c, score
+             ORDER BY score DESC
+             LIMIT {}",
+            group_filter("c", group_ids),
+            similarity_score(metric, "c.name_embedding", embedding),
+            min_score,
+            limit
+        );
+
+        let result = self.run(&cypher).await?;
+
+        parser_v2::parse_communities_from_falkor_v2(result.data)
+    }
+
+    /// Count entities matching a fulltext query and/or an embedding threshold
+    #[instrument(skip(self, similarity))]
+    pub async fn count_nodes(
+        &mut self,
+        query: Option<&str>,
+        similarity: Option<SimilarityThreshold<'_>>,
+        group_ids: Option<&[String]>,
+    ) -> Result<usize> {
+        let mut conditions = Vec::new();
+        if let Some(query) = query {
+            let escaped_query = query.replace('\'', "\\'").to_lowercase();
+            conditions.push(format!(
+                "toLower(n.name) CONTAINS '{}' OR toLower(n.summary) CONTAINS '{}'",
+                escaped_query, escaped_query
+            ));
+        }
+        if let Some((embedding, min_score, metric)) = similarity {
+            conditions.push(format!(
+                "(n.name_embedding IS NOT NULL AND {} >= {})",
+                similarity_score(metric, "n.name_embedding", embedding),
+                min_score
+            ));
+        }
+        if conditions.is_empty() {
+            return Ok(0);
+        }
+
+        let cypher = format!(
+            "MATCH (n:Entity)
+             WHERE ({}){}
+             RETURN count(DISTINCT n)",
+            conditions.join(" OR "),
+            group_filter("n", group_ids)
+        );
+
+        let result = self.run(&cypher).await?;
+        first_count(result.data)
+    }
+
+    /// Count edges matching a fulltext query and/or an embedding threshold
+    #[instrument(skip(self, similarity))]
+    pub async fn count_edges(
+        &mut self,
+        query: Option<&str>,
+        similarity: Option<SimilarityThreshold<'_>>,
+        group_ids: Option<&[String]>,
+    ) -> Result<usize> {
+        let mut conditions = Vec::new();
+        if let Some(query) = query {
+            let escaped_query = query.replace('\'', "\\'").to_lowercase();
+            conditions.push(format!(
+                "toLower(r.fact) CONTAINS '{}' OR toLower(r.name) CONTAINS '{}'",
+                escaped_query, escaped_query
+            ));
+        }
+        if let Some((embedding, min_score, metric)) = similarity {
+            conditions.push(format!(
+                "(r.fact_embedding IS NOT NULL AND {} >= {})",
+                similarity_score(metric, "r.fact_embedding", embedding),
+                min_score
+            ));
+        }
+        if conditions.is_empty() {
+            return Ok(0);
+        }
+
+        let cypher = format!(
+            "MATCH (a)-[r:RELATES_TO]->(b)
+             WHERE ({}){}
+             RETURN count(DISTINCT r)",
+            conditions.join(" OR "),
+            group_filter("r", group_ids)
+        );
+
+        let result = self.run(&cypher).await?;
+        first_count(result.data)
+    }
+
+    /// Count communities matching a fulltext query and/or an embedding threshold
+    #[instrument(skip(self, similarity))]
+    pub async fn count_communities(
+        &mut self,
+        query: Option<&str>,
+        similarity: Option<SimilarityThreshold<'_>>,
+        group_ids: Option<&[String]>,
+    ) -> Result<usize> {
+        let mut conditions = Vec::new();
+        if let Some(query) = query {
+            let escaped_query = query.replace('\'', "\\'").to_lowercase();
+            conditions.push(format!(
+                "toLower(c.name) CONTAINS '{}' OR toLower(c.summary) CONTAINS '{}'",
+                escaped_query, escaped_query
+            ));
+        }
+        if let Some((embedding, min_score, metric)) = similarity {
+            conditions.push(format!(
+                "(c.name_embedding IS NOT NULL AND {} >= {})",
+                similarity_score(metric, "c.name_embedding", embedding),
+                min_score
+            ));
+        }
+        if conditions.is_empty() {
+            return Ok(0);
+        }
+
+        let cypher = format!(
+            "MATCH (c:Community)
+             WHERE ({}){}
+             RETURN count(DISTINCT c)",
+            conditions.join(" OR "),
+            group_filter("c", group_ids)
+        );
+
+        let result = self.run(&cypher).await?;
+        first_count(result.data)
+    }
+
+    /// Count episodes matching a fulltext query
+    #[instrument(skip(self))]
+    pub async fn count_episodes(
+        &mut self,
+        query: &str,
+        group_ids: Option<&[String]>,
+        created_after: Option<DateTime<Utc>>,
+        created_before: Option<DateTime<Utc>>,
+    ) -> Result<usize> {
+        let escaped_query = query.replace('\'', "\\'").to_lowercase();
+
+        let cypher = format!(
+            "MATCH (e:Episode)
+             WHERE (toLower(e.content) CONTAINS '{}'
+                OR toLower(e.name) CONTAINS '{}'){}{}
+             RETURN count(e)",
+            escaped_query,
+            escaped_query,
+            group_filter("e", group_ids),
+            created_range_filter("e", created_after, created_before)
+        );
+
+        let result = self.run(&cypher).await?;
+        first_count(result.data)
+    }
+
+    /// Up to `limit` entities after `after_uuid` in uuid order, with their
+    /// stored name embedding
+    #[instrument(skip(self))]
+    pub async fn entity_embedding_page(
+        &mut self,
+        after_uuid: Option<&str>,
+        group_ids: Option<&[String]>,
+        limit: usize,
+    ) -> Result<Vec<EntityEmbedding>> {
+        let cursor = after_uuid
+            .map(|uuid| format!(" AND n.uuid > '{}'", uuid.replace('\'', "\\'")))
+            .unwrap_or_default();
+
+        let cypher = format!(
+            "MATCH (n:Entity)
+             WHERE n.uuid IS NOT NULL{}{}
+             RETURN n.uuid, n.name, n.name_embedding
+             ORDER BY n.uuid
+             LIMIT {}",
+            cursor,
+            group_filter("n", group_ids),
+            limit
+        );
+
+        let result = self.run(&cypher).await?;
+
+        let mut entities = Vec::new();
+        for row in result.data {
+            let mut values = row.into_iter();
+            let uuid = match values.next() {
+                Some(falkordb::FalkorValue::String(uuid)) => uuid,
+                other => return Err(anyhow::anyhow!("Unexpected uuid value: {:?}", other)),
+            };
+            let name = match values.next() {
+                Some(falkordb::FalkorValue::String(name)) => name,
+                _ => String::new(),
+            };
+            let embedding = values.next().and_then(|value| vector_value(&value));
+            entities.push(EntityEmbedding {
+                uuid,
+                name,
+                embedding,
+            });
+        }
+        Ok(entities)
+    }
+
+    /// Overwrite `name_embedding` on each entity in one batched update,
+    /// returning how many entities were written
+    #[instrument(skip(self, embeddings), fields(count = embeddings.len()))]
+    pub async fn set_name_embeddings(
+        &mut self,
+        embeddings: &[(String, Vec<f32>)],
+    ) -> Result<usize> {
+        if embeddings.is_empty() {
+            return Ok(0);
+        }
+        let cypher = name_embedding_update_query(embeddings);
+        let result = self.run(&cypher).await?;
+        first_count(result.data)
+    }
+}
+
+/// FalkorDB reports an exceeded `TIMEOUT` as "Query timed out"
+fn is_timeout_error(message: &str) -> bool {
+    message.to_lowercase().contains("timed out")
+}
+
+fn embedding_literal(embedding: &[f32]) -> String {
+    embedding
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Batched `SET` of entity name embeddings, keyed by uuid
+fn name_embedding_update_query(embeddings: &[(String, Vec<f32>)]) -> String {
+    let rows = embeddings
+        .iter()
+        .map(|(uuid, embedding)| {
+            format!(
+                "{{uuid: '{}', embedding: [{}]}}",
+                uuid.replace('\'', "\\'"),
+                embedding_literal(embedding)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        "UNWIND [{}] AS row
+         MATCH (n:Entity {{uuid: row.uuid}})
+         SET n.name_embedding = vecf32(row.embedding)
+         RETURN count(n)",
+        rows
+    )
+}
+
+/// A `vecf32` value, or a plain list of numbers
+fn vector_value(value: &falkordb::FalkorValue) -> Option<Vec<f32>> {
+    match value {
+        falkordb::FalkorValue::Vec32(vector) => Some(vector.values.clone()),
+        falkordb::FalkorValue::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                falkordb::FalkorValue::F64(f) => Some(*f as f32),
+                falkordb::FalkorValue::I64(i) => Some(*i as f32),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Cypher expression scoring `property` against the query embedding, where
+/// higher is more similar. FalkorDB has no dot-product function, so dot is
+/// derived from L2 distances: a·b = (|a|² + |b|² - |a - b|²) / 2.
+fn similarity_score(metric: DistanceMetric, property: &str, embedding: &[f32]) -> String {
+    let query = format!("vecf32([{}])", embedding_literal(embedding));
+    match metric {
+        DistanceMetric::Cosine => format!("(2 - vec.cosineDistance({property}, {query}))/2"),
+        DistanceMetric::Euclidean => {
+            format!("1.0 / (1.0 + vec.euclideanDistance({property}, {query}))")
+        }
+        DistanceMetric::Dot => {
+            let query_norm_sq: f32 = embedding.iter().map(|v| v * v).sum();
+            let origin = format!("vecf32([{}])", vec!["0"; embedding.len()].join(","));
+            format!(
+                "({query_norm_sq} + vec.euclideanDistance({property}, {origin})^2 - vec.euclideanDistance({property}, {query})^2) / 2"
+            )
+        }
+    }
+}
+
+/// Nearest neighbours fetched from the vector index per requested result,
+/// leaving room for the group and score filters applied afterwards
+const VECTOR_INDEX_OVERFETCH: usize = 4;
+
+/// Approximate nearest-neighbour node search through the `name_embedding`
+/// vector index. Candidates are rescored with the same expression as the
+/// scan so scores stay comparable between the two paths.
+fn indexed_similarity_query(
+    embedding: &[f32],
+    limit: usize,
+    min_score: f32,
+    group_ids: Option<&[String]>,
+) -> String {
+    format!(
+        "CALL db.idx.vector.queryNodes('Entity', 'name_embedding', {}, vecf32([{}]))
+         YIELD node AS n
+         WITH n WHERE n.name_embedding IS NOT NULL{}
+         WITH n, {} AS score
+         WHERE score >= {}
+         RETURN n, score
+         ORDER BY score DESC
+         LIMIT {}",
+        limit.saturating_mul(VECTOR_INDEX_OVERFETCH),
+        embedding_literal(embedding),
+        group_filter("n", group_ids),
+        similarity_score(DistanceMetric::Cosine, "n.name_embedding", embedding),
+        min_score,
+        limit
+    )
+}
+
+fn group_filter(alias: &str, group_ids: Option<&[String]>) -> String {
+    match group_ids {
+        Some(groups) if !groups.is_empty() => {
+            let group_list = groups
+                .iter()
+                .map(|g| format!("'{}'", g.replace('\'', "\\'")))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!(" AND {}.group_id IN [{}]", alias, group_list)
+        }
+        _ => String::new(),
+    }
+}
+
+/// `created_at` bounds; timestamps are stored as epoch seconds
+fn created_range_filter(
+    alias: &str,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+) -> String {
+    let mut filter = String::new();
+    if let Some(after) = created_after {
+        filter.push_str(&format!(
+            " AND {}.created_at >= {}",
+            alias,
+            after.timestamp()
+        ));
+    }
+    if let Some(before) = created_before {
+        filter.push_str(&format!(
+            " AND {}.created_at <= {}",
+            alias,
+            before.timestamp()
+        ));
+    }
+    filter
+}
+
+/// Upper bound on queries issued by the one-result-per-query similarity workaround
+const MAX_SIMILARITY_QUERIES: usize = 100;
+
+/// Bookkeeping for the one-result-per-query similarity workaround: tracks the
+/// hits so far and decides whether another query is worth issuing, so the
+/// number of queries never exceeds `limit`.
+struct OneAtATimeScores {
+    limit: usize,
+    min_score: f32,
+    scores: Vec<(String, f32)>,
+    queries: usize,
+    exhausted: bool,
+}
+
+impl OneAtATimeScores {
+    fn new(limit: usize, min_score: f32) -> Self {
+        Self {
+            limit,
+            min_score,
+            scores: Vec::new(),
+            queries: 0,
+            exhausted: false,
+        }
+    }
+
+    fn wants_more(&self) -> bool {
+        !self.exhausted && self.scores.len() < self.limit && self.queries < MAX_SIMILARITY_QUERIES
+    }
+
+    /// Record the result of one query. No hit, or a hit below the threshold,
+    /// means there is nothing left worth fetching.
+    fn record(&mut self, hit: Option<(String, f32)>) {
+        self.queries += 1;
+        match hit {
+            Some((uuid, score)) if score >= self.min_score => self.scores.push((uuid, score)),
+            _ => self.exhausted = true,
+        }
+    }
+
+    /// Cypher clause excluding the UUIDs already found
+    fn exclusion_filter(&self, alias: &str) -> String {
+        if self.scores.is_empty() {
+            return String::new();
+        }
+        let exclude_list = self
+            .scores
+            .iter()
+            .map(|(uuid, _)| format!("'{}'", uuid))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(" AND {alias}.uuid NOT IN [{exclude_list}]")
+    }
+
+    /// UUIDs sorted by score descending, capped at the limit
+    fn into_ranked_uuids(mut self) -> Vec<String> {
+        self.scores
+            .sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        self.scores.truncate(self.limit);
+        self.scores.into_iter().map(|(uuid, _)| uuid).collect()
+    }
+}
+
+/// The `(uuid, score)` pair from the first row of a similarity probe, if any
+fn first_scored_uuid(
+    rows: impl IntoIterator<Item = Vec<falkordb::FalkorValue>>,
+) -> Option<(String, f32)> {
+    let row = rows.into_iter().next()?;
+    match (row.first(), row.get(1)) {
+        (Some(falkordb::FalkorValue::String(uuid)), Some(score_val)) => {
+            let score = match score_val {
+                falkordb::FalkorValue::F64(f) => *f as f32,
+                falkordb::FalkorValue::I64(i) => *i as f32,
+                _ => 0.0,
+            };
+            Some((uuid.clone(), score))
+        }
+        _ => None,
+    }
+}
+
+/// Read the single integer produced by a `RETURN count(...)` query
+fn first_count(rows: impl IntoIterator<Item = Vec<falkordb::FalkorValue>>) -> Result<usize> {
+    match rows
+        .into_iter()
+        .next()
+        .and_then(|row| row.into_iter().next())
+    {
+        Some(falkordb::FalkorValue::I64(count)) => Ok(count.max(0) as usize),
+        Some(other) => Err(anyhow::anyhow!("Unexpected count value: {:?}", other)),
+        None => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::falkor::create_falkor_pool;
+    use crate::models::{CommunityReranker, CommunitySearchConfig, SearchFilters};
+    use crate::search::SearchEngine;
+
+    #[test]
+    fn test_similarity_score_expression_per_metric() {
+        let embedding = [3.0_f32, 4.0];
+        let cosine = similarity_score(DistanceMetric::Cosine, "n.name_embedding", &embedding);
+        assert!(cosine.contains("vec.cosineDistance(n.name_embedding, vecf32([3,4]))"));
+
+        let euclidean = similarity_score(DistanceMetric::Euclidean, "n.name_embedding", &embedding);
+        assert!(euclidean.contains("vec.euclideanDistance(n.name_embedding, vecf32([3,4]))"));
+
+        // |q|² = 25 and the stored norm comes from the distance to the origin
+        let dot = similarity_score(DistanceMetric::Dot, "n.name_embedding", &embedding);
+        assert!(dot.starts_with("(25 + "));
+        assert!(dot.contains("vec.euclideanDistance(n.name_embedding, vecf32([0,0]))"));
+    }
+
+    #[test]
+    fn test_indexed_similarity_query_overfetches_and_filters() {
+        let groups = vec!["g1".to_string()];
+        let cypher = indexed_similarity_query(&[3.0, 4.0], 5, 0.5, Some(&groups));
+        assert!(cypher
+            .contains("db.idx.vector.queryNodes('Entity', 'name_embedding', 20, vecf32([3,4]))"));
+        assert!(cypher.contains("n.group_id IN ['g1']"));
+        assert!(cypher.contains("WHERE score >= 0.5"));
+        assert!(cypher.trim_end().ends_with("LIMIT 5"));
+    }
+
+    #[test]
+    fn test_name_embedding_update_is_one_batched_query() {
+        let cypher = name_embedding_update_query(&[
+            ("a".to_string(), vec![0.5, 1.0]),
+            ("o'b".to_string(), vec![2.0, 0.0]),
+        ]);
+        assert!(cypher.starts_with(
+            "UNWIND [{uuid: 'a', embedding: [0.5,1]},{uuid: 'o\\'b', embedding: [2,0]}] AS row"
+        ));
+        assert!(cypher.contains("SET n.name_embedding = vecf32(row.embedding)"));
+    }
+
+    #[test]
+    fn test_timeout_error_detection() {
+        assert!(is_timeout_error("Query timed out"));
+        assert!(!is_timeout_error("Unknown function 'foo'"));
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB instance running on FALKORDB_HOST"]
+    async fn test_expensive_query_aborts_at_timeout() {
+        let mut config = Config::from_env().unwrap();
+        config.query_timeout_ms = 1;
+        let mut client = FalkorClientV2::new(&config).await.unwrap();
+
+        let started = std::time::Instant::now();
+        let error = client
+            .run("UNWIND range(1, 100000000) AS x WITH x WHERE x % 7 = 0 RETURN count(x)")
+            .await
+            .err()
+            .expect("query should time out");
+
+        assert!(error.downcast_ref::<QueryTimeout>().is_some(), "{error}");
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB instance running on FALKORDB_HOST"]
+    async fn test_similarity_search_orders_results_for_each_metric() {
+        let config = Config::from_env().unwrap();
+        let mut client = FalkorClientV2::new(&config).await.unwrap();
+        let group = format!("metric-test-{}", uuid::Uuid::new_v4());
+        let uuids: Vec<String> = (0..3).map(|_| uuid::Uuid::new_v4().to_string()).collect();
+
+        // Increasingly far from the query [1, 0]
+        for (uuid, embedding) in uuids.iter().zip(["1.0, 0.0", "0.8, 0.6", "0.0, 1.0"]) {
+            client
+                .graph
+                .query(&format!(
+                    "CREATE (:Entity {{uuid: '{uuid}', name: '{uuid}', summary: '', \
+                     group_id: '{group}', created_at: 0, name_embedding: vecf32([{embedding}])}})"
+                ))
+                .execute()
+                .await
+                .unwrap();
+        }
+
+        let groups = vec![group];
+        for metric in [
+            DistanceMetric::Cosine,
+            DistanceMetric::Euclidean,
+            DistanceMetric::Dot,
+        ] {
+            let nodes = client
+                .similarity_search_nodes(&[1.0, 0.0], 10, f32::MIN, metric, Some(&groups))
+                .await
+                .unwrap();
+            let found: Vec<String> = nodes.iter().map(|n| n.uuid.to_string()).collect();
+            assert_eq!(found, uuids, "unexpected order for {metric:?}");
+        }
+    }
+
+    #[cfg(feature = "vector-index-tests")]
+    #[tokio::test]
+    async fn test_vector_index_results_overlap_scan() {
+        let mut config = Config::from_env().unwrap();
+        config.use_vector_index = false;
+        let mut scan = FalkorClientV2::new(&config).await.unwrap();
+        config.use_vector_index = true;
+        let mut indexed = FalkorClientV2::new(&config).await.unwrap();
+
+        // Query with a stored embedding so there are guaranteed neighbours
+        let mut rows = scan
+            .run("MATCH (n:Entity) WHERE n.name_embedding IS NOT NULL RETURN n.name_embedding LIMIT 1")
+            .await
+            .unwrap()
+            .data;
+        let embedding = match rows.next().and_then(|row| row.into_iter().next()) {
+            Some(falkordb::FalkorValue::Vec32(vector)) => vector.values,
+            other => panic!("expected a stored embedding, got {other:?}"),
+        };
+        drop(rows);
+
+        let uuids = |nodes: Vec<Node>| -> Vec<String> {
+            nodes.iter().map(|n| n.uuid.to_string()).collect()
+        };
+        let scanned = uuids(
+            scan.similarity_search_nodes(&embedding, 10, f32::MIN, DistanceMetric::Cosine, None)
+                .await
+                .unwrap(),
+        );
+        let found = uuids(
+            indexed
+                .similarity_search_nodes(&embedding, 10, f32::MIN, DistanceMetric::Cosine, None)
+                .await
+                .unwrap(),
+        );
+
+        assert!(
+            !indexed.vector_index_missing,
+            "index query fell back to the scan; create the name_embedding vector index"
+        );
+        let overlap = found.iter().filter(|uuid| scanned.contains(uuid)).count();
+        assert!(
+            overlap * 10 >= scanned.len() * 8,
+            "indexed results share only {overlap} of {} scanned results",
+            scanned.len()
+        );
+    }
+
+    #[test]
+    fn test_one_at_a_time_queries_bounded_by_limit() {
+        let mut collector = OneAtATimeScores::new(3, 0.5);
+        let mut queries = 0;
+        while collector.wants_more() {
+            queries += 1;
+            collector.record(Some((format!("edge-{queries}"), 0.9)));
+        }
+        assert_eq!(queries, 3);
+        assert_eq!(collector.into_ranked_uuids().len(), 3);
+
+        // A hit below the threshold ends the search without another query
+        let mut collector = OneAtATimeScores::new(10, 0.5);
+        collector.record(Some(("edge-1".to_string(), 0.8)));
+        collector.record(Some(("edge-2".to_string(), 0.2)));
+        assert!(!collector.wants_more());
+        assert_eq!(collector.into_ranked_uuids(), vec!["edge-1".to_string()]);
+
+        assert!(!OneAtATimeScores::new(0, 0.5).wants_more());
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB instance running on FALKORDB_HOST"]
+    async fn test_episode_search_orders_by_time_within_range() {
+        let config = Config::from_env().unwrap();
+        let mut client = FalkorClientV2::new(&config).await.unwrap();
+        let token = format!("quokkadate{}", uuid::Uuid::new_v4().simple());
+
+        // Episodes on consecutive days, inserted out of order
+        let day = 86_400;
+        let base = 1_700_000_000;
+        let mut uuids = Vec::new();
+        for offset in [2, 0, 3, 1] {
+            let uuid = uuid::Uuid::new_v4().to_string();
+            client
+                .graph
+                .query(&format!(
+                    "CREATE (:Episode {{uuid: '{uuid}', name: 'episode {offset}', \
+                     content: 'notes about {token}', created_at: {}}})",
+                    base + offset * day
+                ))
+                .execute()
+                .await
+                .unwrap();
+            uuids.push((offset, uuid));
+        }
+
+        let after = DateTime::from_timestamp(base + day, 0);
+        let before = DateTime::from_timestamp(base + 2 * day, 0);
+        let results = client
+            .fulltext_search_episodes(&token, None, after, before, true, 10)
+            .await;
+
+        client
+            .graph
+            .query(&format!(
+                "MATCH (e:Episode) WHERE e.content CONTAINS '{token}' DELETE e"
+            ))
+            .execute()
+            .await
+            .unwrap();
+
+        let episodes = results.unwrap();
+        let expected: Vec<String> = [2, 1]
+            .iter()
+            .map(|day| {
+                uuids
+                    .iter()
+                    .find(|(offset, _)| offset == day)
+                    .unwrap()
+                    .1
+                    .clone()
+            })
+            .collect();
+        let found: Vec<String> = episodes.iter().map(|e| e.uuid.to_string()).collect();
+        assert_eq!(found, expected);
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB instance running on FALKORDB_HOST"]
+    async fn test_text_only_community_search() {
+        let config = Config::from_env().unwrap();
+        let uuid = uuid::Uuid::new_v4().to_string();
+
+        let mut client = FalkorClientV2::new(&config).await.unwrap();
+        client
+            .graph
+            .query(&format!(
+                "CREATE (:Community {{uuid: '{uuid}', name: 'Zebraflux Research Group', \
+                 summary: 'Members studying zebraflux dynamics', created_at: 0}})"
+            ))
+            .execute()
+            .await
+            .unwrap();
+
+        let falkor_pool = create_falkor_pool(&config).await.unwrap();
+        let redis_pool = deadpool_redis::Config::from_url(config.redis_url.clone())
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+        let engine = SearchEngine::new(falkor_pool, redis_pool);
+        let community_config = CommunitySearchConfig {
+            reranker: CommunityReranker::Rrf,
+            sim_min_score: 0.7,
+            mmr_lambda: 0.5,
+            distance_metric: DistanceMetric::Cosine,
+        };
+
+        let results = engine
+            .search_communities(
+                "zebraflux",
+                &community_config,
+                &SearchFilters::default(),
+                None,
+            )
+            .await;
+
+        client
+            .graph
+            .query(&format!("MATCH (c:Community {{uuid: '{uuid}'}}) DELETE c"))
+            .execute()
+            .await
+            .unwrap();
+
+        let communities = results.unwrap();
+        assert!(communities.iter().any(|c| c.uuid.to_string() == uuid));
+    }
 }
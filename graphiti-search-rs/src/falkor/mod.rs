@@ -2,7 +2,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use deadpool::managed::{Manager, Object, Pool, RecycleResult};
 use std::sync::Arc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::config::Config;
 use crate::error::{SearchError, SearchResult};
@@ -40,9 +40,27 @@ impl Manager for FalkorManager {
 
     async fn create(&self) -> Result<FalkorClientV2, Self::Error> {
         debug!("Creating new FalkorDB connection with SDK");
-        FalkorClientV2::new(&self.config)
-            .await
-            .map_err(|e| SearchError::Database(format!("Failed to create connection: {e}")))
+        let mut last_error = None;
+
+        // Use the first endpoint that connects and answers a ping
+        for endpoint in &self.config.falkor_hosts {
+            let attempt = match FalkorClientV2::connect(&self.config, endpoint).await {
+                Ok(mut client) => client.ping().await.map(|_| client),
+                Err(e) => Err(e),
+            };
+            match attempt {
+                Ok(client) => return Ok(client),
+                Err(e) => {
+                    warn!("FalkorDB host {endpoint} unavailable: {e}");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(SearchError::Database(match last_error {
+            Some(e) => format!("Failed to create connection: {e}"),
+            None => "Failed to create connection: no FalkorDB hosts configured".to_string(),
+        }))
     }
 
     async fn recycle(
@@ -65,16 +83,66 @@ pub async fn create_falkor_pool(config: &Config) -> SearchResult<FalkorPool> {
         .build()
         .map_err(|e| SearchError::Database(format!("Failed to create pool: {e}")))?;
 
-    // Test connection
-    let conn = pool
-        .get()
-        .await
-        .map_err(|e| SearchError::Database(format!("Failed to get connection: {e}")))?;
-    drop(conn);
+    warm_up_pool(&pool, config.min_idle_connections).await?;
 
+    let status = pool.status();
     info!(
-        "FalkorDB connection pool created with {} connections",
-        config.max_connections
+        "FalkorDB connection pool created with {} connections ({} warm)",
+        config.max_connections, status.available
     );
     Ok(pool)
 }
+
+/// Open `min_idle` connections up front and run a trivial query on each, so
+/// the first burst of requests does not pay connection setup. At least one
+/// connection is always checked to fail fast on a bad configuration.
+pub async fn warm_up_pool(pool: &FalkorPool, min_idle: usize) -> SearchResult<()> {
+    // Hold every connection until all are checked so the pool opens distinct ones
+    let connections = futures::future::try_join_all((0..min_idle.max(1)).map(|_| async {
+        let mut conn = pool
+            .get()
+            .await
+            .map_err(|e| SearchError::Database(format!("Failed to get connection: {e}")))?;
+        conn.ping()
+            .await
+            .map_err(|e| SearchError::Database(format!("Warmup query failed: {e}")))?;
+        Ok::<_, SearchError>(conn)
+    }))
+    .await?;
+
+    debug!("Warmed up {} FalkorDB connections", connections.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB instance running on FALKORDB_HOST"]
+    async fn test_pool_warm_up_opens_min_idle_connections() {
+        let mut config = Config::from_env().unwrap();
+        config.max_connections = 8;
+        config.min_idle_connections = 3;
+
+        let pool = create_falkor_pool(&config).await.unwrap();
+        let status = pool.status();
+        assert_eq!(status.size, 3);
+        assert_eq!(status.available, 3);
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB instance running on FALKORDB_HOST"]
+    async fn test_create_falls_back_to_next_host() {
+        let mut config = Config::from_env().unwrap();
+        let good_host = format!("{}:{}", config.falkor_host, config.falkor_port);
+        config.falkor_hosts = vec!["127.0.0.1:1".to_string(), good_host];
+
+        let manager = FalkorManager::new(&config);
+        let mut conn = manager
+            .create()
+            .await
+            .expect("Fallback host should connect");
+        conn.ping().await.unwrap();
+    }
+}
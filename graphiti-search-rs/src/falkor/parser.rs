@@ -1,6 +1,7 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use redis::Value;
+use tracing::warn;
 use uuid::Uuid;
 
 use crate::models::{Edge, Episode, Node};
@@ -43,6 +44,7 @@ fn parse_single_node(node_data: &Value) -> Result<Option<Node>> {
     };
 
     let mut uuid_str = String::new();
+    let mut internal_id = None;
     let mut name = String::new();
     let mut node_type = String::new();
     let mut summary = None;
@@ -59,7 +61,7 @@ fn parse_single_node(node_data: &Value) -> Result<Option<Node>> {
                 match key.as_str() {
                     "id" => {
                         if let Value::Int(id) = &field_data[1] {
-                            uuid_str = format!("node-{id}");
+                            internal_id = Some(*id);
                         }
                     }
                     "labels" => {
@@ -90,31 +92,21 @@ fn parse_single_node(node_data: &Value) -> Result<Option<Node>> {
         }
     }
 
-    // Only return a node if we have a valid UUID
-    if !uuid_str.is_empty() {
-        // Try to parse UUID, fallback to generated one if invalid
-        let uuid = Uuid::parse_str(&uuid_str).unwrap_or_else(|_| {
-            // If it's a FalkorDB ID format (node-123), generate a deterministic UUID
-            if uuid_str.starts_with("node-") {
-                Uuid::new_v5(&Uuid::NAMESPACE_OID, uuid_str.as_bytes())
-            } else {
-                Uuid::new_v4()
-            }
-        });
-
-        Ok(Some(Node {
-            uuid,
-            name,
-            node_type,
-            summary,
-            created_at,
-            embedding: None,
-            group_id,
-            centrality,
-        }))
-    } else {
-        Ok(None)
-    }
+    // Only return a node if it has a uuid property or at least an internal id
+    let Some(uuid) = resolve_node_uuid(&uuid_str, internal_id) else {
+        return Ok(None);
+    };
+
+    Ok(Some(Node {
+        uuid,
+        name,
+        node_type,
+        summary,
+        created_at,
+        embedding: None,
+        group_id,
+        centrality,
+    }))
 }
 
 /// Parse node properties from FalkorDB property list
@@ -217,6 +209,8 @@ fn parse_single_edge(
     let mut uuid_str = String::new();
     let mut source_uuid_str = String::new();
     let mut target_uuid_str = String::new();
+    let mut source_internal_id = None;
+    let mut target_internal_id = None;
     let mut fact = String::new();
     let mut created_at = Utc::now();
     let mut group_id = None;
@@ -230,7 +224,7 @@ fn parse_single_edge(
                     let key = extract_string(&field_data[0]).unwrap_or_default();
                     if key == "id" {
                         if let Value::Int(id) = &field_data[1] {
-                            source_uuid_str = format!("node-{id}");
+                            source_internal_id = Some(*id);
                         }
                     } else if key == "properties" {
                         if let Value::Bulk(ref props) = &field_data[1] {
@@ -262,7 +256,7 @@ fn parse_single_edge(
                     let key = extract_string(&field_data[0]).unwrap_or_default();
                     if key == "id" {
                         if let Value::Int(id) = &field_data[1] {
-                            target_uuid_str = format!("node-{id}");
+                            target_internal_id = Some(*id);
                         }
                     } else if key == "properties" {
                         if let Value::Bulk(ref props) = &field_data[1] {
@@ -320,8 +314,10 @@ fn parse_single_edge(
 
     // Create UUIDs
     let uuid = create_uuid(&uuid_str);
-    let source_node_uuid = create_uuid(&source_uuid_str);
-    let target_node_uuid = create_uuid(&target_uuid_str);
+    let source_node_uuid =
+        resolve_node_uuid(&source_uuid_str, source_internal_id).unwrap_or_else(Uuid::new_v4);
+    let target_node_uuid =
+        resolve_node_uuid(&target_uuid_str, target_internal_id).unwrap_or_else(Uuid::new_v4);
 
     Ok(Some(Edge {
         uuid,
@@ -517,6 +513,35 @@ fn extract_string(value: &Value) -> Option<String> {
     }
 }
 
+/// Resolve a node's UUID, preferring its `uuid` property over FalkorDB's internal id.
+///
+/// The internal id is only used (as a deterministic v5 UUID of `node-{id}`) when the
+/// node has no parseable `uuid` property, since those ids are reused after deletes.
+fn resolve_node_uuid(uuid_prop: &str, internal_id: Option<i64>) -> Option<Uuid> {
+    if let Ok(uuid) = Uuid::parse_str(uuid_prop) {
+        return Some(uuid);
+    }
+
+    match internal_id {
+        Some(id) => {
+            if uuid_prop.is_empty() {
+                warn!("Node {id} has no uuid property, falling back to internal id");
+            } else {
+                warn!("Node {id} has unparseable uuid {uuid_prop:?}, falling back to internal id");
+            }
+            Some(Uuid::new_v5(
+                &Uuid::NAMESPACE_OID,
+                format!("node-{id}").as_bytes(),
+            ))
+        }
+        None if !uuid_prop.is_empty() => {
+            warn!("Node has unparseable uuid {uuid_prop:?} and no internal id");
+            Some(Uuid::new_v4())
+        }
+        None => None,
+    }
+}
+
 /// Create UUID from string, with fallback
 #[allow(dead_code)]
 fn create_uuid(uuid_str: &str) -> Uuid {
@@ -536,3 +561,44 @@ fn create_uuid(uuid_str: &str) -> Uuid {
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(key: &str, value: Value) -> Value {
+        Value::Bulk(vec![Value::Data(key.as_bytes().to_vec()), value])
+    }
+
+    fn falkor_node(id: i64, uuid: Option<&str>) -> Value {
+        let mut props = vec![pair("name", Value::Data(b"Alice".to_vec()))];
+        if let Some(uuid) = uuid {
+            props.push(pair("uuid", Value::Data(uuid.as_bytes().to_vec())));
+        }
+        // The internal id comes after the properties to make sure field order doesn't matter
+        Value::Bulk(vec![
+            pair("labels", Value::Bulk(vec![Value::Data(b"Entity".to_vec())])),
+            pair("properties", Value::Bulk(props)),
+            pair("id", Value::Int(id)),
+        ])
+    }
+
+    #[test]
+    fn test_node_uuid_property_used_verbatim() {
+        let uuid = "5f0c3a52-8a4e-4d8b-9f7e-2b1d6c9e4a10";
+        let node = parse_single_node(&falkor_node(42, Some(uuid)))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(node.uuid, Uuid::parse_str(uuid).unwrap());
+        assert_ne!(node.uuid, Uuid::new_v5(&Uuid::NAMESPACE_OID, b"node-42"));
+        assert_eq!(node.name, "Alice");
+    }
+
+    #[test]
+    fn test_node_without_uuid_falls_back_to_internal_id() {
+        let node = parse_single_node(&falkor_node(42, None)).unwrap().unwrap();
+
+        assert_eq!(node.uuid, Uuid::new_v5(&Uuid::NAMESPACE_OID, b"node-42"));
+    }
+}
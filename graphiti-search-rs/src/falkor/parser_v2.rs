@@ -5,7 +5,7 @@ use chrono::{DateTime, Utc};
 use falkordb::{FalkorValue, LazyResultSet};
 use uuid::Uuid;
 
-use crate::models::{Edge, Episode, Node};
+use crate::models::{Community, Edge, Episode, Node};
 
 /// Parse nodes from FalkorDB LazyResultSet
 pub fn parse_nodes_from_falkor_v2(result: LazyResultSet<'_>) -> Result<Vec<Node>> {
@@ -142,6 +142,24 @@ pub fn parse_episodes_from_falkor_v2(result: LazyResultSet<'_>) -> Result<Vec<Ep
     Ok(episodes)
 }
 
+/// Parse communities from FalkorDB LazyResultSet
+pub fn parse_communities_from_falkor_v2(result: LazyResultSet<'_>) -> Result<Vec<Community>> {
+    let mut communities = Vec::new();
+
+    // Iterate over the lazy result set
+    for row in result {
+        for value in row {
+            if let FalkorValue::Node(falkor_node) = value {
+                if let Some(community) = parse_single_community_v2(&falkor_node)? {
+                    communities.push(community);
+                }
+            }
+        }
+    }
+
+    Ok(communities)
+}
+
 /// Parse a single node from FalkorDB Node
 fn parse_single_node_v2(falkor_node: &falkordb::Node) -> Result<Option<Node>> {
     // Extract properties
@@ -154,6 +172,7 @@ fn parse_single_node_v2(falkor_node: &falkordb::Node) -> Result<Option<Node>> {
     let created_at =
         get_datetime_property(falkor_node, "created_at").unwrap_or_else(|_| Utc::now());
     let centrality = get_optional_float_property(falkor_node, "centrality").map(|f| f as f32);
+    let embedding = get_optional_vector_property(falkor_node, "name_embedding");
 
     // Parse UUID
     let uuid = Uuid::parse_str(&uuid_str).map_err(|e| anyhow!("Failed to parse UUID: {}", e))?;
@@ -164,7 +183,7 @@ fn parse_single_node_v2(falkor_node: &falkordb::Node) -> Result<Option<Node>> {
         node_type,
         summary,
         created_at,
-        embedding: None, // Embeddings are not returned in queries
+        embedding,
         group_id,
         centrality,
     }))
@@ -232,6 +251,31 @@ fn parse_single_episode_v2(falkor_node: &falkordb::Node) -> Result<Option<Episod
     }))
 }
 
+/// Parse a single community from FalkorDB Node
+fn parse_single_community_v2(falkor_node: &falkordb::Node) -> Result<Option<Community>> {
+    // Check if this is a Community node
+    if !falkor_node.labels.contains(&"Community".to_string()) {
+        return Ok(None);
+    }
+
+    let uuid_str = get_string_property(falkor_node, "uuid")?;
+    let name = get_string_property(falkor_node, "name")?;
+    let summary = get_optional_string_property(falkor_node, "summary").unwrap_or_default();
+    let created_at =
+        get_datetime_property(falkor_node, "created_at").unwrap_or_else(|_| Utc::now());
+
+    let uuid = Uuid::parse_str(&uuid_str)?;
+
+    Ok(Some(Community {
+        uuid,
+        name,
+        summary,
+        members: Vec::new(), // Members are stored as HAS_MEMBER edges, not properties
+        created_at,
+        embedding: None,
+    }))
+}
+
 // Helper functions for extracting properties from FalkorNode
 fn get_string_property(node: &falkordb::Node, key: &str) -> Result<String> {
     node.properties
@@ -283,6 +327,22 @@ fn get_optional_float_property(node: &falkordb::Node, key: &str) -> Option<f64>
     })
 }
 
+/// A `vecf32` property, or a plain list of numbers
+fn get_optional_vector_property(node: &falkordb::Node, key: &str) -> Option<Vec<f32>> {
+    match node.properties.get(key)? {
+        FalkorValue::Vec32(vector) => Some(vector.values.clone()),
+        FalkorValue::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                FalkorValue::F64(f) => Some(*f as f32),
+                FalkorValue::I64(i) => Some(*i as f32),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
 // Helper functions for extracting properties from FalkorEdge
 fn get_edge_string_property(edge: &falkordb::Edge, key: &str) -> Result<String> {
     edge.properties
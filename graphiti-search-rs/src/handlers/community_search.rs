@@ -30,7 +30,7 @@ pub async fn community_search_handler(
     let start = std::time::Instant::now();
 
     // Create search engine with pools
-    let mut engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
+    let engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
 
     // Execute community search
     let communities = engine
@@ -4,7 +4,7 @@ use tracing::{error, info, instrument};
 
 use crate::embeddings::EMBEDDER;
 use crate::error::SearchResult;
-use crate::models::{Edge, EdgeSearchConfig, SearchFilters};
+use crate::models::{Edge, EdgeSearchConfig, SearchFilters, SearchMethod};
 use crate::search::SearchEngine;
 use crate::AppState;
 
@@ -20,6 +20,8 @@ pub struct EdgeSearchRequest {
 pub struct EdgeSearchResponse {
     pub edges: Vec<Edge>,
     pub total: usize,
+    /// Search methods that errored and were skipped
+    pub failed_methods: Vec<SearchMethod>,
     pub latency_ms: u64,
 }
 
@@ -55,10 +57,10 @@ pub async fn edge_search_handler(
     }
 
     // Create search engine with pools
-    let mut engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
+    let engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
 
     // Execute edge search
-    let edges = engine
+    let (edges, failed_methods) = engine
         .search_edges(
             &request.query,
             &request.config,
@@ -73,6 +75,7 @@ pub async fn edge_search_handler(
     Ok(Json(EdgeSearchResponse {
         edges,
         total,
+        failed_methods,
         latency_ms,
     }))
 }
@@ -12,6 +12,9 @@ pub struct EpisodeSearchRequest {
     pub query: String,
     pub filters: Option<SearchFilters>,
     pub limit: Option<usize>,
+    /// Return the most recent episodes first
+    #[serde(default)]
+    pub order_by_time: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,13 +32,14 @@ pub async fn episode_search_handler(
     let start = std::time::Instant::now();
 
     // Create search engine with pools
-    let mut engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
+    let engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
 
     // Execute episode search
     let episodes = engine
         .search_episodes(
             &request.query,
             &request.filters.unwrap_or_default(),
+            request.order_by_time,
             request.limit.unwrap_or(100),
         )
         .await?;
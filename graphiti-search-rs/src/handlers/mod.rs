@@ -1,11 +1,26 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
 use serde_json::json;
-use tracing::{error, info, instrument};
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::{error, info, instrument, warn};
 
-use crate::embeddings::EMBEDDER;
-use crate::error::SearchResult;
-use crate::models::{SearchRequest, SearchResults};
-use crate::search::SearchEngine;
+use crate::embeddings::{OllamaEmbedder, EMBEDDER};
+use crate::error::{SearchError, SearchResult};
+use crate::falkor::FalkorPool;
+use crate::models::{
+    ReembedProgress, ReembedRequest, SearchCounts, SearchExplanation, SearchRequest,
+    SearchStreamItem,
+};
+use crate::search::{
+    cache::CACHE_STATS, count::count_results, reembed::reembed_entities, SearchEngine,
+};
 use crate::AppState;
 
 pub mod community_search;
@@ -27,12 +42,14 @@ pub async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
             match conn.ping().await {
                 Ok(_) => {
                     info!("Health check passed");
+                    drop(conn);
                     (
                         StatusCode::OK,
                         Json(json!({
                             "status": "healthy",
                             "service": "graphiti-search-rs",
                             "database": "connected",
+                            "pool": pool_metrics(&state.falkor_pool),
                         })),
                     )
                 }
@@ -45,6 +62,7 @@ pub async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
                             "service": "graphiti-search-rs",
                             "database": "ping failed",
                             "error": e.to_string(),
+                            "pool": pool_metrics(&state.falkor_pool),
                         })),
                     )
                 }
@@ -59,51 +77,492 @@ pub async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
                     "service": "graphiti-search-rs",
                     "database": "connection failed",
                     "error": e.to_string(),
+                    "pool": pool_metrics(&state.falkor_pool),
                 })),
             )
         }
     }
 }
 
+/// Connection pool usage for the health endpoint
+fn pool_metrics(pool: &FalkorPool) -> serde_json::Value {
+    let status = pool.status();
+    json!({
+        "max_size": status.max_size,
+        "size": status.size,
+        "available": status.available,
+        "in_use": status.size.saturating_sub(status.available),
+        "waiting": status.waiting,
+    })
+}
+
+/// Pool utilization and cache counters since startup
+pub async fn stats_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let status = state.falkor_pool.status();
+    Json(json!({
+        "pool": {
+            "size": status.size,
+            "available": status.available,
+            "in_use": status.size.saturating_sub(status.available),
+            "max": status.max_size,
+        },
+        "cache": CACHE_STATS.snapshot(),
+    }))
+}
+
 /// Main search endpoint
 #[instrument(skip(state))]
 pub async fn search_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(mut request): Json<SearchRequest>,
-) -> SearchResult<Json<SearchResults>> {
+) -> SearchResult<Response> {
     info!("Processing search request for query: {}", request.query);
 
-    // Generate embedding if not provided
+    let start = Instant::now();
+    let degraded = ensure_query_vector(&EMBEDDER, &mut request).await;
+    let embedding_ms = start.elapsed().as_millis() as u64;
+
+    // Create search engine with pools
+    let engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
+
+    // Execute search
+    let mut results = engine.search(request).await?;
+    results.degraded = degraded;
+    results.timings.embedding_ms = embedding_ms;
+    results.latency_ms = start.elapsed().as_millis() as u64;
+
+    info!(
+        "Search completed - edges: {}, nodes: {}, episodes: {}, communities: {}, latency: {}ms",
+        results.edges.len(),
+        results.nodes.len(),
+        results.episodes.len(),
+        results.communities.len(),
+        results.latency_ms
+    );
+
+    negotiated_response(&headers, &results)
+}
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Results buffered between the search task and a slow client
+const STREAM_BUFFER: usize = 256;
+
+/// Unified search streamed as newline-delimited JSON, one `SearchStreamItem`
+/// per line. Always computed fresh rather than served from the cache.
+#[instrument(skip(state))]
+pub async fn search_stream_handler(
+    State(state): State<AppState>,
+    Json(mut request): Json<SearchRequest>,
+) -> Response {
+    info!(
+        "Processing streaming search request for query: {}",
+        request.query
+    );
+
+    let degraded = ensure_query_vector(&EMBEDDER, &mut request).await;
+    let engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+
+    tokio::spawn(async move {
+        let start = Instant::now();
+        let last = match engine.search_stream(&request, &tx).await {
+            Ok(failed_methods) => SearchStreamItem::Summary {
+                failed_methods,
+                degraded,
+                latency_ms: start.elapsed().as_millis() as u64,
+            },
+            Err(e) => {
+                error!("Streaming search failed: {}", e);
+                SearchStreamItem::Error {
+                    error: e.to_string(),
+                }
+            }
+        };
+        let _ = tx.send(last).await;
+    });
+
+    let lines = futures::stream::unfold(rx, |mut rx| async move {
+        let item = rx.recv().await?;
+        let line = serde_json::to_vec(&item).map(|mut line| {
+            line.push(b'\n');
+            line
+        });
+        Some((line, rx))
+    });
+
+    (
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Serialize as MessagePack when the client asks for it, JSON otherwise
+fn negotiated_response<T: Serialize>(headers: &HeaderMap, body: &T) -> SearchResult<Response> {
+    let wants_msgpack = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| {
+            accept.split(',').any(|media| {
+                let media = media.split(';').next().unwrap_or("").trim();
+                media.eq_ignore_ascii_case(MSGPACK_CONTENT_TYPE)
+                    || media.eq_ignore_ascii_case("application/x-msgpack")
+            })
+        });
+
+    if !wants_msgpack {
+        return Ok(Json(body).into_response());
+    }
+
+    let bytes = rmp_serde::to_vec_named(body).map_err(|e| {
+        SearchError::Internal(anyhow::anyhow!("MessagePack encoding failed: {}", e))
+    })?;
+    Ok(([(header::CONTENT_TYPE, MSGPACK_CONTENT_TYPE)], bytes).into_response())
+}
+
+/// Count matches per category without fetching result rows
+#[instrument(skip(state))]
+pub async fn search_count_handler(
+    State(state): State<AppState>,
+    Json(mut request): Json<SearchRequest>,
+) -> SearchResult<Json<SearchCounts>> {
+    info!("Processing count request for query: {}", request.query);
+
+    ensure_query_vector(&EMBEDDER, &mut request).await;
+
+    let mut conn = state
+        .falkor_pool
+        .get()
+        .await
+        .map_err(|e| SearchError::Database(format!("Failed to get connection: {}", e)))?;
+
+    let counts = count_results(&mut conn, &request).await?;
+
+    Ok(Json(counts))
+}
+
+/// Backfill missing or wrong-size entity name embeddings, a bounded number
+/// of batches per call
+#[instrument(skip(state))]
+pub async fn reembed_handler(
+    State(state): State<AppState>,
+    Json(request): Json<ReembedRequest>,
+) -> SearchResult<Json<ReembedProgress>> {
+    let mut conn = state
+        .falkor_pool
+        .get()
+        .await
+        .map_err(|e| SearchError::Database(format!("Failed to get connection: {}", e)))?;
+
+    let progress = reembed_entities(&mut conn, &EMBEDDER, &request).await?;
+    drop(conn);
+
+    // Similarity results cached before this batch ranked the old embeddings
+    if progress.embedded > 0 {
+        let engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
+        if let Err(e) = engine.invalidate_cached_searches().await {
+            warn!("Failed to invalidate cached searches after re-embed: {}", e);
+        }
+    }
+
+    info!(
+        "Re-embed finished {} batches - embedded: {}, failed: {}, done: {}",
+        progress.batches, progress.embedded, progress.failed, progress.done
+    );
+
+    Ok(Json(progress))
+}
+
+/// Edge and node results with each one's rank per search method and its
+/// reranker score, for relevance tuning. Always computed fresh.
+#[instrument(skip(state))]
+pub async fn search_explain_handler(
+    State(state): State<AppState>,
+    Json(mut request): Json<SearchRequest>,
+) -> SearchResult<Json<SearchExplanation>> {
+    info!("Processing explain request for query: {}", request.query);
+
+    ensure_query_vector(&EMBEDDER, &mut request).await;
+
+    let engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
+    let explanation = engine.explain(&request).await?;
+
+    Ok(Json(explanation))
+}
+
+/// Generate an embedding for the query if the caller did not provide one.
+/// Returns true when one was needed but couldn't be generated, leaving the
+/// search fulltext-only.
+async fn ensure_query_vector(embedder: &OllamaEmbedder, request: &mut SearchRequest) -> bool {
     if request.query_vector.is_none() && !request.query.is_empty() {
         info!("Generating embedding for query: {}", request.query);
-        match EMBEDDER.generate_embedding(&request.query).await {
+        match embedder.generate_embedding(&request.query).await {
             Ok(Some(embedding)) => {
                 info!("Generated embedding with {} dimensions", embedding.len());
                 request.query_vector = Some(embedding);
             }
             Ok(None) => {
                 info!("No embedding generated, continuing with fulltext search only");
+                return true;
             }
             Err(e) => {
                 error!("Failed to generate embedding: {}, continuing without it", e);
+                return true;
             }
         }
     }
+    false
+}
 
-    // Create search engine with pools
-    let mut engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Edge, Node, SearchMethod, SearchResults};
+    use axum::http::HeaderValue;
 
-    // Execute search
-    let results = engine.search(request).await?;
+    fn sample_results() -> SearchResults {
+        let created_at = chrono::Utc::now();
+        let node = Node {
+            uuid: uuid::Uuid::new_v4(),
+            name: "Alice".to_string(),
+            node_type: "Entity".to_string(),
+            summary: Some("A person".to_string()),
+            created_at,
+            embedding: Some(vec![0.25, 0.5]),
+            group_id: Some("g".to_string()),
+            centrality: None,
+        };
+        let edge = Edge {
+            uuid: uuid::Uuid::new_v4(),
+            source_node_uuid: node.uuid,
+            target_node_uuid: uuid::Uuid::new_v4(),
+            fact: "Alice knows Bob".to_string(),
+            created_at,
+            episodes: vec![uuid::Uuid::new_v4()],
+            group_id: None,
+            weight: 1.0,
+        };
+        SearchResults {
+            edges: vec![edge],
+            nodes: vec![node],
+            episodes: vec![],
+            communities: vec![],
+            failed_methods: vec![SearchMethod::Similarity],
+            cache_hit: false,
+            cache_age_ms: None,
+            degraded: false,
+            latency_ms: 12,
+            timings: Default::default(),
+        }
+    }
 
-    info!(
-        "Search completed - edges: {}, nodes: {}, episodes: {}, communities: {}, latency: {}ms",
-        results.edges.len(),
-        results.nodes.len(),
-        results.episodes.len(),
-        results.communities.len(),
-        results.latency_ms
-    );
+    async fn body_bytes(response: Response) -> Vec<u8> {
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap()
+            .to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_search_results_negotiate_msgpack_and_json() {
+        let results = sample_results();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/msgpack"),
+        );
+        let response = negotiated_response(&headers, &results).unwrap();
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            MSGPACK_CONTENT_TYPE
+        );
+        let decoded: SearchResults = rmp_serde::from_slice(&body_bytes(response).await).unwrap();
+        assert_eq!(decoded, results);
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        let response = negotiated_response(&headers, &results).unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let decoded: SearchResults = serde_json::from_slice(&body_bytes(response).await).unwrap();
+        assert_eq!(decoded, results);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_timeout_degrades_to_fulltext() {
+        // Embedding backend that accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                held.push(stream);
+            }
+        });
+
+        let embedder = OllamaEmbedder::with_timeout(
+            format!("http://{}/v1", addr),
+            "test-model".to_string(),
+            std::time::Duration::from_millis(100),
+        );
+        let mut request: SearchRequest = serde_json::from_value(serde_json::json!({
+            "query": "alice",
+            "config": {"limit": 10, "reranker_min_score": 0.0},
+            "filters": {},
+        }))
+        .unwrap();
+
+        let start = std::time::Instant::now();
+        let degraded = ensure_query_vector(&embedder, &mut request).await;
+
+        assert!(degraded);
+        assert!(request.query_vector.is_none());
+        assert!(start.elapsed() < std::time::Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB and Redis instances from FALKORDB_HOST and REDIS_URL"]
+    async fn test_stats_count_cache_lookups_of_searches() {
+        let request: SearchRequest = serde_json::from_value(serde_json::json!({
+            "query": format!("stats-{}", uuid::Uuid::new_v4()),
+            "config": {"limit": 10, "reranker_min_score": 0.0},
+            "filters": {},
+            "query_vector": [],
+        }))
+        .unwrap();
+
+        let config = crate::config::Config::from_env().unwrap();
+        let state = AppState {
+            falkor_pool: crate::falkor::create_falkor_pool(&config).await.unwrap(),
+            redis_pool: deadpool_redis::Config::from_url(config.redis_url.clone())
+                .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+                .unwrap(),
+            config,
+        };
+
+        let before = CACHE_STATS.snapshot();
+        // A fresh query misses, then the repeat is served from Redis
+        for _ in 0..2 {
+            search_handler(
+                State(state.clone()),
+                HeaderMap::new(),
+                Json(request.clone()),
+            )
+            .await
+            .unwrap();
+        }
+
+        let stats: serde_json::Value = serde_json::from_slice(
+            &body_bytes(stats_handler(State(state)).await.into_response()).await,
+        )
+        .unwrap();
+        let counter = |name: &str| stats["cache"][name].as_u64().unwrap();
+        assert!(counter("misses") > before.misses);
+        assert!(counter("hits") > before.hits);
+        assert!(counter("coalesced") >= before.coalesced);
+        assert!(counter("negative_cache_marks") > before.negative_cache_marks);
+
+        let pool = &stats["pool"];
+        let size = pool["size"].as_u64().unwrap();
+        assert!(size >= 1);
+        assert!(size <= pool["max"].as_u64().unwrap());
+        assert_eq!(
+            pool["available"].as_u64().unwrap() + pool["in_use"].as_u64().unwrap(),
+            size
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB and Redis instances from FALKORDB_HOST and REDIS_URL"]
+    async fn test_stream_reassembles_to_batch_results() {
+        let request: SearchRequest = serde_json::from_value(serde_json::json!({
+            "query": "alice",
+            "config": {
+                "edge_config": {
+                    "search_methods": ["fulltext"],
+                    "reranker": "rrf",
+                    "bfs_max_depth": 1,
+                    "sim_min_score": 0.7,
+                    "mmr_lambda": 0.5,
+                },
+                "node_config": {
+                    "search_methods": ["fulltext"],
+                    "reranker": "rrf",
+                    "bfs_max_depth": 1,
+                    "sim_min_score": 0.7,
+                    "mmr_lambda": 0.5,
+                    "centrality_boost_factor": null,
+                },
+                "limit": 50,
+                "reranker_min_score": 0.0,
+            },
+            "filters": {},
+            "query_vector": [],
+            "bypass_cache": true,
+        }))
+        .unwrap();
+
+        let config = crate::config::Config::from_env().unwrap();
+        let state = AppState {
+            falkor_pool: crate::falkor::create_falkor_pool(&config).await.unwrap(),
+            redis_pool: deadpool_redis::Config::from_url(config.redis_url.clone())
+                .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+                .unwrap(),
+            config,
+        };
 
-    Ok(Json(results))
+        let batch = search_handler(
+            State(state.clone()),
+            HeaderMap::new(),
+            Json(request.clone()),
+        )
+        .await
+        .unwrap();
+        let batch: SearchResults = serde_json::from_slice(&body_bytes(batch).await).unwrap();
+
+        let stream = search_stream_handler(State(state), Json(request)).await;
+        assert_eq!(stream.headers()[header::CONTENT_TYPE], NDJSON_CONTENT_TYPE);
+        let body = body_bytes(stream).await;
+        let items: Vec<SearchStreamItem> = body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+
+        let mut streamed = SearchResults {
+            edges: vec![],
+            nodes: vec![],
+            episodes: vec![],
+            communities: vec![],
+            failed_methods: vec![],
+            cache_hit: false,
+            cache_age_ms: None,
+            degraded: false,
+            latency_ms: 0,
+            timings: Default::default(),
+        };
+        let mut summaries = 0;
+        for item in items {
+            match item {
+                SearchStreamItem::Edge(edge) => streamed.edges.push(edge),
+                SearchStreamItem::Node(node) => streamed.nodes.push(node),
+                SearchStreamItem::Episode(episode) => streamed.episodes.push(episode),
+                SearchStreamItem::Community(community) => streamed.communities.push(community),
+                SearchStreamItem::Summary { failed_methods, .. } => {
+                    streamed.failed_methods = failed_methods;
+                    summaries += 1;
+                }
+                SearchStreamItem::Error { error } => panic!("stream failed: {}", error),
+            }
+        }
+
+        assert_eq!(summaries, 1);
+        assert_eq!(streamed.edges, batch.edges);
+        assert_eq!(streamed.nodes, batch.nodes);
+        assert_eq!(streamed.episodes, batch.episodes);
+        assert_eq!(streamed.failed_methods, batch.failed_methods);
+    }
 }
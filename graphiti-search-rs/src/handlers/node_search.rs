@@ -4,7 +4,7 @@ use tracing::{error, info, instrument};
 
 use crate::embeddings::EMBEDDER;
 use crate::error::SearchResult;
-use crate::models::{Node, NodeSearchConfig, SearchFilters};
+use crate::models::{Node, NodeSearchConfig, SearchFilters, SearchMethod};
 use crate::search::SearchEngine;
 use crate::AppState;
 
@@ -20,6 +20,8 @@ pub struct NodeSearchRequest {
 pub struct NodeSearchResponse {
     pub nodes: Vec<Node>,
     pub total: usize,
+    /// Search methods that errored and were skipped
+    pub failed_methods: Vec<SearchMethod>,
     pub latency_ms: u64,
 }
 
@@ -55,10 +57,10 @@ pub async fn node_search_handler(
     }
 
     // Create search engine with pools
-    let mut engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
+    let engine = SearchEngine::new(state.falkor_pool.clone(), state.redis_pool.clone());
 
     // Execute node search
-    let nodes = engine
+    let (nodes, failed_methods) = engine
         .search_nodes(
             &request.query,
             &request.config,
@@ -73,6 +75,7 @@ pub async fn node_search_handler(
     Ok(Json(NodeSearchResponse {
         nodes,
         total,
+        failed_methods,
         latency_ms,
     }))
 }
@@ -1,4 +1,6 @@
 #![allow(clippy::uninlined_format_args)]
+// The nested search futures overflow the default limit when checked for Send
+#![recursion_limit = "256"]
 
 pub mod config;
 pub mod embeddings;
@@ -6,6 +8,7 @@ pub mod error;
 pub mod falkor;
 pub mod handlers;
 pub mod models;
+pub mod request_id;
 pub mod search;
 
 // Re-export AppState
@@ -1,4 +1,6 @@
 #![allow(clippy::uninlined_format_args)]
+// The nested search futures overflow the default limit when checked for Send
+#![recursion_limit = "256"]
 
 use anyhow::Result;
 use axum::{
@@ -6,7 +8,14 @@ use axum::{
     Router,
 };
 use std::net::SocketAddr;
-use tower_http::{compression::CompressionLayer, cors::CorsLayer, trace::TraceLayer};
+use tower_http::{
+    compression::{
+        predicate::{NotForContentType, Predicate},
+        CompressionLayer, DefaultPredicate,
+    },
+    cors::CorsLayer,
+    trace::TraceLayer,
+};
 use tracing::info;
 use tracing_subscriber::{filter::EnvFilter, FmtSubscriber};
 
@@ -16,6 +25,7 @@ mod error;
 mod falkor;
 mod handlers;
 mod models;
+mod request_id;
 mod search;
 
 use crate::config::Config;
@@ -69,7 +79,11 @@ async fn main() -> Result<()> {
     // Build router
     let app = Router::new()
         .route("/health", get(health_check))
+        .route("/stats", get(handlers::stats_handler))
         .route("/search", post(search_handler))
+        .route("/search/stream", post(handlers::search_stream_handler))
+        .route("/search/count", post(handlers::search_count_handler))
+        .route("/search/explain", post(handlers::search_explain_handler))
         .route("/search/edges", post(handlers::edge_search_handler))
         .route("/search/nodes", post(handlers::node_search_handler))
         .route("/search/episodes", post(handlers::episode_search_handler))
@@ -77,9 +91,17 @@ async fn main() -> Result<()> {
             "/search/communities",
             post(handlers::community_search_handler),
         )
-        .layer(CompressionLayer::new())
+        .route("/admin/reembed", post(handlers::reembed_handler))
+        // Compressing the stream would hold lines back until the encoder flushes
+        .layer(
+            CompressionLayer::new().compress_when(
+                DefaultPredicate::new()
+                    .and(NotForContentType::const_new(handlers::NDJSON_CONTENT_TYPE)),
+            ),
+        )
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
+        .layer(axum::middleware::from_fn(request_id::propagate_request_id))
         .with_state(state);
 
     // Start server
@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Node {
     pub uuid: Uuid,
     pub name: String,
@@ -14,7 +15,7 @@ pub struct Node {
     pub centrality: Option<f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Edge {
     pub uuid: Uuid,
     pub source_node_uuid: Uuid,
@@ -26,7 +27,7 @@ pub struct Edge {
     pub weight: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Episode {
     pub uuid: Uuid,
     pub content: String,
@@ -35,7 +36,7 @@ pub struct Episode {
     pub timestamp: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Community {
     pub uuid: Uuid,
     pub name: String,
@@ -53,6 +54,10 @@ pub struct SearchRequest {
     pub center_node_uuid: Option<Uuid>,
     pub bfs_origin_node_uuids: Option<Vec<Uuid>>,
     pub query_vector: Option<Vec<f32>>,
+    /// Skip the cache lookup and compute fresh results, which then refresh the cache.
+    /// Not serialized, so it never becomes part of the cache key.
+    #[serde(default, skip_serializing)]
+    pub bypass_cache: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +68,37 @@ pub struct SearchConfig {
     pub community_config: Option<CommunitySearchConfig>,
     pub limit: usize,
     pub reranker_min_score: f32,
+    /// Overrides the MMR lambda of the edge and node configs for this request
+    #[serde(default)]
+    pub mmr_lambda: Option<f32>,
+    /// Overrides the node config's centrality boost factor for this request
+    #[serde(default)]
+    pub centrality_boost_factor: Option<f32>,
+}
+
+impl SearchConfig {
+    /// Edge config with the request-level overrides applied
+    pub fn resolved_edge_config(&self) -> Option<EdgeSearchConfig> {
+        self.edge_config.clone().map(|mut config| {
+            if let Some(lambda) = self.mmr_lambda {
+                config.mmr_lambda = lambda;
+            }
+            config
+        })
+    }
+
+    /// Node config with the request-level overrides applied
+    pub fn resolved_node_config(&self) -> Option<NodeSearchConfig> {
+        self.node_config.clone().map(|mut config| {
+            if let Some(lambda) = self.mmr_lambda {
+                config.mmr_lambda = lambda;
+            }
+            if self.centrality_boost_factor.is_some() {
+                config.centrality_boost_factor = self.centrality_boost_factor;
+            }
+            config
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +108,8 @@ pub struct EdgeSearchConfig {
     pub bfs_max_depth: usize,
     pub sim_min_score: f32,
     pub mmr_lambda: f32,
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,11 +120,23 @@ pub struct NodeSearchConfig {
     pub sim_min_score: f32,
     pub mmr_lambda: f32,
     pub centrality_boost_factor: Option<f32>,
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
+    /// Return each node's `name_embedding` in `Node.embedding`; omitted by
+    /// default to keep responses small
+    #[serde(default)]
+    pub include_embeddings: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EpisodeSearchConfig {
     pub reranker: EpisodeReranker,
+    /// Return the most recent episodes first
+    #[serde(default)]
+    pub order_by_time: bool,
+    /// Add the entities the matched episodes mention to the `nodes` results
+    #[serde(default)]
+    pub expand_entities: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,9 +144,24 @@ pub struct CommunitySearchConfig {
     pub reranker: CommunityReranker,
     pub sim_min_score: f32,
     pub mmr_lambda: f32,
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How embedding similarity is scored; higher scores are always more similar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceMetric {
+    /// `(2 - cosine distance) / 2`, in [0, 1]
+    #[default]
+    Cosine,
+    /// `1 / (1 + L2 distance)`, in (0, 1]
+    Euclidean,
+    /// Raw inner product, for models trained on dot-product similarity
+    Dot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SearchMethod {
     Fulltext,
@@ -149,11 +214,128 @@ pub struct SearchFilters {
     pub created_before: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where a search's `latency_ms` went. The parts add up to roughly the
+/// total; the remainder is request handling around them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchTimings {
+    /// Generating the query embedding
+    pub embedding_ms: u64,
+    /// FalkorDB queries, including waiting for a pooled connection
+    pub query_ms: u64,
+    /// Reranking edge and node results
+    pub rerank_ms: u64,
+    /// Looking up and storing results in Redis, or waiting on an identical
+    /// in-flight search
+    pub cache_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchResults {
     pub edges: Vec<Edge>,
     pub nodes: Vec<Node>,
     pub episodes: Vec<Episode>,
     pub communities: Vec<Community>,
+    /// Search methods that errored and were skipped for edges or nodes, e.g. `similarity`
+    #[serde(default)]
+    pub failed_methods: Vec<SearchMethod>,
+    /// Whether these results were served from the cache
+    #[serde(default)]
+    pub cache_hit: bool,
+    /// Time since the cached results were stored, on a cache hit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_age_ms: Option<u64>,
+    /// The query embedding could not be generated (e.g. it timed out), so
+    /// similarity methods were skipped and results are fulltext-only
+    #[serde(default)]
+    pub degraded: bool,
     pub latency_ms: u64,
+    #[serde(default)]
+    pub timings: SearchTimings,
+}
+
+/// One line of a `/search/stream` response, tagged with its `category`.
+/// Results come first, category by category, followed by a single `summary`
+/// line, or an `error` line if the search fails part way through.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "category", rename_all = "snake_case")]
+pub enum SearchStreamItem {
+    Edge(Edge),
+    Node(Node),
+    Episode(Episode),
+    Community(Community),
+    Summary {
+        failed_methods: Vec<SearchMethod>,
+        degraded: bool,
+        latency_ms: u64,
+    },
+    Error {
+        error: String,
+    },
+}
+
+/// Where one result came from, as reported by `/search/explain`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultExplanation {
+    pub uuid: Uuid,
+    /// 1-based position in the reranked results
+    pub rank: usize,
+    /// 1-based rank in each search method that returned the result, e.g. `fulltext`
+    pub method_ranks: BTreeMap<String, usize>,
+    /// Fused or boosted score from the reranker; absent for rerankers that only reorder
+    pub score: Option<f32>,
+}
+
+/// Reranked edge and node results with how each method contributed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchExplanation {
+    pub edges: Vec<ResultExplanation>,
+    pub nodes: Vec<ResultExplanation>,
+    #[serde(default)]
+    pub failed_methods: Vec<SearchMethod>,
+    pub latency_ms: u64,
+}
+
+/// Per-category match counts for a search request
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchCounts {
+    pub edges: usize,
+    pub nodes: usize,
+    pub episodes: usize,
+    pub communities: usize,
+}
+
+/// Request for `POST /admin/reembed`. Entities are visited in uuid order, so
+/// a run is resumed by passing the previous response's `next_cursor` as
+/// `after_uuid`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReembedRequest {
+    /// Entities fetched per batch, capped at `MAX_REEMBED_BATCH_SIZE`
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    /// Batches processed before this call returns
+    #[serde(default)]
+    pub max_batches: Option<usize>,
+    #[serde(default)]
+    pub after_uuid: Option<String>,
+    /// Expected embedding size; probed from the embedder when omitted
+    #[serde(default)]
+    pub dimension: Option<usize>,
+    #[serde(default)]
+    pub group_ids: Option<Vec<String>>,
+}
+
+/// Progress of one `POST /admin/reembed` call
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReembedProgress {
+    pub dimension: usize,
+    pub batches: usize,
+    /// Entities looked at
+    pub scanned: usize,
+    /// Entities given a new `name_embedding`
+    pub embedded: usize,
+    /// Entities the embedder returned nothing usable for; picked up again by a later run
+    pub failed: usize,
+    /// Pass as `after_uuid` to continue; `None` once every entity was visited
+    pub next_cursor: Option<String>,
+    pub done: bool,
 }
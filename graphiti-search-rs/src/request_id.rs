@@ -0,0 +1,119 @@
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::Instrument;
+
+/// Correlation header shared with the visualizer and centrality services
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Take the caller's `X-Request-Id` or generate one, run the request inside a
+/// span carrying it and echo it on the response. Outbound calls made while
+/// handling the request pick it up through [`RequestIdExt`].
+pub async fn propagate_request_id(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= 128)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request).instrument(span))
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Request id of the request being handled, if any. Work spawned onto other
+/// tasks doesn't inherit it.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+pub trait RequestIdExt {
+    /// Forward the current request id, when there is one
+    fn with_request_id(self) -> Self;
+}
+
+impl RequestIdExt for reqwest::RequestBuilder {
+    fn with_request_id(self) -> Self {
+        match current_request_id() {
+            Some(request_id) => self.header(REQUEST_ID_HEADER, request_id),
+            None => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::Service;
+
+    #[tokio::test]
+    async fn test_incoming_request_id_forwarded_on_outbound_calls() {
+        // The handler reports the header its outbound call would carry
+        let mut app = Router::new()
+            .route(
+                "/",
+                get(|| async {
+                    let outbound = reqwest::Client::new()
+                        .get("http://ollama.invalid/api/embeddings")
+                        .with_request_id()
+                        .build()
+                        .unwrap();
+                    outbound
+                        .headers()
+                        .get(REQUEST_ID_HEADER)
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string()
+                }),
+            )
+            .layer(middleware::from_fn(propagate_request_id));
+
+        let request = Request::get("/")
+            .header(REQUEST_ID_HEADER, "req-123")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.call(request).await.unwrap();
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-123");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"req-123");
+
+        // Without one, an id is generated and forwarded
+        let response = app
+            .call(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let generated = response.headers()[REQUEST_ID_HEADER].clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], generated.as_bytes());
+
+        // Outside a request nothing is added
+        let outbound = reqwest::Client::new()
+            .get("http://ollama.invalid/")
+            .with_request_id()
+            .build()
+            .unwrap();
+        assert!(outbound.headers().get(REQUEST_ID_HEADER).is_none());
+    }
+}
@@ -14,7 +14,7 @@ pub async fn _bfs_search_nodes(
 ) -> SearchResult<Vec<Node>> {
     conn.bfs_search_nodes(origin_uuids, max_depth, limit)
         .await
-        .map_err(|e| crate::error::SearchError::Database(e.to_string()))
+        .map_err(crate::error::SearchError::database)
 }
 
 /// Perform BFS to find edges within a certain depth
@@ -5,11 +5,55 @@ use lru::LruCache;
 use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, instrument};
 
+/// Process-wide cache counters. Engines, and so their caches, are created per
+/// request, so the counters can't live on them.
+pub struct CacheStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    coalesced: AtomicU64,
+    negative_cache_marks: AtomicU64,
+}
+
+pub static CACHE_STATS: CacheStats = CacheStats {
+    hits: AtomicU64::new(0),
+    misses: AtomicU64::new(0),
+    coalesced: AtomicU64::new(0),
+    negative_cache_marks: AtomicU64::new(0),
+};
+
+/// Counters reported by `GET /stats`
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStatsSnapshot {
+    /// Lookups served from Redis
+    pub hits: u64,
+    /// Lookups that had to compute the value
+    pub misses: u64,
+    /// Requests that waited on an identical in-flight request instead. The
+    /// coalescer belongs to a per-request engine, so only lookups within one
+    /// request can coalesce.
+    pub coalesced: u64,
+    /// Keys marked in the negative-cache bloom filter since startup. The
+    /// filter is cleared on invalidation, which does not reset this count.
+    pub negative_cache_marks: u64,
+}
+
+impl CacheStats {
+    pub fn snapshot(&self) -> CacheStatsSnapshot {
+        CacheStatsSnapshot {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            negative_cache_marks: self.negative_cache_marks.load(Ordering::Relaxed),
+        }
+    }
+}
+
 /// Cache entry with access tracking for adaptive TTL
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -90,6 +134,7 @@ impl<K: Clone + Eq + std::hash::Hash + Send + Sync + 'static, V: Clone + Send +
             let guard = mutex.lock().await;
             if let Some(value) = guard.as_ref() {
                 debug!("Request coalesced for key");
+                CACHE_STATS.coalesced.fetch_add(1, Ordering::Relaxed);
                 return value.clone();
             }
         }
@@ -122,15 +167,23 @@ impl<K: Clone + Eq + std::hash::Hash + Send + Sync + 'static, V: Clone + Send +
 /// Bloom filter for negative caching
 pub struct NegativeCache {
     filter: Arc<RwLock<BloomFilter>>,
-    #[allow(dead_code)]
+    expected_items: usize,
     false_positive_rate: f64,
 }
 
+lazy_static::lazy_static! {
+    /// Keys this process has stored in Redis. Process-wide, since engines and
+    /// their caches are per request; a key absent from it is computed
+    /// without a Redis lookup.
+    static ref NEGATIVE_CACHE: NegativeCache = NegativeCache::new(1_000_000, 0.01); // 1% false positive rate
+}
+
 impl NegativeCache {
     pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
         let filter = BloomFilter::with_rate(false_positive_rate as f32, expected_items as u32);
         Self {
             filter: Arc::new(RwLock::new(filter)),
+            expected_items,
             false_positive_rate,
         }
     }
@@ -144,17 +197,18 @@ impl NegativeCache {
     /// Mark key as existing
     pub async fn mark_exists(&self, key: &str) {
         let mut filter = self.filter.write().await;
-        filter.insert(&key);
+        if filter.insert(&key) {
+            CACHE_STATS
+                .negative_cache_marks
+                .fetch_add(1, Ordering::Relaxed);
+        }
     }
 
-    /// Clear the filter (use periodically to prevent saturation)
-    #[allow(dead_code)]
+    /// Clear the filter, after its keys are invalidated or to prevent saturation
     pub async fn clear(&self) {
         let mut filter = self.filter.write().await;
-        *filter = BloomFilter::with_rate(
-            self.false_positive_rate as f32,
-            1_000_000, // Default size
-        );
+        *filter =
+            BloomFilter::with_rate(self.false_positive_rate as f32, self.expected_items as u32);
     }
 }
 
@@ -215,12 +269,42 @@ impl AccessCounter {
     }
 }
 
+/// Result of `EnhancedCache::get_or_compute`
+#[derive(Debug, Clone)]
+pub struct CacheOutcome<T> {
+    pub value: Option<T>,
+    /// Whether the value was served from Redis rather than computed
+    pub hit: bool,
+    /// Time since the cached value was stored, on a hit
+    pub age_ms: Option<u64>,
+}
+
+/// A value as stored in Redis, with when it was written
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEnvelope<T> {
+    stored_at_ms: i64,
+    value: T,
+}
+
+impl<T> CacheEnvelope<T> {
+    fn new(value: T) -> Self {
+        Self {
+            stored_at_ms: chrono::Utc::now().timestamp_millis(),
+            value,
+        }
+    }
+
+    fn age_ms(&self) -> u64 {
+        (chrono::Utc::now().timestamp_millis() - self.stored_at_ms).max(0) as u64
+    }
+}
+
 /// Enhanced cache operations with all optimizations
 pub struct EnhancedCache {
     redis_pool: RedisPool,
     adaptive_ttl: AdaptiveTTL,
-    coalescer: RequestCoalescer<String, Option<String>>,
-    negative_cache: NegativeCache,
+    coalescer: RequestCoalescer<String, Option<(String, bool)>>,
+    negative_cache: &'static NegativeCache,
     access_counter: AccessCounter,
 }
 
@@ -230,7 +314,7 @@ impl EnhancedCache {
             redis_pool,
             adaptive_ttl: AdaptiveTTL::default(),
             coalescer: RequestCoalescer::new(),
-            negative_cache: NegativeCache::new(1_000_000, 0.01), // 1% false positive rate
+            negative_cache: &NEGATIVE_CACHE,
             access_counter: AccessCounter::new(10_000),
         }
     }
@@ -240,17 +324,15 @@ impl EnhancedCache {
         &self,
         key: &str,
         compute: F,
-    ) -> Result<Option<T>, anyhow::Error>
+    ) -> Result<CacheOutcome<T>, anyhow::Error>
     where
         T: Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync + 'static,
         F: FnOnce() -> Fut + Send,
         Fut: Future<Output = Result<Option<T>, anyhow::Error>> + Send,
     {
-        // Check negative cache first
-        if !self.negative_cache.might_exist(key).await {
-            debug!("Negative cache hit for key: {}", key);
-            return Ok(None);
-        }
+        // A key this process never stored is computed without a Redis lookup;
+        // one stored by another instance is only computed once more here
+        let might_be_cached = self.negative_cache.might_exist(key).await;
 
         // Increment access counter
         let access_count = self.access_counter.increment(key).await;
@@ -260,20 +342,24 @@ impl EnhancedCache {
             .coalescer
             .get_or_compute(key.to_string(), || async move {
                 // Try to get from Redis
-                if let Ok(mut conn) = self.redis_pool.get().await {
+                if !might_be_cached {
+                    debug!("Negative cache hit for key: {}", key);
+                } else if let Ok(mut conn) = self.redis_pool.get().await {
                     if let Ok(cached) = conn.get::<_, String>(key).await {
-                        if let Ok(value) = serde_json::from_str::<T>(&cached) {
+                        if serde_json::from_str::<CacheEnvelope<T>>(&cached).is_ok() {
                             debug!(
                                 "Cache hit for key: {} (access count: {})",
                                 key, access_count
                             );
-                            return serde_json::to_string(&value).ok();
+                            CACHE_STATS.hits.fetch_add(1, Ordering::Relaxed);
+                            return Some((cached, true));
                         }
                     }
                 }
 
                 // Cache miss, compute value
                 debug!("Cache miss for key: {}", key);
+                CACHE_STATS.misses.fetch_add(1, Ordering::Relaxed);
                 match compute().await {
                     Ok(Some(value)) => {
                         // Mark as existing in negative cache
@@ -286,14 +372,14 @@ impl EnhancedCache {
                             ttl, key, access_count
                         );
 
+                        let json = serde_json::to_string(&CacheEnvelope::new(value)).ok()?;
+
                         // Store in Redis with adaptive TTL
                         if let Ok(mut conn) = self.redis_pool.get().await {
-                            if let Ok(json) = serde_json::to_string(&value) {
-                                let _ = conn.set_ex::<_, _, ()>(key, &json, ttl).await;
-                            }
+                            let _ = conn.set_ex::<_, _, ()>(key, &json, ttl).await;
                         }
 
-                        serde_json::to_string(&value).ok()
+                        Some((json, false))
                     }
                     Ok(None) => {
                         // Value doesn't exist, don't add to negative cache
@@ -310,11 +396,57 @@ impl EnhancedCache {
 
         // Parse the result
         match result {
-            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
-            None => Ok(None),
+            Some((json, hit)) => {
+                let envelope: CacheEnvelope<T> = serde_json::from_str(&json)?;
+                Ok(CacheOutcome {
+                    age_ms: hit.then(|| envelope.age_ms()),
+                    value: Some(envelope.value),
+                    hit,
+                })
+            }
+            None => Ok(CacheOutcome {
+                value: None,
+                hit: false,
+                age_ms: None,
+            }),
         }
     }
 
+    /// Store a freshly computed value, replacing any cached one
+    #[instrument(skip(self, value))]
+    pub async fn refresh<T: Serialize>(&self, key: &str, value: &T) -> Result<(), anyhow::Error> {
+        let access_count = self.access_counter.increment(key).await;
+        let ttl = self.adaptive_ttl.calculate_ttl(access_count);
+        let json = serde_json::to_string(&CacheEnvelope::new(value))?;
+
+        let mut conn = self.redis_pool.get().await?;
+        conn.set_ex::<_, _, ()>(key, &json, ttl).await?;
+        self.negative_cache.mark_exists(key).await;
+        Ok(())
+    }
+
+    /// Drop every cached value whose key starts with `prefix`, e.g. after a
+    /// write that changes what those searches return. Returns how many keys
+    /// were removed.
+    #[instrument(skip(self))]
+    pub async fn invalidate_prefix(&self, prefix: &str) -> Result<usize, anyhow::Error> {
+        let mut conn = self.redis_pool.get().await?;
+        let keys: Vec<String> = {
+            let mut iter = conn.scan_match::<_, String>(format!("{}*", prefix)).await?;
+            let mut keys = Vec::new();
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+            keys
+        };
+        if !keys.is_empty() {
+            conn.del::<_, ()>(&keys).await?;
+        }
+        self.negative_cache.clear().await;
+        debug!("Invalidated {} cached keys under {}", keys.len(), prefix);
+        Ok(keys.len())
+    }
+
     /// Periodic maintenance task
     #[allow(dead_code)]
     pub async fn maintenance(&self) {
@@ -325,3 +457,21 @@ impl EnhancedCache {
         // (in production, monitor false positive rate and clear when needed)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_negative_cache_forgets_keys_when_cleared() {
+        let cache = NegativeCache::new(1_000, 0.01);
+        assert!(!cache.might_exist("search:a").await);
+
+        cache.mark_exists("search:a").await;
+        assert!(cache.might_exist("search:a").await);
+        assert!(!cache.might_exist("search:b").await);
+
+        cache.clear().await;
+        assert!(!cache.might_exist("search:a").await);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::error::{SearchError, SearchResult};
+use crate::falkor::client_v2::SimilarityThreshold;
+use crate::falkor::FalkorConnection;
+use crate::models::{DistanceMetric, SearchCounts, SearchMethod, SearchRequest};
+use crate::search::fulltext::sanitize_lucene_query;
+use tracing::instrument;
+
+/// Count matches per category using the same conditions as a full search.
+///
+/// Counts cover every match rather than the per-method fetch limit used by
+/// `/search`, so they only equal the result lengths when fewer matches exist.
+#[instrument(skip(conn, request), fields(query = %request.query))]
+pub async fn count_results(
+    conn: &mut FalkorConnection,
+    request: &SearchRequest,
+) -> SearchResult<SearchCounts> {
+    let query = sanitize_lucene_query(&request.query);
+    let group_ids = request.filters.group_ids.as_deref();
+    let mut counts = SearchCounts::default();
+
+    if let Some(edge_config) = &request.config.edge_config {
+        let (fulltext, similarity) = method_inputs(
+            &edge_config.search_methods,
+            &query,
+            request.query_vector.as_deref(),
+            edge_config.sim_min_score,
+            edge_config.distance_metric,
+        );
+        counts.edges = conn
+            .count_edges(fulltext, similarity, group_ids)
+            .await
+            .map_err(SearchError::database)?;
+    }
+
+    if let Some(node_config) = &request.config.node_config {
+        let (fulltext, similarity) = method_inputs(
+            &node_config.search_methods,
+            &query,
+            request.query_vector.as_deref(),
+            node_config.sim_min_score,
+            node_config.distance_metric,
+        );
+        counts.nodes = conn
+            .count_nodes(fulltext, similarity, group_ids)
+            .await
+            .map_err(SearchError::database)?;
+    }
+
+    if request.config.episode_config.is_some() {
+        counts.episodes = conn
+            .count_episodes(
+                &query,
+                group_ids,
+                request.filters.created_after,
+                request.filters.created_before,
+            )
+            .await
+            .map_err(SearchError::database)?;
+    }
+
+    if let Some(community_config) = &request.config.community_config {
+        let similarity = request.query_vector.as_deref().map(|vector| {
+            (
+                vector,
+                community_config.sim_min_score,
+                community_config.distance_metric,
+            )
+        });
+        counts.communities = conn
+            .count_communities(
+                (!query.is_empty()).then_some(query.as_str()),
+                similarity,
+                group_ids,
+            )
+            .await
+            .map_err(SearchError::database)?;
+    }
+
+    Ok(counts)
+}
+
+/// Which match conditions apply, mirroring the methods a full search runs
+fn method_inputs<'a>(
+    methods: &[SearchMethod],
+    query: &'a str,
+    query_vector: Option<&'a [f32]>,
+    min_score: f32,
+    metric: DistanceMetric,
+) -> (Option<&'a str>, Option<SimilarityThreshold<'a>>) {
+    let fulltext = methods
+        .iter()
+        .any(|m| matches!(m, SearchMethod::Fulltext))
+        .then_some(query);
+    let similarity = query_vector
+        .filter(|_| {
+            methods
+                .iter()
+                .any(|m| matches!(m, SearchMethod::Similarity))
+        })
+        .map(|vector| (vector, min_score, metric));
+    (fulltext, similarity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::falkor::create_falkor_pool;
+    use crate::models::{
+        EdgeReranker, EdgeSearchConfig, EpisodeReranker, EpisodeSearchConfig, NodeReranker,
+        NodeSearchConfig, SearchConfig, SearchFilters,
+    };
+    use crate::search::SearchEngine;
+
+    #[test]
+    fn test_method_inputs_follow_search_methods() {
+        let vector = [0.5_f32, 0.5];
+        let (fulltext, similarity) = method_inputs(
+            &[SearchMethod::Fulltext],
+            "alice",
+            Some(&vector),
+            0.7,
+            DistanceMetric::Cosine,
+        );
+        assert_eq!(fulltext, Some("alice"));
+        assert!(similarity.is_none());
+
+        let (fulltext, similarity) = method_inputs(
+            &[SearchMethod::Similarity],
+            "alice",
+            None,
+            0.7,
+            DistanceMetric::Cosine,
+        );
+        assert!(fulltext.is_none());
+        assert!(similarity.is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB instance running on FALKORDB_HOST"]
+    async fn test_counts_match_full_search() {
+        let config = Config::from_env().unwrap();
+        let falkor_pool = create_falkor_pool(&config).await.unwrap();
+        let redis_pool = deadpool_redis::Config::from_url(config.redis_url.clone())
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+
+        // A narrow query keeps every category below the per-method fetch limit
+        let request = SearchRequest {
+            query: "graphiti".to_string(),
+            config: SearchConfig {
+                edge_config: Some(EdgeSearchConfig {
+                    search_methods: vec![SearchMethod::Fulltext],
+                    reranker: EdgeReranker::Rrf,
+                    bfs_max_depth: 1,
+                    sim_min_score: 0.7,
+                    mmr_lambda: 0.5,
+                    distance_metric: DistanceMetric::Cosine,
+                }),
+                node_config: Some(NodeSearchConfig {
+                    search_methods: vec![SearchMethod::Fulltext],
+                    reranker: NodeReranker::Rrf,
+                    bfs_max_depth: 1,
+                    sim_min_score: 0.7,
+                    mmr_lambda: 0.5,
+                    centrality_boost_factor: None,
+                    distance_metric: DistanceMetric::Cosine,
+                    include_embeddings: false,
+                }),
+                episode_config: Some(EpisodeSearchConfig {
+                    reranker: EpisodeReranker::Rrf,
+                    order_by_time: false,
+                    expand_entities: false,
+                }),
+                community_config: None,
+                limit: 100,
+                reranker_min_score: 0.0,
+                mmr_lambda: None,
+                centrality_boost_factor: None,
+            },
+            filters: SearchFilters::default(),
+            center_node_uuid: None,
+            bfs_origin_node_uuids: None,
+            query_vector: None,
+            bypass_cache: false,
+        };
+
+        let mut conn = falkor_pool.get().await.unwrap();
+        let counts = count_results(&mut conn, &request).await.unwrap();
+        drop(conn);
+
+        let engine = SearchEngine::new(falkor_pool, redis_pool);
+        let results = engine.search(request).await.unwrap();
+
+        assert_eq!(counts.edges, results.edges.len());
+        assert_eq!(counts.nodes, results.nodes.len());
+        assert_eq!(counts.episodes, results.episodes.len());
+        assert_eq!(counts.communities, results.communities.len());
+    }
+}
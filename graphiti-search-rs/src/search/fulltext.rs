@@ -1,6 +1,6 @@
 use crate::error::SearchResult;
 use crate::falkor::FalkorConnection;
-use crate::models::{Edge, Episode, Node, SearchFilters};
+use crate::models::{Community, Edge, Episode, Node, SearchFilters};
 use regex::Regex;
 use tracing::instrument;
 
@@ -9,7 +9,7 @@ lazy_static::lazy_static! {
     static ref SPECIAL_CHARS: Regex = Regex::new(r#"[\\+\-!(){}\[\]^~*?:/]"#).unwrap();
 }
 
-fn sanitize_lucene_query(query: &str) -> String {
+pub fn sanitize_lucene_query(query: &str) -> String {
     // Escape special Lucene characters
     let escaped = SPECIAL_CHARS.replace_all(query, r"\$0");
 
@@ -43,7 +43,7 @@ pub async fn search_nodes(
     let sanitized_query = sanitize_lucene_query(query);
     conn.fulltext_search_nodes(&sanitized_query, filters.group_ids.as_deref(), limit)
         .await
-        .map_err(|e| crate::error::SearchError::Database(e.to_string()))
+        .map_err(crate::error::SearchError::database)
 }
 
 #[instrument(skip(conn))]
@@ -56,7 +56,7 @@ pub async fn search_edges(
     let sanitized_query = sanitize_lucene_query(query);
     conn.fulltext_search_edges(&sanitized_query, filters.group_ids.as_deref(), limit)
         .await
-        .map_err(|e| crate::error::SearchError::Database(e.to_string()))
+        .map_err(crate::error::SearchError::database)
 }
 
 #[instrument(skip(conn))]
@@ -64,12 +64,33 @@ pub async fn search_episodes(
     conn: &mut FalkorConnection,
     query: &str,
     filters: &SearchFilters,
+    order_by_time: bool,
     limit: usize,
 ) -> SearchResult<Vec<Episode>> {
     let sanitized_query = sanitize_lucene_query(query);
-    conn.fulltext_search_episodes(&sanitized_query, filters.group_ids.as_deref(), limit)
+    conn.fulltext_search_episodes(
+        &sanitized_query,
+        filters.group_ids.as_deref(),
+        filters.created_after,
+        filters.created_before,
+        order_by_time,
+        limit,
+    )
+    .await
+    .map_err(crate::error::SearchError::database)
+}
+
+#[instrument(skip(conn))]
+pub async fn search_communities(
+    conn: &mut FalkorConnection,
+    query: &str,
+    filters: &SearchFilters,
+    limit: usize,
+) -> SearchResult<Vec<Community>> {
+    let sanitized_query = sanitize_lucene_query(query);
+    conn.fulltext_search_communities(&sanitized_query, filters.group_ids.as_deref(), limit)
         .await
-        .map_err(|e| crate::error::SearchError::Database(e.to_string()))
+        .map_err(crate::error::SearchError::database)
 }
 
 #[cfg(test)]
@@ -1,21 +1,44 @@
 pub mod bfs;
 pub mod cache;
+pub mod count;
 pub mod fulltext;
+pub mod reembed;
 pub mod reranking;
 pub mod similarity;
 
-use crate::error::SearchResult;
-use crate::falkor::FalkorPool;
+use crate::error::{SearchError, SearchResult};
+use crate::falkor::{FalkorConnection, FalkorPool};
 use crate::models::{
     Community, CommunitySearchConfig, Edge, EdgeSearchConfig, Episode, Node, NodeSearchConfig,
-    SearchFilters, SearchMethod, SearchRequest, SearchResults,
+    ResultExplanation, SearchExplanation, SearchFilters, SearchMethod, SearchRequest,
+    SearchResults, SearchStreamItem, SearchTimings,
 };
 use deadpool_redis::Pool as RedisPool;
-use std::time::Instant;
-use tracing::{debug, instrument};
+use futures::future::join_all;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, instrument, warn};
 
 use self::cache::EnhancedCache;
 
+/// Result lists of the search methods that succeeded, and the methods that failed
+type MethodResults<T> = (Vec<(SearchMethod, Vec<T>)>, Vec<SearchMethod>);
+
+/// Query and rerank time accumulated across categories, kept unrounded
+/// until reported
+#[derive(Default)]
+struct PhaseDurations {
+    query: Duration,
+    rerank: Duration,
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
 pub struct SearchEngine {
     falkor_pool: FalkorPool,
     #[allow(dead_code)]
@@ -33,44 +56,150 @@ impl SearchEngine {
         }
     }
 
+    /// Drop every cached unified search result, after a write through this
+    /// service changed what searches return. Returns how many were dropped.
+    pub async fn invalidate_cached_searches(&self) -> SearchResult<usize> {
+        self.cache
+            .invalidate_prefix(SEARCH_CACHE_PREFIX)
+            .await
+            .map_err(|e| SearchError::Cache(e.to_string()))
+    }
+
+    /// Run a unified search, serving identical requests from the cache unless
+    /// `bypass_cache` is set. Partial results (some method failed) are returned
+    /// but never cached.
     #[instrument(skip(self))]
-    pub async fn search(&mut self, request: SearchRequest) -> SearchResult<SearchResults> {
+    pub async fn search(&self, request: SearchRequest) -> SearchResult<SearchResults> {
         let start = Instant::now();
+        let cache_key = search_cache_key(&request)?;
+
+        if request.bypass_cache {
+            let mut results = self.search_uncached(&request).await?;
+            let refresh_start = Instant::now();
+            if results.failed_methods.is_empty() {
+                if let Err(e) = self.cache.refresh(&cache_key, &results).await {
+                    warn!("Failed to refresh cached search results: {}", e);
+                }
+            }
+            results.timings.cache_ms = millis(refresh_start.elapsed());
+            results.latency_ms = millis(start.elapsed());
+            return Ok(results);
+        }
+
+        // Partial results and errors come back through here rather than the cache
+        let uncached: Mutex<Option<SearchResult<SearchResults>>> = Mutex::new(None);
+        // Time spent searching, when this request ran the search itself
+        let computed: Mutex<Option<Duration>> = Mutex::new(None);
+        let lookup_start = Instant::now();
+        let outcome = self
+            .cache
+            .get_or_compute(&cache_key, || async {
+                let compute_start = Instant::now();
+                let results = self.search_uncached(&request).await;
+                *computed.lock().unwrap() = Some(compute_start.elapsed());
+                match results {
+                    Ok(results) if results.failed_methods.is_empty() => Ok(Some(results)),
+                    other => {
+                        *uncached.lock().unwrap() = Some(other);
+                        Ok(None)
+                    }
+                }
+            })
+            .await?;
+        let computed = computed.into_inner().unwrap();
+        let cache_time = lookup_start
+            .elapsed()
+            .saturating_sub(computed.unwrap_or_default());
+
+        let mut results = match outcome.value {
+            Some(mut results) => {
+                // Served from Redis or an identical in-flight search, so the
+                // stored breakdown belongs to another request
+                if computed.is_none() {
+                    results.timings = SearchTimings::default();
+                }
+                results
+            }
+            None => match uncached.into_inner().unwrap() {
+                Some(results) => results?,
+                // Coalesced onto an identical request that produced nothing cacheable
+                None => self.search_uncached(&request).await?,
+            },
+        };
+
+        results.cache_hit = outcome.hit;
+        results.cache_age_ms = outcome.age_ms;
+        results.timings.cache_ms = millis(cache_time);
+        results.latency_ms = millis(start.elapsed());
+        debug!(
+            "Search completed in {}ms (cache hit: {})",
+            results.latency_ms, results.cache_hit
+        );
+
+        Ok(results)
+    }
+
+    async fn search_uncached(&self, request: &SearchRequest) -> SearchResult<SearchResults> {
+        let start = Instant::now();
+        let mut phases = PhaseDurations::default();
 
         let mut edges = Vec::new();
         let mut nodes = Vec::new();
         let mut episodes = Vec::new();
         let mut communities = Vec::new();
+        let mut failed_methods = Vec::new();
 
         // Execute edge search if configured
-        if let Some(edge_config) = &request.config.edge_config {
-            edges = self
-                .search_edges(
+        if let Some(edge_config) = request.config.resolved_edge_config() {
+            let (found, failed) = self
+                .search_edges_timed(
                     &request.query,
-                    edge_config,
+                    &edge_config,
                     &request.filters,
                     request.query_vector.as_deref(),
+                    &mut phases,
                 )
                 .await?;
+            edges = found;
+            record_failed(&mut failed_methods, failed);
         }
 
         // Execute node search if configured
-        if let Some(node_config) = &request.config.node_config {
-            nodes = self
-                .search_nodes(
+        if let Some(node_config) = request.config.resolved_node_config() {
+            let (found, failed) = self
+                .search_nodes_timed(
                     &request.query,
-                    node_config,
+                    &node_config,
                     &request.filters,
                     request.query_vector.as_deref(),
+                    &mut phases,
                 )
                 .await?;
+            nodes = found;
+            record_failed(&mut failed_methods, failed);
         }
 
+        // Episodes and communities are fused inline, so count entirely as query time
+        let query_start = Instant::now();
+
         // Execute episode search if configured
-        if request.config.episode_config.is_some() {
+        if let Some(episode_config) = &request.config.episode_config {
             episodes = self
-                .search_episodes(&request.query, &request.filters, request.config.limit)
+                .search_episodes(
+                    &request.query,
+                    &request.filters,
+                    episode_config.order_by_time,
+                    request.config.limit,
+                )
                 .await?;
+
+            if episode_config.expand_entities {
+                let mut seen: HashSet<_> = nodes.iter().map(|n| n.uuid).collect();
+                let entities = self
+                    .expand_episode_entities(&episodes, request, request.config.limit)
+                    .await?;
+                nodes.extend(entities.into_iter().filter(|n| seen.insert(n.uuid)));
+            }
         }
 
         // Execute community search if configured
@@ -84,128 +213,320 @@ impl SearchEngine {
                 )
                 .await?;
         }
-
-        let latency_ms = start.elapsed().as_millis() as u64;
-        debug!("Search completed in {}ms", latency_ms);
+        phases.query += query_start.elapsed();
 
         Ok(SearchResults {
             edges,
             nodes,
             episodes,
             communities,
-            latency_ms,
+            failed_methods,
+            cache_hit: false,
+            cache_age_ms: None,
+            degraded: false,
+            latency_ms: millis(start.elapsed()),
+            timings: SearchTimings {
+                query_ms: millis(phases.query),
+                rerank_ms: millis(phases.rerank),
+                ..Default::default()
+            },
         })
     }
 
-    pub async fn search_edges(
-        &mut self,
-        query: &str,
-        config: &EdgeSearchConfig,
-        filters: &SearchFilters,
-        query_vector: Option<&[f32]>,
-    ) -> SearchResult<Vec<Edge>> {
-        // Direct execution without cache
-        let mut falkor_conn = self.falkor_pool.get().await.map_err(|e| {
-            crate::error::SearchError::Database(format!("Failed to get connection: {}", e))
-        })?;
+    /// Run a unified search without the cache, sending each result through `tx`
+    /// as soon as its category has been searched. Returns the failed methods,
+    /// or stops early once the receiver has gone away.
+    pub async fn search_stream(
+        &self,
+        request: &SearchRequest,
+        tx: &mpsc::Sender<SearchStreamItem>,
+    ) -> SearchResult<Vec<SearchMethod>> {
+        let mut failed_methods = Vec::new();
+        let mut sent_nodes = HashSet::new();
 
-        let mut method_results = Vec::new();
+        if let Some(edge_config) = request.config.resolved_edge_config() {
+            let (found, failed) = self
+                .search_edges(
+                    &request.query,
+                    &edge_config,
+                    &request.filters,
+                    request.query_vector.as_deref(),
+                )
+                .await?;
+            record_failed(&mut failed_methods, failed);
+            if !send_all(tx, found.into_iter().map(SearchStreamItem::Edge)).await {
+                return Ok(failed_methods);
+            }
+        }
 
-        for method in &config.search_methods {
-            let edges = match method {
-                SearchMethod::Fulltext => {
-                    fulltext::search_edges(&mut falkor_conn, query, filters, 100).await?
-                }
-                SearchMethod::Similarity if query_vector.is_some() => {
-                    similarity::search_edges_by_embedding(
-                        &mut falkor_conn,
-                        query_vector.unwrap(),
-                        config.sim_min_score,
-                        filters,
-                        100,
-                    )
+        if let Some(node_config) = request.config.resolved_node_config() {
+            let (found, failed) = self
+                .search_nodes(
+                    &request.query,
+                    &node_config,
+                    &request.filters,
+                    request.query_vector.as_deref(),
+                )
+                .await?;
+            record_failed(&mut failed_methods, failed);
+            sent_nodes.extend(found.iter().map(|n| n.uuid));
+            if !send_all(tx, found.into_iter().map(SearchStreamItem::Node)).await {
+                return Ok(failed_methods);
+            }
+        }
+
+        if let Some(episode_config) = &request.config.episode_config {
+            let found = self
+                .search_episodes(
+                    &request.query,
+                    &request.filters,
+                    episode_config.order_by_time,
+                    request.config.limit,
+                )
+                .await?;
+            let entities = if episode_config.expand_entities {
+                self.expand_episode_entities(&found, request, request.config.limit)
                     .await?
-                }
-                SearchMethod::Bfs => {
-                    // BFS requires origin nodes, skip if not provided
-                    vec![]
-                }
-                _ => vec![],
+            } else {
+                Vec::new()
             };
+            if !send_all(tx, found.into_iter().map(SearchStreamItem::Episode)).await {
+                return Ok(failed_methods);
+            }
+            let entities = entities.into_iter().filter(|n| sent_nodes.insert(n.uuid));
+            if !send_all(tx, entities.map(SearchStreamItem::Node)).await {
+                return Ok(failed_methods);
+            }
+        }
 
-            method_results.push(edges);
+        if let Some(community_config) = &request.config.community_config {
+            let found = self
+                .search_communities(
+                    &request.query,
+                    community_config,
+                    &request.filters,
+                    request.query_vector.as_deref(),
+                )
+                .await?;
+            send_all(tx, found.into_iter().map(SearchStreamItem::Community)).await;
+        }
+
+        Ok(failed_methods)
+    }
+
+    /// Rerank edge and node results as `search_uncached` would, reporting
+    /// each result's rank in every method that found it and its reranker score
+    pub async fn explain(&self, request: &SearchRequest) -> SearchResult<SearchExplanation> {
+        let start = Instant::now();
+        let query_vector = request.query_vector.as_deref();
+        let mut edges = Vec::new();
+        let mut nodes = Vec::new();
+        let mut failed_methods = Vec::new();
+
+        if let Some(config) = request.config.resolved_edge_config() {
+            let (method_results, failed) = self
+                .edge_method_results(&request.query, &config, &request.filters, query_vector)
+                .await?;
+            record_failed(&mut failed_methods, failed);
+            let scored = reranking::rerank_edges_scored(
+                ranked_lists(method_results.clone()),
+                &config.reranker,
+                query_vector,
+                config.mmr_lambda,
+            )?;
+            edges = explain_ranking(&method_results, scored, |edge| edge.uuid);
+        }
+
+        if let Some(config) = request.config.resolved_node_config() {
+            let (method_results, failed) = self
+                .node_method_results(&request.query, &config, &request.filters, query_vector)
+                .await?;
+            record_failed(&mut failed_methods, failed);
+            let scored = reranking::rerank_nodes_scored(
+                ranked_lists(method_results.clone()),
+                &config.reranker,
+                query_vector,
+                config.mmr_lambda,
+                config.centrality_boost_factor.unwrap_or(1.0),
+            )?;
+            nodes = explain_ranking(&method_results, scored, |node| node.uuid);
         }
 
+        Ok(SearchExplanation {
+            edges,
+            nodes,
+            failed_methods,
+            latency_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+
+    pub async fn search_edges(
+        &self,
+        query: &str,
+        config: &EdgeSearchConfig,
+        filters: &SearchFilters,
+        query_vector: Option<&[f32]>,
+    ) -> SearchResult<(Vec<Edge>, Vec<SearchMethod>)> {
+        self.search_edges_timed(
+            query,
+            config,
+            filters,
+            query_vector,
+            &mut PhaseDurations::default(),
+        )
+        .await
+    }
+
+    async fn search_edges_timed(
+        &self,
+        query: &str,
+        config: &EdgeSearchConfig,
+        filters: &SearchFilters,
+        query_vector: Option<&[f32]>,
+        phases: &mut PhaseDurations,
+    ) -> SearchResult<(Vec<Edge>, Vec<SearchMethod>)> {
+        let query_start = Instant::now();
+        let (method_results, failed_methods) = self
+            .edge_method_results(query, config, filters, query_vector)
+            .await?;
+        phases.query += query_start.elapsed();
+
         // Apply reranking
+        let rerank_start = Instant::now();
         let reranked = reranking::rerank_edges(
-            method_results,
+            ranked_lists(method_results),
             &config.reranker,
             query_vector,
             config.mmr_lambda,
         )?;
+        phases.rerank += rerank_start.elapsed();
+
+        Ok((reranked, failed_methods))
+    }
+
+    /// Each configured method's edge results, before reranking
+    async fn edge_method_results(
+        &self,
+        query: &str,
+        config: &EdgeSearchConfig,
+        filters: &SearchFilters,
+        query_vector: Option<&[f32]>,
+    ) -> SearchResult<MethodResults<Edge>> {
+        // Direct execution without cache, each method on its own connection
+        let searches = runnable_methods(&config.search_methods, query_vector).map(|method| {
+            let search = async move {
+                let mut falkor_conn = self.connection().await?;
+                match (method, query_vector) {
+                    (SearchMethod::Similarity, Some(embedding)) => {
+                        similarity::search_edges_by_embedding(
+                            &mut falkor_conn,
+                            embedding,
+                            config.sim_min_score,
+                            config.distance_metric,
+                            filters,
+                            100,
+                        )
+                        .await
+                    }
+                    _ => fulltext::search_edges(&mut falkor_conn, query, filters, 100).await,
+                }
+            };
+            (method.clone(), search)
+        });
 
-        Ok(reranked)
+        collect_method_results(run_concurrently(searches).await)
     }
 
     pub async fn search_nodes(
-        &mut self,
+        &self,
         query: &str,
         config: &NodeSearchConfig,
         filters: &SearchFilters,
         query_vector: Option<&[f32]>,
-    ) -> SearchResult<Vec<Node>> {
-        // Direct execution without cache
-        let mut falkor_conn = self.falkor_pool.get().await.map_err(|e| {
-            crate::error::SearchError::Database(format!("Failed to get connection: {}", e))
-        })?;
+    ) -> SearchResult<(Vec<Node>, Vec<SearchMethod>)> {
+        self.search_nodes_timed(
+            query,
+            config,
+            filters,
+            query_vector,
+            &mut PhaseDurations::default(),
+        )
+        .await
+    }
 
-        let mut method_results = Vec::new();
+    async fn search_nodes_timed(
+        &self,
+        query: &str,
+        config: &NodeSearchConfig,
+        filters: &SearchFilters,
+        query_vector: Option<&[f32]>,
+        phases: &mut PhaseDurations,
+    ) -> SearchResult<(Vec<Node>, Vec<SearchMethod>)> {
+        let query_start = Instant::now();
+        let (method_results, failed_methods) = self
+            .node_method_results(query, config, filters, query_vector)
+            .await?;
+        phases.query += query_start.elapsed();
 
-        for method in &config.search_methods {
-            let nodes = match method {
-                SearchMethod::Fulltext => {
-                    fulltext::search_nodes(&mut falkor_conn, query, filters, 100).await?
-                }
-                SearchMethod::Similarity if query_vector.is_some() => {
-                    similarity::search_nodes_by_embedding(
-                        &mut falkor_conn,
-                        query_vector.unwrap(),
-                        config.sim_min_score,
-                        filters,
-                        100,
-                    )
-                    .await?
-                }
-                SearchMethod::Bfs => {
-                    // BFS requires origin nodes, skip if not provided
-                    vec![]
+        let rerank_start = Instant::now();
+        let reranked = rerank_node_results(method_results, config, query_vector)?;
+        phases.rerank += rerank_start.elapsed();
+
+        Ok((reranked, failed_methods))
+    }
+
+    /// Each configured method's node results, before reranking
+    async fn node_method_results(
+        &self,
+        query: &str,
+        config: &NodeSearchConfig,
+        filters: &SearchFilters,
+        query_vector: Option<&[f32]>,
+    ) -> SearchResult<MethodResults<Node>> {
+        // Direct execution without cache, each method on its own connection
+        let searches = runnable_methods(&config.search_methods, query_vector).map(|method| {
+            let search = async move {
+                let mut falkor_conn = self.connection().await?;
+                match (method, query_vector) {
+                    (SearchMethod::Similarity, Some(embedding)) => {
+                        similarity::search_nodes_by_embedding(
+                            &mut falkor_conn,
+                            embedding,
+                            config.sim_min_score,
+                            config.distance_metric,
+                            filters,
+                            100,
+                        )
+                        .await
+                    }
+                    _ => fulltext::search_nodes(&mut falkor_conn, query, filters, 100).await,
                 }
-                _ => vec![],
             };
+            (method.clone(), search)
+        });
 
-            method_results.push(nodes);
-        }
-
-        // Apply reranking with centrality boost factor
-        let reranked = reranking::rerank_nodes(
-            method_results,
-            &config.reranker,
-            query_vector,
-            config.mmr_lambda,
-            config.centrality_boost_factor.unwrap_or(1.0),
-        )?;
+        collect_method_results(run_concurrently(searches).await)
+    }
 
-        Ok(reranked)
+    async fn connection(&self) -> SearchResult<FalkorConnection> {
+        self.falkor_pool.get().await.map_err(|e| {
+            crate::error::SearchError::Database(format!("Failed to get connection: {e}"))
+        })
     }
 
     pub async fn search_episodes(
-        &mut self,
+        &self,
         query: &str,
         filters: &SearchFilters,
+        order_by_time: bool,
         limit: usize,
     ) -> SearchResult<Vec<Episode>> {
         // Create cache key
-        let cache_key = format!("episodes:{query}:{limit}");
+        let cache_key = format!(
+            "episodes:{query}:{limit}:{order_by_time}:{}:{}",
+            filters.created_after.map_or(0, |t| t.timestamp()),
+            filters.created_before.map_or(0, |t| t.timestamp())
+        );
 
         // Clone values needed in the closure
         let query_str = query.to_string();
@@ -226,6 +547,7 @@ impl SearchEngine {
                     &mut falkor_conn,
                     &query_str,
                     &filters_clone,
+                    order_by_time,
                     limit_clone,
                 )
                 .await?;
@@ -238,32 +560,601 @@ impl SearchEngine {
             })
             .await?;
 
-        Ok(result.unwrap_or_else(Vec::new))
+        Ok(result.value.unwrap_or_default())
+    }
+
+    /// Up to `limit` entities mentioned by `episodes`, without embeddings
+    /// unless the request's node config asks for them
+    pub async fn expand_episode_entities(
+        &self,
+        episodes: &[Episode],
+        request: &SearchRequest,
+        limit: usize,
+    ) -> SearchResult<Vec<Node>> {
+        if episodes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut falkor_conn = self.falkor_pool.get().await.map_err(|e| {
+            crate::error::SearchError::Database(format!("Failed to get connection: {e}"))
+        })?;
+        let episode_uuids: Vec<String> = episodes.iter().map(|e| e.uuid.to_string()).collect();
+        let mut entities = falkor_conn
+            .episode_entities(&episode_uuids, limit)
+            .await
+            .map_err(crate::error::SearchError::database)?;
+
+        let include_embeddings = request
+            .config
+            .node_config
+            .as_ref()
+            .is_some_and(|config| config.include_embeddings);
+        if !include_embeddings {
+            for node in &mut entities {
+                node.embedding = None;
+            }
+        }
+        Ok(entities)
     }
 
     pub async fn search_communities(
-        &mut self,
-        _query: &str,
+        &self,
+        query: &str,
         config: &CommunitySearchConfig,
         filters: &SearchFilters,
         query_vector: Option<&[f32]>,
     ) -> SearchResult<Vec<Community>> {
-        // Communities are typically searched via similarity
+        let mut falkor_conn = self.falkor_pool.get().await.map_err(|e| {
+            crate::error::SearchError::Database(format!("Failed to get connection: {e}"))
+        })?;
+
+        let mut method_results = Vec::new();
+
+        // Text matching on name/summary works without an embedding
+        if !query.is_empty() {
+            method_results
+                .push(fulltext::search_communities(&mut falkor_conn, query, filters, 50).await?);
+        }
+
         if let Some(embedding) = query_vector {
-            let mut falkor_conn = self.falkor_pool.get().await.map_err(|e| {
-                crate::error::SearchError::Database(format!("Failed to get connection: {e}"))
-            })?;
-
-            similarity::search_communities_by_embedding(
-                &mut falkor_conn,
-                embedding,
-                config.sim_min_score,
-                filters,
-                50,
-            )
+            method_results.push(
+                similarity::search_communities_by_embedding(
+                    &mut falkor_conn,
+                    embedding,
+                    config.sim_min_score,
+                    config.distance_metric,
+                    filters,
+                    50,
+                )
+                .await?,
+            );
+        }
+
+        if method_results.len() == 1 {
+            return Ok(method_results.pop().unwrap_or_default());
+        }
+
+        Ok(reranking::reciprocal_rank_fusion(
+            method_results,
+            60.0,
+            |community| community.uuid.to_string(),
+        ))
+    }
+}
+
+/// Redis key for a unified search request, stable across processes
+/// Redis prefix of cached unified search results
+const SEARCH_CACHE_PREFIX: &str = "search:";
+
+fn search_cache_key(request: &SearchRequest) -> SearchResult<String> {
+    let body = serde_json::to_string(request)?;
+    Ok(format!(
+        "{}{}",
+        SEARCH_CACHE_PREFIX,
+        uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_OID, body.as_bytes())
+    ))
+}
+
+/// Split per-method outcomes into the result lists that succeeded and the
+/// methods that failed, so one broken method doesn't sink the whole search.
+/// Errors only when every method that ran failed.
+fn collect_method_results<T>(
+    outcomes: Vec<(SearchMethod, SearchResult<Vec<T>>)>,
+) -> SearchResult<MethodResults<T>> {
+    let mut results = Vec::new();
+    let mut failed = Vec::new();
+    let mut last_error = None;
+
+    for (method, outcome) in outcomes {
+        match outcome {
+            Ok(items) => results.push((method, items)),
+            Err(e) => {
+                warn!("{:?} search failed, continuing without it: {}", method, e);
+                failed.push(method);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) if results.is_empty() => Err(e),
+        _ => Ok((results, failed)),
+    }
+}
+
+/// The configured methods that can run: BFS requires origin nodes and
+/// similarity a query vector
+fn runnable_methods<'a>(
+    methods: &'a [SearchMethod],
+    query_vector: Option<&'a [f32]>,
+) -> impl Iterator<Item = &'a SearchMethod> {
+    methods.iter().filter(move |method| match method {
+        SearchMethod::Fulltext => true,
+        SearchMethod::Similarity => query_vector.is_some(),
+        SearchMethod::Bfs => false,
+    })
+}
+
+/// Await every method's search at once, so a multi-method search takes as
+/// long as its slowest method rather than the sum of them
+async fn run_concurrently<T, F>(
+    searches: impl IntoIterator<Item = (SearchMethod, F)>,
+) -> Vec<(SearchMethod, SearchResult<Vec<T>>)>
+where
+    F: Future<Output = SearchResult<Vec<T>>>,
+{
+    join_all(
+        searches
+            .into_iter()
+            .map(|(method, search)| async move { (method, search.await) }),
+    )
+    .await
+}
+
+/// The result lists of `collect_method_results` without their methods, for reranking
+fn ranked_lists<T>(method_results: Vec<(SearchMethod, Vec<T>)>) -> Vec<Vec<T>> {
+    method_results.into_iter().map(|(_, items)| items).collect()
+}
+
+/// Rerank node results with the centrality boost factor. The parser keeps
+/// embeddings, which MMR needs; they are dropped afterwards unless asked for,
+/// so the order doesn't depend on the payload option.
+fn rerank_node_results(
+    method_results: Vec<(SearchMethod, Vec<Node>)>,
+    config: &NodeSearchConfig,
+    query_vector: Option<&[f32]>,
+) -> SearchResult<Vec<Node>> {
+    let mut reranked = reranking::rerank_nodes(
+        ranked_lists(method_results),
+        &config.reranker,
+        query_vector,
+        config.mmr_lambda,
+        config.centrality_boost_factor.unwrap_or(1.0),
+    )?;
+    if !config.include_embeddings {
+        for node in reranked.iter_mut() {
+            node.embedding = None;
+        }
+    }
+    Ok(reranked)
+}
+
+/// Pair each reranked result with its 1-based rank in every method list
+/// that contained it
+fn explain_ranking<T>(
+    method_results: &[(SearchMethod, Vec<T>)],
+    scored: Vec<(T, Option<f32>)>,
+    get_id: impl Fn(&T) -> uuid::Uuid,
+) -> Vec<ResultExplanation> {
+    scored
+        .into_iter()
+        .enumerate()
+        .map(|(position, (item, score))| {
+            let uuid = get_id(&item);
+            let method_ranks = method_results
+                .iter()
+                .filter_map(|(method, items)| {
+                    let rank = items.iter().position(|other| get_id(other) == uuid)?;
+                    Some((method_name(method).to_string(), rank + 1))
+                })
+                .collect();
+            ResultExplanation {
+                uuid,
+                rank: position + 1,
+                method_ranks,
+                score,
+            }
+        })
+        .collect()
+}
+
+/// Send every item, returning false once the receiver has been dropped
+async fn send_all(
+    tx: &mpsc::Sender<SearchStreamItem>,
+    items: impl IntoIterator<Item = SearchStreamItem>,
+) -> bool {
+    for item in items {
+        if tx.send(item).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Add the methods that failed for one category, listing each method once
+fn record_failed(failed_methods: &mut Vec<SearchMethod>, failed: Vec<SearchMethod>) {
+    for method in failed {
+        if !failed_methods.contains(&method) {
+            failed_methods.push(method);
+        }
+    }
+}
+
+fn method_name(method: &SearchMethod) -> &'static str {
+    match method {
+        SearchMethod::Fulltext => "fulltext",
+        SearchMethod::Similarity => "similarity",
+        SearchMethod::Bfs => "bfs",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::error::SearchError;
+    use crate::falkor::create_falkor_pool;
+    use crate::models::{
+        DistanceMetric, EdgeReranker, EpisodeReranker, EpisodeSearchConfig, NodeReranker,
+        NodeSearchConfig, SearchConfig,
+    };
+
+    fn edge(uuid: uuid::Uuid) -> Edge {
+        Edge {
+            uuid,
+            source_node_uuid: uuid::Uuid::new_v4(),
+            target_node_uuid: uuid::Uuid::new_v4(),
+            fact: "a relates to b".to_string(),
+            created_at: chrono::Utc::now(),
+            episodes: vec![],
+            group_id: None,
+            weight: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_failing_similarity_keeps_fulltext_results() {
+        let (first, second) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let outcomes = vec![
+            (SearchMethod::Fulltext, Ok(vec![edge(first), edge(second)])),
+            (
+                SearchMethod::Similarity,
+                Err(SearchError::Database("vector index missing".to_string())),
+            ),
+        ];
+
+        let (method_results, failed) = collect_method_results(outcomes).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert!(matches!(failed[0], SearchMethod::Similarity));
+
+        let reranked =
+            reranking::rerank_edges(ranked_lists(method_results), &EdgeReranker::Rrf, None, 0.5)
+                .unwrap();
+        let uuids: Vec<_> = reranked.iter().map(|e| e.uuid).collect();
+        assert_eq!(uuids, vec![first, second]);
+    }
+
+    #[test]
+    fn test_explanation_lists_rank_in_each_method() {
+        let (shared, fulltext_only, similarity_only) = (
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+        );
+        let method_results = vec![
+            (
+                SearchMethod::Fulltext,
+                vec![edge(fulltext_only), edge(shared)],
+            ),
+            (
+                SearchMethod::Similarity,
+                vec![edge(shared), edge(similarity_only)],
+            ),
+        ];
+
+        let scored = reranking::rerank_edges_scored(
+            ranked_lists(method_results.clone()),
+            &EdgeReranker::Rrf,
+            None,
+            0.5,
+        )
+        .unwrap();
+        let explained = explain_ranking(&method_results, scored, |edge| edge.uuid);
+
+        // Found by both methods, so RRF puts it first with both contributions
+        let top = &explained[0];
+        assert_eq!(top.uuid, shared);
+        assert_eq!(top.rank, 1);
+        assert_eq!(top.method_ranks["fulltext"], 2);
+        assert_eq!(top.method_ranks["similarity"], 1);
+        let expected = 1.0 / 62.0 + 1.0 / 61.0;
+        assert!((top.score.unwrap() - expected).abs() < 1e-6);
+
+        let single = explained.iter().find(|e| e.uuid == fulltext_only).unwrap();
+        assert_eq!(single.method_ranks.len(), 1);
+        assert_eq!(single.method_ranks["fulltext"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_methods_run_concurrently() {
+        let delay = std::time::Duration::from_millis(200);
+        let search = |uuid| async move {
+            tokio::time::sleep(delay).await;
+            Ok(vec![edge(uuid)])
+        };
+        let searches = vec![
+            (SearchMethod::Fulltext, search(uuid::Uuid::new_v4())),
+            (SearchMethod::Similarity, search(uuid::Uuid::new_v4())),
+        ];
+
+        let start = Instant::now();
+        let outcomes = run_concurrently(searches).await;
+        let elapsed = start.elapsed();
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(outcomes[0].0, SearchMethod::Fulltext));
+        assert!(outcomes.iter().all(|(_, outcome)| outcome.is_ok()));
+        // Close to one delay, well short of the two a sequential run takes
+        assert!(elapsed < delay * 2 - delay / 4, "took {elapsed:?}");
+    }
+
+    #[test]
+    fn test_all_methods_failing_is_an_error() {
+        let outcomes: Vec<(SearchMethod, SearchResult<Vec<Edge>>)> = vec![
+            (
+                SearchMethod::Fulltext,
+                Err(SearchError::Database("down".to_string())),
+            ),
+            (
+                SearchMethod::Similarity,
+                Err(SearchError::Database("down".to_string())),
+            ),
+        ];
+
+        assert!(collect_method_results(outcomes).is_err());
+    }
+
+    fn node_search_request(query: &str) -> SearchRequest {
+        SearchRequest {
+            query: query.to_string(),
+            config: SearchConfig {
+                edge_config: None,
+                node_config: Some(NodeSearchConfig {
+                    search_methods: vec![SearchMethod::Fulltext],
+                    reranker: NodeReranker::Rrf,
+                    bfs_max_depth: 1,
+                    sim_min_score: 0.7,
+                    mmr_lambda: 0.5,
+                    centrality_boost_factor: None,
+                    distance_metric: DistanceMetric::Cosine,
+                    include_embeddings: false,
+                }),
+                episode_config: None,
+                community_config: None,
+                limit: 10,
+                reranker_min_score: 0.0,
+                mmr_lambda: None,
+                centrality_boost_factor: None,
+            },
+            filters: SearchFilters::default(),
+            center_node_uuid: None,
+            bfs_origin_node_uuids: None,
+            query_vector: None,
+            bypass_cache: false,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB and Redis instances from FALKORDB_HOST and REDIS_URL"]
+    async fn test_repeated_search_reports_cache_hit() {
+        let config = Config::from_env().unwrap();
+        let falkor_pool = create_falkor_pool(&config).await.unwrap();
+        let redis_pool = deadpool_redis::Config::from_url(config.redis_url.clone())
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+        let engine = SearchEngine::new(falkor_pool, redis_pool);
+
+        // A query no earlier run has cached
+        let request = node_search_request(&format!("cache-probe-{}", uuid::Uuid::new_v4()));
+
+        let first = engine.search(request.clone()).await.unwrap();
+        assert!(!first.cache_hit);
+        assert!(first.cache_age_ms.is_none());
+
+        // Wait out request coalescing so the second call reads from Redis
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let second = engine.search(request).await.unwrap();
+        assert!(second.cache_hit);
+        assert!(second.cache_age_ms.is_some());
+        assert_eq!(second.nodes, first.nodes);
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB and Redis instances from FALKORDB_HOST and REDIS_URL"]
+    async fn test_bypass_cache_recomputes_cached_search() {
+        let config = Config::from_env().unwrap();
+        let falkor_pool = create_falkor_pool(&config).await.unwrap();
+        let redis_pool = deadpool_redis::Config::from_url(config.redis_url.clone())
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+        let engine = SearchEngine::new(falkor_pool, redis_pool);
+
+        let request = node_search_request(&format!("bypass-probe-{}", uuid::Uuid::new_v4()));
+        engine.search(request.clone()).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(engine.search(request.clone()).await.unwrap().cache_hit);
+
+        let bypassed = engine
+            .search(SearchRequest {
+                bypass_cache: true,
+                ..request.clone()
+            })
             .await
-        } else {
-            Ok(vec![])
+            .unwrap();
+        assert!(!bypassed.cache_hit);
+
+        // The fresh results replaced the cached entry under the same key
+        let refreshed = engine.search(request).await.unwrap();
+        assert!(refreshed.cache_hit);
+        assert!(refreshed.cache_age_ms.unwrap() <= bypassed.latency_ms + 1000);
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB and Redis instances from FALKORDB_HOST and REDIS_URL"]
+    async fn test_invalidated_search_is_recomputed() {
+        let config = Config::from_env().unwrap();
+        let falkor_pool = create_falkor_pool(&config).await.unwrap();
+        let redis_pool = deadpool_redis::Config::from_url(config.redis_url.clone())
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+        let engine = SearchEngine::new(falkor_pool, redis_pool);
+
+        let request = node_search_request(&format!("invalidate-probe-{}", uuid::Uuid::new_v4()));
+        engine.search(request.clone()).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(engine.search(request.clone()).await.unwrap().cache_hit);
+
+        assert!(engine.invalidate_cached_searches().await.unwrap() >= 1);
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(!engine.search(request).await.unwrap().cache_hit);
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB and Redis instances from FALKORDB_HOST and REDIS_URL"]
+    async fn test_timings_add_up_to_latency() {
+        let config = Config::from_env().unwrap();
+        let falkor_pool = create_falkor_pool(&config).await.unwrap();
+        let redis_pool = deadpool_redis::Config::from_url(config.redis_url.clone())
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+        let engine = SearchEngine::new(falkor_pool, redis_pool);
+
+        let mut request = node_search_request(&format!("timings-probe-{}", uuid::Uuid::new_v4()));
+        request.config.node_config.as_mut().unwrap().search_methods =
+            vec![SearchMethod::Fulltext, SearchMethod::Similarity];
+        request.query_vector = Some(vec![0.6, 0.8]);
+
+        let computed = engine.search(request.clone()).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let cached = engine.search(request).await.unwrap();
+        assert!(!computed.cache_hit);
+        assert!(cached.cache_hit);
+        assert_eq!((cached.timings.query_ms, cached.timings.rerank_ms), (0, 0));
+
+        for results in [computed, cached] {
+            let json = serde_json::to_value(&results).unwrap();
+            for field in ["embedding_ms", "query_ms", "rerank_ms", "cache_ms"] {
+                assert!(json["timings"][field].is_u64(), "missing {field}");
+            }
+
+            let t = &results.timings;
+            let sum = t.embedding_ms + t.query_ms + t.rerank_ms + t.cache_ms;
+            assert!(
+                sum <= results.latency_ms,
+                "{t:?} exceeds {}ms",
+                results.latency_ms
+            );
+            assert!(
+                results.latency_ms - sum <= 50,
+                "{t:?} leaves too much of {}ms unaccounted",
+                results.latency_ms
+            );
         }
     }
+
+    #[test]
+    fn test_node_order_independent_of_include_embeddings() {
+        let node = |name: &str, embedding: [f32; 2]| Node {
+            uuid: uuid::Uuid::new_v4(),
+            name: name.to_string(),
+            node_type: "Entity".to_string(),
+            summary: None,
+            created_at: chrono::Utc::now(),
+            embedding: Some(embedding.to_vec()),
+            group_id: None,
+            centrality: None,
+        };
+        // "b" nearly duplicates "a", so MMR diversifies past it to "c"
+        let nodes = vec![
+            node("a", [1.0, 0.0]),
+            node("b", [0.99, 0.05]),
+            node("c", [0.6, 0.8]),
+        ];
+        let method_results = vec![(SearchMethod::Similarity, nodes.clone())];
+        let mut config = node_search_request("q").config.node_config.unwrap();
+        config.reranker = NodeReranker::Mmr;
+        config.mmr_lambda = 0.3;
+
+        let query = [1.0, 0.0];
+        let without = rerank_node_results(method_results.clone(), &config, Some(&query)).unwrap();
+        config.include_embeddings = true;
+        let with = rerank_node_results(method_results, &config, Some(&query)).unwrap();
+
+        let names = |nodes: &[Node]| nodes.iter().map(|n| n.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&without), names(&with));
+        assert_eq!(names(&with), ["a", "c", "b"]);
+        assert!(without.iter().all(|n| n.embedding.is_none()));
+        assert!(with.iter().all(|n| n.embedding.is_some()));
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB and Redis instances from FALKORDB_HOST and REDIS_URL"]
+    async fn test_episode_expansion_adds_mentioned_entities_once() {
+        let config = Config::from_env().unwrap();
+        let falkor_pool = create_falkor_pool(&config).await.unwrap();
+        let redis_pool = deadpool_redis::Config::from_url(config.redis_url.clone())
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+        let engine = SearchEngine::new(falkor_pool, redis_pool);
+
+        // The first entity also matches the node search; the second is only
+        // reachable through the episode
+        let probe = format!("expansion-probe-{}", uuid::Uuid::new_v4().simple());
+        let (named, mentioned) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let conn_info: falkordb::FalkorConnectionInfo =
+            format!("redis://{}:{}", config.falkor_host, config.falkor_port)
+                .try_into()
+                .unwrap();
+        let client = falkordb::FalkorClientBuilder::new_async()
+            .with_connection_info(conn_info)
+            .build()
+            .await
+            .unwrap();
+        client
+            .select_graph(&config.graph_name)
+            .query(&format!(
+                "CREATE (e:Episode {{uuid: '{}', name: 'episode', content: 'about {probe}', created_at: 0}}),
+                        (a:Entity {{uuid: '{named}', name: '{probe}', summary: '', created_at: 0}}),
+                        (b:Entity {{uuid: '{mentioned}', name: 'other', summary: '', created_at: 0}}),
+                        (e)-[:MENTIONS]->(a), (e)-[:MENTIONS]->(b)",
+                uuid::Uuid::new_v4()
+            ))
+            .execute()
+            .await
+            .unwrap();
+
+        let mut request = node_search_request(&probe);
+        request.bypass_cache = true;
+        request.config.episode_config = Some(EpisodeSearchConfig {
+            reranker: EpisodeReranker::Rrf,
+            order_by_time: false,
+            expand_entities: true,
+        });
+        let results = engine.search(request).await.unwrap();
+
+        assert_eq!(results.episodes.len(), 1);
+        let uuids: Vec<_> = results.nodes.iter().map(|n| n.uuid).collect();
+        assert_eq!(uuids.iter().filter(|u| **u == named).count(), 1);
+        assert_eq!(uuids.iter().filter(|u| **u == mentioned).count(), 1);
+    }
 }
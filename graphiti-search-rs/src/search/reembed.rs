@@ -0,0 +1,239 @@
+use crate::embeddings::OllamaEmbedder;
+use crate::error::{SearchError, SearchResult};
+use crate::falkor::FalkorConnection;
+use crate::models::{ReembedProgress, ReembedRequest};
+use tracing::{info, instrument, warn};
+
+pub const DEFAULT_REEMBED_BATCH_SIZE: usize = 100;
+pub const MAX_REEMBED_BATCH_SIZE: usize = 500;
+const DEFAULT_REEMBED_MAX_BATCHES: usize = 10;
+
+/// Give entities without a `name_embedding`, or with one whose size differs
+/// from the embedder's, a fresh embedding of their name.
+///
+/// Runs at most `max_batches` batches; the returned cursor continues the scan
+/// on the next call. An entity the embedder fails on is counted and skipped,
+/// so it is retried by the next full run rather than stalling this one.
+#[instrument(skip(conn, embedder))]
+pub async fn reembed_entities(
+    conn: &mut FalkorConnection,
+    embedder: &OllamaEmbedder,
+    request: &ReembedRequest,
+) -> SearchResult<ReembedProgress> {
+    let batch_size = request
+        .batch_size
+        .unwrap_or(DEFAULT_REEMBED_BATCH_SIZE)
+        .clamp(1, MAX_REEMBED_BATCH_SIZE);
+    let max_batches = request
+        .max_batches
+        .unwrap_or(DEFAULT_REEMBED_MAX_BATCHES)
+        .max(1);
+    let dimension = match request.dimension {
+        Some(dimension) => dimension,
+        None => probe_dimension(embedder).await?,
+    };
+
+    let mut progress = ReembedProgress {
+        dimension,
+        next_cursor: request.after_uuid.clone(),
+        ..Default::default()
+    };
+
+    while progress.batches < max_batches {
+        let page = conn
+            .entity_embedding_page(
+                progress.next_cursor.as_deref(),
+                request.group_ids.as_deref(),
+                batch_size,
+            )
+            .await
+            .map_err(SearchError::database)?;
+
+        let mut updates = Vec::new();
+        for entity in &page {
+            if entity
+                .embedding
+                .as_ref()
+                .is_some_and(|embedding| embedding.len() == dimension)
+            {
+                continue;
+            }
+            match embedder.generate_embedding(&entity.name).await {
+                Ok(Some(embedding)) if embedding.len() == dimension => {
+                    updates.push((entity.uuid.clone(), embedding));
+                }
+                Ok(other) => {
+                    warn!(
+                        "No {}-dimension embedding for entity {} (got {:?} dimensions)",
+                        dimension,
+                        entity.uuid,
+                        other.map(|e| e.len())
+                    );
+                    progress.failed += 1;
+                }
+                Err(e) => {
+                    warn!("Failed to embed entity {}: {}", entity.uuid, e);
+                    progress.failed += 1;
+                }
+            }
+        }
+
+        progress.embedded += conn
+            .set_name_embeddings(&updates)
+            .await
+            .map_err(SearchError::database)?;
+        progress.scanned += page.len();
+        progress.batches += 1;
+        progress.next_cursor = page.last().map(|entity| entity.uuid.clone());
+
+        info!(
+            "Re-embed batch {}: scanned {}, embedded {}, failed {}",
+            progress.batches, progress.scanned, progress.embedded, progress.failed
+        );
+
+        if page.len() < batch_size {
+            progress.done = true;
+            progress.next_cursor = None;
+            break;
+        }
+    }
+
+    Ok(progress)
+}
+
+/// Size of the vectors the embedder currently produces
+async fn probe_dimension(embedder: &OllamaEmbedder) -> SearchResult<usize> {
+    match embedder.generate_embedding("dimension probe").await {
+        Ok(Some(embedding)) if !embedding.is_empty() => Ok(embedding.len()),
+        Ok(_) => Err(SearchError::Embedding(
+            "embedder returned no embedding for the dimension probe".to_string(),
+        )),
+        Err(e) => Err(SearchError::Embedding(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::falkor::create_falkor_pool;
+    use axum::{routing::post, Json, Router};
+
+    /// Embedding backend answering every request with the same vector
+    async fn mock_embedder(embedding: Vec<f32>) -> OllamaEmbedder {
+        let app = Router::new().route(
+            "/v1/embeddings",
+            post(move || {
+                let embedding = embedding.clone();
+                async move { Json(serde_json::json!({"data": [{"embedding": embedding}]})) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        OllamaEmbedder::with_timeout(
+            format!("http://{}/v1", addr),
+            "test-model".to_string(),
+            std::time::Duration::from_secs(2),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_dimension_probed_from_embedder() {
+        let embedder = mock_embedder(vec![0.25, 0.5, 0.75]).await;
+        assert_eq!(probe_dimension(&embedder).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires FalkorDB instance running on FALKORDB_HOST"]
+    async fn test_reembed_writes_missing_and_mismatched_embeddings() {
+        let config = Config::from_env().unwrap();
+        let falkor_pool = create_falkor_pool(&config).await.unwrap();
+        let embedder = mock_embedder(vec![0.25, 0.5, 0.75]).await;
+
+        let group = format!("reembed-test-{}", uuid::Uuid::new_v4());
+        let conn_info: falkordb::FalkorConnectionInfo =
+            format!("redis://{}:{}", config.falkor_host, config.falkor_port)
+                .try_into()
+                .unwrap();
+        let client = falkordb::FalkorClientBuilder::new_async()
+            .with_connection_info(conn_info)
+            .build()
+            .await
+            .unwrap();
+        let mut graph = client.select_graph(&config.graph_name);
+        // Missing, wrong-size and already current embeddings
+        for (name, embedding) in [
+            ("missing", None),
+            ("stale", Some("[1.0, 0.0]")),
+            ("current", Some("[0.0, 0.0, 1.0]")),
+        ] {
+            let embedding = embedding
+                .map(|e| format!(", name_embedding: vecf32({e})"))
+                .unwrap_or_default();
+            graph
+                .query(&format!(
+                    "CREATE (:Entity {{uuid: '{}', name: '{name}', summary: '', \
+                     group_id: '{group}', created_at: 0{embedding}}})",
+                    uuid::Uuid::new_v4()
+                ))
+                .execute()
+                .await
+                .unwrap();
+        }
+
+        // One entity per batch and call, so every call resumes from the last
+        let mut request = ReembedRequest {
+            batch_size: Some(1),
+            max_batches: Some(1),
+            group_ids: Some(vec![group.clone()]),
+            ..Default::default()
+        };
+        let mut conn = falkor_pool.get().await.unwrap();
+        let (mut embedded, mut calls) = (0, 0);
+        loop {
+            let progress = reembed_entities(&mut conn, &embedder, &request)
+                .await
+                .unwrap();
+            assert_eq!(progress.dimension, 3);
+            assert_eq!(progress.failed, 0);
+            embedded += progress.embedded;
+            calls += 1;
+            if progress.done {
+                break;
+            }
+            request.after_uuid = progress.next_cursor;
+            assert!(calls <= 4, "re-embed did not finish");
+        }
+        drop(conn);
+
+        assert_eq!(embedded, 2);
+        let mut result = graph
+            .query(&format!(
+                "MATCH (n:Entity) WHERE n.group_id = '{group}' RETURN n.name, n.name_embedding"
+            ))
+            .execute()
+            .await
+            .unwrap();
+        let mut found = 0;
+        for row in result.data.by_ref() {
+            let name = match &row[0] {
+                falkordb::FalkorValue::String(name) => name.clone(),
+                other => panic!("unexpected name {other:?}"),
+            };
+            let embedding = match &row[1] {
+                falkordb::FalkorValue::Vec32(vector) => vector.values.clone(),
+                other => panic!("{name} has no embedding: {other:?}"),
+            };
+            let expected = if name == "current" {
+                vec![0.0, 0.0, 1.0]
+            } else {
+                vec![0.25, 0.5, 0.75]
+            };
+            assert_eq!(embedding, expected, "embedding of {name}");
+            found += 1;
+        }
+        assert_eq!(found, 3);
+    }
+}
@@ -10,6 +10,18 @@ pub fn reciprocal_rank_fusion<T: Clone>(
     k: f32,
     get_id: impl Fn(&T) -> String + Sync,
 ) -> Vec<T> {
+    reciprocal_rank_fusion_scored(ranked_lists, k, get_id)
+        .into_iter()
+        .map(|(item, _)| item)
+        .collect()
+}
+
+/// [`reciprocal_rank_fusion`] keeping each item's fused score
+pub fn reciprocal_rank_fusion_scored<T: Clone>(
+    ranked_lists: Vec<Vec<T>>,
+    k: f32,
+    get_id: impl Fn(&T) -> String + Sync,
+) -> Vec<(T, f32)> {
     let mut scores: HashMap<String, (T, f32)> = HashMap::new();
 
     for list in ranked_lists {
@@ -26,20 +38,21 @@ pub fn reciprocal_rank_fusion<T: Clone>(
 
     let mut results: Vec<(T, f32)> = scores.into_values().collect();
     results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-    results.into_iter().map(|(item, _)| item).collect()
+    results
 }
 
-/// Centrality-based boosting for structurally important nodes
-pub fn centrality_boosted_rerank<T: Clone>(
+/// Centrality-based boosting for structurally important nodes, returning
+/// each item with its boosted score
+pub fn centrality_boosted_rerank_scored<T: Clone>(
     items: Vec<T>,
     query_embedding: Option<&[f32]>,
     get_embedding: impl Fn(&T) -> Option<&[f32]> + Sync,
     get_centrality: impl Fn(&T) -> Option<f32> + Sync,
     boost_factor: f32,
     limit: usize,
-) -> Vec<T> {
+) -> Vec<(T, f32)> {
     if items.is_empty() {
-        return items;
+        return Vec::new();
     }
 
     let query = query_embedding.unwrap_or(&[]);
@@ -73,23 +86,26 @@ pub fn centrality_boosted_rerank<T: Clone>(
     scored_items.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
     // Return top results
+    scored_items.truncate(limit);
     scored_items
-        .into_iter()
-        .take(limit)
-        .map(|(item, _)| item)
-        .collect()
 }
 
-/// Maximal Marginal Relevance (MMR) for diversity-aware reranking
-pub fn maximal_marginal_relevance<T: Clone>(
+/// Maximal Marginal Relevance (MMR) for diversity-aware reranking, returning
+/// each item with the MMR score it had when selected; `None` when there was
+/// no query embedding to score against
+pub fn maximal_marginal_relevance_scored<T: Clone>(
     items: Vec<T>,
     query_embedding: Option<&[f32]>,
     get_embedding: impl Fn(&T) -> Option<&[f32]> + Sync,
     lambda: f32,
     limit: usize,
-) -> Vec<T> {
+) -> Vec<(T, Option<f32>)> {
     if items.is_empty() || query_embedding.is_none() {
-        return items.into_iter().take(limit).collect();
+        return items
+            .into_iter()
+            .take(limit)
+            .map(|item| (item, None))
+            .collect();
     }
 
     let query = query_embedding.unwrap();
@@ -105,7 +121,7 @@ pub fn maximal_marginal_relevance<T: Clone>(
 
                     let max_similarity = selected
                         .iter()
-                        .filter_map(|s: &T| get_embedding(s))
+                        .filter_map(|(s, _): &(T, Option<f32>)| get_embedding(s))
                         .map(|s_emb| cosine_similarity_simd(item_emb, s_emb))
                         .max_by(|a, b| a.partial_cmp(b).unwrap())
                         .unwrap_or(0.0);
@@ -117,13 +133,13 @@ pub fn maximal_marginal_relevance<T: Clone>(
             })
             .collect();
 
-        if let Some((max_idx, _)) = scores
+        if let Some((max_idx, &score)) = scores
             .iter()
             .enumerate()
             .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
         {
             let (orig_idx, _item) = remaining.remove(max_idx);
-            selected.push(items[orig_idx].clone());
+            selected.push((items[orig_idx].clone(), Some(score)));
         } else {
             break;
         }
@@ -167,13 +183,31 @@ pub fn rerank_edges(
     query_vector: Option<&[f32]>,
     mmr_lambda: f32,
 ) -> SearchResult<Vec<Edge>> {
+    let scored = rerank_edges_scored(method_results, reranker, query_vector, mmr_lambda)?;
+    Ok(scored.into_iter().map(|(edge, _)| edge).collect())
+}
+
+/// [`rerank_edges`] keeping the score the reranker assigned each edge, for
+/// rerankers that score rather than only reorder
+pub fn rerank_edges_scored(
+    method_results: Vec<Vec<Edge>>,
+    reranker: &EdgeReranker,
+    query_vector: Option<&[f32]>,
+    mmr_lambda: f32,
+) -> SearchResult<Vec<(Edge, Option<f32>)>> {
+    let unscored = |edges: Vec<Edge>| edges.into_iter().map(|edge| (edge, None)).collect();
     match reranker {
-        EdgeReranker::Rrf => Ok(reciprocal_rank_fusion(method_results, 60.0, |edge| {
-            edge.uuid.to_string()
-        })),
+        EdgeReranker::Rrf => {
+            Ok(
+                reciprocal_rank_fusion_scored(method_results, 60.0, |edge| edge.uuid.to_string())
+                    .into_iter()
+                    .map(|(edge, score)| (edge, Some(score)))
+                    .collect(),
+            )
+        }
         EdgeReranker::Mmr => {
             let all_edges: Vec<Edge> = method_results.into_iter().flatten().collect();
-            Ok(maximal_marginal_relevance(
+            Ok(maximal_marginal_relevance_scored(
                 all_edges,
                 query_vector,
                 |_edge| None, // Edges typically don't have embeddings
@@ -193,18 +227,18 @@ pub fn rerank_edges(
                     }
                 }
             }
-            Ok(result)
+            Ok(unscored(result))
         }
         EdgeReranker::NodeDistance => {
             // Would require distance calculation from graph
             let all_edges: Vec<Edge> = method_results.into_iter().flatten().collect();
-            Ok(all_edges)
+            Ok(unscored(all_edges))
         }
         EdgeReranker::EpisodeMentions => {
             // Sort by number of episode mentions
             let mut all_edges: Vec<Edge> = method_results.into_iter().flatten().collect();
             all_edges.sort_by_key(|edge| std::cmp::Reverse(edge.episodes.len()));
-            Ok(all_edges)
+            Ok(unscored(all_edges))
         }
     }
 }
@@ -217,13 +251,38 @@ pub fn rerank_nodes(
     mmr_lambda: f32,
     centrality_boost_factor: f32,
 ) -> SearchResult<Vec<Node>> {
+    let scored = rerank_nodes_scored(
+        method_results,
+        reranker,
+        query_vector,
+        mmr_lambda,
+        centrality_boost_factor,
+    )?;
+    Ok(scored.into_iter().map(|(node, _)| node).collect())
+}
+
+/// [`rerank_nodes`] keeping the fused or boosted score the reranker assigned
+/// each node, for rerankers that score rather than only reorder
+pub fn rerank_nodes_scored(
+    method_results: Vec<Vec<Node>>,
+    reranker: &NodeReranker,
+    query_vector: Option<&[f32]>,
+    mmr_lambda: f32,
+    centrality_boost_factor: f32,
+) -> SearchResult<Vec<(Node, Option<f32>)>> {
+    let unscored = |nodes: Vec<Node>| nodes.into_iter().map(|node| (node, None)).collect();
     match reranker {
-        NodeReranker::Rrf => Ok(reciprocal_rank_fusion(method_results, 60.0, |node| {
-            node.uuid.to_string()
-        })),
+        NodeReranker::Rrf => {
+            Ok(
+                reciprocal_rank_fusion_scored(method_results, 60.0, |node| node.uuid.to_string())
+                    .into_iter()
+                    .map(|(node, score)| (node, Some(score)))
+                    .collect(),
+            )
+        }
         NodeReranker::Mmr => {
             let all_nodes: Vec<Node> = method_results.into_iter().flatten().collect();
-            Ok(maximal_marginal_relevance(
+            Ok(maximal_marginal_relevance_scored(
                 all_nodes,
                 query_vector,
                 |node| node.embedding.as_deref(),
@@ -242,23 +301,26 @@ pub fn rerank_nodes(
                     }
                 }
             }
-            Ok(result)
+            Ok(unscored(result))
         }
         NodeReranker::CentralityBoosted => {
             let all_nodes: Vec<Node> = method_results.into_iter().flatten().collect();
-            Ok(centrality_boosted_rerank(
+            Ok(centrality_boosted_rerank_scored(
                 all_nodes,
                 query_vector,
                 |node| node.embedding.as_deref(),
                 |node| node.centrality,
                 centrality_boost_factor,
                 100,
-            ))
+            )
+            .into_iter()
+            .map(|(node, score)| (node, Some(score)))
+            .collect())
         }
         NodeReranker::NodeDistance | NodeReranker::EpisodeMentions => {
             // Would require additional context
             let all_nodes: Vec<Node> = method_results.into_iter().flatten().collect();
-            Ok(all_nodes)
+            Ok(unscored(all_nodes))
         }
     }
 }
@@ -286,6 +348,46 @@ mod tests {
         assert_eq!(result[0], "c");
     }
 
+    #[test]
+    fn test_mmr_lambda_trades_relevance_for_diversity() {
+        let nodes = vec![
+            MockNode {
+                id: "best".to_string(),
+                centrality: None,
+                embedding: Some(vec![1.0, 0.0, 0.0]),
+            },
+            MockNode {
+                id: "near_duplicate".to_string(),
+                centrality: None,
+                embedding: Some(vec![0.99, 0.14, 0.0]),
+            },
+            MockNode {
+                id: "diverse".to_string(),
+                centrality: None,
+                embedding: Some(vec![0.7, 0.0, 0.71]),
+            },
+        ];
+        let query_embedding = vec![1.0, 0.0, 0.0];
+
+        let rank = |lambda: f32| -> Vec<String> {
+            maximal_marginal_relevance_scored(
+                nodes.clone(),
+                Some(&query_embedding),
+                |node| node.embedding.as_deref(),
+                lambda,
+                10,
+            )
+            .into_iter()
+            .map(|(node, _)| node.id)
+            .collect()
+        };
+
+        // High lambda favours relevance, so the near duplicate comes second
+        assert_eq!(rank(0.9), ["best", "near_duplicate", "diverse"]);
+        // Low lambda penalises similarity to what is already selected
+        assert_eq!(rank(0.3), ["best", "diverse", "near_duplicate"]);
+    }
+
     #[test]
     fn test_centrality_boosted_rerank() {
         let nodes = vec![
@@ -309,7 +411,7 @@ mod tests {
         let query_embedding = vec![1.0, 0.0, 0.0]; // Should match low_centrality best
         let boost_factor = 2.0;
 
-        let result = centrality_boosted_rerank(
+        let result = centrality_boosted_rerank_scored(
             nodes,
             Some(&query_embedding),
             |node| node.embedding.as_deref(),
@@ -319,6 +421,6 @@ mod tests {
         );
 
         // Despite lower semantic similarity, high_centrality should rank first due to boost
-        assert_eq!(result[0].id, "high_centrality");
+        assert_eq!(result[0].0.id, "high_centrality");
     }
 }
@@ -1,14 +1,28 @@
 use crate::error::{SearchError, SearchResult};
 use crate::falkor::FalkorConnection;
-use crate::models::{Community, Edge, Node, SearchFilters};
+use crate::models::{Community, DistanceMetric, Edge, Node, SearchFilters};
 use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tracing::instrument;
+use tracing::{instrument, warn};
 
-/// Calculate cosine similarity between two vectors
+/// Set once a dimension mismatch has been reported, to avoid flooding the log
+static DIMENSION_MISMATCH_LOGGED: AtomicBool = AtomicBool::new(false);
+
+/// Calculate cosine similarity between two vectors.
+///
+/// Vectors of different dimensions (e.g. embeddings stored before a model
+/// change) are not comparable and score 0.0.
 #[inline]
 pub fn cosine_similarity_simd(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() {
+        if !DIMENSION_MISMATCH_LOGGED.swap(true, Ordering::Relaxed) {
+            warn!(
+                "Embedding dimension mismatch ({} vs {}), scoring as 0.0; stored embeddings may come from a different model",
+                a.len(),
+                b.len()
+            );
+        }
         return 0.0;
     }
 
@@ -72,12 +86,19 @@ pub async fn search_nodes_by_embedding(
     conn: &mut FalkorConnection,
     embedding: &[f32],
     min_score: f32,
+    metric: DistanceMetric,
     filters: &SearchFilters,
     limit: usize,
 ) -> SearchResult<Vec<Node>> {
-    conn.similarity_search_nodes(embedding, limit, min_score, filters.group_ids.as_deref())
-        .await
-        .map_err(|e| SearchError::Database(e.to_string()))
+    conn.similarity_search_nodes(
+        embedding,
+        limit,
+        min_score,
+        metric,
+        filters.group_ids.as_deref(),
+    )
+    .await
+    .map_err(SearchError::database)
 }
 
 #[instrument(skip(conn, embedding))]
@@ -85,24 +106,39 @@ pub async fn search_edges_by_embedding(
     conn: &mut FalkorConnection,
     embedding: &[f32],
     min_score: f32,
+    metric: DistanceMetric,
     filters: &SearchFilters,
     limit: usize,
 ) -> SearchResult<Vec<Edge>> {
-    conn.similarity_search_edges(embedding, limit, min_score, filters.group_ids.as_deref())
-        .await
-        .map_err(|e| SearchError::Database(e.to_string()))
+    conn.similarity_search_edges(
+        embedding,
+        limit,
+        min_score,
+        metric,
+        filters.group_ids.as_deref(),
+    )
+    .await
+    .map_err(SearchError::database)
 }
 
-#[instrument(skip(_conn, _embedding))]
+#[instrument(skip(conn, embedding))]
 pub async fn search_communities_by_embedding(
-    _conn: &mut FalkorConnection,
-    _embedding: &[f32],
-    _min_score: f32,
-    _filters: &SearchFilters,
-    _limit: usize,
+    conn: &mut FalkorConnection,
+    embedding: &[f32],
+    min_score: f32,
+    metric: DistanceMetric,
+    filters: &SearchFilters,
+    limit: usize,
 ) -> SearchResult<Vec<Community>> {
-    // Community search by embedding
-    Ok(vec![])
+    conn.similarity_search_communities(
+        embedding,
+        limit,
+        min_score,
+        metric,
+        filters.group_ids.as_deref(),
+    )
+    .await
+    .map_err(SearchError::database)
 }
 
 #[cfg(test)]
@@ -119,6 +155,15 @@ mod tests {
         assert!((cosine_similarity_simd(&a, &c) + 1.0).abs() < 0.0001);
     }
 
+    #[test]
+    fn test_cosine_similarity_mismatched_lengths() {
+        let query = vec![1.0, 2.0, 3.0, 4.0];
+        let stored = vec![1.0, 2.0];
+        assert_eq!(cosine_similarity_simd(&query, &stored), 0.0);
+        assert_eq!(cosine_similarity_simd(&stored, &query), 0.0);
+        assert_eq!(cosine_similarity_simd(&query, &[]), 0.0);
+    }
+
     #[test]
     fn test_batch_similarity() {
         let query = vec![1.0, 2.0, 3.0];
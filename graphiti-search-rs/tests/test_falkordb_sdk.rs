@@ -123,6 +123,7 @@ async fn test_falkordb_sdk_similarity_search() -> Result<()> {
 async fn test_falkordb_client_v2() -> Result<()> {
     use graphiti_search_rs::config::Config;
     use graphiti_search_rs::falkor::FalkorClientV2;
+    use graphiti_search_rs::models::DistanceMetric;
 
     println!("\n{}", "=".repeat(60));
     println!("Testing FalkorClientV2 Implementation");
@@ -146,7 +147,7 @@ async fn test_falkordb_client_v2() -> Result<()> {
     // Test similarity search with a dummy embedding
     let test_embedding: Vec<f32> = (0..1024).map(|i| ((i as f32) * 0.001).sin()).collect();
     let results = client
-        .similarity_search_nodes(&test_embedding, 5, 0.0, None)
+        .similarity_search_nodes(&test_embedding, 5, 0.0, DistanceMetric::Cosine, None)
         .await?;
     println!(
         "✓ Node similarity search returned {} results",
@@ -154,7 +155,7 @@ async fn test_falkordb_client_v2() -> Result<()> {
     );
 
     let edge_results = client
-        .similarity_search_edges(&test_embedding, 5, 0.0, None)
+        .similarity_search_edges(&test_embedding, 5, 0.0, DistanceMetric::Cosine, None)
         .await?;
     println!(
         "✓ Edge similarity search returned {} results",